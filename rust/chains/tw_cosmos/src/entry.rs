@@ -6,21 +6,63 @@ use std::str::FromStr;
 use tw_coin_entry::coin_context::CoinContext;
 use tw_coin_entry::coin_entry::{CoinEntry, PublicKeyBytes, SignatureBytes};
 use tw_coin_entry::derivation::Derivation;
-use tw_coin_entry::error::AddressResult;
+use tw_coin_entry::error::{AddressError, AddressResult};
 use tw_coin_entry::modules::json_signer::NoJsonSigner;
-use tw_coin_entry::modules::message_signer::NoMessageSigner;
 use tw_coin_entry::modules::plan_builder::NoPlanBuilder;
-use tw_coin_entry::modules::wallet_connector::NoWalletConnector;
 use tw_cosmos_sdk::address::{Address, Bech32Prefix};
 use tw_cosmos_sdk::context::StandardCosmosContext;
 use tw_cosmos_sdk::modules::compiler::tw_compiler::TWTransactionCompiler;
+use tw_cosmos_sdk::modules::decoder::tw_decoder::TWTransactionDecoder;
+use tw_cosmos_sdk::modules::message_signer::tw_message_signer::TWMessageSigner;
 use tw_cosmos_sdk::modules::signer::tw_signer::TWSigner;
-use tw_keypair::tw;
+use tw_cosmos_sdk::modules::wallet_connect::connector::CosmosWalletConnector;
+use tw_cosmos_sdk::public_key::multisig::MultisigPublicKey;
+use tw_cosmos_sdk::public_key::secp256k1::Secp256PublicKey;
+use tw_cosmos_sdk::public_key::CosmosPublicKey;
+use tw_keypair::{tw, KeyPairResult};
 use tw_proto::Cosmos::Proto;
 use tw_proto::TxCompiler::Proto as CompilerProto;
 
 pub struct CosmosEntry;
 
+impl CosmosEntry {
+    /// Derives the address of a `LegacyAminoPubKey` multisig account
+    /// from the public keys of its members and the threshold.
+    #[inline]
+    pub fn derive_multisig_address(
+        &self,
+        coin: &dyn CoinContext,
+        threshold: u32,
+        public_keys: &[PublicKeyBytes],
+        prefix: Option<Bech32Prefix>,
+    ) -> AddressResult<Address> {
+        let public_keys = public_keys
+            .iter()
+            .map(|public_key| Secp256PublicKey::from_bytes(coin, public_key, None))
+            .collect::<KeyPairResult<Vec<_>>>()
+            .map_err(|_| AddressError::PublicKeyTypeMismatch)?;
+        let multisig = MultisigPublicKey::new(threshold, public_keys)
+            .map_err(|_| AddressError::InvalidInput)?;
+
+        let hrp = match prefix {
+            Some(Bech32Prefix { hrp }) => hrp,
+            None => coin.hrp().ok_or(AddressError::InvalidHrp)?,
+        };
+        Address::new(hrp, multisig.address_hash())
+    }
+
+    /// Decodes a serialized `TxRaw`, `SignDoc`, `AuthInfo` or `TxBody`
+    /// into the typed messages and a human-readable JSON.
+    #[inline]
+    pub fn decode_transaction(
+        &self,
+        coin: &dyn CoinContext,
+        input: Proto::DecodingInput<'_>,
+    ) -> Proto::DecodingOutput<'static> {
+        TWTransactionDecoder::decode(coin, input)
+    }
+}
+
 impl CoinEntry for CosmosEntry {
     type AddressPrefix = Bech32Prefix;
     type Address = Address;
@@ -29,8 +71,8 @@ impl CoinEntry for CosmosEntry {
     type PreSigningOutput = CompilerProto::PreSigningOutput<'static>;
     type JsonSigner = NoJsonSigner;
     type PlanBuilder = NoPlanBuilder;
-    type MessageSigner = NoMessageSigner;
-    type WalletConnector = NoWalletConnector;
+    type MessageSigner = TWMessageSigner<StandardCosmosContext>;
+    type WalletConnector = CosmosWalletConnector;
 
     #[inline]
     fn parse_address(
@@ -91,4 +133,14 @@ impl CoinEntry for CosmosEntry {
             public_keys,
         )
     }
+
+    #[inline]
+    fn message_signer(&self) -> Option<Self::MessageSigner> {
+        Some(TWMessageSigner::default())
+    }
+
+    #[inline]
+    fn wallet_connector(&self) -> Option<Self::WalletConnector> {
+        Some(CosmosWalletConnector)
+    }
 }
@@ -5,14 +5,17 @@
 use serde::Serialize;
 use std::fmt;
 use std::str::FromStr;
+use tw_coin_entry::coin_context::CoinContext;
 use tw_coin_entry::coin_entry::CoinAddress;
-use tw_coin_entry::error::AddressError;
+use tw_coin_entry::error::{AddressError, AddressResult};
 use tw_cosmos_sdk::address::CosmosAddress;
+use tw_cosmos_sdk::modules::message_signer::tw_message_signer::MessageSignerAddress;
 use tw_evm::address::Address as EthereumAddress;
 use tw_keypair::ecdsa::secp256k1;
+use tw_keypair::tw;
 use tw_memory::Data;
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, PartialEq, Serialize)]
 pub struct GreenfieldAddress(EthereumAddress);
 
 impl GreenfieldAddress {
@@ -24,6 +27,22 @@ impl GreenfieldAddress {
 
 impl CosmosAddress for GreenfieldAddress {}
 
+impl MessageSignerAddress for GreenfieldAddress {
+    fn from_str_with_coin(_coin: &dyn CoinContext, address: &str) -> AddressResult<Self> {
+        GreenfieldAddress::from_str(address)
+    }
+
+    fn from_public_key_with_coin(
+        _coin: &dyn CoinContext,
+        public_key: &tw::PublicKey,
+    ) -> AddressResult<Self> {
+        let public_key = public_key
+            .to_secp256k1()
+            .ok_or(AddressError::PublicKeyTypeMismatch)?;
+        Ok(GreenfieldAddress::with_secp256k1_pubkey(public_key))
+    }
+}
+
 impl CoinAddress for GreenfieldAddress {
     #[inline]
     fn data(&self) -> Data {
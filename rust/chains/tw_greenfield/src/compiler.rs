@@ -80,7 +80,10 @@ impl GreenfieldCompiler {
         input.public_key = Cow::from(public_key.to_bytes());
         let unsigned = TxBuilder::unsigned_tx_from_proto(coin, &input)?;
 
-        let signed_tx = unsigned.into_signed(signature);
+        let mut signed_tx = unsigned.into_signed(signature);
+        // The fee payer is a part of the EIP-712 sign doc only.
+        // It is the signer by default, so it's not included into `AuthInfo`.
+        signed_tx.fee.payer = None;
         let signed_tx_raw = ProtobufSerializer::<GreenfieldContext>::build_signed_tx(&signed_tx)?;
 
         let broadcast_mode = Self::broadcast_mode(input.mode);
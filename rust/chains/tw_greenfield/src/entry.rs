@@ -4,6 +4,7 @@
 
 use crate::address::GreenfieldAddress;
 use crate::compiler::GreenfieldCompiler;
+use crate::context::GreenfieldContext;
 use crate::signer::GreenfieldSigner;
 use std::str::FromStr;
 use tw_coin_entry::coin_context::CoinContext;
@@ -11,10 +12,10 @@ use tw_coin_entry::coin_entry::{CoinEntry, PublicKeyBytes, SignatureBytes};
 use tw_coin_entry::derivation::Derivation;
 use tw_coin_entry::error::{AddressError, AddressResult};
 use tw_coin_entry::modules::json_signer::NoJsonSigner;
-use tw_coin_entry::modules::message_signer::NoMessageSigner;
 use tw_coin_entry::modules::plan_builder::NoPlanBuilder;
 use tw_coin_entry::modules::wallet_connector::NoWalletConnector;
 use tw_coin_entry::prefix::NoPrefix;
+use tw_cosmos_sdk::modules::message_signer::tw_message_signer::TWMessageSigner;
 use tw_keypair::tw::PublicKey;
 use tw_proto::Greenfield::Proto;
 use tw_proto::TxCompiler::Proto as CompilerProto;
@@ -31,7 +32,9 @@ impl CoinEntry for GreenfieldEntry {
     // Optional modules:
     type JsonSigner = NoJsonSigner;
     type PlanBuilder = NoPlanBuilder;
-    type MessageSigner = NoMessageSigner;
+    type MessageSigner = TWMessageSigner<GreenfieldContext>;
+    // Greenfield transactions are only signed in the EIP-712 mode, which neither
+    // `cosmos_signAmino` nor `cosmos_signDirect` requests can be parsed into.
     type WalletConnector = NoWalletConnector;
 
     #[inline]
@@ -91,4 +94,9 @@ impl CoinEntry for GreenfieldEntry {
     ) -> Self::SigningOutput {
         GreenfieldCompiler::compile(coin, input, signatures, public_keys)
     }
+
+    #[inline]
+    fn message_signer(&self) -> Option<Self::MessageSigner> {
+        Some(TWMessageSigner::default())
+    }
 }
@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::modules::message_signer::MessageSigner;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_encoding::base64;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_greenfield::entry::GreenfieldEntry;
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+
+const PRIVATE_KEY_15560: &str = "9066aa168c379a403becb235c15e7129c133c244e56a757ab07bc369288bcab0";
+const ADDRESS_15560: &str = "0x9d1d97aDFcd324Bbd603D3872BD78e04098510b1";
+const PUBLIC_KEY_15560: &str = "AnnvNAZNoQ2wRjxwSAYWugIHA+w6RQJt73vr0ggvXW/I";
+const SIGNATURE_15560: &str =
+    "LipWtBtNZKhUgJaGt9KZay5NpYAR7G0sWSSv7T0mlOl39bXiWwHf2thbfV5BfoWwljGsBIoQGO+NChgLuQC8qQ==";
+
+const MESSAGE: &str = "Hello, Greenfield!";
+
+fn greenfield_coin() -> TestCoinContext {
+    TestCoinContext::default().with_public_key_type(PublicKeyType::Secp256k1)
+}
+
+fn signing_input(address: &str) -> Proto::MessageSigningInput<'_> {
+    Proto::MessageSigningInput {
+        private_key: PRIVATE_KEY_15560.decode_hex().unwrap().into(),
+        message: MESSAGE.into(),
+        address: address.into(),
+    }
+}
+
+fn verifying_input<'a>(address: &'a str, message: &'a str) -> Proto::MessageVerifyingInput<'a> {
+    Proto::MessageVerifyingInput {
+        message: message.into(),
+        address: address.into(),
+        signature: SIGNATURE_15560.into(),
+        public_key: base64::decode(PUBLIC_KEY_15560, false).unwrap().into(),
+    }
+}
+
+/// The ADR-036 sign doc is hashed with Keccak256, and the signer is the hex address.
+#[test]
+fn test_greenfield_adr036_preimage_hashes() {
+    let coin = greenfield_coin();
+    let signer = GreenfieldEntry.message_signer().unwrap();
+
+    let output = signer.message_preimage_hashes(&coin, signing_input(ADDRESS_15560));
+    assert_eq!(output.error, SigningError::OK, "{}", output.error_message);
+    assert_eq!(
+        String::from_utf8(output.data.to_vec()).unwrap(),
+        r#"{"account_number":"0","chain_id":"","fee":{"amount":[],"gas":"0"},"memo":"","msgs":[{"type":"sign/MsgSignData","value":{"data":"SGVsbG8sIEdyZWVuZmllbGQh","signer":"0x9d1d97aDFcd324Bbd603D3872BD78e04098510b1"}}],"sequence":"0"}"#
+    );
+    assert_eq!(
+        output.data_hash.to_hex(),
+        "887b0e9f2f0ea2de22de2aca20be5e253492f11ac25a62d4df04ece3dbaa14bd"
+    );
+}
+
+#[test]
+fn test_greenfield_adr036_sign_message() {
+    let coin = greenfield_coin();
+    let signer = GreenfieldEntry.message_signer().unwrap();
+
+    // The signer address is derived from the private key if not specified.
+    for address in [ADDRESS_15560, ""] {
+        let output = signer.sign_message(&coin, signing_input(address));
+        assert_eq!(output.error, SigningError::OK, "{}", output.error_message);
+        assert_eq!(output.signature, SIGNATURE_15560);
+        assert_eq!(base64::encode(&output.public_key, false), PUBLIC_KEY_15560);
+        assert_eq!(
+            output.signature_json,
+            format!(
+                r#"{{"pub_key":{{"type":"/cosmos.crypto.eth.ethsecp256k1.PubKey","value":"{PUBLIC_KEY_15560}"}},"signature":"{SIGNATURE_15560}"}}"#
+            )
+        );
+    }
+
+    let output = signer.sign_message(
+        &coin,
+        signing_input("0x280b27f3676db1C4475EE10F75D510Eb527fd155"),
+    );
+    assert_eq!(output.error, SigningError::Error_invalid_address);
+}
+
+#[test]
+fn test_greenfield_adr036_verify_message() {
+    let coin = greenfield_coin();
+    let signer = GreenfieldEntry.message_signer().unwrap();
+
+    assert!(signer.verify_message(&coin, verifying_input(ADDRESS_15560, MESSAGE)));
+    assert!(!signer.verify_message(&coin, verifying_input(ADDRESS_15560, "Hello, Cosmos!")));
+
+    // The public key does not belong to the address.
+    let input = verifying_input("0x280b27f3676db1C4475EE10F75D510Eb527fd155", MESSAGE);
+    assert!(!signer.verify_message(&coin, input));
+}
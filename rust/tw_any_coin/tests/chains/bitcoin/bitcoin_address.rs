@@ -2,10 +2,24 @@
 //
 // Copyright © 2017 Trust Wallet.
 
+use tw_any_coin::ffi::tw_any_address::{
+    tw_any_address_create_with_public_key_derivation, tw_any_address_description,
+};
 use tw_any_coin::test_utils::address_utils::{
+    test_address_base58_is_valid, test_address_bech32_is_valid,
+    test_address_create_base58_with_public_key, test_address_create_bech32_with_public_key,
     test_address_get_data, test_address_invalid, test_address_normalization, test_address_valid,
+    AddressBase58IsValid, AddressBech32IsValid, AddressCreateBase58WithPublicKey,
+    AddressCreateBech32WithPublicKey, TWAnyAddressHelper,
 };
 use tw_coin_registry::coin_type::CoinType;
+use tw_keypair::ffi::privkey::tw_private_key_get_public_key_by_type;
+use tw_keypair::test_utils::tw_private_key_helper::TWPrivateKeyHelper;
+use tw_keypair::test_utils::tw_public_key_helper::TWPublicKeyHelper;
+use tw_keypair::tw::PublicKeyType;
+use tw_memory::test_utils::tw_string_helper::TWStringHelper;
+
+const PRIVATE_KEY: &str = "afeefca74d9a325cf1d6b6911d61a65c32afa8e02bd5e78e2e4ac2910bab45f5";
 
 #[test]
 fn test_bitcoin_address_normalization() {
@@ -45,3 +59,101 @@ fn test_bitcoin_address_get_data() {
         "314d725a4e474e376d66575a695a4e517474727a486a667737326a6e4a43324a4e78",
     );
 }
+
+#[test]
+fn test_bitcoin_testnet_address_get_data() {
+    test_address_get_data(
+        CoinType::Bitcoin,
+        "tb1qten42eesehw0ktddcp0fws7d3ycsqez3rck8lc",
+        "7462317174656e3432656573656877306b74646463703066777337643379637371657a3372636b386c63",
+    );
+}
+
+#[test]
+fn test_bitcoin_address_is_valid_bech32() {
+    test_address_bech32_is_valid(AddressBech32IsValid {
+        coin: CoinType::Bitcoin,
+        address: "tb1qten42eesehw0ktddcp0fws7d3ycsqez3rck8lc",
+        hrp: "tb",
+    });
+    test_address_bech32_is_valid(AddressBech32IsValid {
+        coin: CoinType::Bitcoin,
+        address: "bcrt1qten42eesehw0ktddcp0fws7d3ycsqez3p302g3",
+        hrp: "bcrt",
+    });
+}
+
+#[test]
+fn test_bitcoin_address_is_valid_base58() {
+    test_address_base58_is_valid(AddressBase58IsValid {
+        coin: CoinType::Bitcoin,
+        address: "mp87bq9LgAB4QHetcRmZCJ2XRhssQX3LLM",
+        p2pkh: 111,
+        p2sh: 196,
+        valid: true,
+    });
+    test_address_base58_is_valid(AddressBase58IsValid {
+        coin: CoinType::Bitcoin,
+        address: "2N6coafA7sffiu19hZKc9BwuJYGSHQtWxjT",
+        p2pkh: 111,
+        p2sh: 196,
+        valid: true,
+    });
+    test_address_base58_is_valid(AddressBase58IsValid {
+        coin: CoinType::Bitcoin,
+        address: "19cAJn4Ms8jodBBGtroBNNpCZiHAWGAq7X",
+        p2pkh: 111,
+        p2sh: 196,
+        valid: false,
+    });
+}
+
+#[test]
+fn test_bitcoin_address_create_with_prefix() {
+    test_address_create_base58_with_public_key(AddressCreateBase58WithPublicKey {
+        coin: CoinType::Bitcoin,
+        private_key: PRIVATE_KEY,
+        public_key_type: PublicKeyType::Secp256k1,
+        p2pkh: 111,
+        p2sh: 196,
+        expected: "mp87bq9LgAB4QHetcRmZCJ2XRhssQX3LLM",
+    });
+    test_address_create_bech32_with_public_key(AddressCreateBech32WithPublicKey {
+        coin: CoinType::Bitcoin,
+        private_key: PRIVATE_KEY,
+        public_key_type: PublicKeyType::Secp256k1,
+        hrp: "bcrt",
+        expected: "bcrt1qten42eesehw0ktddcp0fws7d3ycsqez3p302g3",
+    });
+}
+
+#[test]
+fn test_bitcoin_address_create_with_derivation() {
+    let private_key = TWPrivateKeyHelper::with_hex(PRIVATE_KEY);
+    let public_key = TWPublicKeyHelper::wrap(unsafe {
+        tw_private_key_get_public_key_by_type(private_key.ptr(), PublicKeyType::Secp256k1 as u32)
+    });
+
+    // Raw `TWDerivation` values.
+    let tests = [
+        (0, "19cAJn4Ms8jodBBGtroBNNpCZiHAWGAq7X"),
+        // TWDerivationBitcoinSegwit
+        (2, "bc1qten42eesehw0ktddcp0fws7d3ycsqez3f7d5yt"),
+        // TWDerivationBitcoinLegacy
+        (3, "19cAJn4Ms8jodBBGtroBNNpCZiHAWGAq7X"),
+    ];
+
+    for (derivation, expected) in tests {
+        let any_address = TWAnyAddressHelper::wrap(unsafe {
+            tw_any_address_create_with_public_key_derivation(
+                public_key.ptr(),
+                CoinType::Bitcoin as u32,
+                derivation,
+            )
+        });
+
+        let description =
+            TWStringHelper::wrap(unsafe { tw_any_address_description(any_address.ptr()) });
+        assert_eq!(description.to_string(), Some(expected.to_string()));
+    }
+}
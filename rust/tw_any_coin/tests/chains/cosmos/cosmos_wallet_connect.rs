@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use std::borrow::Cow;
+use tw_any_coin::test_utils::sign_utils::AnySignerHelper;
+use tw_any_coin::test_utils::wallet_connect_utils::WalletConnectRequestHelper;
+use tw_coin_registry::coin_type::CoinType;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto::{self, mod_Message::OneOfmessage_oneof as MessageEnum};
+use tw_proto::WalletConnect::Proto as WCProto;
+
+const ACCOUNT_1037_PRIVATE_KEY: &str =
+    "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005";
+const WC_SIGN_AMINO_REQUEST: &str = include_str!("data/wc_sign_amino_request.json");
+const WC_SIGN_DIRECT_REQUEST: &str = include_str!("data/wc_sign_direct_request.json");
+
+fn parse_request<'a>(
+    parser: &'a mut WalletConnectRequestHelper,
+    method: WCProto::Method,
+    payload: &str,
+) -> Proto::SigningInput<'a> {
+    let input = WCProto::ParseRequestInput {
+        protocol: WCProto::Protocol::V2,
+        method,
+        payload: payload.to_string().into(),
+    };
+
+    let parsing_output = parser.parse(CoinType::Cosmos, &input);
+    assert_eq!(parsing_output.error, SigningError::OK);
+
+    match parsing_output.signing_input_oneof {
+        WCProto::mod_ParseRequestOutput::OneOfsigning_input_oneof::cosmos(input) => input,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_cosmos_sign_wallet_connect_amino() {
+    let mut parser = WalletConnectRequestHelper::default();
+    let mut signing_input = parse_request(
+        &mut parser,
+        WCProto::Method::CosmosSignAmino,
+        WC_SIGN_AMINO_REQUEST,
+    );
+
+    let expected_msg = Proto::mod_Message::RawJSON {
+        type_pb: "cosmos-sdk/MsgSend".into(),
+        value: r#"{"amount":[{"amount":"1","denom":"muon"}],"from_address":"cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02","to_address":"cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573"}"#.into(),
+    };
+    let expected_signing_input = Proto::SigningInput {
+        signing_mode: Proto::SigningMode::JSON,
+        account_number: 1037,
+        chain_id: "gaia-13003".into(),
+        fee: Some(Proto::Fee {
+            amounts: vec![Proto::Amount {
+                denom: "muon".into(),
+                amount: "200".into(),
+            }],
+            gas: 200000,
+            ..Proto::Fee::default()
+        }),
+        sequence: 8,
+        messages: vec![Proto::Message {
+            message_oneof: MessageEnum::raw_json_message(expected_msg),
+        }],
+        ..Proto::SigningInput::default()
+    };
+    assert_eq!(signing_input, expected_signing_input);
+
+    // Set missing private key.
+    signing_input.private_key = ACCOUNT_1037_PRIVATE_KEY.decode_hex().unwrap().into();
+
+    let mut signer = AnySignerHelper::<Proto::SigningOutput>::default();
+    let signing_output = signer.sign(CoinType::Cosmos, signing_input);
+
+    assert_eq!(signing_output.error, SigningError::OK);
+    assert_eq!(
+        signing_output.signature.to_hex(),
+        "fc3ef899d206c88077fec42f21ba0b4df4bd3fd115fdf606ae01d9136fef363f57e9e33a7b9ec6ddab658cd07e3c0067470de94e4e75b979a1085a29f0efd926"
+    );
+}
+
+#[test]
+fn test_cosmos_sign_wallet_connect_direct() {
+    let mut parser = WalletConnectRequestHelper::default();
+    let mut signing_input = parse_request(
+        &mut parser,
+        WCProto::Method::CosmosSignDirect,
+        WC_SIGN_DIRECT_REQUEST,
+    );
+
+    let expected_msg = Proto::mod_Message::SignDirect {
+        body_bytes: "0a89010a1c2f636f736d6f732e62616e6b2e763162657461312e4d736753656e6412690a2d636f736d6f733168736b366a727979716a6668703564686335357463396a74636b796778306570683664643032122d636f736d6f73317a743530617a7570616e716c66616d356166687633686578777975746e756b656834633537331a090a046d756f6e120131".decode_hex().unwrap().into(),
+        auth_info_bytes: "0a500a460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a210257286ec3f37d33557bbbaa000b27744ac9023aa9967cae75a181d1ff91fa9dc512040a020801180812110a0b0a046d756f6e120332303010c09a0c".decode_hex().unwrap().into(),
+    };
+    let expected_signing_input = Proto::SigningInput {
+        signing_mode: Proto::SigningMode::Protobuf,
+        account_number: 1037,
+        chain_id: "gaia-13003".into(),
+        private_key: Cow::default(),
+        messages: vec![Proto::Message {
+            message_oneof: MessageEnum::sign_direct_message(expected_msg),
+        }],
+        ..Proto::SigningInput::default()
+    };
+    assert_eq!(signing_input, expected_signing_input);
+
+    // Set missing private key.
+    signing_input.private_key = ACCOUNT_1037_PRIVATE_KEY.decode_hex().unwrap().into();
+
+    let mut signer = AnySignerHelper::<Proto::SigningOutput>::default();
+    let signing_output = signer.sign(CoinType::Cosmos, signing_input);
+
+    assert_eq!(signing_output.error, SigningError::OK);
+    assert_eq!(
+        signing_output.signature.to_hex(),
+        "f9e1f4001657a42009c4eb6859625d2e41e961fc72efd2842909c898e439fc1f549916e4ecac676ee353c7d54c5ae30a29b4210b8bff0ebfdcb375e105002f47"
+    );
+}
+
+#[test]
+fn test_cosmos_wallet_connect_invalid_request() {
+    let mut parser = WalletConnectRequestHelper::default();
+
+    // The signer address belongs to another chain.
+    let input = WCProto::ParseRequestInput {
+        protocol: WCProto::Protocol::V2,
+        method: WCProto::Method::CosmosSignAmino,
+        payload: WC_SIGN_AMINO_REQUEST
+            .replace(
+                "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02",
+                "osmo1hsk6jryyqjfhp5dhc55tc9jtckygx0eplp7aec",
+            )
+            .into(),
+    };
+    let output = parser.parse(CoinType::Cosmos, &input);
+    assert_eq!(output.error, SigningError::Error_invalid_address);
+
+    // `bodyBytes` is not Base64-encoded.
+    let input = WCProto::ParseRequestInput {
+        protocol: WCProto::Protocol::V2,
+        method: WCProto::Method::CosmosSignDirect,
+        payload: WC_SIGN_DIRECT_REQUEST
+            .replace("CokBChwv", "CokBChw!")
+            .into(),
+    };
+    let output = parser.parse(CoinType::Cosmos, &input);
+    assert_eq!(output.error, SigningError::Error_input_parse);
+
+    // The payload is not a `cosmos_signDirect` request.
+    let input = WCProto::ParseRequestInput {
+        protocol: WCProto::Protocol::V2,
+        method: WCProto::Method::CosmosSignDirect,
+        payload: WC_SIGN_AMINO_REQUEST.into(),
+    };
+    let output = parser.parse(CoinType::Cosmos, &input);
+    assert_eq!(output.error, SigningError::Error_input_parse);
+}
@@ -4,3 +4,4 @@
 
 mod cosmos_address;
 mod cosmos_sign;
+mod cosmos_wallet_connect;
@@ -1,4 +1,10 @@
+use crate::modules::chain_info;
+use crate::modules::message_signer::BitcoinMessageSigner;
+use crate::modules::network::{resolve_address_format, AddressFormat, BitcoinPrefix};
+use crate::modules::plan_builder::BitcoinPlanBuilder;
 use crate::modules::signer::Signer;
+use crate::modules::silent_payments::SilentPaymentAddress;
+use crate::modules::timelock;
 use crate::{Error, Result};
 use bitcoin::address::NetworkChecked;
 use std::borrow::Cow;
@@ -9,34 +15,40 @@ use tw_coin_entry::coin_entry::{CoinAddress, CoinEntry, PublicKeyBytes, Signatur
 use tw_coin_entry::derivation::Derivation;
 use tw_coin_entry::error::{AddressError, AddressResult};
 use tw_coin_entry::modules::json_signer::NoJsonSigner;
-use tw_coin_entry::modules::message_signer::NoMessageSigner;
-use tw_coin_entry::modules::plan_builder::NoPlanBuilder;
 use tw_coin_entry::modules::wallet_connector::NoWalletConnector;
-use tw_coin_entry::prefix::NoPrefix;
 use tw_coin_entry::signing_output_error;
 use tw_keypair::tw::PublicKey;
 use tw_misc::traits::ToBytesVec;
 use tw_proto::BitcoinV2::Proto;
+use tw_proto::Lightning::Proto as LightningProto;
 use tw_proto::Utxo::Proto as UtxoProto;
 
-pub struct Address(pub bitcoin::address::Address<NetworkChecked>);
+pub enum Address {
+    Bitcoin(bitcoin::address::Address<NetworkChecked>),
+    /// An address of a Bitcoin fork, e.g. Litecoin or Bitcoin Cash, in the
+    /// format of the chain.
+    Fork(String),
+}
 
 impl Display for Address {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            Address::Bitcoin(address) => write!(f, "{address}"),
+            Address::Fork(address) => write!(f, "{address}"),
+        }
     }
 }
 
 impl CoinAddress for Address {
     fn data(&self) -> tw_memory::Data {
-        self.0.to_string().into_bytes()
+        self.to_string().into_bytes()
     }
 }
 
 pub struct BitcoinEntry;
 
 impl CoinEntry for BitcoinEntry {
-    type AddressPrefix = NoPrefix;
+    type AddressPrefix = BitcoinPrefix;
     type Address = Address;
     type SigningInput<'a> = Proto::SigningInput<'a>;
     type SigningOutput = Proto::SigningOutput<'static>;
@@ -44,8 +56,8 @@ impl CoinEntry for BitcoinEntry {
 
     // Optional modules:
     type JsonSigner = NoJsonSigner;
-    type PlanBuilder = NoPlanBuilder;
-    type MessageSigner = NoMessageSigner;
+    type PlanBuilder = BitcoinPlanBuilder;
+    type MessageSigner = BitcoinMessageSigner;
     type WalletConnector = NoWalletConnector;
 
     #[inline]
@@ -53,49 +65,113 @@ impl CoinEntry for BitcoinEntry {
         &self,
         coin: &dyn CoinContext,
         address: &str,
-        _prefix: Option<Self::AddressPrefix>,
+        prefix: Option<Self::AddressPrefix>,
     ) -> AddressResult<Self::Address> {
-        self.parse_address_unchecked(coin, address)
+        let network = match resolve_address_format(coin, prefix) {
+            AddressFormat::Bitcoin(network) => network,
+            AddressFormat::Fork(info) => return parse_fork_address(&info, address),
+        };
+
+        let address = bitcoin::address::Address::from_str(address)
+            .map_err(|_| AddressError::FromHexError)?
+            .require_network(network)
+            .map_err(|_| AddressError::InvalidInput)?;
+
+        Ok(Address::Bitcoin(address))
     }
 
     #[inline]
     fn parse_address_unchecked(
         &self,
-        _coin: &dyn CoinContext,
+        coin: &dyn CoinContext,
         address: &str,
     ) -> AddressResult<Self::Address> {
+        if let AddressFormat::Fork(info) = resolve_address_format(coin, None) {
+            return parse_fork_address(&info, address);
+        }
+
+        // The address is accepted for any Bitcoin network.
         let address = bitcoin::address::Address::from_str(address)
             .map_err(|_| AddressError::FromHexError)?
-            // At this moment, we support mainnet only.
-            // This check will be removed in coming PRs.
-            .require_network(bitcoin::Network::Bitcoin)
-            .map_err(|_| AddressError::InvalidInput)?;
+            .assume_checked();
 
-        Ok(Address(address))
+        Ok(Address::Bitcoin(address))
     }
 
     #[inline]
     fn derive_address(
         &self,
-        _coin: &dyn CoinContext,
+        coin: &dyn CoinContext,
         public_key: PublicKey,
-        _derivation: Derivation,
-        _prefix: Option<Self::AddressPrefix>,
+        derivation: Derivation,
+        prefix: Option<Self::AddressPrefix>,
     ) -> AddressResult<Self::Address> {
+        // A bech32 prefix selects a Segwit address.
+        let derivation = match (&derivation, &prefix) {
+            (Derivation::Default, Some(BitcoinPrefix::Hrp(_))) => Derivation::Bip84,
+            _ => derivation,
+        };
+
+        let format = match derivation {
+            Derivation::BitcoinTestnet => AddressFormat::Bitcoin(bitcoin::Network::Testnet),
+            _ => resolve_address_format(coin, prefix),
+        };
+        // Fork addresses are encoded from the scriptPubkey, so any network
+        // will do.
+        let network = match format {
+            AddressFormat::Bitcoin(network) => network,
+            AddressFormat::Fork(_) => bitcoin::Network::Bitcoin,
+        };
+
+        // The serialization of the public key is a part of the P2PKH address.
         let pubkey = match public_key {
-            PublicKey::Secp256k1(pubkey) | PublicKey::Secp256k1Extended(pubkey) => pubkey,
+            PublicKey::Secp256k1(pubkey) => {
+                bitcoin::PublicKey::from_slice(pubkey.compressed().as_slice())
+            },
+            PublicKey::Secp256k1Extended(pubkey) => {
+                bitcoin::PublicKey::from_slice(pubkey.uncompressed().as_slice())
+            },
             _ => return Err(AddressError::InvalidInput),
-        };
+        }
+        .map_err(|_| AddressError::InvalidInput)?;
 
-        let pubkey = bitcoin::PublicKey::from_slice(pubkey.to_vec().as_ref())
-            .map_err(|_| AddressError::InvalidInput)?;
+        let is_segwit = !matches!(derivation, Derivation::Default | Derivation::Bip44);
+        // Segwit and Taproot addresses require a compressed public key (BIP-143).
+        if is_segwit && !pubkey.compressed {
+            return Err(AddressError::InvalidInput);
+        }
 
-        let address: bitcoin::address::Address<NetworkChecked> = bitcoin::address::Address::new(
-            bitcoin::Network::Bitcoin,
-            bitcoin::address::Payload::PubkeyHash(pubkey.pubkey_hash()),
-        );
+        let address = match derivation {
+            Derivation::Default | Derivation::Bip44 => {
+                bitcoin::address::Address::p2pkh(&pubkey, network)
+            },
+            Derivation::Bip49 => bitcoin::address::Address::p2shwpkh(&pubkey, network)
+                .map_err(|_| AddressError::InvalidInput)?,
+            Derivation::Bip84 | Derivation::BitcoinTestnet => {
+                bitcoin::address::Address::p2wpkh(&pubkey, network)
+                    .map_err(|_| AddressError::InvalidInput)?
+            },
+            Derivation::Bip86 => {
+                // The output key is tweaked without a script tree (BIP-86).
+                let internal_key = bitcoin::key::XOnlyPublicKey::from(pubkey.inner);
+                bitcoin::address::Address::p2tr(
+                    &secp256k1::Secp256k1::verification_only(),
+                    internal_key,
+                    None,
+                    network,
+                )
+            },
+        };
 
-        Ok(Address(address))
+        match format {
+            AddressFormat::Bitcoin(_) => Ok(Address::Bitcoin(address)),
+            // The chain may not support the address type, e.g. Segwit.
+            AddressFormat::Fork(info) => {
+                chain_info::address_from_script_pubkey(&info, &address.script_pubkey())
+                    .map(Address::Fork)
+                    .map_err(|_| AddressError::UnexpectedAddressPrefix)
+            },
+        }
     }
 
     #[inline]
@@ -128,17 +204,131 @@ impl CoinEntry for BitcoinEntry {
 
     #[inline]
     fn plan_builder(&self) -> Option<Self::PlanBuilder> {
-        None
+        Some(BitcoinPlanBuilder)
+    }
+
+    #[inline]
+    fn message_signer(&self) -> Option<Self::MessageSigner> {
+        Some(BitcoinMessageSigner)
     }
 }
 
 impl BitcoinEntry {
+    /// Decodes a raw transaction (legacy or Segwit) and analyzes its inputs
+    /// and outputs, such as the script types, addresses and fee.
+    #[inline]
+    pub fn decode_transaction(
+        &self,
+        _coin: &dyn CoinContext,
+        proto: Proto::DecodingInput<'_>,
+    ) -> Proto::DecodingOutput<'static> {
+        crate::modules::decoder::Decoder::decode_proto(proto)
+            .unwrap_or_else(|err| signing_output_error!(Proto::DecodingOutput, err))
+    }
+
+    /// Decodes a BOLT-11 Lightning invoice and verifies its signature.
+    #[inline]
+    pub fn decode_lightning_invoice(
+        &self,
+        _coin: &dyn CoinContext,
+        proto: LightningProto::DecodingInput<'_>,
+    ) -> LightningProto::DecodingOutput<'static> {
+        crate::modules::bolt11::Bolt11Invoice::decode_proto(proto)
+            .unwrap_or_else(|err| signing_output_error!(LightningProto::DecodingOutput, err))
+    }
+
+    /// Signs all multisig inputs the private key is a cosigner of. The
+    /// resulting partial signatures of all cosigners are passed on to
+    /// [`BitcoinEntry::combine_partial_signatures`].
+    #[inline]
+    pub fn sign_partial(
+        &self,
+        _coin: &dyn CoinContext,
+        proto: Proto::SigningInput<'_>,
+    ) -> Proto::PartialSigningOutput<'static> {
+        Signer::sign_partial_proto(_coin, proto)
+            .unwrap_or_else(|err| signing_output_error!(Proto::PartialSigningOutput, err))
+    }
+
+    /// Combines the partial signatures of the cosigners into the final
+    /// transaction, once the threshold of each multisig input is met.
+    #[inline]
+    pub fn combine_partial_signatures(
+        &self,
+        _coin: &dyn CoinContext,
+        proto: Proto::CombiningInput<'_>,
+    ) -> Proto::SigningOutput<'static> {
+        Signer::combine_proto(_coin, proto)
+            .unwrap_or_else(|err| signing_output_error!(Proto::SigningOutput, err))
+    }
+
+    /// Estimates the worst-case size and fee of a transaction without
+    /// signing it.
+    #[inline]
+    pub fn estimate_fee(
+        &self,
+        _coin: &dyn CoinContext,
+        proto: Proto::FeeEstimationInput<'_>,
+    ) -> Proto::FeeEstimationOutput<'static> {
+        crate::modules::fee_estimator::FeeEstimator::estimate_proto(_coin, proto)
+            .unwrap_or_else(|err| signing_output_error!(Proto::FeeEstimationOutput, err))
+    }
+
+    /// Checks which outputs of a transaction pay to the given silent payment
+    /// keys of the receiver.
+    #[inline]
+    pub fn scan_silent_payments(
+        &self,
+        _coin: &dyn CoinContext,
+        proto: Proto::SilentPaymentScanInput<'_>,
+    ) -> Proto::SilentPaymentScanOutput<'static> {
+        crate::modules::silent_payments::SilentPaymentScanner::scan_proto(proto)
+            .unwrap_or_else(|err| signing_output_error!(Proto::SilentPaymentScanOutput, err))
+    }
+
+    /// Derives the silent payment address of the given scan and spend public
+    /// keys.
+    pub fn derive_silent_payment_address(
+        &self,
+        _coin: &dyn CoinContext,
+        scan_public_key: &[u8],
+        spend_public_key: &[u8],
+        network: Proto::Network,
+    ) -> AddressResult<SilentPaymentAddress> {
+        let network = crate::modules::decoder::network_from_proto(network);
+
+        let scan = secp256k1::PublicKey::from_slice(scan_public_key)
+            .map_err(|_| AddressError::InvalidInput)?;
+        let spend = secp256k1::PublicKey::from_slice(spend_public_key)
+            .map_err(|_| AddressError::InvalidInput)?;
+
+        Ok(SilentPaymentAddress::new(scan, spend, network))
+    }
+
+    /// Derives the address of an m-of-n multisig.
+    pub fn derive_multisig_address(
+        &self,
+        _coin: &dyn CoinContext,
+        multisig: &Proto::Multisig<'_>,
+        network: Proto::Network,
+    ) -> AddressResult<Address> {
+        let network = crate::modules::decoder::network_from_proto(network);
+
+        let address = crate::modules::multisig::MultisigScript::from_proto(multisig)
+            .and_then(|multisig| multisig.address(network))
+            .map_err(|_| AddressError::InvalidInput)?;
+
+        Ok(Address::Bitcoin(address))
+    }
+
     pub(crate) fn preimage_hashes_impl(
         &self,
         _coin: &dyn CoinContext,
         proto: Proto::SigningInput<'_>,
     ) -> Result<Proto::PreSigningOutput<'static>> {
-        let proto = pre_processor(proto);
+        let proto = chain_info::resolve_addresses(pre_processor(proto))?;
+        let proto = timelock::apply_timelocks(proto)?;
+        let utxo_chain = chain_info::utxo_chain(&proto);
 
         // Convert input builders into Utxo inputs.
         let utxo_inputs = proto
@@ -185,12 +375,13 @@ impl BitcoinEntry {
             weight_base: proto.fee_per_vb,
             change_script_pubkey,
             disable_change_output: proto.disable_change_output,
+            chain: utxo_chain,
         };
 
         // Generate the sighashes to be signed. This also selects the inputs
         // according to the input selecter and appends a change output, if
         // enabled.
-        let utxo_presigning = tw_utxo::compiler::Compiler::preimage_hashes(utxo_signing);
+        let utxo_presigning = chain_info::preimage_hashes(utxo_signing);
         handle_utxo_error(&utxo_presigning.error)?;
 
         // Check whether the change output is present.
@@ -236,7 +427,10 @@ impl BitcoinEntry {
         signatures: Vec<SignatureBytes>,
         _public_keys: Vec<PublicKeyBytes>,
     ) -> Result<Proto::SigningOutput<'static>> {
-        let proto = pre_processor(proto);
+        let proto = chain_info::resolve_addresses(pre_processor(proto))?;
+        let proto = timelock::apply_timelocks(proto)?;
+        let utxo_chain = chain_info::utxo_chain(&proto);
+        let fork_id = chain_info::uses_fork_id(&proto);
 
         // There must be a signature for each input.
         if proto.inputs.len() != signatures.len() {
@@ -250,7 +444,10 @@ impl BitcoinEntry {
         for (input, signature) in proto.inputs.iter().zip(signatures.into_iter()) {
             let utxo_claim =
                 crate::modules::transactions::InputClaimBuilder::utxo_claim_from_proto(
-                    input, signature,
+                    input,
+                    signature,
+                    &proto.lock_time,
+                    fork_id,
                 )?;
 
             utxo_input_claims.push(utxo_claim);
@@ -277,11 +474,12 @@ impl BitcoinEntry {
                 })
                 .collect(),
             weight_base: proto.fee_per_vb,
+            chain: utxo_chain,
         };
 
         // Compile the transaction, build the final encoded transaction
         // containing the signatures/scriptSigs/witnesses.
-        let utxo_serialized = tw_utxo::compiler::Compiler::compile(utxo_preserializtion);
+        let utxo_serialized = chain_info::compile(utxo_preserializtion);
         handle_utxo_error(&utxo_serialized.error)?;
 
         let mut total_input_amount = 0;
@@ -386,7 +584,23 @@ fn handle_utxo_error(utxo_err: &UtxoProto::Error) -> Result<()> {
         UtxoProto::Error::Error_insufficient_inputs => Proto::Error::Error_utxo_insufficient_inputs,
         UtxoProto::Error::Error_no_outputs_specified => Proto::Error::Error_utxo_no_outputs_specified,
         UtxoProto::Error::Error_missing_change_script_pubkey => Proto::Error::Error_utxo_missing_change_script_pubkey,
+        UtxoProto::Error::Error_invalid_signing_method => Proto::Error::Error_utxo_invalid_signing_method,
+        UtxoProto::Error::Error_missing_branch_id => Proto::Error::Error_utxo_missing_branch_id,
+        UtxoProto::Error::Error_protected_input => Proto::Error::Error_utxo_protected_input,
+        UtxoProto::Error::Error_inscription_not_preserved => Proto::Error::Error_utxo_inscription_not_preserved,
+        UtxoProto::Error::Error_unsupported_chain => Proto::Error::Error_utxo_unsupported_chain,
     };
 
     Err(Error::from(bitcoin_err))
 }
+
+// Parses the address of a Bitcoin fork, normalized to the encoding of its
+// scriptPubkey (e.g. CashAddr with the prefix).
+fn parse_fork_address(info: &Proto::ChainInfo<'_>, address: &str) -> AddressResult<Address> {
+    let script_pubkey = chain_info::script_pubkey_from_address(info, address)
+        .map_err(|_| AddressError::InvalidInput)?;
+
+    chain_info::address_from_script_pubkey(info, &script_pubkey)
+        .map(Address::Fork)
+        .map_err(|_| AddressError::InvalidInput)
+}
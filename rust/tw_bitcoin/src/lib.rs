@@ -64,4 +64,6 @@ pub mod aliases {
     pub type ProtoRedeemScriptOrHash<'a> = Proto::mod_Output::mod_OutputRedeemScriptOrHash::OneOfvariant<'a>;
     pub type ProtoInputRecipient<'a> = Proto::mod_Input::OneOfto_recipient<'a>;
     pub type ProtoInputBuilder<'a> = Proto::mod_Input::mod_InputBuilder::OneOfvariant<'a>;
+    pub type ProtoComposePlan<'a> = Proto::mod_ComposePlan::OneOfcompose<'a>;
+    pub type ProtoTransactionPlan<'a> = Proto::mod_TransactionPlan::OneOfplan<'a>;
 }
@@ -0,0 +1,376 @@
+//! Generic message signing as specified in
+//! [BIP-322](https://github.com/bitcoin/bips/blob/master/bip-0322.mediawiki).
+//!
+//! The message is committed to by a virtual `to_spend` transaction paying to
+//! the address, the signature is a virtual `to_sign` transaction spending it.
+//! Supported are P2PKH, P2SH-P2WPKH, P2WPKH and P2TR (key-path) addresses.
+
+use crate::{Error, Result};
+use bitcoin::absolute::LockTime;
+use bitcoin::blockdata::opcodes::all::{OP_PUSHBYTES_0, OP_RETURN};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::key::TapTweak;
+use bitcoin::script::{Builder, Instruction, PushBytesBuf};
+use bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType};
+use bitcoin::{
+    OutPoint, PublicKey, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+};
+use secp256k1::{KeyPair, Message, Secp256k1, SecretKey, XOnlyPublicKey};
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const BIP322_TAG: &[u8] = b"BIP0322-signed-message";
+
+/// The spending condition of the address the message is signed for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SpendType {
+    P2pkh,
+    P2shP2wpkh,
+    P2wpkh,
+    P2tr,
+}
+
+impl SpendType {
+    fn from_script_pubkey(script_pubkey: &Script) -> Result<Self> {
+        if script_pubkey.is_p2pkh() {
+            Ok(SpendType::P2pkh)
+        } else if script_pubkey.is_p2sh() {
+            // Only P2SH-P2WPKH is supported.
+            Ok(SpendType::P2shP2wpkh)
+        } else if script_pubkey.is_v0_p2wpkh() {
+            Ok(SpendType::P2wpkh)
+        } else if script_pubkey.is_v1_p2tr() {
+            Ok(SpendType::P2tr)
+        } else {
+            Err(Error::from(
+                Proto::Error::Error_unsupported_address_recipient,
+            ))
+        }
+    }
+}
+
+/// The tagged hash of the message: `sha256(sha256(tag) || sha256(tag) || message)`.
+pub fn message_hash(message: &[u8]) -> sha256::Hash {
+    let tag = sha256::Hash::hash(BIP322_TAG);
+
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_ref());
+    engine.input(tag.as_ref());
+    engine.input(message);
+    sha256::Hash::from_engine(engine)
+}
+
+/// Creates the virtual `to_spend` transaction, paying to the given address.
+pub fn to_spend(script_pubkey: &Script, message: &[u8]) -> Transaction {
+    let script_sig = Builder::new()
+        .push_opcode(OP_PUSHBYTES_0)
+        .push_slice(message_hash(message).to_byte_array())
+        .into_script();
+
+    Transaction {
+        version: 0,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: Txid::all_zeros(),
+                vout: 0xFFFFFFFF,
+            },
+            script_sig,
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: script_pubkey.to_owned(),
+        }],
+    }
+}
+
+/// Creates the unsigned virtual `to_sign` transaction, spending `to_spend`.
+pub fn to_sign(to_spend: &Transaction) -> Transaction {
+    Transaction {
+        version: 0,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: to_spend.txid(),
+                vout: 0,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: Builder::new().push_opcode(OP_RETURN).into_script(),
+        }],
+    }
+}
+
+/// Returns the sighash of the `to_sign` input. The public key is only
+/// required for P2SH-P2WPKH addresses, since the address does not reveal
+/// the witness program.
+pub fn signature_hash(
+    to_spend: &Transaction,
+    to_sign: &Transaction,
+    public_key: Option<&PublicKey>,
+) -> Result<(Message, UtxoProto::SigningMethod)> {
+    let script_pubkey = &to_spend.output[0].script_pubkey;
+
+    match SpendType::from_script_pubkey(script_pubkey)? {
+        SpendType::P2pkh => Ok((
+            legacy_sighash(to_sign, script_pubkey, EcdsaSighashType::All)?,
+            UtxoProto::SigningMethod::Legacy,
+        )),
+        SpendType::P2shP2wpkh => {
+            let public_key =
+                public_key.ok_or_else(|| Error::from(Proto::Error::Error_invalid_public_key))?;
+            let witness_program = p2sh_p2wpkh_redeem_script(script_pubkey, public_key)?;
+
+            Ok((
+                segwit_sighash(to_sign, &witness_program, EcdsaSighashType::All)?,
+                UtxoProto::SigningMethod::Segwit,
+            ))
+        },
+        SpendType::P2wpkh => Ok((
+            segwit_sighash(to_sign, script_pubkey, EcdsaSighashType::All)?,
+            UtxoProto::SigningMethod::Segwit,
+        )),
+        SpendType::P2tr => Ok((
+            taproot_sighash(to_spend, to_sign, TapSighashType::Default)?,
+            UtxoProto::SigningMethod::TaprootAll,
+        )),
+    }
+}
+
+/// Signs the message and returns the signed `to_sign` transaction. Fails if
+/// the private key does not belong to the address of `to_spend`.
+pub fn sign(
+    private_key: &SecretKey,
+    to_spend: &Transaction,
+    dangerous_use_fixed_schnorr_rng: bool,
+) -> Result<Transaction> {
+    let secp = Secp256k1::new();
+    let public_key = PublicKey::new(private_key.public_key(&secp));
+    let script_pubkey = &to_spend.output[0].script_pubkey;
+    let spend_type = SpendType::from_script_pubkey(script_pubkey)?;
+
+    let mut to_sign = to_sign(to_spend);
+    let (sighash, _) = signature_hash(to_spend, &to_sign, Some(&public_key))?;
+
+    match spend_type {
+        SpendType::P2pkh => {
+            if *script_pubkey != ScriptBuf::new_p2pkh(&public_key.pubkey_hash()) {
+                return Err(Error::from(Proto::Error::Error_message_address_mismatch));
+            }
+
+            let sig =
+                bitcoin::ecdsa::Signature::sighash_all(secp.sign_ecdsa(&sighash, private_key));
+            to_sign.input[0].script_sig = Builder::new()
+                .push_slice(push_bytes(sig.to_vec())?)
+                .push_key(&public_key)
+                .into_script();
+        },
+        SpendType::P2shP2wpkh | SpendType::P2wpkh => {
+            if spend_type == SpendType::P2shP2wpkh {
+                // Checks whether the public key belongs to the address.
+                let redeem_script = p2sh_p2wpkh_redeem_script(script_pubkey, &public_key)?;
+                to_sign.input[0].script_sig = Builder::new()
+                    .push_slice(push_bytes(redeem_script.to_bytes())?)
+                    .into_script();
+            } else if Some(script_pubkey.clone()) != p2wpkh_script(&public_key) {
+                return Err(Error::from(Proto::Error::Error_message_address_mismatch));
+            }
+
+            let sig =
+                bitcoin::ecdsa::Signature::sighash_all(secp.sign_ecdsa(&sighash, private_key));
+            to_sign.input[0].witness = Witness::from_slice(&[sig.to_vec(), public_key.to_bytes()]);
+        },
+        SpendType::P2tr => {
+            let keypair = KeyPair::from_secret_key(&secp, private_key);
+            let (internal_key, _) = keypair.x_only_public_key();
+            if *script_pubkey != ScriptBuf::new_v1_p2tr(&secp, internal_key, None) {
+                return Err(Error::from(Proto::Error::Error_message_address_mismatch));
+            }
+
+            let tweaked = keypair.tap_tweak(&secp, None).to_inner();
+            let sig = if dangerous_use_fixed_schnorr_rng {
+                secp.sign_schnorr_no_aux_rand(&sighash, &tweaked)
+            } else {
+                secp.sign_schnorr(&sighash, &tweaked)
+            };
+
+            let sig = bitcoin::taproot::Signature {
+                sig,
+                hash_ty: TapSighashType::Default,
+            };
+            to_sign.input[0].witness = Witness::from_slice(&[sig.to_vec()]);
+        },
+    }
+
+    Ok(to_sign)
+}
+
+/// Verifies that the signed `to_sign` transaction spends `to_spend`.
+pub fn verify(to_spend: &Transaction, to_sign: &Transaction) -> Result<bool> {
+    let expected = self::to_sign(to_spend);
+
+    // The transaction must spend `to_spend` and must not move any funds.
+    if to_sign.input.len() != 1
+        || to_sign.input[0].previous_output != expected.input[0].previous_output
+        || to_sign.output != expected.output
+    {
+        return Ok(false);
+    }
+
+    let secp = Secp256k1::verification_only();
+    let script_pubkey = &to_spend.output[0].script_pubkey;
+    let script_sig = &to_sign.input[0].script_sig;
+    let witness = &to_sign.input[0].witness;
+
+    match SpendType::from_script_pubkey(script_pubkey)? {
+        SpendType::P2pkh => {
+            let pushes = script_sig
+                .instructions()
+                .map(|ins| match ins {
+                    Ok(Instruction::PushBytes(push)) => Ok(push.as_bytes().to_vec()),
+                    _ => Err(Error::from(Proto::Error::Error_invalid_message_signature)),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            if pushes.len() != 2 || !witness.is_empty() {
+                return Ok(false);
+            }
+
+            let sig = bitcoin::ecdsa::Signature::from_slice(&pushes[0])?;
+            let public_key = PublicKey::from_slice(&pushes[1])?;
+            if *script_pubkey != ScriptBuf::new_p2pkh(&public_key.pubkey_hash()) {
+                return Ok(false);
+            }
+
+            let sighash = legacy_sighash(to_sign, script_pubkey, sig.hash_ty)?;
+            Ok(sig.hash_ty == EcdsaSighashType::All
+                && secp
+                    .verify_ecdsa(&sighash, &sig.sig, &public_key.inner)
+                    .is_ok())
+        },
+        spend_type @ (SpendType::P2shP2wpkh | SpendType::P2wpkh) => {
+            if witness.len() != 2 {
+                return Ok(false);
+            }
+
+            let sig = bitcoin::ecdsa::Signature::from_slice(&witness[0])?;
+            let public_key = PublicKey::from_slice(&witness[1])?;
+
+            let witness_program = if spend_type == SpendType::P2shP2wpkh {
+                let Ok(redeem_script) = p2sh_p2wpkh_redeem_script(script_pubkey, &public_key)
+                else {
+                    return Ok(false);
+                };
+
+                let expected_script_sig = Builder::new()
+                    .push_slice(push_bytes(redeem_script.to_bytes())?)
+                    .into_script();
+                if *script_sig != expected_script_sig {
+                    return Ok(false);
+                }
+                redeem_script
+            } else {
+                if Some(script_pubkey.clone()) != p2wpkh_script(&public_key)
+                    || !script_sig.is_empty()
+                {
+                    return Ok(false);
+                }
+                script_pubkey.clone()
+            };
+
+            let sighash = segwit_sighash(to_sign, &witness_program, sig.hash_ty)?;
+            Ok(sig.hash_ty == EcdsaSighashType::All
+                && secp
+                    .verify_ecdsa(&sighash, &sig.sig, &public_key.inner)
+                    .is_ok())
+        },
+        SpendType::P2tr => {
+            if witness.len() != 1 || !script_sig.is_empty() {
+                return Ok(false);
+            }
+
+            let sig = bitcoin::taproot::Signature::from_slice(&witness[0])?;
+            if !matches!(sig.hash_ty, TapSighashType::Default | TapSighashType::All) {
+                return Ok(false);
+            }
+
+            let output_key = XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..])
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_taproot_tweaked_pubkey))?;
+
+            let sighash = taproot_sighash(to_spend, to_sign, sig.hash_ty)?;
+            Ok(secp.verify_schnorr(&sig.sig, &sighash, &output_key).is_ok())
+        },
+    }
+}
+
+fn legacy_sighash(
+    to_sign: &Transaction,
+    script_pubkey: &Script,
+    sighash_type: EcdsaSighashType,
+) -> Result<Message> {
+    let sighash = SighashCache::new(to_sign)
+        .legacy_signature_hash(0, script_pubkey, sighash_type.to_u32())
+        .map_err(|_| Error::from(Proto::Error::Error_utxo_sighash_failed))?;
+
+    Message::from_slice(sighash.as_ref())
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash))
+}
+
+fn segwit_sighash(
+    to_sign: &Transaction,
+    witness_program: &ScriptBuf,
+    sighash_type: EcdsaSighashType,
+) -> Result<Message> {
+    let script_code = witness_program
+        .p2wpkh_script_code()
+        .ok_or_else(|| Error::from(Proto::Error::Error_invalid_wpkh_script_code))?;
+
+    let sighash = SighashCache::new(to_sign)
+        .segwit_signature_hash(0, &script_code, 0, sighash_type)
+        .map_err(|_| Error::from(Proto::Error::Error_utxo_sighash_failed))?;
+
+    Message::from_slice(sighash.as_ref())
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash))
+}
+
+fn taproot_sighash(
+    to_spend: &Transaction,
+    to_sign: &Transaction,
+    sighash_type: TapSighashType,
+) -> Result<Message> {
+    let sighash = SighashCache::new(to_sign)
+        .taproot_key_spend_signature_hash(0, &Prevouts::All(&to_spend.output), sighash_type)
+        .map_err(|_| Error::from(Proto::Error::Error_utxo_sighash_failed))?;
+
+    Message::from_slice(sighash.as_ref())
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash))
+}
+
+fn p2wpkh_script(public_key: &PublicKey) -> Option<ScriptBuf> {
+    public_key
+        .wpubkey_hash()
+        .map(|wpkh| ScriptBuf::new_v0_p2wpkh(&wpkh))
+}
+
+/// Returns the P2WPKH redeem script if the public key belongs to the P2SH address.
+fn p2sh_p2wpkh_redeem_script(script_pubkey: &Script, public_key: &PublicKey) -> Result<ScriptBuf> {
+    let redeem_script = p2wpkh_script(public_key)
+        .ok_or_else(|| Error::from(Proto::Error::Error_invalid_public_key))?;
+
+    if *script_pubkey != ScriptBuf::new_p2sh(&redeem_script.script_hash()) {
+        return Err(Error::from(Proto::Error::Error_message_address_mismatch));
+    }
+
+    Ok(redeem_script)
+}
+
+fn push_bytes(bytes: Vec<u8>) -> Result<PushBytesBuf> {
+    PushBytesBuf::try_from(bytes)
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_redeem_script))
+}
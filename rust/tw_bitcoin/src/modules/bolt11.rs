@@ -0,0 +1,554 @@
+use bitcoin::address::{Payload, WitnessProgram, WitnessVersion};
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::{Address, Network, PubkeyHash, ScriptHash};
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, PublicKey, Secp256k1};
+use std::fmt::Display;
+use tw_encoding::bech32;
+use tw_proto::Lightning::Proto;
+
+const INVOICE_URI_PREFIX: &str = "lightning:";
+const DEFAULT_EXPIRY: u64 = 3600;
+const DEFAULT_MIN_FINAL_CLTV_EXPIRY_DELTA: u64 = 18;
+const MSAT_PER_BTC: u64 = 100_000_000_000;
+
+// The sizes of fixed-size fields, in 5-bit words.
+const TIMESTAMP_WORDS: usize = 7;
+const SIGNATURE_WORDS: usize = 104;
+const HASH_WORDS: usize = 52;
+const PUBKEY_WORDS: usize = 53;
+
+// Node public key (33) + short channel ID (8) + base fee (4) + proportional
+// fee (4) + CLTV expiry delta (2).
+const ROUTING_HOP_SIZE: usize = 51;
+
+// The tagged field types, as specified by BOLT-11.
+const TAG_PAYMENT_HASH: u8 = 1;
+const TAG_ROUTE_HINT: u8 = 3;
+const TAG_FEATURES: u8 = 5;
+const TAG_EXPIRY: u8 = 6;
+const TAG_FALLBACK: u8 = 9;
+const TAG_DESCRIPTION: u8 = 13;
+const TAG_PAYMENT_SECRET: u8 = 16;
+const TAG_PAYEE: u8 = 19;
+const TAG_DESCRIPTION_HASH: u8 = 23;
+const TAG_MIN_FINAL_CLTV_EXPIRY_DELTA: u8 = 24;
+const TAG_METADATA: u8 = 27;
+
+pub type InvoiceResult<T> = std::result::Result<T, InvoiceError>;
+
+#[derive(Debug)]
+pub struct InvoiceError(pub Proto::Error);
+
+impl Display for InvoiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl From<Proto::Error> for InvoiceError {
+    fn from(value: Proto::Error) -> Self {
+        InvoiceError(value)
+    }
+}
+
+/// A hop of a private route to the payee.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutingHop {
+    pub public_key: PublicKey,
+    pub short_channel_id: u64,
+    pub fee_base_msat: u32,
+    pub fee_proportional_millionths: u32,
+    pub cltv_expiry_delta: u16,
+}
+
+/// An on-chain fallback address. The address is `None` if the program is
+/// not valid for the given version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackAddress {
+    pub version: u8,
+    pub program: Vec<u8>,
+    pub address: Option<Address>,
+}
+
+/// A decoded BOLT-11 payment request with a verified signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bolt11Invoice {
+    pub currency: String,
+    pub network: Network,
+    pub amount_msat: Option<u64>,
+    pub timestamp: u64,
+    pub payment_hash: [u8; 32],
+    pub payment_secret: Option<[u8; 32]>,
+    pub description: Option<String>,
+    pub description_hash: Option<[u8; 32]>,
+    pub expiry: u64,
+    pub min_final_cltv_expiry_delta: u64,
+    pub fallbacks: Vec<FallbackAddress>,
+    pub route_hints: Vec<Vec<RoutingHop>>,
+    pub features: Vec<u32>,
+    pub payee: PublicKey,
+    pub metadata: Option<Vec<u8>>,
+    pub signature: [u8; 65],
+}
+
+/// The tagged fields of an invoice. Fields with an unexpected length are
+/// skipped, as required by BOLT-11.
+#[derive(Default)]
+struct TaggedFields {
+    payment_hash: Option<[u8; 32]>,
+    payment_secret: Option<[u8; 32]>,
+    description: Option<String>,
+    description_hash: Option<[u8; 32]>,
+    expiry: Option<u64>,
+    min_final_cltv_expiry_delta: Option<u64>,
+    fallbacks: Vec<FallbackAddress>,
+    route_hints: Vec<Vec<RoutingHop>>,
+    features: Vec<u32>,
+    payee: Option<PublicKey>,
+    metadata: Option<Vec<u8>>,
+}
+
+impl Bolt11Invoice {
+    /// Decodes the invoice and verifies its signature. If the invoice does
+    /// not contain the payee public key, it is recovered from the signature.
+    pub fn decode(invoice: &str) -> InvoiceResult<Self> {
+        let invoice = invoice.trim();
+        let invoice = match invoice.get(..INVOICE_URI_PREFIX.len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(INVOICE_URI_PREFIX) => {
+                &invoice[INVOICE_URI_PREFIX.len()..]
+            },
+            _ => invoice,
+        };
+
+        let decoded = bech32::decode_words(invoice)
+            .map_err(|_| InvoiceError::from(Proto::Error::Error_invalid_encoding))?;
+        let (currency, network, amount_msat) = parse_hrp(&decoded.hrp)?;
+
+        let words = decoded.bytes;
+        if words.len() < TIMESTAMP_WORDS + SIGNATURE_WORDS {
+            return Err(InvoiceError::from(Proto::Error::Error_invalid_tagged_field));
+        }
+        let (data, signature_words) = words.split_at(words.len() - SIGNATURE_WORDS);
+
+        let timestamp =
+            words_to_int(&data[..TIMESTAMP_WORDS]).expect("timestamp is shorter than 64 bits");
+        let fields = TaggedFields::parse(&data[TIMESTAMP_WORDS..], network)?;
+
+        let payment_hash = fields
+            .payment_hash
+            .ok_or_else(|| InvoiceError::from(Proto::Error::Error_missing_payment_hash))?;
+        if fields.description.is_none() && fields.description_hash.is_none() {
+            return Err(InvoiceError::from(Proto::Error::Error_missing_description));
+        }
+
+        // The signature commits to the human-readable part and the data part
+        // (without the signature), padded with zeros to a byte boundary.
+        let mut preimage = decoded.hrp.as_bytes().to_vec();
+        preimage.extend(words_to_bytes(data, true).expect("padding is always valid"));
+        let signature: [u8; 65] = words_to_bytes(signature_words, false)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| InvoiceError::from(Proto::Error::Error_invalid_signature))?;
+        let payee = verify_signature(&preimage, &signature, fields.payee)?;
+
+        Ok(Bolt11Invoice {
+            currency,
+            network,
+            amount_msat,
+            timestamp,
+            payment_hash,
+            payment_secret: fields.payment_secret,
+            description: fields.description,
+            description_hash: fields.description_hash,
+            expiry: fields.expiry.unwrap_or(DEFAULT_EXPIRY),
+            min_final_cltv_expiry_delta: fields
+                .min_final_cltv_expiry_delta
+                .unwrap_or(DEFAULT_MIN_FINAL_CLTV_EXPIRY_DELTA),
+            fallbacks: fields.fallbacks,
+            route_hints: fields.route_hints,
+            features: fields.features,
+            payee,
+            metadata: fields.metadata,
+            signature,
+        })
+    }
+
+    /// The UNIX timestamp at which the invoice expires.
+    pub fn expires_at(&self) -> u64 {
+        self.timestamp.saturating_add(self.expiry)
+    }
+
+    pub fn decode_proto(
+        proto: Proto::DecodingInput<'_>,
+    ) -> InvoiceResult<Proto::DecodingOutput<'static>> {
+        let invoice = Self::decode(&proto.invoice)?;
+
+        let fallbacks = invoice
+            .fallbacks
+            .iter()
+            .map(|fallback| Proto::FallbackAddress {
+                version: fallback.version as u32,
+                program: fallback.program.clone().into(),
+                address: fallback
+                    .address
+                    .as_ref()
+                    .map(Address::to_string)
+                    .unwrap_or_default()
+                    .into(),
+            })
+            .collect();
+
+        let route_hints = invoice
+            .route_hints
+            .iter()
+            .map(|hops| Proto::RouteHint {
+                hops: hops
+                    .iter()
+                    .map(|hop| Proto::RoutingHop {
+                        public_key: hop.public_key.serialize().to_vec().into(),
+                        short_channel_id: hop.short_channel_id,
+                        fee_base_msat: hop.fee_base_msat,
+                        fee_proportional_millionths: hop.fee_proportional_millionths,
+                        cltv_expiry_delta: hop.cltv_expiry_delta as u32,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Proto::DecodingOutput {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            currency: invoice.currency.clone().into(),
+            amount_msat: invoice.amount_msat.unwrap_or_default(),
+            timestamp: invoice.timestamp,
+            payment_hash: invoice.payment_hash.to_vec().into(),
+            payment_secret: invoice
+                .payment_secret
+                .map(|secret| secret.to_vec())
+                .unwrap_or_default()
+                .into(),
+            description: invoice.description.clone().unwrap_or_default().into(),
+            description_hash: invoice
+                .description_hash
+                .map(|hash| hash.to_vec())
+                .unwrap_or_default()
+                .into(),
+            expiry: invoice.expiry,
+            expires_at: invoice.expires_at(),
+            expired: proto.now != 0 && proto.now >= invoice.expires_at(),
+            min_final_cltv_expiry_delta: invoice.min_final_cltv_expiry_delta,
+            fallbacks,
+            route_hints,
+            features: invoice.features.clone(),
+            payee_public_key: invoice.payee.serialize().to_vec().into(),
+            metadata: invoice.metadata.clone().unwrap_or_default().into(),
+            signature: invoice.signature.to_vec().into(),
+        })
+    }
+}
+
+impl TaggedFields {
+    fn parse(mut words: &[u8], network: Network) -> InvoiceResult<Self> {
+        let mut fields = TaggedFields::default();
+
+        while !words.is_empty() {
+            // Type (5 bits) + data length (10 bits) + data.
+            if words.len() < 3 {
+                return Err(InvoiceError::from(Proto::Error::Error_invalid_tagged_field));
+            }
+            let len = words[1] as usize * 32 + words[2] as usize;
+            let value = words
+                .get(3..3 + len)
+                .ok_or_else(|| InvoiceError::from(Proto::Error::Error_invalid_tagged_field))?;
+
+            fields.apply(words[0], value, network)?;
+            words = &words[3 + len..];
+        }
+
+        Ok(fields)
+    }
+
+    fn apply(&mut self, tag: u8, value: &[u8], network: Network) -> InvoiceResult<()> {
+        let invalid_field = || InvoiceError::from(Proto::Error::Error_invalid_tagged_field);
+
+        match tag {
+            TAG_PAYMENT_HASH if self.payment_hash.is_none() => {
+                self.payment_hash = words_to_hash(value);
+            },
+            TAG_PAYMENT_SECRET if self.payment_secret.is_none() => {
+                self.payment_secret = words_to_hash(value);
+            },
+            TAG_DESCRIPTION_HASH if self.description_hash.is_none() => {
+                self.description_hash = words_to_hash(value);
+            },
+            TAG_DESCRIPTION if self.description.is_none() => {
+                let bytes = words_to_bytes(value, false).ok_or_else(invalid_field)?;
+                self.description = Some(String::from_utf8(bytes).map_err(|_| invalid_field())?);
+            },
+            TAG_PAYEE if self.payee.is_none() && value.len() == PUBKEY_WORDS => {
+                self.payee = words_to_bytes(value, false)
+                    .and_then(|bytes| PublicKey::from_slice(&bytes).ok());
+            },
+            TAG_EXPIRY if self.expiry.is_none() => {
+                self.expiry = Some(words_to_int(value).ok_or_else(invalid_field)?);
+            },
+            TAG_MIN_FINAL_CLTV_EXPIRY_DELTA if self.min_final_cltv_expiry_delta.is_none() => {
+                self.min_final_cltv_expiry_delta =
+                    Some(words_to_int(value).ok_or_else(invalid_field)?);
+            },
+            TAG_FALLBACK if !value.is_empty() => {
+                if let Some(program) = words_to_bytes(&value[1..], false) {
+                    let version = value[0];
+                    self.fallbacks.push(FallbackAddress {
+                        version,
+                        address: fallback_address(version, &program, network),
+                        program,
+                    });
+                }
+            },
+            TAG_ROUTE_HINT => {
+                let bytes = words_to_bytes(value, false).ok_or_else(invalid_field)?;
+                if bytes.is_empty() || bytes.len() % ROUTING_HOP_SIZE != 0 {
+                    return Err(invalid_field());
+                }
+
+                let hops = bytes
+                    .chunks(ROUTING_HOP_SIZE)
+                    .map(parse_routing_hop)
+                    .collect::<InvoiceResult<Vec<_>>>()?;
+                self.route_hints.push(hops);
+            },
+            TAG_FEATURES if self.features.is_empty() => {
+                // Big-endian bit field, the last word contains bits 0-4.
+                for (index, word) in value.iter().rev().enumerate() {
+                    for bit in 0..5 {
+                        if word >> bit & 1 == 1 {
+                            self.features.push((index * 5 + bit) as u32);
+                        }
+                    }
+                }
+            },
+            TAG_METADATA if self.metadata.is_none() => {
+                self.metadata = Some(words_to_bytes(value, false).ok_or_else(invalid_field)?);
+            },
+            // Unknown or duplicate fields are skipped.
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the human-readable part, `ln` + currency prefix + optional amount.
+fn parse_hrp(hrp: &str) -> InvoiceResult<(String, Network, Option<u64>)> {
+    let invalid_hrp = || InvoiceError::from(Proto::Error::Error_invalid_hrp);
+
+    let rest = hrp.strip_prefix("ln").ok_or_else(invalid_hrp)?;
+
+    // `tbs` and `bcrt` must be matched before `tb` and `bc`.
+    let (currency, network) = [
+        ("bcrt", Network::Regtest),
+        ("bc", Network::Bitcoin),
+        ("tbs", Network::Signet),
+        ("tb", Network::Testnet),
+    ]
+    .into_iter()
+    .find(|(prefix, _)| rest.starts_with(prefix))
+    .ok_or_else(invalid_hrp)?;
+
+    let amount = &rest[currency.len()..];
+    let amount_msat = if amount.is_empty() {
+        None
+    } else {
+        Some(parse_amount(amount)?)
+    };
+
+    Ok((currency.to_string(), network, amount_msat))
+}
+
+/// Parses the amount in bitcoin with an optional multiplier into
+/// millisatoshis.
+fn parse_amount(amount: &str) -> InvoiceResult<u64> {
+    let invalid_amount = || InvoiceError::from(Proto::Error::Error_invalid_amount);
+
+    let (digits, multiplier) = match amount.as_bytes()[amount.len() - 1] {
+        multiplier @ (b'm' | b'u' | b'n' | b'p') => (&amount[..amount.len() - 1], Some(multiplier)),
+        _ => (amount, None),
+    };
+
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(invalid_amount());
+    }
+    let value: u64 = digits.parse().map_err(|_| invalid_amount())?;
+
+    let amount_msat = match multiplier {
+        None => value.checked_mul(MSAT_PER_BTC),
+        Some(b'm') => value.checked_mul(MSAT_PER_BTC / 1_000),
+        Some(b'u') => value.checked_mul(MSAT_PER_BTC / 1_000_000),
+        Some(b'n') => value.checked_mul(MSAT_PER_BTC / 1_000_000_000),
+        // A pico-bitcoin is a tenth of a millisatoshi.
+        Some(_) => (value % 10 == 0).then_some(value / 10),
+    };
+
+    amount_msat.ok_or_else(invalid_amount)
+}
+
+/// Verifies the signature against the payee public key, if given, or
+/// recovers the public key from the signature.
+fn verify_signature(
+    preimage: &[u8],
+    signature: &[u8; 65],
+    payee: Option<PublicKey>,
+) -> InvoiceResult<PublicKey> {
+    let invalid_signature = || InvoiceError::from(Proto::Error::Error_invalid_signature);
+
+    let message = Message::from_slice(sha256::Hash::hash(preimage).as_byte_array())
+        .expect("sha256 is 32 bytes");
+    let recovery_id =
+        RecoveryId::from_i32(signature[64] as i32).map_err(|_| invalid_signature())?;
+    let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id)
+        .map_err(|_| invalid_signature())?;
+
+    let secp = Secp256k1::verification_only();
+    match payee {
+        Some(payee) => {
+            secp.verify_ecdsa(&message, &signature.to_standard(), &payee)
+                .map_err(|_| invalid_signature())?;
+            Ok(payee)
+        },
+        None => secp
+            .recover_ecdsa(&message, &signature)
+            .map_err(|_| invalid_signature()),
+    }
+}
+
+fn parse_routing_hop(bytes: &[u8]) -> InvoiceResult<RoutingHop> {
+    let public_key = PublicKey::from_slice(&bytes[..33])
+        .map_err(|_| InvoiceError::from(Proto::Error::Error_invalid_tagged_field))?;
+    let be_u32 =
+        |start: usize| u32::from_be_bytes(bytes[start..start + 4].try_into().expect("4 bytes"));
+
+    Ok(RoutingHop {
+        public_key,
+        short_channel_id: u64::from_be_bytes(bytes[33..41].try_into().expect("8 bytes")),
+        fee_base_msat: be_u32(41),
+        fee_proportional_millionths: be_u32(45),
+        cltv_expiry_delta: u16::from_be_bytes([bytes[49], bytes[50]]),
+    })
+}
+
+fn fallback_address(version: u8, program: &[u8], network: Network) -> Option<Address> {
+    let payload = match version {
+        17 => Payload::PubkeyHash(PubkeyHash::from_slice(program).ok()?),
+        18 => Payload::ScriptHash(ScriptHash::from_slice(program).ok()?),
+        _ => {
+            let version = WitnessVersion::try_from(version).ok()?;
+            Payload::WitnessProgram(WitnessProgram::new(version, program.to_vec()).ok()?)
+        },
+    };
+
+    Some(Address::new(network, payload))
+}
+
+/// Converts 5-bit words to a 32-byte hash, `None` if the field has an
+/// unexpected length.
+fn words_to_hash(words: &[u8]) -> Option<[u8; 32]> {
+    if words.len() != HASH_WORDS {
+        return None;
+    }
+    words_to_bytes(words, false)?.try_into().ok()
+}
+
+/// Converts 5-bit words to a big-endian integer of at most 60 bits.
+fn words_to_int(words: &[u8]) -> Option<u64> {
+    if words.len() > 12 {
+        return None;
+    }
+    Some(words.iter().fold(0, |acc, word| acc << 5 | *word as u64))
+}
+
+/// Converts 5-bit words to bytes. If `pad` is set, the remaining bits are
+/// padded with zeros, otherwise they must be zero and fewer than 5.
+fn words_to_bytes(words: &[u8], pad: bool) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(words.len() * 5 / 8 + 1);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+
+    for word in words {
+        acc = acc << 5 | *word as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            bytes.push((acc << (8 - bits)) as u8);
+        }
+    } else if bits >= 5 || acc != 0 {
+        return None;
+    }
+
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hrp_amounts() {
+        let parse = |hrp: &str| parse_hrp(hrp).map_err(|err| err.0);
+
+        assert_eq!(parse("lnbc"), Ok(("bc".into(), Network::Bitcoin, None)));
+        assert_eq!(
+            parse("lnbc2500u"),
+            Ok(("bc".into(), Network::Bitcoin, Some(250_000_000)))
+        );
+        assert_eq!(
+            parse("lntbs1m"),
+            Ok(("tbs".into(), Network::Signet, Some(100_000_000)))
+        );
+        assert_eq!(
+            parse("lnbcrt10n"),
+            Ok(("bcrt".into(), Network::Regtest, Some(1_000)))
+        );
+        assert_eq!(
+            parse("lntb10p"),
+            Ok(("tb".into(), Network::Testnet, Some(1)))
+        );
+        assert_eq!(
+            parse("lnbc1"),
+            Ok(("bc".into(), Network::Bitcoin, Some(MSAT_PER_BTC)))
+        );
+
+        // Sub-millisatoshi amount.
+        assert_eq!(parse("lnbc15p"), Err(Proto::Error::Error_invalid_amount));
+        assert_eq!(parse("lnbcm"), Err(Proto::Error::Error_invalid_amount));
+        assert_eq!(parse("lnbc1x"), Err(Proto::Error::Error_invalid_amount));
+        assert_eq!(
+            parse("lnbc184467440737"),
+            Err(Proto::Error::Error_invalid_amount)
+        );
+        assert_eq!(parse("lnxy1m"), Err(Proto::Error::Error_invalid_hrp));
+        assert_eq!(parse("bc1m"), Err(Proto::Error::Error_invalid_hrp));
+    }
+
+    #[test]
+    fn convert_words() {
+        // 52 words (260 bits) of which the last 4 bits are padding.
+        assert_eq!(words_to_bytes(&[0; 52], false), Some(vec![0; 32]));
+        assert_eq!(words_to_hash(&[0; 51]), None);
+
+        let mut words = [0; 52];
+        words[51] = 1;
+        assert_eq!(words_to_bytes(&words, false), None);
+        assert_eq!(words_to_bytes(&[31, 16], true), Some(vec![0xfc, 0]));
+
+        assert_eq!(words_to_int(&[1, 0]), Some(32));
+        assert_eq!(words_to_int(&[1; 13]), None);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{Error, Result};
+use bitcoin::bech32::convert_bits;
+use bitcoin::hashes::Hash;
+use bitcoin::{PubkeyHash, ScriptBuf, ScriptHash};
+use tw_proto::BitcoinV2::Proto;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const CHECKSUM_LEN: usize = 8;
+
+/// The version byte of a P2PKH address with a 160-bit hash.
+const P2PKH_VERSION: u8 = 0x00;
+/// The version byte of a P2SH address with a 160-bit hash.
+const P2SH_VERSION: u8 = 0x08;
+
+/// The CashAddr prefixes of Bitcoin Cash and eCash (mainnet, testnet and
+/// regtest), which the coin context specifies as HRP.
+const PREFIXES: [&str; 6] = [
+    "bitcoincash",
+    "bchtest",
+    "bchreg",
+    "ecash",
+    "ectest",
+    "ecregtest",
+];
+
+/// Whether the HRP is a CashAddr prefix rather than a Segwit HRP.
+pub fn is_cash_addr_prefix(hrp: &str) -> bool {
+    PREFIXES.contains(&hrp)
+}
+
+/// A CashAddr address, as used by Bitcoin Cash.
+///
+/// See <https://github.com/bitcoincashorg/bitcoincash.org/blob/master/spec/cashaddr.md>
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CashAddress {
+    PubkeyHash(PubkeyHash),
+    ScriptHash(ScriptHash),
+}
+
+impl CashAddress {
+    /// Decodes the address, which may omit the prefix.
+    pub fn decode(address: &str, prefix: &str) -> Result<Self> {
+        let invalid = || Error::from(Proto::Error::Error_invalid_cash_address);
+
+        let has_lower = address.chars().any(|c| c.is_ascii_lowercase());
+        let has_upper = address.chars().any(|c| c.is_ascii_uppercase());
+        if has_lower && has_upper {
+            return Err(invalid());
+        }
+
+        let address = address.to_ascii_lowercase();
+        let payload = match address.split_once(':') {
+            Some((addr_prefix, payload)) if addr_prefix == prefix => payload,
+            Some(_) => return Err(invalid()),
+            None => address.as_str(),
+        };
+
+        let data = payload
+            .bytes()
+            .map(|c| {
+                CHARSET
+                    .iter()
+                    .position(|&x| x == c)
+                    .map(|pos| pos as u8)
+                    .ok_or_else(invalid)
+            })
+            .collect::<Result<Vec<u8>>>()?;
+
+        if data.len() <= CHECKSUM_LEN || polymod(prefix, &data) != 0 {
+            return Err(invalid());
+        }
+
+        let payload =
+            convert_bits(&data[..data.len() - CHECKSUM_LEN], 5, 8, false).map_err(|_| invalid())?;
+
+        let (version, hash) = payload.split_first().ok_or_else(invalid)?;
+        match *version {
+            P2PKH_VERSION => Ok(CashAddress::PubkeyHash(
+                PubkeyHash::from_slice(hash).map_err(|_| invalid())?,
+            )),
+            P2SH_VERSION => Ok(CashAddress::ScriptHash(
+                ScriptHash::from_slice(hash).map_err(|_| invalid())?,
+            )),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Encodes the address including the prefix.
+    pub fn encode(&self, prefix: &str) -> String {
+        let mut payload = vec![];
+        match self {
+            CashAddress::PubkeyHash(hash) => {
+                payload.push(P2PKH_VERSION);
+                payload.extend_from_slice(hash.as_byte_array());
+            },
+            CashAddress::ScriptHash(hash) => {
+                payload.push(P2SH_VERSION);
+                payload.extend_from_slice(hash.as_byte_array());
+            },
+        }
+
+        let mut data = convert_bits(&payload, 8, 5, true).expect("valid bits conversion");
+
+        let checksum = polymod(prefix, &[data.as_slice(), &[0; CHECKSUM_LEN]].concat());
+        for i in 0..CHECKSUM_LEN {
+            data.push(((checksum >> (5 * (CHECKSUM_LEN - 1 - i))) & 0x1f) as u8);
+        }
+
+        let payload: String = data.iter().map(|&d| CHARSET[d as usize] as char).collect();
+        format!("{prefix}:{payload}")
+    }
+
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        match self {
+            CashAddress::PubkeyHash(hash) => ScriptBuf::new_p2pkh(hash),
+            CashAddress::ScriptHash(hash) => ScriptBuf::new_p2sh(hash),
+        }
+    }
+}
+
+// Computes the checksum over the lower five bits of the prefix, a zero
+// separator and the payload.
+fn polymod(prefix: &str, data: &[u8]) -> u64 {
+    let values = prefix
+        .bytes()
+        .map(|c| c & 0x1f)
+        .chain(std::iter::once(0))
+        .chain(data.iter().copied());
+
+    let mut c: u64 = 1;
+    for d in values {
+        let c0 = (c >> 35) as u8;
+        c = ((c & 0x07_ffff_ffff) << 5) ^ u64::from(d);
+
+        if c0 & 0x01 != 0 {
+            c ^= 0x98_f2bc_8e61;
+        }
+        if c0 & 0x02 != 0 {
+            c ^= 0x79_b76d_99e2;
+        }
+        if c0 & 0x04 != 0 {
+            c ^= 0xf3_3e5f_b3c4;
+        }
+        if c0 & 0x08 != 0 {
+            c ^= 0xae_2eab_e2a8;
+        }
+        if c0 & 0x10 != 0 {
+            c ^= 0x1e_4f43_e470;
+        }
+    }
+
+    c ^ 1
+}
@@ -0,0 +1,264 @@
+use crate::aliases::*;
+use crate::modules::cash_addr::{is_cash_addr_prefix, CashAddress};
+use crate::modules::network::BitcoinPrefix;
+use crate::{Error, Result};
+use bitcoin::address::{WitnessProgram, WitnessVersion};
+use bitcoin::bech32::{self, FromBase32, ToBase32, Variant};
+use bitcoin::hashes::Hash;
+use bitcoin::script::PushBytesBuf;
+use bitcoin::sighash::EcdsaSighashType;
+use bitcoin::{PubkeyHash, Script, ScriptBuf, ScriptHash};
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::prefix::BitcoinBase58Prefix;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+use tw_utxo::compiler::{Compiler, StandardBitcoinContext};
+use tw_utxo::forks::bitcoin_cash::{BitcoinCashContext, SIGHASH_FORKID};
+use tw_utxo::forks::sighash_algorithm;
+use tw_utxo::forks::zcash::ZcashContext;
+
+type SighashAlgorithm = UtxoProto::mod_Chain::SighashAlgorithm;
+
+/// Returns the transaction format of the chain, Bitcoin by default.
+pub fn utxo_chain(proto: &Proto::SigningInput<'_>) -> Option<UtxoProto::Chain> {
+    proto
+        .chain_info
+        .as_ref()
+        .and_then(|info| info.utxo_chain.clone())
+}
+
+/// Computes the sighashes with the compiler of the chain.
+pub fn preimage_hashes(proto: UtxoProto::SigningInput<'_>) -> UtxoProto::PreSigningOutput<'static> {
+    match sighash_algorithm(&proto.chain) {
+        SighashAlgorithm::Bitcoin => Compiler::<StandardBitcoinContext>::preimage_hashes(proto),
+        SighashAlgorithm::ForkId => Compiler::<BitcoinCashContext>::preimage_hashes(proto),
+        SighashAlgorithm::ZcashSapling | SighashAlgorithm::ZcashNu5 => {
+            Compiler::<ZcashContext>::preimage_hashes(proto)
+        },
+    }
+}
+
+/// Compiles the transaction with the compiler of the chain.
+pub fn compile(
+    proto: UtxoProto::PreSerialization<'_>,
+) -> UtxoProto::SerializedTransaction<'static> {
+    match sighash_algorithm(&proto.chain) {
+        SighashAlgorithm::Bitcoin => Compiler::<StandardBitcoinContext>::compile(proto),
+        SighashAlgorithm::ForkId => Compiler::<BitcoinCashContext>::compile(proto),
+        SighashAlgorithm::ZcashSapling | SighashAlgorithm::ZcashNu5 => {
+            Compiler::<ZcashContext>::compile(proto)
+        },
+    }
+}
+
+/// Whether the signatures of the chain must carry the `SIGHASH_FORKID` flag
+/// (e.g. Bitcoin Cash).
+pub fn uses_fork_id(proto: &Proto::SigningInput<'_>) -> bool {
+    utxo_chain(proto).map_or(false, |chain| {
+        chain.sighash_algorithm == SighashAlgorithm::ForkId
+    })
+}
+
+/// Validates an ECDSA signature with the sighash type appended. Unlike
+/// `bitcoin::ecdsa::Signature`, this accepts the `SIGHASH_FORKID` flag of
+/// Bitcoin Cash signatures, and adds the flag if `fork_id` is set, so that
+/// signatures created from the sighashes of `preimage_hashes` can be passed
+/// on to `compile` as is.
+pub fn legacy_signature(signature: &[u8], fork_id: bool) -> Result<PushBytesBuf> {
+    let invalid = || Error::from(Proto::Error::Error_invalid_ecdsa_signature);
+
+    let (sighash_type, sig) = signature.split_last().ok_or_else(invalid)?;
+    EcdsaSighashType::from_standard(u32::from(*sighash_type) & !SIGHASH_FORKID)
+        .map_err(|_| invalid())?;
+    secp256k1::ecdsa::Signature::from_der(sig).map_err(|_| invalid())?;
+
+    let mut signature = signature.to_vec();
+    if fork_id {
+        if let Some(sighash_type) = signature.last_mut() {
+            *sighash_type |= SIGHASH_FORKID as u8;
+        }
+    }
+
+    PushBytesBuf::try_from(signature).map_err(|_| invalid())
+}
+
+/// Replaces the addresses of the outputs (including the change output) with
+/// the scriptPubkeys, based on the address formats of the chain. Bitcoin
+/// addresses are handled by the output builder itself.
+pub fn resolve_addresses(mut proto: Proto::SigningInput<'_>) -> Result<Proto::SigningInput<'_>> {
+    let Some(info) = proto.chain_info.clone() else {
+        return Ok(proto);
+    };
+
+    for output in proto.outputs.iter_mut().chain(proto.change_output.as_mut()) {
+        if let ProtoOutputRecipient::from_address(addr) = &output.to_recipient {
+            let script_pubkey = script_pubkey_from_address(&info, addr.as_ref())?;
+            output.to_recipient =
+                ProtoOutputRecipient::custom_script_pubkey(script_pubkey.to_bytes().into());
+        }
+    }
+
+    Ok(proto)
+}
+
+/// Derives the scriptPubkey from a CashAddr, Segwit or base58 address of the
+/// chain.
+pub fn script_pubkey_from_address(info: &Proto::ChainInfo<'_>, address: &str) -> Result<ScriptBuf> {
+    if !info.cash_addr_prefix.is_empty() {
+        if let Ok(cash_addr) = CashAddress::decode(address, info.cash_addr_prefix.as_ref()) {
+            return Ok(cash_addr.script_pubkey());
+        }
+    }
+
+    if !info.hrp.is_empty() {
+        if let Some(script_pubkey) = segwit_script_pubkey(info.hrp.as_ref(), address) {
+            return Ok(script_pubkey);
+        }
+    }
+
+    let payload = bitcoin::base58::decode_check(address)
+        .map_err(|_| Error::from(Proto::Error::Error_bad_address_recipient))?;
+
+    let p2pkh_prefix = info.p2pkh_prefix.as_ref();
+    let p2sh_prefix = info.p2sh_prefix.as_ref();
+
+    if !p2pkh_prefix.is_empty() && payload.starts_with(p2pkh_prefix) {
+        if let Ok(hash) = PubkeyHash::from_slice(&payload[p2pkh_prefix.len()..]) {
+            return Ok(ScriptBuf::new_p2pkh(&hash));
+        }
+    }
+
+    if !p2sh_prefix.is_empty() && payload.starts_with(p2sh_prefix) {
+        if let Ok(hash) = ScriptHash::from_slice(&payload[p2sh_prefix.len()..]) {
+            return Ok(ScriptBuf::new_p2sh(&hash));
+        }
+    }
+
+    Err(Error::from(Proto::Error::Error_bad_address_recipient))
+}
+
+/// Encodes the scriptPubkey as CashAddr, Segwit or base58 address of the
+/// chain, the inverse of [`script_pubkey_from_address`].
+pub fn address_from_script_pubkey(
+    info: &Proto::ChainInfo<'_>,
+    script_pubkey: &Script,
+) -> Result<String> {
+    let unsupported = || Error::from(Proto::Error::Error_unsupported_address_recipient);
+    let bytes = script_pubkey.as_bytes();
+
+    if script_pubkey.is_p2pkh() {
+        let hash = PubkeyHash::from_slice(&bytes[3..23]).map_err(|_| unsupported())?;
+        if !info.cash_addr_prefix.is_empty() {
+            return Ok(CashAddress::PubkeyHash(hash).encode(info.cash_addr_prefix.as_ref()));
+        }
+        return base58_address(info.p2pkh_prefix.as_ref(), hash.as_byte_array())
+            .ok_or_else(unsupported);
+    }
+
+    if script_pubkey.is_p2sh() {
+        let hash = ScriptHash::from_slice(&bytes[2..22]).map_err(|_| unsupported())?;
+        if !info.cash_addr_prefix.is_empty() {
+            return Ok(CashAddress::ScriptHash(hash).encode(info.cash_addr_prefix.as_ref()));
+        }
+        return base58_address(info.p2sh_prefix.as_ref(), hash.as_byte_array())
+            .ok_or_else(unsupported);
+    }
+
+    match script_pubkey.witness_version() {
+        Some(version) if !info.hrp.is_empty() => {
+            // Version 0 uses bech32, later versions use bech32m.
+            let variant = match version {
+                WitnessVersion::V0 => Variant::Bech32,
+                _ => Variant::Bech32m,
+            };
+
+            let mut data = vec![bech32::u5::try_from_u8(version.to_num()).expect("valid version")];
+            data.extend(bytes[2..].to_vec().to_base32());
+
+            bech32::encode(info.hrp.as_ref(), data, variant).map_err(|_| unsupported())
+        },
+        _ => Err(unsupported()),
+    }
+}
+
+/// Returns the address formats of a Bitcoin fork (e.g. Litecoin or Bitcoin
+/// Cash) described by the coin context. The address prefix, if given,
+/// replaces the HRP or the base58 version bytes of the context.
+pub fn chain_info_from_coin(
+    coin: &dyn CoinContext,
+    prefix: Option<BitcoinPrefix>,
+) -> Proto::ChainInfo<'static> {
+    let mut hrp = coin.hrp().unwrap_or_default();
+    let mut base58 = match (coin.p2pkh_prefix(), coin.p2sh_prefix()) {
+        (Some(p2pkh), Some(p2sh)) => Some(BitcoinBase58Prefix { p2pkh, p2sh }),
+        _ => None,
+    };
+
+    match prefix {
+        Some(BitcoinPrefix::Hrp(prefix)) => hrp = prefix,
+        Some(BitcoinPrefix::Base58(prefix)) => base58 = Some(prefix),
+        None => (),
+    }
+
+    // Zcash transparent addresses have two version bytes.
+    let version_bytes = |version: u8| -> Vec<u8> {
+        coin.static_prefix()
+            .into_iter()
+            .chain(Some(version))
+            .collect()
+    };
+
+    // Bitcoin Cash and eCash specify the CashAddr prefix as HRP.
+    let (hrp, cash_addr_prefix) = if is_cash_addr_prefix(&hrp) {
+        (String::new(), hrp)
+    } else {
+        (hrp, String::new())
+    };
+
+    Proto::ChainInfo {
+        p2pkh_prefix: base58
+            .map(|base58| version_bytes(base58.p2pkh))
+            .unwrap_or_default()
+            .into(),
+        p2sh_prefix: base58
+            .map(|base58| version_bytes(base58.p2sh))
+            .unwrap_or_default()
+            .into(),
+        hrp: hrp.into(),
+        cash_addr_prefix: cash_addr_prefix.into(),
+        utxo_chain: None,
+    }
+}
+
+// Encodes a base58 address, unless the chain has no version bytes for it.
+fn base58_address(prefix: &[u8], hash: &[u8]) -> Option<String> {
+    if prefix.is_empty() {
+        return None;
+    }
+    Some(bitcoin::base58::encode_check(&[prefix, hash].concat()))
+}
+
+// Decodes a Segwit address with the given HRP (BIP-173 and BIP-350).
+fn segwit_script_pubkey(hrp: &str, address: &str) -> Option<ScriptBuf> {
+    let (addr_hrp, data, variant) = bech32::decode(address).ok()?;
+    if addr_hrp != hrp {
+        return None;
+    }
+
+    let (version, program) = data.split_first()?;
+    let version = WitnessVersion::try_from(*version).ok()?;
+
+    // Version 0 uses bech32, later versions use bech32m.
+    let expected_variant = match version {
+        WitnessVersion::V0 => Variant::Bech32,
+        _ => Variant::Bech32m,
+    };
+    if variant != expected_variant {
+        return None;
+    }
+
+    let program = Vec::<u8>::from_base32(program).ok()?;
+    let program = WitnessProgram::new(version, program).ok()?;
+
+    Some(ScriptBuf::new_witness_program(&program))
+}
@@ -0,0 +1,242 @@
+use crate::modules::transactions::{InscriptionEnvelope, Runestone};
+use crate::{Error, Result};
+use bitcoin::blockdata::locktime::absolute::LockTime;
+use bitcoin::consensus::Decodable;
+use bitcoin::hashes::Hash;
+use bitcoin::{Address, Network, Script, Transaction};
+use std::borrow::Cow;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoDecodedInput = Proto::mod_DecodingOutput::DecodedInput<'static>;
+type ProtoDecodedOutput = Proto::mod_DecodingOutput::DecodedOutput<'static>;
+type ProtoDecodedInscription = Proto::mod_DecodingOutput::DecodedInscription<'static>;
+
+pub struct Decoder;
+
+impl Decoder {
+    /// Decodes a raw transaction and analyzes its inputs and outputs.
+    pub fn decode_proto(proto: Proto::DecodingInput<'_>) -> Result<Proto::DecodingOutput<'static>> {
+        let network = network_from_proto(proto.network);
+
+        let tx = Transaction::consensus_decode(&mut proto.encoded.as_ref())
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_transaction_encoding))?;
+
+        // If prevouts are provided, there must be one for each input.
+        if !proto.prevouts.is_empty() && proto.prevouts.len() != tx.input.len() {
+            return Err(Error::from(Proto::Error::Error_unmatched_prevout_count));
+        }
+
+        let inputs = tx
+            .input
+            .iter()
+            .enumerate()
+            .map(|(index, txin)| {
+                let (script_type, address, value) = match proto.prevouts.get(index) {
+                    Some(prevout) => {
+                        let script = Script::from_bytes(prevout.script_pubkey.as_ref());
+                        let (script_type, address) = classify_script(script, network);
+                        (script_type, address, prevout.value)
+                    },
+                    None => (Proto::ScriptType::Unknown, String::new(), 0),
+                };
+
+                // Inscriptions can only be revealed in a Taproot script-path spend.
+                let inscriptions = if txin.witness.len() >= 2 {
+                    txin.witness
+                        .tapscript()
+                        .map(InscriptionEnvelope::parse_envelopes)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|envelope| ProtoDecodedInscription {
+                            mime_type: String::from_utf8_lossy(&envelope.mime_type)
+                                .into_owned()
+                                .into(),
+                            payload: envelope.payload.into(),
+                        })
+                        .collect()
+                } else {
+                    vec![]
+                };
+
+                ProtoDecodedInput {
+                    script_type,
+                    address: address.into(),
+                    value,
+                    inscriptions,
+                }
+            })
+            .collect();
+
+        let outputs = tx
+            .output
+            .iter()
+            .map(|txout| {
+                let (script_type, address) = classify_script(&txout.script_pubkey, network);
+                let op_return_data = if let Proto::ScriptType::OpReturn = script_type {
+                    op_return_data(&txout.script_pubkey)
+                } else {
+                    vec![]
+                };
+
+                ProtoDecodedOutput {
+                    script_type,
+                    address: address.into(),
+                    op_return_data: op_return_data.into(),
+                }
+            })
+            .collect();
+
+        // The fee can only be calculated if the spent outputs are known.
+        let vsize = tx.vsize() as u64;
+        let (fee, fee_rate) = if proto.prevouts.is_empty() {
+            (0, 0.0)
+        } else {
+            let total_input_amount =
+                checked_sum(proto.prevouts.iter().map(|prevout| prevout.value))?;
+            let total_output_amount = checked_sum(tx.output.iter().map(|txout| txout.value))?;
+
+            let fee = total_input_amount
+                .checked_sub(total_output_amount)
+                .ok_or_else(|| Error::from(Proto::Error::Error_utxo_insufficient_inputs))?;
+
+            (fee, fee as f64 / vsize as f64)
+        };
+
+        // Only the first `OP_RETURN OP_13` output is interpreted as runestone.
+        let runestone = Runestone::decipher(&tx).map(|runestone| runestone.to_proto());
+
+        // The transaction identifiers, which we represent in
+        // non-reversed/non-network order.
+        let txid: Vec<u8> = tx.txid().as_byte_array().iter().copied().rev().collect();
+        let wtxid: Vec<u8> = tx.wtxid().as_byte_array().iter().copied().rev().collect();
+
+        Ok(Proto::DecodingOutput {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            transaction: Some(transaction_to_proto(&tx)),
+            txid: txid.into(),
+            wtxid: wtxid.into(),
+            size: tx.size() as u64,
+            vsize,
+            weight: tx.weight().to_wu(),
+            rbf_signaling: tx.is_explicitly_rbf(),
+            inputs,
+            outputs,
+            fee,
+            fee_rate,
+            runestone,
+        })
+    }
+}
+
+/// Converts the `BitcoinV2.proto` network to the `bitcoin` crate native type.
+pub(crate) fn network_from_proto(network: Proto::Network) -> Network {
+    match network {
+        Proto::Network::Bitcoin => Network::Bitcoin,
+        Proto::Network::Testnet => Network::Testnet,
+        Proto::Network::Signet => Network::Signet,
+        Proto::Network::Regtest => Network::Regtest,
+    }
+}
+
+/// Identifies the type of the given spending condition and renders the
+/// corresponding address, if there is one.
+pub fn classify_script(script: &Script, network: Network) -> (Proto::ScriptType, String) {
+    let script_type = if script.is_p2pkh() {
+        Proto::ScriptType::P2PKH
+    } else if script.is_p2sh() {
+        Proto::ScriptType::P2SH
+    } else if script.is_v0_p2wpkh() {
+        Proto::ScriptType::P2WPKH
+    } else if script.is_v0_p2wsh() {
+        Proto::ScriptType::P2WSH
+    } else if script.is_v1_p2tr() {
+        Proto::ScriptType::P2TR
+    } else if script.is_op_return() {
+        Proto::ScriptType::OpReturn
+    } else if script.is_p2pk() {
+        Proto::ScriptType::P2PK
+    } else {
+        Proto::ScriptType::Unknown
+    };
+
+    // P2PK and data carrier outputs do not have an address representation.
+    let address = match script_type {
+        Proto::ScriptType::Unknown | Proto::ScriptType::P2PK | Proto::ScriptType::OpReturn => {
+            String::new()
+        },
+        _ => Address::from_script(script, network)
+            .map(|addr| addr.to_string())
+            .unwrap_or_default(),
+    };
+
+    (script_type, address)
+}
+
+// Concatenates all the data pushed after `OP_RETURN`.
+fn op_return_data(script: &Script) -> Vec<u8> {
+    let mut data = vec![];
+    for instruction in script.instructions().skip(1) {
+        match instruction {
+            Ok(bitcoin::script::Instruction::PushBytes(push)) => {
+                data.extend_from_slice(push.as_bytes())
+            },
+            Ok(bitcoin::script::Instruction::Op(_)) => continue,
+            Err(_) => break,
+        }
+    }
+
+    data
+}
+
+// Converts the `bitcoin` crate native transaction to `BitcoinV2.proto`.
+fn transaction_to_proto(tx: &Transaction) -> Proto::Transaction<'static> {
+    let lock_time = match tx.lock_time {
+        LockTime::Blocks(height) if height.to_consensus_u32() == 0 => None,
+        LockTime::Blocks(height) => Some(UtxoProto::LockTime {
+            variant: UtxoProto::mod_LockTime::OneOfvariant::blocks(height.to_consensus_u32()),
+        }),
+        LockTime::Seconds(time) => Some(UtxoProto::LockTime {
+            variant: UtxoProto::mod_LockTime::OneOfvariant::seconds(time.to_consensus_u32()),
+        }),
+    };
+
+    let inputs = tx
+        .input
+        .iter()
+        .map(|txin| Proto::TransactionInput {
+            txid: txin.previous_output.txid.as_byte_array().to_vec().into(),
+            vout: txin.previous_output.vout,
+            sequence: txin.sequence.to_consensus_u32(),
+            script_sig: txin.script_sig.to_bytes().into(),
+            witness_items: txin.witness.to_vec().into_iter().map(Cow::Owned).collect(),
+        })
+        .collect();
+
+    let outputs = tx
+        .output
+        .iter()
+        .map(|txout| Proto::TransactionOutput {
+            script_pubkey: txout.script_pubkey.to_bytes().into(),
+            value: txout.value,
+            ..Default::default()
+        })
+        .collect();
+
+    Proto::Transaction {
+        version: tx.version,
+        lock_time,
+        inputs,
+        outputs,
+    }
+}
+
+/// Sums up the amounts. The amounts are not trusted, so an overflow is
+/// reported as an invalid transaction rather than wrapped.
+fn checked_sum(amounts: impl IntoIterator<Item = u64>) -> Result<u64> {
+    amounts
+        .into_iter()
+        .try_fold(0_u64, |total, amount| total.checked_add(amount))
+        .ok_or_else(|| Error::from(Proto::Error::Error_invalid_transaction_encoding))
+}
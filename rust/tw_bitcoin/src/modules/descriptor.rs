@@ -0,0 +1,308 @@
+use crate::{Error, Result};
+use bitcoin::address::WitnessVersion;
+use bitcoin::blockdata::locktime::absolute::LockTime;
+use bitcoin::hashes::{hash160, ripemd160, sha256, sha256d, Hash};
+use bitcoin::key::XOnlyPublicKey;
+use bitcoin::script::{Instruction, PushBytesBuf};
+use bitcoin::taproot::{ControlBlock, LeafVersion, TapLeafHash};
+use bitcoin::{Address, Network, ScriptBuf, Sequence};
+use miniscript::descriptor::{DefiniteDescriptorKey, DescriptorPublicKey};
+use miniscript::{Descriptor, MiniscriptKey, Preimage32, Satisfier, ToPublicKey};
+use std::collections::HashMap;
+use std::str::FromStr;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+use tw_utxo::forks::bitcoin_cash::SIGHASH_FORKID;
+
+/// An output descriptor (BIP-380 family, including Miniscript) derived at a
+/// specific index.
+pub struct WalletDescriptor {
+    descriptor: Descriptor<DefiniteDescriptorKey>,
+}
+
+impl WalletDescriptor {
+    /// Parses the descriptor and derives it at the given index. The index is
+    /// ignored if the descriptor is not ranged (does not contain `*`).
+    pub fn new(descriptor: &str, index: u32) -> Result<Self> {
+        let descriptor = Descriptor::<DescriptorPublicKey>::from_str(descriptor)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_descriptor))?
+            .at_derivation_index(index)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_descriptor))?;
+
+        Ok(WalletDescriptor { descriptor })
+    }
+
+    /// The spending condition of the output.
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        self.descriptor.script_pubkey()
+    }
+
+    /// The address of the output. Fails for descriptors without an address
+    /// representation, such as `bare(...)`.
+    pub fn address(&self, network: Network) -> Result<Address> {
+        self.descriptor
+            .address(network)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_descriptor))
+    }
+
+    /// The segwit version of the output, `None` for legacy outputs.
+    pub fn witness_version(&self) -> Option<WitnessVersion> {
+        self.descriptor.desc_type().segwit_version()
+    }
+
+    /// The script that is committed to by the sighash (the _scriptCode_).
+    /// Not applicable for Taproot outputs.
+    pub fn script_code(&self) -> Result<ScriptBuf> {
+        self.descriptor
+            .script_code()
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_descriptor))
+    }
+
+    /// The maximum weight of the _scriptSig_ and _Witness_ required to satisfy
+    /// the descriptor, assuming worst-case signature sizes. Used for fee
+    /// estimation.
+    pub fn max_weight_to_satisfy(&self) -> Result<u64> {
+        self.descriptor
+            .max_weight_to_satisfy()
+            .map(|weight| weight as u64)
+            .map_err(|_| Error::from(Proto::Error::Error_descriptor_unsatisfiable))
+    }
+
+    /// Returns the leaf hash and the control block of the given Taproot leaf
+    /// script. Fails if the descriptor is not a Taproot descriptor or does
+    /// not contain the leaf.
+    pub fn taproot_leaf(&self, leaf_script: &ScriptBuf) -> Result<(TapLeafHash, ControlBlock)> {
+        let Descriptor::Tr(ref tr) = self.descriptor else {
+            return Err(Error::from(
+                Proto::Error::Error_invalid_descriptor_leaf_script,
+            ));
+        };
+
+        let is_known_leaf = tr.iter_scripts().any(|(_, ms)| &ms.encode() == leaf_script);
+        if !is_known_leaf {
+            return Err(Error::from(
+                Proto::Error::Error_invalid_descriptor_leaf_script,
+            ));
+        }
+
+        let control_block = tr
+            .spend_info()
+            .control_block(&(leaf_script.clone(), LeafVersion::TapScript))
+            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_descriptor_leaf_script))?;
+
+        let leaf_hash = TapLeafHash::from_script(leaf_script, LeafVersion::TapScript);
+
+        Ok((leaf_hash, control_block))
+    }
+
+    /// Whether the descriptor is a Taproot descriptor with a script tree.
+    pub fn has_taproot_tree(&self) -> bool {
+        matches!(self.descriptor, Descriptor::Tr(ref tr) if tr.taptree().is_some())
+    }
+
+    /// Creates the _scriptSig_ and _Witness_ that satisfy the descriptor with
+    /// the available signatures and preimages.
+    pub fn satisfy(&self, satisfier: &DescriptorSatisfier) -> Result<(ScriptBuf, Vec<Vec<u8>>)> {
+        let (witness, script_sig) = self
+            .descriptor
+            .get_satisfaction(satisfier)
+            .map_err(|_| Error::from(Proto::Error::Error_descriptor_unsatisfiable))?;
+
+        if satisfier.fork_id_sigs.is_empty() {
+            return Ok((script_sig, witness));
+        }
+
+        satisfier.restore_fork_id_sigs(script_sig, witness)
+    }
+}
+
+/// Collects the signatures, preimages and timelock information used to
+/// satisfy a descriptor.
+#[derive(Default)]
+pub struct DescriptorSatisfier {
+    ecdsa_sigs: HashMap<bitcoin::PublicKey, bitcoin::ecdsa::Signature>,
+    // Signatures with non-standard sighash types (`SIGHASH_FORKID`), by their
+    // standard serialization.
+    fork_id_sigs: HashMap<Vec<u8>, Vec<u8>>,
+    schnorr_sigs: HashMap<XOnlyPublicKey, bitcoin::taproot::Signature>,
+    key_spend_sig: Option<bitcoin::taproot::Signature>,
+    leaf_hash: Option<TapLeafHash>,
+    preimages: Vec<Preimage32>,
+    sequence: Option<Sequence>,
+    lock_time: Option<LockTime>,
+}
+
+impl DescriptorSatisfier {
+    /// Restricts Schnorr script-path signatures to the given leaf.
+    pub fn with_leaf_hash(mut self, leaf_hash: TapLeafHash) -> Self {
+        self.leaf_hash = Some(leaf_hash);
+        self
+    }
+
+    /// The sequence of the spending input, required to satisfy `older(n)`.
+    pub fn with_sequence(mut self, sequence: Sequence) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    /// The lock time of the spending transaction, required to satisfy `after(n)`.
+    pub fn with_lock_time(mut self, lock_time: LockTime) -> Self {
+        self.lock_time = Some(lock_time);
+        self
+    }
+
+    /// Adds an ECDSA signature (legacy and Segwit) with the sighash type
+    /// appended. Miniscript only knows the standard sighash types, so the
+    /// `SIGHASH_FORKID` flag is restored after the satisfaction.
+    pub fn add_ecdsa_sig(&mut self, pubkey: bitcoin::PublicKey, sig: PushBytesBuf) -> Result<()> {
+        let mut standard = sig.as_bytes().to_vec();
+        if let Some(sighash_type) = standard.last_mut() {
+            *sighash_type &= !(SIGHASH_FORKID as u8);
+        }
+
+        let ecdsa_sig = bitcoin::ecdsa::Signature::from_slice(&standard)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_ecdsa_signature))?;
+        if standard != sig.as_bytes() {
+            self.fork_id_sigs.insert(standard, sig.as_bytes().to_vec());
+        }
+
+        self.ecdsa_sigs.insert(pubkey, ecdsa_sig);
+        Ok(())
+    }
+
+    /// Adds a Schnorr signature for a Taproot script-path spend.
+    pub fn add_schnorr_sig(&mut self, pubkey: XOnlyPublicKey, sig: bitcoin::taproot::Signature) {
+        self.schnorr_sigs.insert(pubkey, sig);
+    }
+
+    /// Sets the Schnorr signature for a Taproot key-path spend.
+    pub fn set_key_spend_sig(&mut self, sig: bitcoin::taproot::Signature) {
+        self.key_spend_sig = Some(sig);
+    }
+
+    /// Adds a 32-byte preimage, which is matched against all supported hash
+    /// functions.
+    pub fn add_preimage(&mut self, preimage: &[u8]) -> Result<()> {
+        let preimage: Preimage32 = preimage
+            .try_into()
+            .map_err(|_| Error::from(Proto::Error::Error_descriptor_unsatisfiable))?;
+        self.preimages.push(preimage);
+        Ok(())
+    }
+
+    // Replaces the standard serialization of the signatures with the
+    // `SIGHASH_FORKID` flag in the satisfaction.
+    fn restore_fork_id_sigs(
+        &self,
+        script_sig: ScriptBuf,
+        mut witness: Vec<Vec<u8>>,
+    ) -> Result<(ScriptBuf, Vec<Vec<u8>>)> {
+        let unsatisfiable = || Error::from(Proto::Error::Error_descriptor_unsatisfiable);
+
+        for item in witness.iter_mut() {
+            if let Some(sig) = self.fork_id_sigs.get(item) {
+                item.clone_from(sig);
+            }
+        }
+
+        let mut builder = ScriptBuf::builder();
+        for instruction in script_sig.instructions() {
+            builder = match instruction.map_err(|_| unsatisfiable())? {
+                Instruction::PushBytes(bytes) => match self.fork_id_sigs.get(bytes.as_bytes()) {
+                    Some(sig) => builder.push_slice(
+                        PushBytesBuf::try_from(sig.clone()).map_err(|_| unsatisfiable())?,
+                    ),
+                    None => builder.push_slice(bytes),
+                },
+                Instruction::Op(opcode) => builder.push_opcode(opcode),
+            };
+        }
+
+        Ok((builder.into_script(), witness))
+    }
+
+    fn find_preimage<F: Fn(&Preimage32) -> bool>(&self, matches_hash: F) -> Option<Preimage32> {
+        self.preimages.iter().find(|p| matches_hash(p)).copied()
+    }
+}
+
+impl<Pk: MiniscriptKey + ToPublicKey> Satisfier<Pk> for DescriptorSatisfier {
+    fn lookup_ecdsa_sig(&self, pk: &Pk) -> Option<bitcoin::ecdsa::Signature> {
+        self.ecdsa_sigs.get(&pk.to_public_key()).copied()
+    }
+
+    fn lookup_tap_key_spend_sig(&self) -> Option<bitcoin::taproot::Signature> {
+        self.key_spend_sig
+    }
+
+    fn lookup_tap_leaf_script_sig(
+        &self,
+        pk: &Pk,
+        leaf_hash: &TapLeafHash,
+    ) -> Option<bitcoin::taproot::Signature> {
+        // Signatures commit to a specific leaf, so only that leaf can be satisfied.
+        if self.leaf_hash.as_ref() != Some(leaf_hash) {
+            return None;
+        }
+
+        self.schnorr_sigs.get(&pk.to_x_only_pubkey()).copied()
+    }
+
+    fn lookup_sha256(&self, hash: &Pk::Sha256) -> Option<Preimage32> {
+        let hash = Pk::to_sha256(hash);
+        self.find_preimage(|p| sha256::Hash::hash(p) == hash)
+    }
+
+    fn lookup_hash256(&self, hash: &Pk::Hash256) -> Option<Preimage32> {
+        let hash = Pk::to_hash256(hash);
+        self.find_preimage(|p| sha256d::Hash::hash(p).to_byte_array() == hash.to_byte_array())
+    }
+
+    fn lookup_ripemd160(&self, hash: &Pk::Ripemd160) -> Option<Preimage32> {
+        let hash = Pk::to_ripemd160(hash);
+        self.find_preimage(|p| ripemd160::Hash::hash(p) == hash)
+    }
+
+    fn lookup_hash160(&self, hash: &Pk::Hash160) -> Option<Preimage32> {
+        let hash = Pk::to_hash160(hash);
+        self.find_preimage(|p| hash160::Hash::hash(p) == hash)
+    }
+
+    fn check_older(&self, n: Sequence) -> bool {
+        self.sequence.map_or(false, |sequence| {
+            <Sequence as Satisfier<Pk>>::check_older(&sequence, n)
+        })
+    }
+
+    fn check_after(&self, n: LockTime) -> bool {
+        self.lock_time.map_or(false, |lock_time| {
+            <LockTime as Satisfier<Pk>>::check_after(&lock_time, n)
+        })
+    }
+}
+
+/// Parses a public key of a descriptor signature, either in compressed or in
+/// x-only representation.
+pub(crate) fn x_only_pubkey_from_slice(pubkey: &[u8]) -> Result<XOnlyPublicKey> {
+    if pubkey.len() == 32 {
+        return XOnlyPublicKey::from_slice(pubkey)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_public_key));
+    }
+
+    Ok(XOnlyPublicKey::from(
+        bitcoin::PublicKey::from_slice(pubkey)?.inner,
+    ))
+}
+
+/// Converts the `Utxo.proto` lock time to the `bitcoin` crate native type.
+pub(crate) fn lock_time_from_proto(proto: &Option<UtxoProto::LockTime>) -> Result<LockTime> {
+    use UtxoProto::mod_LockTime::OneOfvariant as ProtoLockTimeVariant;
+
+    let lock_time = match proto.as_ref().map(|lock_time| &lock_time.variant) {
+        Some(ProtoLockTimeVariant::blocks(blocks)) => LockTime::from_height(*blocks),
+        Some(ProtoLockTimeVariant::seconds(seconds)) => LockTime::from_time(*seconds),
+        Some(ProtoLockTimeVariant::None) | None => LockTime::from_height(0),
+    };
+
+    lock_time.map_err(|_| Error::from(Proto::Error::Error_utxo_invalid_lock_time))
+}
@@ -0,0 +1,383 @@
+use crate::aliases::*;
+use crate::entry::BitcoinEntry;
+use crate::modules::multisig::MultisigScript;
+use crate::{Error, Result};
+use bitcoin::VarInt;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoEstimationTransaction<'a> = Proto::mod_FeeEstimationInput::OneOftransaction<'a>;
+type ProtoInputType = Proto::mod_FeeEstimationInput::InputType;
+type ProtoOutputType = Proto::mod_FeeEstimationInput::OutputType;
+type ProtoMultisigType = Proto::mod_Multisig::MultisigType;
+
+// Worst-case ECDSA signature (DER encoded, high R) including the sighash type.
+const ECDSA_SIG_SIZE: u64 = 72 + 1;
+// Schnorr signature including a non-default sighash type.
+const SCHNORR_SIG_SIZE: u64 = 64 + 1;
+// Compressed public key.
+const PUBKEY_SIZE: u64 = 33;
+// Txid + vout + sequence.
+const OUTPOINT_AND_SEQUENCE_SIZE: u64 = 32 + 4 + 4;
+// Version + lock time.
+const VERSION_AND_LOCK_TIME_SIZE: u64 = 4 + 4;
+// Segwit marker and flag, not scaled.
+const SEGWIT_MARKER_WEIGHT: u64 = 2;
+// Taproot control block without the merkle path.
+const CONTROL_BLOCK_BASE_SIZE: u64 = 33;
+const TAPROOT_NODE_SIZE: u64 = 32;
+
+/// The spending condition of an input, which determines the size of its
+/// _scriptSig_ and _Witness_.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputType {
+    P2PK,
+    P2PKH {
+        pubkey_len: u64,
+    },
+    P2SHP2WPKH,
+    P2WPKH {
+        pubkey_len: u64,
+    },
+    P2TRKeyPath,
+    /// Taproot script-path spend of a leaf that is satisfied by a single
+    /// signature.
+    P2TRScriptPath {
+        script_len: u64,
+        control_block_len: u64,
+    },
+    Multisig {
+        multisig_type: ProtoMultisigType,
+        threshold: u64,
+        script_len: u64,
+    },
+    /// An input of which only the claim weight is known, such as a custom
+    /// script.
+    Estimate {
+        weight: u64,
+        segwit: bool,
+    },
+}
+
+impl InputType {
+    pub fn from_proto(proto: &ProtoInputType) -> Result<Self> {
+        let input_type = match proto.script_type {
+            Proto::ScriptType::P2PK => InputType::P2PK,
+            Proto::ScriptType::P2PKH => InputType::P2PKH {
+                pubkey_len: PUBKEY_SIZE,
+            },
+            Proto::ScriptType::P2SH if proto.multisig_threshold == 0 => InputType::P2SHP2WPKH,
+            Proto::ScriptType::P2SH => Self::multisig(proto, ProtoMultisigType::P2SH)?,
+            Proto::ScriptType::P2WPKH => InputType::P2WPKH {
+                pubkey_len: PUBKEY_SIZE,
+            },
+            Proto::ScriptType::P2WSH => Self::multisig(proto, ProtoMultisigType::P2WSH)?,
+            Proto::ScriptType::P2TR if proto.tapscript_size == 0 => InputType::P2TRKeyPath,
+            Proto::ScriptType::P2TR => InputType::P2TRScriptPath {
+                script_len: proto.tapscript_size as u64,
+                control_block_len: CONTROL_BLOCK_BASE_SIZE
+                    + TAPROOT_NODE_SIZE * proto.tapscript_depth as u64,
+            },
+            Proto::ScriptType::Unknown | Proto::ScriptType::OpReturn => {
+                return Err(Error::from(Proto::Error::Error_unsupported_script_type))
+            },
+        };
+
+        Ok(input_type)
+    }
+
+    /// Derives the input type from the input builder. Inputs with a custom
+    /// spending condition fall back to the estimate of the builder.
+    pub fn from_signing_input(
+        input: &Proto::Input<'_>,
+        utxo_input: &UtxoProto::TxIn<'_>,
+    ) -> Result<Self> {
+        let estimate = InputType::Estimate {
+            weight: utxo_input.weight_estimate,
+            segwit: utxo_input.signing_method != UtxoProto::SigningMethod::Legacy,
+        };
+
+        let builder = match &input.to_recipient {
+            ProtoInputRecipient::builder(builder) => builder,
+            _ => return Ok(estimate),
+        };
+
+        let input_type = match &builder.variant {
+            ProtoInputBuilder::p2pkh(pubkey) => InputType::P2PKH {
+                pubkey_len: pubkey.len() as u64,
+            },
+            ProtoInputBuilder::p2wpkh(pubkey) => InputType::P2WPKH {
+                pubkey_len: pubkey.len() as u64,
+            },
+            ProtoInputBuilder::p2tr_key_path(_) | ProtoInputBuilder::p2tr_musig2(_) => {
+                InputType::P2TRKeyPath
+            },
+            ProtoInputBuilder::p2tr_script_path(complex) => InputType::P2TRScriptPath {
+                script_len: complex.payload.len() as u64,
+                control_block_len: complex.control_block.len() as u64,
+            },
+            ProtoInputBuilder::multisig(input_multisig) => {
+                let proto = input_multisig
+                    .multisig
+                    .as_ref()
+                    .ok_or_else(|| Error::from(Proto::Error::Error_invalid_multisig))?;
+                let multisig = MultisigScript::from_proto(proto)?;
+
+                InputType::Multisig {
+                    multisig_type: proto.multisig_type,
+                    threshold: proto.threshold as u64,
+                    script_len: multisig.multisig_script().len() as u64,
+                }
+            },
+            _ => estimate,
+        };
+
+        Ok(input_type)
+    }
+
+    fn multisig(proto: &ProtoInputType, multisig_type: ProtoMultisigType) -> Result<Self> {
+        let (threshold, keys) = (proto.multisig_threshold, proto.multisig_keys);
+        if threshold == 0 || threshold > keys || keys > 20 {
+            return Err(Error::from(Proto::Error::Error_invalid_multisig));
+        }
+
+        Ok(InputType::Multisig {
+            multisig_type,
+            threshold: threshold as u64,
+            // OP_M + n * (length + public key) + OP_N + OP_CHECKMULTISIG
+            script_len: 1 + keys as u64 * (1 + PUBKEY_SIZE) + 1 + 1,
+        })
+    }
+
+    /// Whether the input is spent with a _Witness_.
+    pub fn is_segwit(&self) -> bool {
+        match self {
+            InputType::P2PK | InputType::P2PKH { .. } => false,
+            InputType::Multisig { multisig_type, .. } => *multisig_type != ProtoMultisigType::P2SH,
+            InputType::Estimate { segwit, .. } => *segwit,
+            _ => true,
+        }
+    }
+
+    /// The worst-case size of the _scriptSig_, without its length prefix.
+    #[rustfmt::skip]
+    pub fn script_sig_size(&self) -> u64 {
+        match self {
+            // length + ECDSA signature
+            InputType::P2PK => 1 + ECDSA_SIG_SIZE,
+            // length + ECDSA signature + length + public key
+            InputType::P2PKH { pubkey_len } => 1 + ECDSA_SIG_SIZE + 1 + pubkey_len,
+            // length + P2WPKH redeem script
+            InputType::P2SHP2WPKH => 1 + 22,
+            InputType::Multisig { multisig_type: ProtoMultisigType::P2SH, threshold, script_len } => {
+                // OP_0 (`OP_CHECKMULTISIG` bug)
+                1 +
+                threshold * (1 + ECDSA_SIG_SIZE) +
+                push_size(*script_len) + script_len
+            },
+            // length + P2WSH redeem script
+            InputType::Multisig { multisig_type: ProtoMultisigType::P2SH_P2WSH, .. } => 1 + 34,
+            _ => 0,
+        }
+    }
+
+    /// The worst-case size of the _Witness_ including the item count, zero
+    /// if the input is not spent with a _Witness_.
+    #[rustfmt::skip]
+    pub fn witness_size(&self) -> u64 {
+        match self {
+            InputType::P2SHP2WPKH => witness_size(&[ECDSA_SIG_SIZE, PUBKEY_SIZE]),
+            InputType::P2WPKH { pubkey_len } => witness_size(&[ECDSA_SIG_SIZE, *pubkey_len]),
+            InputType::P2TRKeyPath => witness_size(&[SCHNORR_SIG_SIZE]),
+            InputType::P2TRScriptPath { script_len, control_block_len } => {
+                witness_size(&[SCHNORR_SIG_SIZE, *script_len, *control_block_len])
+            },
+            InputType::Multisig { multisig_type: ProtoMultisigType::P2SH, .. } => 0,
+            InputType::Multisig { threshold, script_len, .. } => {
+                // Empty item (`OP_CHECKMULTISIG` bug) + signatures + witness script
+                let mut items = vec![0];
+                items.extend(std::iter::repeat(ECDSA_SIG_SIZE).take(*threshold as usize));
+                items.push(*script_len);
+                witness_size(&items)
+            },
+            _ => 0,
+        }
+    }
+
+    /// The worst-case weight of the input. Non-segwit inputs of a segwit
+    /// transaction require an empty _Witness_ (one byte).
+    pub fn weight(&self, segwit_tx: bool) -> u64 {
+        if let InputType::Estimate { weight, segwit } = self {
+            // The estimate of the builder covers the claim, but not the
+            // length prefix of the scriptSig.
+            let empty_witness = (segwit_tx && !segwit) as u64;
+            return 4 * (OUTPOINT_AND_SEQUENCE_SIZE + 1) + weight + empty_witness;
+        }
+
+        let script_sig = self.script_sig_size();
+        let witness = match self.witness_size() {
+            0 if segwit_tx => 1,
+            witness => witness,
+        };
+
+        4 * (OUTPOINT_AND_SEQUENCE_SIZE + var_int_size(script_sig) + script_sig) + witness
+    }
+}
+
+/// The size of the _scriptPubkey_ of an output, without its length prefix.
+pub fn output_script_size(proto: &ProtoOutputType) -> Result<u64> {
+    let size = match proto.script_type {
+        Proto::ScriptType::P2PK => 1 + PUBKEY_SIZE + 1,
+        Proto::ScriptType::P2PKH => 25,
+        Proto::ScriptType::P2SH => 23,
+        Proto::ScriptType::P2WPKH => 22,
+        Proto::ScriptType::P2WSH | Proto::ScriptType::P2TR => 34,
+        Proto::ScriptType::OpReturn => {
+            let data_size = proto.data_size as u64;
+            1 + push_size(data_size) + data_size
+        },
+        Proto::ScriptType::Unknown => {
+            return Err(Error::from(Proto::Error::Error_unsupported_script_type))
+        },
+    };
+
+    Ok(size)
+}
+
+/// The size and fee of a transaction before it is signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub weight: u64,
+    pub vsize: u64,
+    pub fee: u64,
+}
+
+pub struct FeeEstimator;
+
+impl FeeEstimator {
+    pub fn estimate_proto(
+        coin: &dyn CoinContext,
+        proto: Proto::FeeEstimationInput<'_>,
+    ) -> Result<Proto::FeeEstimationOutput<'static>> {
+        let estimate = match proto.transaction {
+            ProtoEstimationTransaction::signing_input(signing) => {
+                Self::estimate_signing_input(coin, signing)?
+            },
+            ProtoEstimationTransaction::script_types(types) => {
+                let inputs = types
+                    .inputs
+                    .iter()
+                    .map(InputType::from_proto)
+                    .collect::<Result<Vec<_>>>()?;
+                let outputs = types
+                    .outputs
+                    .iter()
+                    .map(output_script_size)
+                    .collect::<Result<Vec<_>>>()?;
+
+                Self::estimate(&inputs, &outputs, types.fee_per_vb)
+            },
+            ProtoEstimationTransaction::None => {
+                return Err(Error::from(Proto::Error::Error_missing_transaction))
+            },
+        };
+
+        Ok(Proto::FeeEstimationOutput {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            weight: estimate.weight,
+            vsize: estimate.vsize,
+            fee: estimate.fee,
+        })
+    }
+
+    /// Estimates the planned transaction, after the input selection and the
+    /// change output have been applied.
+    pub fn estimate_signing_input(
+        coin: &dyn CoinContext,
+        proto: Proto::SigningInput<'_>,
+    ) -> Result<FeeEstimate> {
+        let fee_per_vb = proto.fee_per_vb;
+        let presigned = BitcoinEntry.preimage_hashes_impl(coin, proto.clone())?;
+
+        let inputs = presigned
+            .utxo_inputs
+            .iter()
+            .map(|utxo_input| {
+                let input = proto
+                    .inputs
+                    .iter()
+                    .find(|input| input.txid == utxo_input.txid && input.vout == utxo_input.vout)
+                    .ok_or_else(|| Error::from(Proto::Error::Error_utxo_invalid_txid))?;
+
+                InputType::from_signing_input(input, utxo_input)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let outputs = presigned
+            .utxo_outputs
+            .iter()
+            .map(|output| output.script_pubkey.len() as u64)
+            .collect::<Vec<_>>();
+
+        Ok(Self::estimate(&inputs, &outputs, fee_per_vb))
+    }
+
+    /// Estimates a transaction with the given inputs and the given output
+    /// _scriptPubkey_ sizes.
+    pub fn estimate(
+        inputs: &[InputType],
+        output_script_sizes: &[u64],
+        fee_per_vb: u64,
+    ) -> FeeEstimate {
+        let segwit = inputs.iter().any(InputType::is_segwit);
+
+        let base_size = VERSION_AND_LOCK_TIME_SIZE
+            + var_int_size(inputs.len() as u64)
+            + var_int_size(output_script_sizes.len() as u64);
+
+        let inputs_weight: u64 = inputs.iter().map(|input| input.weight(segwit)).sum();
+
+        let outputs_size: u64 = output_script_sizes
+            .iter()
+            // value + length + scriptPubkey
+            .map(|script_size| 8 + var_int_size(*script_size) + script_size)
+            .sum();
+
+        let weight = 4 * (base_size + outputs_size)
+            + inputs_weight
+            + if segwit { SEGWIT_MARKER_WEIGHT } else { 0 };
+
+        let vsize = (weight + 3) / 4;
+
+        FeeEstimate {
+            weight,
+            vsize,
+            fee: vsize * fee_per_vb,
+        }
+    }
+}
+
+fn var_int_size(len: u64) -> u64 {
+    VarInt(len).len() as u64
+}
+
+/// The size of the push opcode(s) of a script push of the given length.
+fn push_size(len: u64) -> u64 {
+    match len {
+        0..=75 => 1,
+        76..=0xff => 2,
+        0x100..=0xffff => 3,
+        _ => 5,
+    }
+}
+
+/// The size of the _Witness_ with the given item sizes.
+fn witness_size(items: &[u64]) -> u64 {
+    var_int_size(items.len() as u64)
+        + items
+            .iter()
+            .map(|item| var_int_size(*item) + item)
+            .sum::<u64>()
+}
@@ -90,6 +90,7 @@ pub fn taproot_build_and_sign_transaction(
         change_output: None,
         disable_change_output: true,
         dangerous_use_fixed_schnorr_rng: false,
+        chain_info: None,
     };
 
     // Build and sign the Bitcoin transaction.
@@ -244,5 +245,6 @@ fn input_from_legacy_utxo(
         sequence_enable_zero: false,
         sighash_type,
         to_recipient: ProtoInputRecipient::builder(input_builder),
+        protection: None,
     })
 }
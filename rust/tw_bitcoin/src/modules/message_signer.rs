@@ -0,0 +1,269 @@
+use crate::modules::bip322;
+use crate::{Error, Result};
+use bitcoin::address::AddressType;
+use bitcoin::consensus::{deserialize, serialize};
+use bitcoin::sign_message::signed_msg_hash;
+use bitcoin::{Address, PublicKey, ScriptBuf, Transaction, Witness};
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, Secp256k1, SecretKey};
+use std::borrow::Cow;
+use std::str::FromStr;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::modules::message_signer::MessageSigner;
+use tw_coin_entry::signing_output_error;
+use tw_encoding::base64;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoSignatureFormat = Proto::mod_MessageSigningInput::SignatureFormat;
+
+/// The length of a legacy (BIP-137) message signature.
+const LEGACY_SIGNATURE_LEN: usize = 65;
+
+/// Signs and verifies messages in the legacy "Bitcoin Signed Message" format
+/// (BIP-137) and the generic BIP-322 format.
+pub struct BitcoinMessageSigner;
+
+impl MessageSigner for BitcoinMessageSigner {
+    type MessageSigningInput<'a> = Proto::MessageSigningInput<'a>;
+    type MessagePreSigningOutput = Proto::PreSigningOutput<'static>;
+    type MessageSigningOutput = Proto::MessageSigningOutput<'static>;
+    type MessageVerifyingInput<'a> = Proto::MessageVerifyingInput<'a>;
+
+    fn message_preimage_hashes(
+        &self,
+        _coin: &dyn CoinContext,
+        input: Self::MessageSigningInput<'_>,
+    ) -> Self::MessagePreSigningOutput {
+        Self::message_preimage_hashes_impl(input)
+            .unwrap_or_else(|err| signing_output_error!(Proto::PreSigningOutput, err))
+    }
+
+    fn sign_message(
+        &self,
+        _coin: &dyn CoinContext,
+        input: Self::MessageSigningInput<'_>,
+    ) -> Self::MessageSigningOutput {
+        Self::sign_message_impl(input)
+            .unwrap_or_else(|err| signing_output_error!(Proto::MessageSigningOutput, err))
+    }
+
+    fn verify_message(
+        &self,
+        _coin: &dyn CoinContext,
+        input: Self::MessageVerifyingInput<'_>,
+    ) -> bool {
+        Self::verify_message_impl(input).unwrap_or_default()
+    }
+}
+
+impl BitcoinMessageSigner {
+    fn message_preimage_hashes_impl(
+        input: Proto::MessageSigningInput<'_>,
+    ) -> Result<Proto::PreSigningOutput<'static>> {
+        let address = parse_address(&input.address)?;
+
+        let (sighash, signing_method) = match input.signature_format {
+            ProtoSignatureFormat::LEGACY => (
+                Message::from(signed_msg_hash(&input.message)),
+                UtxoProto::SigningMethod::Legacy,
+            ),
+            ProtoSignatureFormat::BIP322_SIMPLE | ProtoSignatureFormat::BIP322_FULL => {
+                // The public key is only required for P2SH-P2WPKH addresses.
+                let public_key = if !input.private_key.is_empty() {
+                    Some(private_key_from_proto(&input)?.1)
+                } else if !input.public_key.is_empty() {
+                    Some(PublicKey::from_slice(input.public_key.as_ref())?)
+                } else {
+                    None
+                };
+
+                let to_spend = bip322::to_spend(&address.script_pubkey(), input.message.as_bytes());
+                let to_sign = bip322::to_sign(&to_spend);
+                bip322::signature_hash(&to_spend, &to_sign, public_key.as_ref())?
+            },
+        };
+
+        Ok(Proto::PreSigningOutput {
+            sighashes: vec![UtxoProto::Sighash {
+                sighash: sighash[..].to_vec().into(),
+                signing_method,
+                sighash_type: UtxoProto::SighashType::All,
+            }],
+            ..Default::default()
+        })
+    }
+
+    fn sign_message_impl(
+        input: Proto::MessageSigningInput<'_>,
+    ) -> Result<Proto::MessageSigningOutput<'static>> {
+        let address = parse_address(&input.address)?;
+        let (private_key, public_key) = private_key_from_proto(&input)?;
+
+        let signature = match input.signature_format {
+            ProtoSignatureFormat::LEGACY => {
+                sign_legacy(&private_key, &public_key, &address, &input.message)?
+            },
+            ProtoSignatureFormat::BIP322_SIMPLE | ProtoSignatureFormat::BIP322_FULL => {
+                let to_spend = bip322::to_spend(&address.script_pubkey(), input.message.as_bytes());
+                let to_sign = bip322::sign(
+                    &private_key,
+                    &to_spend,
+                    input.dangerous_use_fixed_schnorr_rng,
+                )?;
+
+                if input.signature_format == ProtoSignatureFormat::BIP322_FULL {
+                    serialize(&to_sign)
+                } else {
+                    // The simple format is only available if the scriptSig is empty.
+                    if !to_sign.input[0].script_sig.is_empty() {
+                        return Err(Error::from(
+                            Proto::Error::Error_unsupported_address_recipient,
+                        ));
+                    }
+                    serialize(&to_sign.input[0].witness)
+                }
+            },
+        };
+
+        let is_url = false;
+        Ok(Proto::MessageSigningOutput {
+            signature: Cow::from(base64::encode(&signature, is_url)),
+            ..Default::default()
+        })
+    }
+
+    fn verify_message_impl(input: Proto::MessageVerifyingInput<'_>) -> Result<bool> {
+        let address = parse_address(&input.address)?;
+
+        let is_url = false;
+        let signature = base64::decode(&input.signature, is_url)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_message_signature))?;
+
+        // A legacy signature has a fixed size, which never matches a BIP-322 signature.
+        if signature.len() == LEGACY_SIGNATURE_LEN {
+            return verify_legacy(&signature, &address, &input.message);
+        }
+
+        let to_spend = bip322::to_spend(&address.script_pubkey(), input.message.as_bytes());
+
+        // Either the witness stack (simple) or the whole `to_sign` transaction (full).
+        let to_sign = if let Ok(witness) = deserialize::<Witness>(&signature) {
+            let mut to_sign = bip322::to_sign(&to_spend);
+            to_sign.input[0].witness = witness;
+            to_sign
+        } else {
+            deserialize::<Transaction>(&signature)
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_message_signature))?
+        };
+
+        bip322::verify(&to_spend, &to_sign)
+    }
+}
+
+fn parse_address(address: &str) -> Result<Address> {
+    let address = Address::from_str(address)
+        .map_err(|_| Error::from(Proto::Error::Error_bad_address_recipient))?;
+
+    // The signature does not depend on the network.
+    Ok(address.assume_checked())
+}
+
+fn private_key_from_proto(
+    input: &Proto::MessageSigningInput<'_>,
+) -> Result<(SecretKey, PublicKey)> {
+    let private_key = SecretKey::from_slice(input.private_key.as_ref())
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_private_key))?;
+    let public_key = PublicKey::new(private_key.public_key(&Secp256k1::new()));
+
+    Ok((private_key, public_key))
+}
+
+/// Returns the BIP-137 header byte offset of the address type, for compressed
+/// public keys.
+fn legacy_header_offset(address_type: Option<AddressType>) -> Result<u8> {
+    match address_type {
+        Some(AddressType::P2pkh) => Ok(31),
+        Some(AddressType::P2sh) => Ok(35),
+        Some(AddressType::P2wpkh) => Ok(39),
+        _ => Err(Error::from(
+            Proto::Error::Error_unsupported_address_recipient,
+        )),
+    }
+}
+
+/// Returns the scriptPubkey of the address type for the public key. P2SH is
+/// assumed to be P2SH-P2WPKH.
+fn legacy_script_pubkey(
+    address_type: Option<AddressType>,
+    public_key: &PublicKey,
+) -> Result<ScriptBuf> {
+    let wpubkey_hash = || {
+        public_key
+            .wpubkey_hash()
+            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_public_key))
+    };
+
+    match address_type {
+        Some(AddressType::P2pkh) => Ok(ScriptBuf::new_p2pkh(&public_key.pubkey_hash())),
+        Some(AddressType::P2sh) => {
+            let redeem_script = ScriptBuf::new_v0_p2wpkh(&wpubkey_hash()?);
+            Ok(ScriptBuf::new_p2sh(&redeem_script.script_hash()))
+        },
+        Some(AddressType::P2wpkh) => Ok(ScriptBuf::new_v0_p2wpkh(&wpubkey_hash()?)),
+        _ => Err(Error::from(
+            Proto::Error::Error_unsupported_address_recipient,
+        )),
+    }
+}
+
+fn sign_legacy(
+    private_key: &SecretKey,
+    public_key: &PublicKey,
+    address: &Address,
+    message: &str,
+) -> Result<Vec<u8>> {
+    let address_type = address.address_type();
+    let header_offset = legacy_header_offset(address_type)?;
+    if legacy_script_pubkey(address_type, public_key)? != address.script_pubkey() {
+        return Err(Error::from(Proto::Error::Error_message_address_mismatch));
+    }
+
+    let sighash = Message::from(signed_msg_hash(message));
+    let (recovery_id, signature) = Secp256k1::new()
+        .sign_ecdsa_recoverable(&sighash, private_key)
+        .serialize_compact();
+
+    let mut serialized = Vec::with_capacity(LEGACY_SIGNATURE_LEN);
+    serialized.push(header_offset + recovery_id.to_i32() as u8);
+    serialized.extend_from_slice(&signature);
+    Ok(serialized)
+}
+
+fn verify_legacy(signature: &[u8], address: &Address, message: &str) -> Result<bool> {
+    let header = signature[0];
+    if !(27..=42).contains(&header) {
+        return Ok(false);
+    }
+
+    let recovery_id = RecoveryId::from_i32(((header - 27) & 0x03) as i32)
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_message_signature))?;
+    let signature = RecoverableSignature::from_compact(&signature[1..], recovery_id)
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_message_signature))?;
+
+    let sighash = Message::from(signed_msg_hash(message));
+    let Ok(recovered) = Secp256k1::verification_only().recover_ecdsa(&sighash, &signature) else {
+        return Ok(false);
+    };
+
+    // Headers 27-30 indicate an uncompressed P2PKH public key. The remaining
+    // headers may not match the address type, since some wallets always use
+    // the P2PKH header.
+    let public_key = PublicKey {
+        inner: recovered,
+        compressed: header >= 31,
+    };
+
+    let script_pubkey = legacy_script_pubkey(address.address_type(), &public_key)?;
+    Ok(script_pubkey == address.script_pubkey())
+}
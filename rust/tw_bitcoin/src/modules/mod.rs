@@ -1,3 +1,17 @@
+pub mod bip322;
+pub mod bolt11;
+pub mod cash_addr;
+pub mod chain_info;
+pub mod decoder;
+pub mod descriptor;
+pub mod fee_estimator;
 pub mod legacy;
+pub mod message_signer;
+pub mod multisig;
+pub mod musig2;
+pub mod network;
+pub mod plan_builder;
 pub mod signer;
+pub mod silent_payments;
+pub mod timelock;
 pub mod transactions;
@@ -0,0 +1,245 @@
+use crate::{Error, Result};
+use bitcoin::blockdata::opcodes::all::OP_CHECKMULTISIG;
+use bitcoin::script::PushBytesBuf;
+use bitcoin::{Address, Network, ScriptBuf, Witness};
+use std::collections::HashMap;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoMultisigType = Proto::mod_Multisig::MultisigType;
+
+// Standardness limit of `OP_CHECKMULTISIG`.
+const MAX_PUBKEYS_PER_MULTISIG: usize = 20;
+// Consensus limit of P2SH redeem scripts.
+const MAX_REDEEM_SCRIPT_SIZE: usize = 520;
+
+/// An m-of-n multisig spending condition.
+pub struct MultisigScript {
+    threshold: usize,
+    public_keys: Vec<bitcoin::PublicKey>,
+    multisig_type: ProtoMultisigType,
+}
+
+impl MultisigScript {
+    pub fn from_proto(proto: &Proto::Multisig<'_>) -> Result<Self> {
+        let mut public_keys = proto
+            .public_keys
+            .iter()
+            .map(|pubkey| bitcoin::PublicKey::from_slice(pubkey.as_ref()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // Sort the public keys by their serialized representation (BIP-67).
+        if !proto.disable_key_sorting {
+            public_keys.sort_by_key(|pubkey| pubkey.to_bytes());
+        }
+
+        Self::new(proto.threshold as usize, public_keys, proto.multisig_type)
+    }
+
+    /// Creates the spending condition, the public keys are used in the given
+    /// order.
+    pub fn new(
+        threshold: usize,
+        public_keys: Vec<bitcoin::PublicKey>,
+        multisig_type: ProtoMultisigType,
+    ) -> Result<Self> {
+        if threshold == 0
+            || threshold > public_keys.len()
+            || public_keys.len() > MAX_PUBKEYS_PER_MULTISIG
+        {
+            return Err(Error::from(Proto::Error::Error_invalid_multisig));
+        }
+
+        // Segwit only allows compressed public keys.
+        let is_segwit = !matches!(multisig_type, ProtoMultisigType::P2SH);
+        if is_segwit && public_keys.iter().any(|pubkey| !pubkey.compressed) {
+            return Err(Error::from(Proto::Error::Error_invalid_public_key));
+        }
+
+        let multisig = MultisigScript {
+            threshold,
+            public_keys,
+            multisig_type,
+        };
+
+        if !is_segwit && multisig.multisig_script().len() > MAX_REDEEM_SCRIPT_SIZE {
+            return Err(Error::from(Proto::Error::Error_invalid_multisig));
+        }
+
+        Ok(multisig)
+    }
+
+    /// The public keys in the order of the script.
+    pub fn public_keys(&self) -> &[bitcoin::PublicKey] {
+        &self.public_keys
+    }
+
+    /// Whether the public key is one of the cosigners.
+    pub fn contains(&self, pubkey: &bitcoin::PublicKey) -> bool {
+        self.public_keys.contains(pubkey)
+    }
+
+    /// The `<m> <pubkey>... <n> OP_CHECKMULTISIG` script, which is either the
+    /// redeem script (P2SH) or the witness script (P2WSH, P2SH-P2WSH).
+    pub fn multisig_script(&self) -> ScriptBuf {
+        let mut builder = ScriptBuf::builder().push_int(self.threshold as i64);
+        for pubkey in &self.public_keys {
+            builder = builder.push_key(pubkey);
+        }
+
+        builder
+            .push_int(self.public_keys.len() as i64)
+            .push_opcode(OP_CHECKMULTISIG)
+            .into_script()
+    }
+
+    /// The spending condition of the output.
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        let script = self.multisig_script();
+
+        match self.multisig_type {
+            ProtoMultisigType::P2SH => ScriptBuf::new_p2sh(&script.script_hash()),
+            ProtoMultisigType::P2WSH => ScriptBuf::new_v0_p2wsh(&script.wscript_hash()),
+            ProtoMultisigType::P2SH_P2WSH => {
+                let redeem_script = ScriptBuf::new_v0_p2wsh(&script.wscript_hash());
+                ScriptBuf::new_p2sh(&redeem_script.script_hash())
+            },
+        }
+    }
+
+    /// The address of the output.
+    pub fn address(&self, network: Network) -> Result<Address> {
+        let script = self.multisig_script();
+
+        let address = match self.multisig_type {
+            ProtoMultisigType::P2SH => Address::p2sh(&script, network)
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_redeem_script))?,
+            ProtoMultisigType::P2WSH => Address::p2wsh(&script, network),
+            ProtoMultisigType::P2SH_P2WSH => Address::p2shwsh(&script, network),
+        };
+
+        Ok(address)
+    }
+
+    /// The signing method and the script that is committed to by the sighash
+    /// (the _scriptCode_).
+    pub fn signing_method(&self) -> (UtxoProto::SigningMethod, ScriptBuf) {
+        let signing_method = match self.multisig_type {
+            ProtoMultisigType::P2SH => UtxoProto::SigningMethod::Legacy,
+            ProtoMultisigType::P2WSH | ProtoMultisigType::P2SH_P2WSH => {
+                UtxoProto::SigningMethod::Segwit
+            },
+        };
+
+        (signing_method, self.multisig_script())
+    }
+
+    /// The estimated weight of the claim (_scriptSig_ and _Witness_).
+    #[rustfmt::skip]
+    pub fn weight_estimate(&self) -> u64 {
+        let script_len = self.multisig_script().len() as u64;
+        let signatures = self.threshold as u64 * (
+            // length + ECDSA signature
+            1 + 72
+        );
+
+        match self.multisig_type {
+            // scale factor applied to non-witness bytes
+            ProtoMultisigType::P2SH => 4 * (
+                // OP_0 (`OP_CHECKMULTISIG` bug)
+                1 +
+                signatures +
+                // length (OP_PUSHDATA1/2) + redeem script
+                3 + script_len
+            ),
+            // witness bytes, scale factor NOT applied.
+            ProtoMultisigType::P2WSH => {
+                // indicator of witness item count + empty item
+                1 + 1 +
+                signatures +
+                // length + witness script
+                3 + script_len
+            },
+            ProtoMultisigType::P2SH_P2WSH => {
+                // scale factor applied to non-witness bytes
+                4 * (
+                    // length + P2WSH redeem script
+                    1 + 34
+                ) +
+                // witness bytes, scale factor NOT applied.
+                (
+                    // indicator of witness item count + empty item
+                    1 + 1 +
+                    signatures +
+                    // length + witness script
+                    3 + script_len
+                )
+            },
+        }
+    }
+
+    /// Creates the claim (_scriptSig_ and _Witness_) from the given
+    /// signatures. Exactly `threshold` signatures are used, in the order of
+    /// the public keys.
+    pub fn claim(
+        &self,
+        signatures: &HashMap<bitcoin::PublicKey, PushBytesBuf>,
+    ) -> Result<(ScriptBuf, Witness)> {
+        if signatures.keys().any(|pubkey| !self.contains(pubkey)) {
+            return Err(Error::from(Proto::Error::Error_multisig_unknown_signer));
+        }
+
+        let ordered: Vec<_> = self
+            .public_keys
+            .iter()
+            .filter_map(|pubkey| signatures.get(pubkey))
+            .take(self.threshold)
+            .collect();
+
+        if ordered.len() < self.threshold {
+            return Err(Error::from(Proto::Error::Error_multisig_threshold_not_met));
+        }
+
+        let script = self.multisig_script();
+
+        match self.multisig_type {
+            ProtoMultisigType::P2SH => {
+                // An additional (dummy) element is consumed by `OP_CHECKMULTISIG`.
+                let mut builder = ScriptBuf::builder().push_int(0);
+                for sig in ordered {
+                    builder = builder.push_slice(sig);
+                }
+
+                let redeem_script = push_bytes(script)?;
+                Ok((
+                    builder.push_slice(redeem_script).into_script(),
+                    Witness::new(),
+                ))
+            },
+            ProtoMultisigType::P2WSH | ProtoMultisigType::P2SH_P2WSH => {
+                let mut witness = Witness::new();
+                // An additional (dummy) element is consumed by `OP_CHECKMULTISIG`.
+                witness.push([]);
+                for sig in ordered {
+                    witness.push(sig.as_bytes());
+                }
+                witness.push(script.as_bytes());
+
+                let script_sig = if let ProtoMultisigType::P2SH_P2WSH = self.multisig_type {
+                    let redeem_script =
+                        push_bytes(ScriptBuf::new_v0_p2wsh(&script.wscript_hash()))?;
+                    ScriptBuf::builder().push_slice(redeem_script).into_script()
+                } else {
+                    ScriptBuf::new()
+                };
+
+                Ok((script_sig, witness))
+            },
+        }
+    }
+}
+
+fn push_bytes(script: ScriptBuf) -> Result<PushBytesBuf> {
+    PushBytesBuf::try_from(script.into_bytes())
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_redeem_script))
+}
@@ -0,0 +1,36 @@
+use crate::{Error, Result};
+use secp256k1::XOnlyPublicKey;
+use tw_keypair::ecdsa::secp256k1::PublicKey;
+use tw_keypair::musig2::{sort_public_keys, KeyAggContext};
+use tw_proto::BitcoinV2::Proto;
+
+/// Aggregates the public keys of the MuSig2 signers (BIP-327). The keys are
+/// sorted unless `disable_key_sorting` is set.
+///
+/// The returned context is not tweaked, signers of a Taproot key-path spend
+/// must apply `KeyAggContext::with_taproot_tweak(None)` before signing.
+pub fn key_agg_context(proto: &Proto::Musig2<'_>) -> Result<KeyAggContext> {
+    let mut public_keys = proto
+        .public_keys
+        .iter()
+        .map(|pubkey| {
+            PublicKey::try_from(pubkey.as_ref())
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_public_key))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if !proto.disable_key_sorting {
+        sort_public_keys(&mut public_keys);
+    }
+
+    KeyAggContext::new(&public_keys).map_err(|_| Error::from(Proto::Error::Error_invalid_musig2))
+}
+
+/// Returns the internal key of the P2TR output, which is the untweaked
+/// aggregated public key of all signers.
+pub fn internal_key(proto: &Proto::Musig2<'_>) -> Result<XOnlyPublicKey> {
+    let key_agg = key_agg_context(proto)?;
+
+    XOnlyPublicKey::from_slice(key_agg.x_only_public_key().as_slice())
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_musig2))
+}
@@ -0,0 +1,104 @@
+use crate::modules::chain_info::chain_info_from_coin;
+use bitcoin::Network;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::error::AddressError;
+use tw_coin_entry::prefix::{AddressPrefix, BitcoinBase58Prefix};
+use tw_proto::BitcoinV2::Proto;
+
+pub const MAINNET_HRP: &str = "bc";
+pub const TESTNET_HRP: &str = "tb";
+pub const REGTEST_HRP: &str = "bcrt";
+
+pub const MAINNET_BASE58: BitcoinBase58Prefix = BitcoinBase58Prefix { p2pkh: 0, p2sh: 5 };
+pub const TESTNET_BASE58: BitcoinBase58Prefix = BitcoinBase58Prefix {
+    p2pkh: 111,
+    p2sh: 196,
+};
+
+/// An address prefix that selects the Bitcoin network, either by the bech32
+/// HRP or by the base58 version bytes. Prefixes of other Bitcoin-based chains
+/// select the address formats of that chain.
+pub enum BitcoinPrefix {
+    Hrp(String),
+    Base58(BitcoinBase58Prefix),
+}
+
+impl TryFrom<AddressPrefix> for BitcoinPrefix {
+    type Error = AddressError;
+
+    fn try_from(prefix: AddressPrefix) -> Result<Self, Self::Error> {
+        match prefix {
+            AddressPrefix::Hrp(hrp) => Ok(BitcoinPrefix::Hrp(hrp)),
+            AddressPrefix::BitcoinBase58(base58) => Ok(BitcoinPrefix::Base58(base58)),
+        }
+    }
+}
+
+impl BitcoinPrefix {
+    /// Returns the network of the prefix, if it belongs to a Bitcoin network.
+    ///
+    /// Note that signet uses the same prefixes as testnet, and its addresses
+    /// are identical to the testnet ones. Testnet prefixes therefore select
+    /// [`Network::Testnet`], whose addresses are valid on signet too.
+    pub fn network(&self) -> Option<Network> {
+        match self {
+            BitcoinPrefix::Hrp(hrp) => network_from_hrp(hrp),
+            BitcoinPrefix::Base58(base58) => network_from_base58(base58),
+        }
+    }
+}
+
+/// The address format of a Bitcoin network or of a Bitcoin fork.
+pub enum AddressFormat {
+    Bitcoin(Network),
+    /// The address formats of a Bitcoin fork, e.g. Litecoin or Bitcoin Cash.
+    Fork(Proto::ChainInfo<'static>),
+}
+
+/// Resolves the address format from the address prefix if given, otherwise
+/// from the HRP or the base58 version bytes of the coin context.
+///
+/// Falls back to mainnet if the coin context doesn't specify any prefix.
+pub fn resolve_address_format(
+    coin: &dyn CoinContext,
+    prefix: Option<BitcoinPrefix>,
+) -> AddressFormat {
+    let network = match &prefix {
+        Some(prefix) => prefix.network(),
+        None => coin_network(coin),
+    };
+
+    match network {
+        Some(network) => AddressFormat::Bitcoin(network),
+        None => AddressFormat::Fork(chain_info_from_coin(coin, prefix)),
+    }
+}
+
+fn coin_network(coin: &dyn CoinContext) -> Option<Network> {
+    if let Some(hrp) = coin.hrp() {
+        return network_from_hrp(&hrp);
+    }
+
+    match (coin.p2pkh_prefix(), coin.p2sh_prefix()) {
+        (Some(p2pkh), Some(p2sh)) => network_from_base58(&BitcoinBase58Prefix { p2pkh, p2sh }),
+        _ => Some(Network::Bitcoin),
+    }
+}
+
+fn network_from_hrp(hrp: &str) -> Option<Network> {
+    match hrp {
+        MAINNET_HRP => Some(Network::Bitcoin),
+        TESTNET_HRP => Some(Network::Testnet),
+        REGTEST_HRP => Some(Network::Regtest),
+        _ => None,
+    }
+}
+
+fn network_from_base58(base58: &BitcoinBase58Prefix) -> Option<Network> {
+    match *base58 {
+        MAINNET_BASE58 => Some(Network::Bitcoin),
+        // Regtest uses the same base58 version bytes as testnet.
+        TESTNET_BASE58 => Some(Network::Testnet),
+        _ => None,
+    }
+}
@@ -0,0 +1,439 @@
+use crate::aliases::*;
+use crate::entry::BitcoinEntry;
+use crate::modules::transactions::{Runestone, COMMIT_CONFIRMATIONS};
+use crate::{Error, Result};
+use std::borrow::Cow;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::modules::plan_builder::PlanBuilder;
+use tw_coin_entry::signing_output_error;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+/// Relative timelocks (BIP-68) require version 2 transactions.
+const TX_VERSION: i32 = 2;
+
+const SEGWIT_MARKER_WEIGHT: u64 = 2;
+
+pub struct BitcoinPlanBuilder;
+
+impl PlanBuilder for BitcoinPlanBuilder {
+    type SigningInput<'a> = Proto::ComposePlan<'a>;
+    type Plan<'a> = Proto::TransactionPlan<'a>;
+
+    #[inline]
+    fn plan<'a>(&self, coin: &dyn CoinContext, proto: Self::SigningInput<'a>) -> Self::Plan<'a> {
+        self.plan_impl(coin, proto)
+            .unwrap_or_else(|err| signing_output_error!(Proto::TransactionPlan, err))
+    }
+}
+
+impl BitcoinPlanBuilder {
+    fn plan_impl<'a>(
+        &self,
+        coin: &dyn CoinContext,
+        proto: Proto::ComposePlan<'a>,
+    ) -> Result<Proto::TransactionPlan<'a>> {
+        let plan = match proto.compose {
+            ProtoComposePlan::brc20(brc20) => self.plan_brc20(coin, brc20)?,
+            ProtoComposePlan::rune_etch(etch) => self.plan_rune_etch(coin, etch)?,
+            ProtoComposePlan::rune_mint(mint) => self.plan_rune_mint(mint)?,
+            ProtoComposePlan::rune_transfer(transfer) => self.plan_rune_transfer(transfer)?,
+            ProtoComposePlan::ordinal(ordinal) => self.plan_ordinal(coin, ordinal)?,
+            ProtoComposePlan::None => {
+                return Err(Error::from(Proto::Error::Error_missing_compose_plan))
+            },
+        };
+
+        Ok(Proto::TransactionPlan {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            plan,
+        })
+    }
+
+    fn plan_brc20<'a>(
+        &self,
+        coin: &dyn CoinContext,
+        proto: Proto::mod_ComposePlan::ComposeBrc20Plan<'a>,
+    ) -> Result<ProtoTransactionPlan<'a>> {
+        let inscription = proto
+            .inscription
+            .ok_or_else(|| Error::from(Proto::Error::Error_missing_inscription))?;
+        let tagged_output = proto
+            .tagged_output
+            .ok_or_else(|| Error::from(Proto::Error::Error_missing_tagged_output))?;
+
+        let commit_output =
+            ProtoOutputBuilder::brc20_inscribe(Proto::mod_Output::OutputBrc20Inscription {
+                inscribe_to: inscription.inscribe_to.clone(),
+                ticker: inscription.ticker.clone(),
+                transfer_amount: inscription.transfer_amount.clone(),
+            });
+
+        let (commit, reveal) = CommitReveal {
+            private_key: proto.private_key,
+            inputs: proto.inputs,
+            input_selector: proto.input_selector,
+            fee_per_vb: proto.fee_per_vb,
+            change_output: proto.change_output,
+            disable_change_output: proto.disable_change_output,
+            commit_output,
+            reveal_input: ProtoInputBuilder::brc20_inscribe(inscription),
+            reveal_sequence: u32::MAX,
+            reveal_outputs: vec![tagged_output],
+            parent: None,
+        }
+        .compose(coin)?;
+
+        Ok(ProtoTransactionPlan::brc20(
+            Proto::mod_TransactionPlan::Brc20Plan {
+                commit: Some(commit),
+                reveal: Some(reveal),
+            },
+        ))
+    }
+
+    fn plan_rune_etch<'a>(
+        &self,
+        coin: &dyn CoinContext,
+        proto: Proto::mod_ComposePlan::ComposeRuneEtchPlan<'a>,
+    ) -> Result<ProtoTransactionPlan<'a>> {
+        let runestone = proto
+            .runestone
+            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_runestone))?;
+        let rune_output = proto
+            .rune_output
+            .ok_or_else(|| Error::from(Proto::Error::Error_missing_tagged_output))?;
+
+        // Only named runes require a commitment, reserved names are assigned
+        // by the protocol and can be etched in a single transaction.
+        let etching = Runestone::from_proto(&runestone)?.etching;
+        if etching.and_then(|etching| etching.rune).is_none() {
+            return Err(Error::from(Proto::Error::Error_invalid_rune_name));
+        }
+
+        let rune = runestone
+            .etching
+            .as_ref()
+            .map(|etching| etching.rune.clone())
+            .unwrap_or_default();
+
+        let commit_output =
+            ProtoOutputBuilder::rune_commit(Proto::mod_Output::OutputRuneCommitment {
+                commit_to: proto.commit_to.clone(),
+                rune: rune.clone(),
+            });
+        let reveal_input = ProtoInputBuilder::rune_commit(Proto::mod_Input::InputRuneCommitment {
+            one_prevout: false,
+            commit_to: proto.commit_to,
+            rune,
+        });
+
+        let (commit, reveal) = CommitReveal {
+            private_key: proto.private_key,
+            inputs: proto.inputs,
+            input_selector: proto.input_selector,
+            fee_per_vb: proto.fee_per_vb,
+            change_output: proto.change_output,
+            disable_change_output: proto.disable_change_output,
+            commit_output,
+            reveal_input,
+            // The commitment must have `COMMIT_CONFIRMATIONS` confirmations
+            // in the block including the reveal transaction. The relative
+            // timelock counts the block of the commit transaction, hence the
+            // reveal transaction is valid one block earlier.
+            reveal_sequence: COMMIT_CONFIRMATIONS - 1,
+            reveal_outputs: vec![rune_output, runestone_output(runestone)],
+            parent: None,
+        }
+        .compose(coin)?;
+
+        Ok(ProtoTransactionPlan::rune_etch(
+            Proto::mod_TransactionPlan::RuneEtchPlan {
+                commit: Some(commit),
+                reveal: Some(reveal),
+            },
+        ))
+    }
+
+    fn plan_ordinal<'a>(
+        &self,
+        coin: &dyn CoinContext,
+        proto: Proto::mod_ComposePlan::ComposeOrdinalPlan<'a>,
+    ) -> Result<ProtoTransactionPlan<'a>> {
+        if proto.inscriptions.is_empty() {
+            return Err(Error::from(Proto::Error::Error_missing_inscription));
+        }
+
+        // The parent inscription is spent by the first input and returned
+        // by the first output, without changing its offset.
+        let parent = match (proto.parent_input, proto.parent_output) {
+            (Some(input), Some(mut output)) => {
+                output.value = input.value;
+                Some((input, output))
+            },
+            (None, None) => None,
+            _ => return Err(Error::from(Proto::Error::Error_missing_tagged_output)),
+        };
+
+        // Each inscription is assigned to the first satoshi of its output.
+        let mut offset = parent.as_ref().map_or(0, |(_, output)| output.value);
+        let mut inscriptions = Vec::with_capacity(proto.inscriptions.len());
+        let mut reveal_outputs = Vec::with_capacity(proto.inscriptions.len());
+        for entry in proto.inscriptions {
+            let mut inscription = entry
+                .inscription
+                .ok_or_else(|| Error::from(Proto::Error::Error_missing_inscription))?;
+            let tagged_output = entry
+                .tagged_output
+                .ok_or_else(|| Error::from(Proto::Error::Error_missing_tagged_output))?;
+
+            inscription.pointer = offset;
+            offset += tagged_output.value;
+
+            inscriptions.push(inscription);
+            reveal_outputs.push(tagged_output);
+        }
+
+        let commit_output =
+            ProtoOutputBuilder::ordinal_batch(Proto::mod_Output::OutputOrdinalBatch {
+                inscribe_to: proto.inscribe_to.clone(),
+                inscriptions: inscriptions.clone(),
+            });
+        let reveal_input = ProtoInputBuilder::ordinal_batch(Proto::mod_Input::InputOrdinalBatch {
+            one_prevout: false,
+            inscribe_to: proto.inscribe_to,
+            inscriptions,
+        });
+
+        let (commit, reveal) = CommitReveal {
+            private_key: proto.private_key,
+            inputs: proto.inputs,
+            input_selector: proto.input_selector,
+            fee_per_vb: proto.fee_per_vb,
+            change_output: proto.change_output,
+            disable_change_output: proto.disable_change_output,
+            commit_output,
+            reveal_input,
+            reveal_sequence: u32::MAX,
+            reveal_outputs,
+            parent,
+        }
+        .compose(coin)?;
+
+        Ok(ProtoTransactionPlan::ordinal(
+            Proto::mod_TransactionPlan::OrdinalPlan {
+                commit: Some(commit),
+                reveal: Some(reveal),
+            },
+        ))
+    }
+
+    fn plan_rune_mint<'a>(
+        &self,
+        proto: Proto::mod_ComposePlan::ComposeRuneMintPlan<'a>,
+    ) -> Result<ProtoTransactionPlan<'a>> {
+        let rune_output = proto
+            .rune_output
+            .ok_or_else(|| Error::from(Proto::Error::Error_missing_tagged_output))?;
+
+        let runestone = Proto::Runestone {
+            mint: Some(
+                proto
+                    .rune_id
+                    .ok_or_else(|| Error::from(Proto::Error::Error_invalid_runestone))?,
+            ),
+            ..Default::default()
+        };
+        Runestone::from_proto(&runestone)?;
+
+        // The minted runes are allocated to the first non-`OP_RETURN` output.
+        Ok(ProtoTransactionPlan::rune_mint(Proto::SigningInput {
+            version: TX_VERSION,
+            private_key: proto.private_key,
+            inputs: proto.inputs,
+            outputs: vec![rune_output, runestone_output(runestone)],
+            input_selector: proto.input_selector,
+            fee_per_vb: proto.fee_per_vb,
+            change_output: proto.change_output,
+            disable_change_output: proto.disable_change_output,
+            ..Default::default()
+        }))
+    }
+
+    fn plan_rune_transfer<'a>(
+        &self,
+        proto: Proto::mod_ComposePlan::ComposeRuneTransferPlan<'a>,
+    ) -> Result<ProtoTransactionPlan<'a>> {
+        let mut outputs = proto.outputs;
+
+        if proto
+            .edicts
+            .iter()
+            .any(|edict| edict.output as usize >= outputs.len())
+        {
+            return Err(Error::from(Proto::Error::Error_invalid_runestone));
+        }
+
+        // The runestone is followed by the change output, which receives the
+        // remaining runes. Otherwise, they are allocated to the first output.
+        let pointer = (!proto.disable_change_output).then(|| Proto::RunePointer {
+            output: outputs.len() as u32 + 1,
+        });
+
+        let runestone = Proto::Runestone {
+            edicts: proto.edicts,
+            pointer,
+            ..Default::default()
+        };
+        Runestone::from_proto(&runestone)?;
+
+        outputs.push(runestone_output(runestone));
+
+        Ok(ProtoTransactionPlan::rune_transfer(Proto::SigningInput {
+            version: TX_VERSION,
+            private_key: proto.private_key,
+            inputs: proto.inputs,
+            outputs,
+            input_selector: proto.input_selector,
+            fee_per_vb: proto.fee_per_vb,
+            change_output: proto.change_output,
+            disable_change_output: proto.disable_change_output,
+            ..Default::default()
+        }))
+    }
+}
+
+/// A commit transaction paying to a Taproot script, which is revealed by
+/// the spending reveal transaction.
+struct CommitReveal<'a> {
+    private_key: Cow<'a, [u8]>,
+    inputs: Vec<Proto::Input<'a>>,
+    input_selector: UtxoProto::InputSelector,
+    fee_per_vb: u64,
+    change_output: Option<Proto::Output<'a>>,
+    disable_change_output: bool,
+    commit_output: ProtoOutputBuilder<'a>,
+    reveal_input: ProtoInputBuilder<'a>,
+    reveal_sequence: u32,
+    reveal_outputs: Vec<Proto::Output<'a>>,
+    /// An additional input spent by the reveal transaction, preceding the
+    /// commit input, and the output returning its full value.
+    parent: Option<(Proto::Input<'a>, Proto::Output<'a>)>,
+}
+
+impl<'a> CommitReveal<'a> {
+    fn compose(
+        self,
+        coin: &dyn CoinContext,
+    ) -> Result<(Proto::SigningInput<'a>, Proto::SigningInput<'a>)> {
+        // The value of the parent input is returned in full, the commit
+        // output covers the remaining outputs and the fee.
+        let total_output = total_value(&self.reveal_outputs);
+
+        let (mut inputs, mut outputs) = match self.parent {
+            Some((input, output)) => (vec![input], vec![output]),
+            None => (vec![], vec![]),
+        };
+        let commit_index = inputs.len();
+
+        inputs.push(Proto::Input {
+            vout: 0,
+            sequence: self.reveal_sequence,
+            sighash_type: UtxoProto::SighashType::UseDefault,
+            to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+                variant: self.reveal_input,
+            }),
+            ..Default::default()
+        });
+        outputs.extend(self.reveal_outputs);
+
+        let mut reveal = Proto::SigningInput {
+            version: TX_VERSION,
+            private_key: self.private_key.clone(),
+            inputs,
+            outputs,
+            input_selector: UtxoProto::InputSelector::UseAll,
+            fee_per_vb: self.fee_per_vb,
+            disable_change_output: true,
+            ..Default::default()
+        };
+
+        let commit_value = total_output + estimate_reveal_fee(coin, &reveal)?;
+
+        let commit = Proto::SigningInput {
+            version: TX_VERSION,
+            private_key: self.private_key,
+            inputs: self.inputs,
+            outputs: vec![Proto::Output {
+                value: commit_value,
+                to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+                    variant: self.commit_output,
+                }),
+            }],
+            input_selector: self.input_selector,
+            fee_per_vb: self.fee_per_vb,
+            change_output: self.change_output,
+            disable_change_output: self.disable_change_output,
+            ..Default::default()
+        };
+
+        let presigning = BitcoinEntry.preimage_hashes_impl(coin, commit.clone())?;
+
+        // The reveal transaction references the commit transaction before it
+        // is signed, so its ID must not depend on the signatures.
+        if presigning
+            .utxo_inputs
+            .iter()
+            .any(|input| input.signing_method == UtxoProto::SigningMethod::Legacy)
+        {
+            return Err(Error::from(Proto::Error::Error_utxo_invalid_signing_method));
+        }
+
+        let reveal_input = &mut reveal.inputs[commit_index];
+        reveal_input.txid = presigning
+            .txid
+            .iter()
+            .copied()
+            .rev()
+            .collect::<Vec<_>>()
+            .into();
+        reveal_input.value = commit_value;
+
+        Ok((commit, reveal))
+    }
+}
+
+// Estimates the fee of the reveal transaction, based on its weight, which
+// includes the exact size of the revealed script. The placeholder inputs only
+// need to cover the outputs.
+fn estimate_reveal_fee(coin: &dyn CoinContext, reveal: &Proto::SigningInput<'_>) -> Result<u64> {
+    let mut proto = reveal.clone();
+    let total_output = total_value(&proto.outputs);
+
+    proto.fee_per_vb = 0;
+    for input in proto.inputs.iter_mut() {
+        input.txid = vec![0; 32].into();
+        input.value = total_output;
+    }
+
+    let presigning = BitcoinEntry.preimage_hashes_impl(coin, proto)?;
+
+    // The estimate is based on the transaction with blanked witnesses, which
+    // does not include the Segwit marker and flag.
+    let weight = presigning.weight_estimate + SEGWIT_MARKER_WEIGHT;
+    Ok((weight + 3) / 4 * reveal.fee_per_vb)
+}
+
+fn total_value(outputs: &[Proto::Output<'_>]) -> u64 {
+    outputs.iter().map(|output| output.value).sum()
+}
+
+fn runestone_output(runestone: Proto::Runestone<'_>) -> Proto::Output<'_> {
+    Proto::Output {
+        value: 0,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::runestone(runestone),
+        }),
+    }
+}
@@ -1,8 +1,15 @@
+use crate::aliases::*;
+use crate::modules::multisig::MultisigScript;
+use crate::modules::silent_payments::{self, SilentPaymentAddress};
+use crate::modules::timelock;
 use crate::{BitcoinEntry, Error, Result};
+use bitcoin::hashes::Hash;
 use bitcoin::key::{TapTweak, TweakedKeyPair};
 use bitcoin::sighash::{EcdsaSighashType, TapSighashType};
-use secp256k1::{KeyPair, Message, Secp256k1};
+use bitcoin::{OutPoint, Script, Txid};
+use secp256k1::{KeyPair, Message, Secp256k1, SecretKey, XOnlyPublicKey};
 use std::collections::HashMap;
+use std::str::FromStr;
 use tw_coin_entry::coin_context::CoinContext;
 use tw_coin_entry::coin_entry::{PrivateKeyBytes, SignatureBytes};
 use tw_misc::traits::ToBytesVec;
@@ -20,7 +27,14 @@ impl Signer {
         // `preimage_hashes_impl` and `compile_impl`. But we're leaving this
         // here in case this methods gets extended and the pre-processing does
         // not get accidentally forgotten.
-        let mut proto = crate::entry::pre_processor(proto);
+        let proto = crate::entry::pre_processor(proto);
+
+        // The lock time must not change with the input selection, since the
+        // sighashes commit to it.
+        let proto = timelock::apply_timelocks(proto)?;
+
+        // Silent payment outputs depend on the keys of the selected inputs.
+        let proto = Self::resolve_silent_payments(_coin, proto)?;
 
         // Generate the sighashes.
         let pre_signed = BitcoinEntry.preimage_hashes_impl(_coin, proto.clone())?;
@@ -32,6 +46,264 @@ impl Signer {
         debug_assert!(proto.inputs.len() >= pre_signed.utxo_inputs.len());
         debug_assert_eq!(pre_signed.utxo_inputs.len(), pre_signed.sighashes.len());
 
+        let proto = Self::apply_selection(proto, &pre_signed);
+
+        // Collect individual private keys per input, if there are any.
+        let individual_keys = Self::individual_keys(&proto);
+
+        // Sign the sighashes.
+        let signatures = crate::modules::signer::Signer::signatures_from_proto(
+            &pre_signed,
+            proto.private_key.to_vec(),
+            individual_keys,
+            proto.dangerous_use_fixed_schnorr_rng,
+        )?;
+
+        // Sanity check.
+        debug_assert_eq!(signatures.len(), proto.inputs.len());
+        debug_assert_eq!(signatures.len(), pre_signed.sighashes.len());
+
+        // Prepare values for sanity check.
+        let total_input_amount = proto.inputs.iter().map(|input| input.value).sum::<u64>();
+        let total_output_amount = proto.outputs.iter().map(|output| output.value).sum::<u64>();
+
+        // Construct the final transaction.
+        let mut compiled = BitcoinEntry.compile_impl(_coin, proto, signatures, vec![])?;
+
+        // Note: the fee that we used for estimation might be SLIGHLY off
+        // from the final fee. This is due to the fact that we must set a
+        // change output (which must consider the fee) before we can calculate
+        // the final fee. This leads to a chicken-and-egg problem. However,
+        // the fee difference, should there be one, is generally as small as
+        // one weight unit. Hence, we overwrite the final fee with the
+        // estimated fee.
+        compiled.weight = pre_signed.weight_estimate;
+
+        // Sanity check.
+        let compiled_total_output_amount = compiled
+            .transaction
+            .as_ref()
+            .expect("No transaction was constructed")
+            .outputs
+            .iter()
+            .map(|output| output.value)
+            .sum::<u64>();
+
+        // Every output is accounted for, including the fee.
+        debug_assert_eq!(total_output_amount, compiled_total_output_amount);
+        debug_assert_eq!(total_input_amount, total_output_amount + compiled.fee);
+
+        Ok(compiled)
+    }
+
+    /// Creates the signatures of a single cosigner for all multisig inputs
+    /// the private key belongs to. The signatures of all cosigners can then
+    /// be combined with [`Signer::combine_proto`].
+    pub fn sign_partial_proto(
+        _coin: &dyn CoinContext,
+        proto: Proto::SigningInput<'_>,
+    ) -> Result<Proto::PartialSigningOutput<'static>> {
+        let proto = timelock::apply_timelocks(crate::entry::pre_processor(proto))?;
+
+        // Generate the sighashes.
+        let pre_signed = BitcoinEntry.preimage_hashes_impl(_coin, proto.clone())?;
+        if pre_signed.error != Proto::Error::OK {
+            return Err(Error::from(pre_signed.error));
+        }
+
+        // Every cosigner must end up with the same selected inputs.
+        let proto = Self::apply_selection(proto, &pre_signed);
+        let individual_keys = Self::individual_keys(&proto);
+
+        // Sign the sighashes.
+        let signatures = Self::signatures_from_proto(
+            &pre_signed,
+            proto.private_key.to_vec(),
+            individual_keys.clone(),
+            proto.dangerous_use_fixed_schnorr_rng,
+        )?;
+
+        let secp = Secp256k1::new();
+
+        let mut inputs = vec![];
+        for (index, (txin, signature)) in proto.inputs.iter().zip(signatures).enumerate() {
+            let ProtoInputRecipient::builder(builder) = &txin.to_recipient else {
+                continue;
+            };
+
+            let ProtoInputBuilder::multisig(input_multisig) = &builder.variant else {
+                continue;
+            };
+
+            let multisig = MultisigScript::from_proto(
+                input_multisig
+                    .multisig
+                    .as_ref()
+                    .ok_or_else(|| Error::from(Proto::Error::Error_invalid_multisig))?,
+            )?;
+
+            let private_key = individual_keys
+                .get(&index)
+                .map(|key| key.as_slice())
+                .unwrap_or_else(|| proto.private_key.as_ref());
+
+            let keypair = KeyPair::from_seckey_slice(&secp, private_key)
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_private_key))?;
+            let pubkey = bitcoin::PublicKey::new(keypair.public_key());
+
+            // Skip the inputs the private key is not a cosigner of.
+            if !multisig.contains(&pubkey) {
+                continue;
+            }
+
+            inputs.push(Proto::InputPartialSignatures {
+                txid: txin.txid.to_vec().into(),
+                vout: txin.vout,
+                signatures: vec![Proto::PartialSignature {
+                    public_key: pubkey.to_bytes().into(),
+                    signature: signature.into(),
+                }],
+            });
+        }
+
+        Ok(Proto::PartialSigningOutput {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            inputs,
+        })
+    }
+
+    /// Combines the partial signatures of all cosigners and constructs the
+    /// final transaction. All inputs must be multisig inputs.
+    pub fn combine_proto(
+        _coin: &dyn CoinContext,
+        proto: Proto::CombiningInput<'_>,
+    ) -> Result<Proto::SigningOutput<'static>> {
+        let mut signing = timelock::apply_timelocks(crate::entry::pre_processor(
+            proto.signing_input.unwrap_or_default(),
+        ))?;
+
+        // Merge the partial signatures into the corresponding inputs.
+        for partial in &proto.partial_signatures {
+            let Some(txin) = signing
+                .inputs
+                .iter_mut()
+                .find(|txin| txin.txid == partial.txid && txin.vout == partial.vout)
+            else {
+                continue;
+            };
+
+            if let ProtoInputRecipient::builder(builder) = &mut txin.to_recipient {
+                if let ProtoInputBuilder::multisig(input_multisig) = &mut builder.variant {
+                    input_multisig
+                        .signatures
+                        .extend(partial.signatures.iter().cloned());
+                }
+            }
+        }
+
+        // Generate the sighashes, which selects the same inputs as the
+        // partial signing did.
+        let pre_signed = BitcoinEntry.preimage_hashes_impl(_coin, signing.clone())?;
+        if pre_signed.error != Proto::Error::OK {
+            return Err(Error::from(pre_signed.error));
+        }
+
+        let signing = Self::apply_selection(signing, &pre_signed);
+
+        // All the signatures are already contained in the inputs.
+        let signatures = vec![SignatureBytes::default(); signing.inputs.len()];
+
+        let mut compiled = BitcoinEntry.compile_impl(_coin, signing, signatures, vec![])?;
+        compiled.weight = pre_signed.weight_estimate;
+
+        Ok(compiled)
+    }
+
+    // Replaces the silent payment outputs with their Taproot output keys. The
+    // inputs are selected with placeholder outputs of the same size first,
+    // the selection and the change output are then fixed, since the output
+    // keys commit to the selected inputs.
+    fn resolve_silent_payments<'a>(
+        _coin: &dyn CoinContext,
+        proto: Proto::SigningInput<'a>,
+    ) -> Result<Proto::SigningInput<'a>> {
+        let mut recipients = vec![];
+        for (index, output) in proto.outputs.iter().enumerate() {
+            let address = match &output.to_recipient {
+                ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+                    variant: ProtoOutputBuilder::silent_payment(address),
+                }) => SilentPaymentAddress::from_str(address.as_ref())?,
+                ProtoOutputRecipient::from_address(address) => {
+                    match SilentPaymentAddress::from_str(address.as_ref()) {
+                        Ok(address) => address,
+                        Err(_) => continue,
+                    }
+                },
+                _ => continue,
+            };
+
+            recipients.push((index, address));
+        }
+
+        if recipients.is_empty() {
+            return Ok(proto);
+        }
+
+        let mut proto = proto;
+        for (index, address) in &recipients {
+            let (placeholder, _) = address.spend.x_only_public_key();
+            proto.outputs[*index].to_recipient = taproot_output(placeholder);
+        }
+
+        let pre_signed = BitcoinEntry.preimage_hashes_impl(_coin, proto.clone())?;
+        if pre_signed.error != Proto::Error::OK {
+            return Err(Error::from(pre_signed.error));
+        }
+
+        let mut proto = Self::apply_selection(proto, &pre_signed);
+        proto.input_selector = UtxoProto::InputSelector::UseAll;
+        proto.disable_change_output = true;
+
+        let individual_keys = Self::individual_keys(&proto);
+
+        let mut input_keys = vec![];
+        let mut outpoints = vec![];
+        for (index, input) in proto.inputs.iter().enumerate() {
+            let txid = Txid::from_slice(input.txid.as_ref())
+                .map_err(|_| Error::from(Proto::Error::Error_utxo_invalid_txid))?;
+            outpoints.push(OutPoint::new(txid, input.vout));
+
+            let Some(taproot) = silent_payment_eligibility(input)? else {
+                continue;
+            };
+
+            let private_key = individual_keys
+                .get(&index)
+                .map(|key| key.as_slice())
+                .unwrap_or_else(|| proto.private_key.as_ref());
+            let private_key = SecretKey::from_slice(private_key)
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_private_key))?;
+
+            input_keys.push(silent_payments::sender_input_key(&private_key, taproot));
+        }
+
+        let addresses: Vec<_> = recipients.iter().map(|(_, address)| *address).collect();
+        let output_keys = silent_payments::sender_output_keys(&input_keys, &outpoints, &addresses)?;
+
+        for ((index, _), output_key) in recipients.iter().zip(output_keys) {
+            proto.outputs[*index].to_recipient = taproot_output(output_key);
+        }
+
+        Ok(proto)
+    }
+
+    // Updates the inputs and change output according to the result of the
+    // input selection.
+    fn apply_selection<'a>(
+        mut proto: Proto::SigningInput<'a>,
+        pre_signed: &Proto::PreSigningOutput<'_>,
+    ) -> Proto::SigningInput<'a> {
         if proto.disable_change_output {
             debug_assert_eq!(proto.outputs.len(), pre_signed.utxo_outputs.len());
         } else {
@@ -73,7 +345,11 @@ impl Signer {
         debug_assert_eq!(proto.outputs.len(), pre_signed.utxo_outputs.len());
         debug_assert_eq!(proto.inputs.len(), pre_signed.utxo_inputs.len());
 
-        // Collect individual private keys per input, if there are any.
+        proto
+    }
+
+    // Collects individual private keys per input, if there are any.
+    fn individual_keys(proto: &Proto::SigningInput<'_>) -> HashMap<usize, PrivateKeyBytes> {
         let mut individual_keys = HashMap::new();
         for (index, txin) in proto.inputs.iter().enumerate() {
             if !txin.private_key.is_empty() {
@@ -81,50 +357,9 @@ impl Signer {
             }
         }
 
-        // Sign the sighashes.
-        let signatures = crate::modules::signer::Signer::signatures_from_proto(
-            &pre_signed,
-            proto.private_key.to_vec(),
-            individual_keys,
-            proto.dangerous_use_fixed_schnorr_rng,
-        )?;
-
-        // Sanity check.
-        debug_assert_eq!(signatures.len(), proto.inputs.len());
-        debug_assert_eq!(signatures.len(), pre_signed.sighashes.len());
-
-        // Prepare values for sanity check.
-        let total_input_amount = proto.inputs.iter().map(|input| input.value).sum::<u64>();
-        let total_output_amount = proto.outputs.iter().map(|output| output.value).sum::<u64>();
-
-        // Construct the final transaction.
-        let mut compiled = BitcoinEntry.compile_impl(_coin, proto, signatures, vec![])?;
-
-        // Note: the fee that we used for estimation might be SLIGHLY off
-        // from the final fee. This is due to the fact that we must set a
-        // change output (which must consider the fee) before we can calculate
-        // the final fee. This leads to a chicken-and-egg problem. However,
-        // the fee difference, should there be one, is generally as small as
-        // one weight unit. Hence, we overwrite the final fee with the
-        // estimated fee.
-        compiled.weight = pre_signed.weight_estimate;
-
-        // Sanity check.
-        let compiled_total_output_amount = compiled
-            .transaction
-            .as_ref()
-            .expect("No transaction was constructed")
-            .outputs
-            .iter()
-            .map(|output| output.value)
-            .sum::<u64>();
-
-        // Every output is accounted for, including the fee.
-        debug_assert_eq!(total_output_amount, compiled_total_output_amount);
-        debug_assert_eq!(total_input_amount, total_output_amount + compiled.fee);
-
-        Ok(compiled)
+        individual_keys
     }
+
     pub fn signatures_from_proto(
         input: &Proto::PreSigningOutput<'_>,
         private_key: PrivateKeyBytes,
@@ -233,3 +468,56 @@ impl Signer {
         Ok(signatures)
     }
 }
+
+// A Taproot output with the given (already tweaked) output key.
+fn taproot_output(output_key: XOnlyPublicKey) -> ProtoOutputRecipient<'static> {
+    ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+        variant: ProtoOutputBuilder::p2tr_dangerous_assume_tweaked(
+            output_key.serialize().to_vec().into(),
+        ),
+    })
+}
+
+// Whether the input contributes its key to the silent payment shared secret,
+// `Some(true)` for Taproot inputs. Inputs of which the receiver would expect a
+// key, but that cannot be provided (such as Taproot script-path spends), are
+// rejected.
+fn silent_payment_eligibility(input: &Proto::Input<'_>) -> Result<Option<bool>> {
+    let unsupported = || Error::from(Proto::Error::Error_silent_payment_unsupported_input);
+
+    let eligibility = match &input.to_recipient {
+        ProtoInputRecipient::builder(builder) => match &builder.variant {
+            // Uncompressed public keys are not eligible.
+            ProtoInputBuilder::p2pkh(pubkey) => (pubkey.len() == 33).then_some(false),
+            ProtoInputBuilder::p2wpkh(_) => Some(false),
+            ProtoInputBuilder::p2tr_key_path(_) => Some(true),
+            ProtoInputBuilder::p2sh(redeem_script) => {
+                if Script::from_bytes(redeem_script.as_ref()).is_v0_p2wpkh() {
+                    return Err(unsupported());
+                }
+                None
+            },
+            // Timelocked Taproot outputs use the NUMS internal key, which
+            // is skipped by the receiver.
+            ProtoInputBuilder::p2wsh(_)
+            | ProtoInputBuilder::multisig(_)
+            | ProtoInputBuilder::timelocked_key(_)
+            | ProtoInputBuilder::htlc(_) => None,
+            _ => return Err(unsupported()),
+        },
+        ProtoInputRecipient::custom_script(custom) => {
+            let script_pubkey = Script::from_bytes(custom.script_pubkey.as_ref());
+            if script_pubkey.is_p2pkh()
+                || script_pubkey.is_p2sh()
+                || script_pubkey.is_v0_p2wpkh()
+                || script_pubkey.is_v1_p2tr()
+            {
+                return Err(unsupported());
+            }
+            None
+        },
+        ProtoInputRecipient::None => None,
+    };
+
+    Ok(eligibility)
+}
@@ -0,0 +1,389 @@
+use crate::modules::transactions::NUMS_INTERNAL_KEY;
+use crate::{Error, Result};
+use bitcoin::bech32::{self, FromBase32, ToBase32, Variant};
+use bitcoin::consensus::{serialize, Decodable};
+use bitcoin::hashes::{hash160, sha256, Hash, HashEngine};
+use bitcoin::key::{TapTweak, TweakedKeyPair};
+use bitcoin::script::Instruction;
+use bitcoin::{Network, OutPoint, Script, Transaction, TxIn, TxOut};
+use secp256k1::{KeyPair, Parity, PublicKey, Scalar, Secp256k1, SecretKey, XOnlyPublicKey};
+use std::fmt::Display;
+use std::str::FromStr;
+use tw_proto::BitcoinV2::Proto;
+
+// Only version 0 addresses can be created. Versions 1-30 are forward
+// compatible and are parsed as version 0, version 31 is reserved.
+const ADDRESS_VERSION: u8 = 0;
+const MAX_ADDRESS_VERSION: u8 = 30;
+const ADDRESS_KEYS_SIZE: usize = 66;
+
+const TAG_INPUTS: &str = "BIP0352/Inputs";
+const TAG_SHARED_SECRET: &str = "BIP0352/SharedSecret";
+
+/// A silent payment address (BIP-352), consisting of the scan and the spend
+/// public key of the receiver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SilentPaymentAddress {
+    pub scan: PublicKey,
+    pub spend: PublicKey,
+    pub network: Network,
+}
+
+impl SilentPaymentAddress {
+    pub fn new(scan: PublicKey, spend: PublicKey, network: Network) -> Self {
+        SilentPaymentAddress {
+            scan,
+            spend,
+            network,
+        }
+    }
+
+    fn hrp(network: Network) -> &'static str {
+        match network {
+            Network::Bitcoin => "sp",
+            Network::Regtest => "sprt",
+            _ => "tsp",
+        }
+    }
+}
+
+impl FromStr for SilentPaymentAddress {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid_address = || Error::from(Proto::Error::Error_invalid_silent_payment_address);
+
+        let (hrp, data, variant) = bech32::decode(s).map_err(|_| invalid_address())?;
+        let network = match hrp.as_str() {
+            "sp" => Network::Bitcoin,
+            "tsp" => Network::Testnet,
+            "sprt" => Network::Regtest,
+            _ => return Err(invalid_address()),
+        };
+
+        let (version, data) = data.split_first().ok_or_else(invalid_address)?;
+        if variant != Variant::Bech32m || version.to_u8() > MAX_ADDRESS_VERSION {
+            return Err(invalid_address());
+        }
+
+        let keys = Vec::<u8>::from_base32(data).map_err(|_| invalid_address())?;
+        // Version 0 addresses must not contain any additional data.
+        if keys.len() < ADDRESS_KEYS_SIZE
+            || (version.to_u8() == ADDRESS_VERSION && keys.len() != ADDRESS_KEYS_SIZE)
+        {
+            return Err(invalid_address());
+        }
+
+        let scan = PublicKey::from_slice(&keys[..33]).map_err(|_| invalid_address())?;
+        let spend = PublicKey::from_slice(&keys[33..66]).map_err(|_| invalid_address())?;
+
+        Ok(SilentPaymentAddress::new(scan, spend, network))
+    }
+}
+
+impl Display for SilentPaymentAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut keys = self.scan.serialize().to_vec();
+        keys.extend(self.spend.serialize());
+
+        let mut data = vec![bech32::u5::try_from_u8(ADDRESS_VERSION).expect("valid version")];
+        data.extend(keys.to_base32());
+
+        let encoded = bech32::encode(Self::hrp(self.network), data, Variant::Bech32m)
+            .map_err(|_| std::fmt::Error)?;
+        write!(f, "{}", encoded)
+    }
+}
+
+/// An output of a transaction that pays to the scanning receiver. The
+/// private key of the output is `b_spend + tweak`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SilentPaymentMatch {
+    pub vout: u32,
+    pub output_key: XOnlyPublicKey,
+    pub tweak: [u8; 32],
+}
+
+/// Derives the output keys of the silent payment `recipients`, in the same
+/// order. The `input_keys` are the private keys of the eligible inputs,
+/// `outpoints` contains all outpoints of the transaction.
+pub fn sender_output_keys(
+    input_keys: &[SecretKey],
+    outpoints: &[OutPoint],
+    recipients: &[SilentPaymentAddress],
+) -> Result<Vec<XOnlyPublicKey>> {
+    let secp = Secp256k1::new();
+    let no_eligible_inputs = || Error::from(Proto::Error::Error_silent_payment_no_eligible_inputs);
+
+    // a = a_1 + ... + a_n
+    let (first, rest) = input_keys.split_first().ok_or_else(no_eligible_inputs)?;
+    let input_key = rest.iter().try_fold(*first, |sum, key| {
+        sum.add_tweak(&Scalar::from(*key))
+            .map_err(|_| no_eligible_inputs())
+    })?;
+
+    let input_hash = input_hash(outpoints, &input_key.public_key(&secp))?;
+    let tweaked_input_key = input_key
+        .mul_tweak(&input_hash)
+        .map_err(|_| no_eligible_inputs())?;
+
+    // The counter `k` is incremented per scan key.
+    let mut output_keys = vec![];
+    for (index, recipient) in recipients.iter().enumerate() {
+        let k = recipients[..index]
+            .iter()
+            .filter(|other| other.scan == recipient.scan)
+            .count() as u32;
+
+        let shared_secret = recipient
+            .scan
+            .mul_tweak(&secp, &Scalar::from(tweaked_input_key))
+            .map_err(|_| no_eligible_inputs())?;
+
+        let (output_key, _) = output_key(&secp, &shared_secret, &recipient.spend, k)?;
+        output_keys.push(output_key);
+    }
+
+    Ok(output_keys)
+}
+
+/// Checks which outputs of a transaction pay to the receiver. The
+/// `input_keys` are the public keys of the eligible inputs, see
+/// [`input_public_key`].
+pub fn scan_outputs(
+    scan_key: &SecretKey,
+    spend_key: &PublicKey,
+    input_keys: &[PublicKey],
+    outpoints: &[OutPoint],
+    outputs: &[TxOut],
+) -> Result<Vec<SilentPaymentMatch>> {
+    let secp = Secp256k1::new();
+
+    // Transactions without eligible inputs do not contain silent payments.
+    if input_keys.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let input_key = PublicKey::combine_keys(&input_keys.iter().collect::<Vec<_>>())
+        .map_err(|_| Error::from(Proto::Error::Error_silent_payment_no_eligible_inputs))?;
+    let input_hash = input_hash(outpoints, &input_key)?;
+
+    let tweak = scan_key
+        .mul_tweak(&input_hash)
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_private_key))?;
+    let shared_secret = input_key
+        .mul_tweak(&secp, &Scalar::from(tweak))
+        .map_err(|_| Error::from(Proto::Error::Error_silent_payment_no_eligible_inputs))?;
+
+    let taproot_outputs: Vec<(u32, XOnlyPublicKey)> = outputs
+        .iter()
+        .enumerate()
+        .filter(|(_, output)| output.script_pubkey.is_v1_p2tr())
+        .filter_map(|(vout, output)| {
+            let key = XOnlyPublicKey::from_slice(&output.script_pubkey.as_bytes()[2..]).ok()?;
+            Some((vout as u32, key))
+        })
+        .collect();
+
+    // Check for `k = 0, 1, ...` until no output matches.
+    let mut matches = vec![];
+    for k in 0..taproot_outputs.len() as u32 {
+        let (output_key, tweak) = output_key(&secp, &shared_secret, spend_key, k)?;
+
+        let Some((vout, _)) = taproot_outputs.iter().find(|(_, key)| *key == output_key) else {
+            break;
+        };
+
+        matches.push(SilentPaymentMatch {
+            vout: *vout,
+            output_key,
+            tweak,
+        });
+    }
+
+    Ok(matches)
+}
+
+/// The private key an input contributes to the shared secret. Taproot key
+/// spends contribute the tweaked key, negated if its public key is odd.
+pub fn sender_input_key(private_key: &SecretKey, taproot: bool) -> SecretKey {
+    if !taproot {
+        return *private_key;
+    }
+
+    let secp = Secp256k1::new();
+    let tweaked: TweakedKeyPair =
+        KeyPair::from_secret_key(&secp, private_key).tap_tweak(&secp, None);
+    let tweaked = KeyPair::from(tweaked);
+
+    match tweaked.x_only_public_key().1 {
+        Parity::Even => tweaked.secret_key(),
+        Parity::Odd => tweaked.secret_key().negate(),
+    }
+}
+
+/// Extracts the public key an input contributes to the shared secret, `None`
+/// if the input is not eligible. Eligible are P2PKH, P2SH-P2WPKH, P2WPKH
+/// and P2TR inputs with compressed keys.
+pub fn input_public_key(txin: &TxIn, prevout: &TxOut) -> Option<PublicKey> {
+    let script_pubkey = prevout.script_pubkey.as_script();
+
+    if script_pubkey.is_p2pkh() {
+        // The scriptSig might be malleated, use the last push matching the
+        // public key hash.
+        let pubkey_hash = &script_pubkey.as_bytes()[3..23];
+        return txin
+            .script_sig
+            .instructions()
+            .filter_map(|instruction| match instruction {
+                Ok(Instruction::PushBytes(push)) => Some(push.as_bytes()),
+                _ => None,
+            })
+            .filter(|push| push.len() == 33)
+            .filter(|push| hash160::Hash::hash(push).as_byte_array() == pubkey_hash)
+            .last()
+            .and_then(|push| PublicKey::from_slice(push).ok());
+    }
+
+    if script_pubkey.is_p2sh() {
+        // Only P2SH-P2WPKH is eligible.
+        let redeem_script = txin.script_sig.instructions().last()?.ok()?;
+        let is_p2wpkh = redeem_script.push_bytes().map_or(false, |push| {
+            Script::from_bytes(push.as_bytes()).is_v0_p2wpkh()
+        });
+        if !is_p2wpkh {
+            return None;
+        }
+    } else if !script_pubkey.is_v0_p2wpkh() {
+        return taproot_input_key(txin, script_pubkey);
+    }
+
+    // P2WPKH or P2SH-P2WPKH.
+    txin.witness
+        .last()
+        .filter(|pubkey| pubkey.len() == 33)
+        .and_then(|pubkey| PublicKey::from_slice(pubkey).ok())
+}
+
+fn taproot_input_key(txin: &TxIn, script_pubkey: &Script) -> Option<PublicKey> {
+    if !script_pubkey.is_v1_p2tr() {
+        return None;
+    }
+
+    // Script-path spends with the NUMS internal key are skipped. The last
+    // item is the control block, unless it is the annex.
+    let mut items: Vec<&[u8]> = txin.witness.iter().collect();
+    if items.len() > 1
+        && items
+            .last()
+            .map_or(false, |item| item.first() == Some(&0x50))
+    {
+        items.pop();
+    }
+    if items.len() > 1 {
+        let control_block = items.last()?;
+        if control_block.get(1..33) == Some(&NUMS_INTERNAL_KEY[..]) {
+            return None;
+        }
+    }
+
+    let output_key = XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..]).ok()?;
+    Some(output_key.public_key(Parity::Even))
+}
+
+pub struct SilentPaymentScanner;
+
+impl SilentPaymentScanner {
+    /// Checks which outputs of the encoded transaction pay to the receiver.
+    pub fn scan_proto(
+        proto: Proto::SilentPaymentScanInput<'_>,
+    ) -> Result<Proto::SilentPaymentScanOutput<'static>> {
+        let tx = Transaction::consensus_decode(&mut proto.encoded.as_ref())
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_transaction_encoding))?;
+        if proto.prevouts.len() != tx.input.len() {
+            return Err(Error::from(Proto::Error::Error_unmatched_prevout_count));
+        }
+
+        let scan_key = SecretKey::from_slice(proto.scan_private_key.as_ref())
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_private_key))?;
+        let spend_key = PublicKey::from_slice(proto.spend_public_key.as_ref())
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_public_key))?;
+
+        let input_keys: Vec<PublicKey> = tx
+            .input
+            .iter()
+            .zip(proto.prevouts.iter())
+            .filter_map(|(txin, prevout)| {
+                let prevout = TxOut {
+                    value: prevout.value,
+                    script_pubkey: prevout.script_pubkey.to_vec().into(),
+                };
+                input_public_key(txin, &prevout)
+            })
+            .collect();
+        let outpoints: Vec<OutPoint> = tx.input.iter().map(|txin| txin.previous_output).collect();
+
+        let matches = scan_outputs(&scan_key, &spend_key, &input_keys, &outpoints, &tx.output)?;
+
+        Ok(Proto::SilentPaymentScanOutput {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            outputs: matches
+                .into_iter()
+                .map(|output| Proto::mod_SilentPaymentScanOutput::Match {
+                    vout: output.vout,
+                    value: tx.output[output.vout as usize].value,
+                    output_key: output.output_key.serialize().to_vec().into(),
+                    tweak: output.tweak.to_vec().into(),
+                })
+                .collect(),
+        })
+    }
+}
+
+/// `input_hash = hash_BIP0352/Inputs(outpoint_L || A)`, where `outpoint_L`
+/// is the smallest serialized outpoint.
+fn input_hash(outpoints: &[OutPoint], input_key: &PublicKey) -> Result<Scalar> {
+    let smallest = outpoints
+        .iter()
+        .map(serialize)
+        .min()
+        .ok_or_else(|| Error::from(Proto::Error::Error_silent_payment_no_eligible_inputs))?;
+
+    let hash = tagged_hash(TAG_INPUTS, &[&smallest, &input_key.serialize()]);
+    Scalar::from_be_bytes(hash)
+        .map_err(|_| Error::from(Proto::Error::Error_silent_payment_no_eligible_inputs))
+}
+
+/// `P_k = B_spend + t_k * G`, with `t_k = hash_BIP0352/SharedSecret(ecdh || k)`.
+fn output_key(
+    secp: &Secp256k1<secp256k1::All>,
+    shared_secret: &PublicKey,
+    spend_key: &PublicKey,
+    k: u32,
+) -> Result<(XOnlyPublicKey, [u8; 32])> {
+    let tweak = tagged_hash(
+        TAG_SHARED_SECRET,
+        &[&shared_secret.serialize(), &k.to_be_bytes()],
+    );
+
+    let output_key = Scalar::from_be_bytes(tweak)
+        .ok()
+        .and_then(|scalar| spend_key.add_exp_tweak(secp, &scalar).ok())
+        .ok_or_else(|| Error::from(Proto::Error::Error_silent_payment_no_eligible_inputs))?;
+
+    Ok((output_key.x_only_public_key().0, tweak))
+}
+
+fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = sha256::Hash::hash(tag.as_bytes());
+
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag_hash.as_byte_array());
+    engine.input(tag_hash.as_byte_array());
+    for data in data {
+        engine.input(data);
+    }
+
+    sha256::Hash::from_engine(engine).to_byte_array()
+}
@@ -0,0 +1,532 @@
+use crate::aliases::*;
+use crate::modules::chain_info::legacy_signature;
+use crate::modules::descriptor::lock_time_from_proto;
+use crate::modules::transactions::NUMS_INTERNAL_KEY;
+use crate::{Error, Result};
+use bitcoin::absolute::LockTime;
+use bitcoin::blockdata::opcodes::all::{
+    OP_CHECKSIG, OP_CLTV, OP_CSV, OP_DROP, OP_ELSE, OP_ENDIF, OP_EQUALVERIFY, OP_IF, OP_SHA256,
+    OP_SIZE,
+};
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::script::Builder;
+use bitcoin::taproot::{LeafVersion, TapLeafHash, TaprootBuilder, TaprootSpendInfo};
+use bitcoin::{ScriptBuf, Sequence, Witness};
+use secp256k1::XOnlyPublicKey;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoTimelockVariant = Proto::mod_Timelock::OneOfvariant;
+
+// The size of the HTLC preimage, enforced by the script.
+const PREIMAGE_SIZE: usize = 32;
+
+/// An absolute (`OP_CHECKLOCKTIMEVERIFY`) or relative
+/// (`OP_CHECKSEQUENCEVERIFY`) timelock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timelock {
+    Absolute(LockTime),
+    Relative(Sequence),
+}
+
+impl Timelock {
+    pub fn from_proto(proto: Option<&Proto::Timelock>) -> Result<Self> {
+        let invalid = || Error::from(Proto::Error::Error_invalid_timelock);
+
+        let timelock = match proto.map(|timelock| &timelock.variant) {
+            Some(ProtoTimelockVariant::after_height(height)) if *height > 0 => {
+                Timelock::Absolute(LockTime::from_height(*height).map_err(|_| invalid())?)
+            },
+            Some(ProtoTimelockVariant::after_time(time)) => {
+                Timelock::Absolute(LockTime::from_time(*time).map_err(|_| invalid())?)
+            },
+            Some(ProtoTimelockVariant::older_blocks(blocks)) if *blocks > 0 => {
+                let blocks = u16::try_from(*blocks).map_err(|_| invalid())?;
+                Timelock::Relative(Sequence::from_height(blocks))
+            },
+            Some(ProtoTimelockVariant::older_seconds(seconds)) if *seconds > 0 => {
+                Timelock::Relative(Sequence::from_seconds_ceil(*seconds).map_err(|_| invalid())?)
+            },
+            _ => return Err(invalid()),
+        };
+
+        Ok(timelock)
+    }
+
+    /// `<n> OP_CHECKLOCKTIMEVERIFY OP_DROP` or `<n> OP_CHECKSEQUENCEVERIFY OP_DROP`.
+    fn push(&self, builder: Builder) -> Builder {
+        let (value, opcode) = match self {
+            Timelock::Absolute(lock_time) => (lock_time.to_consensus_u32(), OP_CLTV),
+            Timelock::Relative(sequence) => (sequence.to_consensus_u32(), OP_CSV),
+        };
+
+        builder
+            .push_int(i64::from(value))
+            .push_opcode(opcode)
+            .push_opcode(OP_DROP)
+    }
+}
+
+/// The spending condition of a timelocked output.
+pub enum TimelockCondition {
+    /// The key can spend the output after the timelock.
+    Key(bitcoin::PublicKey),
+    /// A hashed timelock contract.
+    Htlc {
+        payment_hash: sha256::Hash,
+        receiver: bitcoin::PublicKey,
+        refund: bitcoin::PublicKey,
+    },
+}
+
+/// A timelocked spending condition, committed to either as P2WSH witness
+/// script or as Taproot leaves.
+pub struct TimelockScript {
+    condition: TimelockCondition,
+    timelock: Timelock,
+    script_type: Proto::TimelockScriptType,
+}
+
+impl TimelockScript {
+    pub fn from_key_proto(proto: &Proto::TimelockedKey<'_>) -> Result<Self> {
+        Ok(TimelockScript {
+            condition: TimelockCondition::Key(public_key(proto.public_key.as_ref())?),
+            timelock: Timelock::from_proto(proto.timelock.as_ref())?,
+            script_type: proto.script_type,
+        })
+    }
+
+    pub fn from_htlc_proto(proto: &Proto::Htlc<'_>) -> Result<Self> {
+        let payment_hash = sha256::Hash::from_slice(proto.payment_hash.as_ref())
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_htlc))?;
+
+        Ok(TimelockScript {
+            condition: TimelockCondition::Htlc {
+                payment_hash,
+                receiver: public_key(proto.receiver_public_key.as_ref())?,
+                refund: public_key(proto.refund_public_key.as_ref())?,
+            },
+            timelock: Timelock::from_proto(proto.timelock.as_ref())?,
+            script_type: proto.script_type,
+        })
+    }
+
+    pub fn timelock(&self) -> Timelock {
+        self.timelock
+    }
+
+    /// The P2WSH witness script:
+    ///
+    /// `<timelock> OP_DROP <key> OP_CHECKSIG`, or for HTLCs
+    /// `OP_IF OP_SIZE <32> OP_EQUALVERIFY OP_SHA256 <hash> OP_EQUALVERIFY <receiver>
+    /// OP_ELSE <timelock> OP_DROP <refund> OP_ENDIF OP_CHECKSIG`.
+    pub fn witness_script(&self) -> ScriptBuf {
+        match &self.condition {
+            TimelockCondition::Key(pubkey) => self
+                .timelock
+                .push(Builder::new())
+                .push_key(pubkey)
+                .push_opcode(OP_CHECKSIG)
+                .into_script(),
+            TimelockCondition::Htlc {
+                payment_hash,
+                receiver,
+                refund,
+            } => {
+                let builder = Builder::new().push_opcode(OP_IF);
+                let builder = push_hashlock(builder, payment_hash)
+                    .push_key(receiver)
+                    .push_opcode(OP_ELSE);
+
+                self.timelock
+                    .push(builder)
+                    .push_key(refund)
+                    .push_opcode(OP_ENDIF)
+                    .push_opcode(OP_CHECKSIG)
+                    .into_script()
+            },
+        }
+    }
+
+    /// The Taproot leaf that can be spent after the timelock:
+    /// `<timelock> OP_DROP <key> OP_CHECKSIG`.
+    pub fn timelock_leaf(&self) -> ScriptBuf {
+        let pubkey = match &self.condition {
+            TimelockCondition::Key(pubkey) => pubkey,
+            TimelockCondition::Htlc { refund, .. } => refund,
+        };
+
+        self.timelock
+            .push(Builder::new())
+            .push_x_only_key(&XOnlyPublicKey::from(pubkey.inner))
+            .push_opcode(OP_CHECKSIG)
+            .into_script()
+    }
+
+    /// The Taproot leaf of an HTLC that can be spent with the preimage:
+    /// `OP_SIZE <32> OP_EQUALVERIFY OP_SHA256 <hash> OP_EQUALVERIFY <receiver> OP_CHECKSIG`.
+    pub fn hashlock_leaf(&self) -> Option<ScriptBuf> {
+        match &self.condition {
+            TimelockCondition::Key(_) => None,
+            TimelockCondition::Htlc {
+                payment_hash,
+                receiver,
+                ..
+            } => Some(
+                push_hashlock(Builder::new(), payment_hash)
+                    .push_x_only_key(&XOnlyPublicKey::from(receiver.inner))
+                    .push_opcode(OP_CHECKSIG)
+                    .into_script(),
+            ),
+        }
+    }
+
+    /// The Taproot tree with the unspendable NUMS point as internal key.
+    pub fn spend_info(&self) -> TaprootSpendInfo {
+        let internal_key =
+            XOnlyPublicKey::from_slice(&NUMS_INTERNAL_KEY).expect("valid NUMS point");
+
+        let builder = match self.hashlock_leaf() {
+            None => TaprootBuilder::new().add_leaf(0, self.timelock_leaf()),
+            Some(hashlock_leaf) => TaprootBuilder::new()
+                .add_leaf(1, hashlock_leaf)
+                .and_then(|builder| builder.add_leaf(1, self.timelock_leaf())),
+        };
+
+        builder
+            .expect("timelock spending info must always build")
+            .finalize(&secp256k1::Secp256k1::new(), internal_key)
+            .expect("timelock spending info must always build")
+    }
+
+    /// The spending condition of the output.
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        match self.script_type {
+            Proto::TimelockScriptType::P2WSH => {
+                ScriptBuf::new_v0_p2wsh(&self.witness_script().wscript_hash())
+            },
+            Proto::TimelockScriptType::P2TR => {
+                let spend_info = self.spend_info();
+                ScriptBuf::new_v1_p2tr_tweaked(spend_info.output_key())
+            },
+        }
+    }
+}
+
+/// How a timelocked output is spent.
+pub enum SpendPath<'a> {
+    /// With the key after the timelock (or the refund key of an HTLC).
+    Timelock,
+    /// With the receiver key of an HTLC and the preimage of the payment hash.
+    Preimage(&'a [u8]),
+}
+
+/// A timelocked input.
+pub struct TimelockSpend<'a> {
+    script: TimelockScript,
+    path: SpendPath<'a>,
+    one_prevout: bool,
+}
+
+impl<'a> TimelockSpend<'a> {
+    /// Returns the timelocked spending condition of the input, if any.
+    pub fn from_input(input: &'a Proto::Input<'_>) -> Result<Option<Self>> {
+        let builder = match &input.to_recipient {
+            ProtoInputRecipient::builder(builder) => &builder.variant,
+            _ => return Ok(None),
+        };
+
+        let spend = match builder {
+            ProtoInputBuilder::timelocked_key(input) => {
+                let proto = input
+                    .timelocked_key
+                    .as_ref()
+                    .ok_or_else(|| Error::from(Proto::Error::Error_invalid_timelock))?;
+
+                TimelockSpend {
+                    script: TimelockScript::from_key_proto(proto)?,
+                    path: SpendPath::Timelock,
+                    one_prevout: input.one_prevout,
+                }
+            },
+            ProtoInputBuilder::htlc(input) => {
+                let proto = input
+                    .htlc
+                    .as_ref()
+                    .ok_or_else(|| Error::from(Proto::Error::Error_invalid_htlc))?;
+                let script = TimelockScript::from_htlc_proto(proto)?;
+
+                let path = if input.preimage.is_empty() {
+                    SpendPath::Timelock
+                } else {
+                    SpendPath::Preimage(input.preimage.as_ref())
+                };
+
+                TimelockSpend {
+                    script,
+                    path,
+                    one_prevout: input.one_prevout,
+                }
+            },
+            _ => return Ok(None),
+        };
+
+        Ok(Some(spend))
+    }
+
+    /// The timelock that must have expired to spend the input, if any.
+    pub fn timelock(&self) -> Option<Timelock> {
+        match self.path {
+            SpendPath::Timelock => Some(self.script.timelock()),
+            SpendPath::Preimage(_) => None,
+        }
+    }
+
+    /// The scriptPubkey of the spent output.
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        self.script.script_pubkey()
+    }
+
+    // The spent Taproot leaf.
+    fn leaf(&self) -> ScriptBuf {
+        match self.path {
+            SpendPath::Timelock => self.script.timelock_leaf(),
+            SpendPath::Preimage(_) => self
+                .script
+                .hashlock_leaf()
+                .expect("preimage spends are only created for HTLCs"),
+        }
+    }
+
+    /// The signing method, the script that is committed to by the sighash
+    /// (the _scriptCode_ or the leaf) and the leaf hash.
+    pub fn signing_method(&self) -> (UtxoProto::SigningMethod, ScriptBuf, Option<TapLeafHash>) {
+        match self.script.script_type {
+            Proto::TimelockScriptType::P2WSH => (
+                UtxoProto::SigningMethod::Segwit,
+                self.script.witness_script(),
+                None,
+            ),
+            Proto::TimelockScriptType::P2TR => {
+                let signing_method = if self.one_prevout {
+                    UtxoProto::SigningMethod::TaprootOnePrevout
+                } else {
+                    UtxoProto::SigningMethod::TaprootAll
+                };
+
+                let leaf = self.leaf();
+                let leaf_hash = TapLeafHash::from_script(&leaf, LeafVersion::TapScript);
+                (signing_method, leaf, Some(leaf_hash))
+            },
+        }
+    }
+
+    /// The estimated weight of the witness.
+    #[rustfmt::skip]
+    pub fn weight_estimate(&self) -> u64 {
+        let path = match self.path {
+            // length + preimage
+            SpendPath::Preimage(_) => 1 + PREIMAGE_SIZE as u64,
+            SpendPath::Timelock => 0,
+        };
+
+        // witness bytes, scale factor NOT applied.
+        match self.script.script_type {
+            Proto::TimelockScriptType::P2WSH => {
+                let branch = match self.script.condition {
+                    // length + `OP_IF` branch selector
+                    TimelockCondition::Htlc { .. } => 1 + 1,
+                    TimelockCondition::Key(_) => 0,
+                };
+
+                // indicator of witness item count
+                1 +
+                // length + ECDSA signature (can be 71 or 72)
+                1 + 72 +
+                path +
+                branch +
+                // length + witness script
+                1 + self.script.witness_script().len() as u64
+            },
+            Proto::TimelockScriptType::P2TR => {
+                let leaf = self.leaf();
+                let control_block = self
+                    .script
+                    .spend_info()
+                    .control_block(&(leaf.clone(), LeafVersion::TapScript))
+                    .expect("badly constructed control block");
+
+                // indicator of witness item count
+                1 +
+                // length + Schnorr signature (64 or 65)
+                1 + 65 +
+                path +
+                // length + leaf
+                1 + leaf.len() as u64 +
+                // length + control block
+                1 + control_block.size() as u64
+            },
+        }
+    }
+
+    /// Creates the witness from the signature of the spending key. If
+    /// `fork_id` is set, the `SIGHASH_FORKID` flag is added to ECDSA signatures.
+    pub fn claim(&self, signature: &[u8], fork_id: bool) -> Result<Witness> {
+        if let SpendPath::Preimage(preimage) = self.path {
+            let matches = match &self.script.condition {
+                TimelockCondition::Htlc { payment_hash, .. } => {
+                    sha256::Hash::hash(preimage) == *payment_hash
+                },
+                TimelockCondition::Key(_) => false,
+            };
+
+            if preimage.len() != PREIMAGE_SIZE || !matches {
+                return Err(Error::from(Proto::Error::Error_invalid_htlc));
+            }
+        }
+
+        let mut witness = Witness::new();
+
+        match self.script.script_type {
+            Proto::TimelockScriptType::P2WSH => {
+                let sig = legacy_signature(signature, fork_id)?;
+                witness.push(sig.as_bytes());
+
+                if let TimelockCondition::Htlc { .. } = self.script.condition {
+                    match self.path {
+                        // Selects the `OP_IF` branch (minimal encoding).
+                        SpendPath::Preimage(preimage) => {
+                            witness.push(preimage);
+                            witness.push([1]);
+                        },
+                        // Selects the `OP_ELSE` branch.
+                        SpendPath::Timelock => witness.push([]),
+                    }
+                }
+
+                witness.push(self.script.witness_script().as_bytes());
+            },
+            Proto::TimelockScriptType::P2TR => {
+                let sig = bitcoin::taproot::Signature::from_slice(signature)?;
+                witness.push(sig.to_vec());
+
+                if let SpendPath::Preimage(preimage) = self.path {
+                    witness.push(preimage);
+                }
+
+                let leaf = self.leaf();
+                let control_block = self
+                    .script
+                    .spend_info()
+                    .control_block(&(leaf.clone(), LeafVersion::TapScript))
+                    .expect("badly constructed control block");
+
+                witness.push(leaf.as_bytes());
+                witness.push(control_block.serialize());
+            },
+        }
+
+        Ok(witness)
+    }
+}
+
+/// Sets the sequence of timelocked inputs and the lock time of the
+/// transaction, unless provided. Provided values must satisfy the timelocks.
+pub(crate) fn apply_timelocks(
+    mut proto: Proto::SigningInput<'_>,
+) -> Result<Proto::SigningInput<'_>> {
+    let conflict = || Error::from(Proto::Error::Error_timelock_conflict);
+
+    let mut required_lock_time: Option<LockTime> = None;
+
+    for input in proto.inputs.iter_mut() {
+        let timelock = match TimelockSpend::from_input(input)? {
+            Some(spend) => spend.timelock(),
+            None => continue,
+        };
+
+        match timelock {
+            Some(Timelock::Relative(sequence)) => {
+                // Relative timelocks require version 2 transactions (BIP-68).
+                if proto.version < 2 {
+                    return Err(conflict());
+                }
+
+                if input.sequence == Sequence::MAX.to_consensus_u32() {
+                    input.sequence = sequence.to_consensus_u32();
+                }
+
+                let required = sequence.to_relative_lock_time().ok_or_else(conflict)?;
+                let satisfied = Sequence::from_consensus(input.sequence)
+                    .to_relative_lock_time()
+                    .map_or(false, |provided| required.is_implied_by(provided));
+
+                if !satisfied {
+                    return Err(conflict());
+                }
+            },
+            Some(Timelock::Absolute(lock_time)) => {
+                // The lock time is only enforced for non-final inputs.
+                if input.sequence == Sequence::MAX.to_consensus_u32() {
+                    input.sequence = Sequence::ENABLE_LOCKTIME_NO_RBF.to_consensus_u32();
+                }
+
+                required_lock_time = match required_lock_time {
+                    None => Some(lock_time),
+                    Some(required) if required.is_same_unit(lock_time) => {
+                        Some(std::cmp::max_by_key(required, lock_time, |lock_time| {
+                            lock_time.to_consensus_u32()
+                        }))
+                    },
+                    // Height and time based timelocks cannot be combined.
+                    Some(_) => return Err(conflict()),
+                };
+            },
+            None => (),
+        }
+    }
+
+    if let Some(required) = required_lock_time {
+        let provided = lock_time_from_proto(&proto.lock_time)?;
+
+        if provided == LockTime::ZERO {
+            proto.lock_time = Some(lock_time_to_proto(required));
+        } else if !required.is_implied_by(provided) {
+            return Err(conflict());
+        }
+    }
+
+    Ok(proto)
+}
+
+fn lock_time_to_proto(lock_time: LockTime) -> UtxoProto::LockTime {
+    use UtxoProto::mod_LockTime::OneOfvariant as ProtoLockTimeVariant;
+
+    let variant = match lock_time {
+        LockTime::Blocks(height) => ProtoLockTimeVariant::blocks(height.to_consensus_u32()),
+        LockTime::Seconds(time) => ProtoLockTimeVariant::seconds(time.to_consensus_u32()),
+    };
+
+    UtxoProto::LockTime { variant }
+}
+
+// `OP_SIZE <32> OP_EQUALVERIFY OP_SHA256 <hash> OP_EQUALVERIFY`
+fn push_hashlock(builder: Builder, payment_hash: &sha256::Hash) -> Builder {
+    builder
+        .push_opcode(OP_SIZE)
+        .push_int(PREIMAGE_SIZE as i64)
+        .push_opcode(OP_EQUALVERIFY)
+        .push_opcode(OP_SHA256)
+        .push_slice(payment_hash.to_byte_array())
+        .push_opcode(OP_EQUALVERIFY)
+}
+
+// Segwit only allows compressed public keys.
+fn public_key(pubkey: &[u8]) -> Result<bitcoin::PublicKey> {
+    let pubkey = bitcoin::PublicKey::from_slice(pubkey)?;
+    if !pubkey.compressed {
+        return Err(Error::from(Proto::Error::Error_invalid_public_key));
+    }
+
+    Ok(pubkey)
+}
@@ -1,9 +1,14 @@
 use super::brc20::{BRC20TransferInscription, Brc20Ticker};
 use crate::aliases::*;
-use crate::modules::transactions::OrdinalNftInscription;
+use crate::modules::descriptor::WalletDescriptor;
+use crate::modules::multisig::MultisigScript;
+use crate::modules::musig2;
+use crate::modules::timelock::TimelockSpend;
+use crate::modules::transactions::{OrdinalNftInscription, OrdinalsBatch, Rune, RuneCommitment};
 use crate::{Error, Result};
+use bitcoin::address::WitnessVersion;
 use bitcoin::taproot::{LeafVersion, TapLeafHash};
-use bitcoin::ScriptBuf;
+use bitcoin::{ScriptBuf, VarInt};
 use secp256k1::XOnlyPublicKey;
 use tw_misc::traits::ToBytesVec;
 use tw_proto::BitcoinV2::Proto;
@@ -261,6 +266,224 @@ impl InputBuilder {
                         ),
                     )
                 },
+                ProtoInputBuilder::descriptor(descriptor) => {
+                    let wallet = WalletDescriptor::new(
+                        descriptor.descriptor.as_ref(),
+                        descriptor.derivation_index,
+                    )?;
+
+                    // scriptSig and witness weight, as reported by Miniscript.
+                    let weight = wallet.max_weight_to_satisfy()?;
+
+                    match wallet.witness_version() {
+                        // The sighash commits to the redeem script (or P2PKH script).
+                        None => (
+                            UtxoProto::SigningMethod::Legacy,
+                            wallet.script_code()?,
+                            NO_LEAF_HASH,
+                            weight,
+                        ),
+                        // The sighash commits to the witness script (or P2WPKH script code).
+                        Some(WitnessVersion::V0) => (
+                            UtxoProto::SigningMethod::Segwit,
+                            wallet.script_code()?,
+                            NO_LEAF_HASH,
+                            weight,
+                        ),
+                        // Taproot; the sighash commits to the actual scriptPubkey
+                        // and, for script-path spends, to the leaf hash.
+                        Some(_) => {
+                            let leaf_hash = if descriptor.leaf_script.is_empty() {
+                                // The signer does not tweak the key with a Merkle
+                                // root, so key-path spends are limited to
+                                // descriptors without a script tree.
+                                if wallet.has_taproot_tree() {
+                                    return Err(Error::from(
+                                        Proto::Error::Error_invalid_descriptor_leaf_script,
+                                    ));
+                                }
+
+                                NO_LEAF_HASH
+                            } else {
+                                let leaf_script =
+                                    ScriptBuf::from_bytes(descriptor.leaf_script.to_vec());
+                                let (leaf_hash, _) = wallet.taproot_leaf(&leaf_script)?;
+                                Some(leaf_hash)
+                            };
+
+                            (
+                                UtxoProto::SigningMethod::TaprootAll,
+                                wallet.script_pubkey(),
+                                leaf_hash,
+                                weight,
+                            )
+                        },
+                    }
+                },
+                ProtoInputBuilder::multisig(input_multisig) => {
+                    let multisig = MultisigScript::from_proto(
+                        input_multisig
+                            .multisig
+                            .as_ref()
+                            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_multisig))?,
+                    )?;
+
+                    // The sighash commits to the redeem script (P2SH) or the
+                    // witness script (P2WSH, P2SH-P2WSH).
+                    let (signing_method, script_code) = multisig.signing_method();
+
+                    (
+                        signing_method,
+                        script_code,
+                        NO_LEAF_HASH,
+                        multisig.weight_estimate(),
+                    )
+                },
+                ProtoInputBuilder::p2tr_musig2(input_musig2) => {
+                    let musig2 = input_musig2
+                        .musig2
+                        .as_ref()
+                        .ok_or_else(|| Error::from(Proto::Error::Error_invalid_musig2))?;
+                    let xonly = musig2::internal_key(musig2)?;
+
+                    let signing_method = if input_musig2.one_prevout {
+                        UtxoProto::SigningMethod::TaprootOnePrevout
+                    } else {
+                        UtxoProto::SigningMethod::TaprootAll
+                    };
+
+                    let script_pubkey =
+                        ScriptBuf::new_v1_p2tr(&secp256k1::Secp256k1::new(), xonly, None);
+
+                    (
+                        signing_method,
+                        script_pubkey,
+                        NO_LEAF_HASH,
+                        // witness bytes, scale factor NOT applied.
+                        (
+                            // indicator of witness item (1)
+                            1 +
+                            // length + aggregated Schnorr signature (can be 64 or 65)
+                            1 + 65
+                        ),
+                    )
+                },
+                ProtoInputBuilder::rune_commit(commit) => {
+                    let pubkey = bitcoin::PublicKey::from_slice(commit.commit_to.as_ref())?;
+                    let rune = Rune::from_name(commit.rune.as_ref())?;
+
+                    let commitment = RuneCommitment::new(rune, pubkey);
+
+                    // We construct a control block to estimate the fee,
+                    // otherwise we do not need it here.
+                    let control_block = commitment
+                        .spend_info()
+                        .control_block(&(
+                            commitment.taproot_program().to_owned(),
+                            LeafVersion::TapScript,
+                        ))
+                        .expect("badly constructed control block");
+
+                    let leaf_hash = Some(TapLeafHash::from_script(
+                        commitment.taproot_program(),
+                        bitcoin::taproot::LeafVersion::TapScript,
+                    ));
+
+                    let signing_method = if commit.one_prevout {
+                        UtxoProto::SigningMethod::TaprootOnePrevout
+                    } else {
+                        UtxoProto::SigningMethod::TaprootAll
+                    };
+
+                    let script_pubkey = ScriptBuf::from(commitment.taproot_program());
+
+                    (
+                        signing_method,
+                        script_pubkey,
+                        leaf_hash,
+                        // witness bytes, scale factor NOT applied.
+                        (
+                            // indicator of witness item (1)
+                            1 +
+                            // length + Schnorr signature (can be 71 or 72)
+                            1 + 72 +
+                            // the payload/witness
+                            commitment.taproot_program().len() as u64 +
+                            // length + control block
+                            1 + control_block.size() as u64
+                        ),
+                    )
+                },
+                ProtoInputBuilder::ordinal_batch(ordinal) => {
+                    let pubkey = bitcoin::PublicKey::from_slice(ordinal.inscribe_to.as_ref())?;
+                    let batch = OrdinalsBatch::from_proto(&ordinal.inscriptions, pubkey)?;
+
+                    // We construct a control block to estimate the fee,
+                    // otherwise we do not need it here.
+                    let control_block = batch
+                        .spend_info()
+                        .control_block(&(
+                            batch.taproot_program().to_owned(),
+                            LeafVersion::TapScript,
+                        ))
+                        .expect("badly constructed control block");
+
+                    let leaf_hash = Some(TapLeafHash::from_script(
+                        batch.taproot_program(),
+                        bitcoin::taproot::LeafVersion::TapScript,
+                    ));
+
+                    let signing_method = if ordinal.one_prevout {
+                        UtxoProto::SigningMethod::TaprootOnePrevout
+                    } else {
+                        UtxoProto::SigningMethod::TaprootAll
+                    };
+
+                    // The Schnorr signature is extended by the sighash type,
+                    // unless the default is used.
+                    let sig_len = if input.sighash_type == UtxoProto::SighashType::UseDefault {
+                        64
+                    } else {
+                        65
+                    };
+                    let script_len = batch.taproot_program().len();
+
+                    let script_pubkey = ScriptBuf::from(batch.taproot_program());
+
+                    (
+                        signing_method,
+                        script_pubkey,
+                        leaf_hash,
+                        // witness bytes, scale factor NOT applied. Unlike the
+                        // other inscriptions, the size is exact, since the
+                        // script can span several kilobytes.
+                        (
+                            // number of witness items (3)
+                            1 +
+                            // length + Schnorr signature
+                            1 + sig_len +
+                            // length + the payload/witness
+                            VarInt(script_len as u64).len() as u64 + script_len as u64 +
+                            // length + control block
+                            1 + control_block.size() as u64
+                        ),
+                    )
+                },
+                ProtoInputBuilder::timelocked_key(_) | ProtoInputBuilder::htlc(_) => {
+                    let spend =
+                        TimelockSpend::from_input(input)?.expect("timelocked input builder");
+
+                    // The sighash commits to the witness script (P2WSH) or
+                    // the spent leaf (P2TR).
+                    let (signing_method, script_code, leaf_hash) = spend.signing_method();
+
+                    (
+                        signing_method,
+                        script_code,
+                        leaf_hash,
+                        spend.weight_estimate(),
+                    )
+                },
                 ProtoInputBuilder::None => {
                     return Err(Error::from(Proto::Error::Error_missing_input_builder))
                 },
@@ -316,8 +539,61 @@ impl InputBuilder {
             leaf_hash: leaf_hash
                 .map(|hash| hash.to_vec().into())
                 .unwrap_or_default(),
+            protection: input.protection.clone(),
+            prevout_script_pubkey: Self::prevout_script_pubkey(input)?
+                .map(|script| script.to_vec().into())
+                .unwrap_or_default(),
         };
 
         Ok(utxo)
     }
+
+    /// Returns the scriptPubkey of the spent output if it differs from the
+    /// script that is committed to by the sighash (e.g. the redeem script of
+    /// P2SH inputs). Taproot and Zcash NU5 sighashes commit to the
+    /// scriptPubkeys of all the spent outputs.
+    fn prevout_script_pubkey(input: &Proto::Input<'_>) -> Result<Option<ScriptBuf>> {
+        let ProtoInputRecipient::builder(builder) = &input.to_recipient else {
+            return Ok(None);
+        };
+
+        let script_pubkey = match &builder.variant {
+            ProtoInputBuilder::p2sh(redeem_script) => {
+                let redeem_script = ScriptBuf::from_bytes(redeem_script.to_vec());
+                ScriptBuf::new_p2sh(&redeem_script.script_hash())
+            },
+            ProtoInputBuilder::p2wsh(redeem_script) => {
+                let redeem_script = ScriptBuf::from_bytes(redeem_script.to_vec());
+                ScriptBuf::new_v0_p2wsh(&redeem_script.wscript_hash())
+            },
+            ProtoInputBuilder::p2wpkh(pubkey) => {
+                let pubkey = bitcoin::PublicKey::from_slice(pubkey.as_ref())?;
+                ScriptBuf::new_v0_p2wpkh(
+                    &pubkey.wpubkey_hash().ok_or_else(|| {
+                        Error::from(Proto::Error::Error_invalid_witness_pubkey_hash)
+                    })?,
+                )
+            },
+            ProtoInputBuilder::descriptor(descriptor) => {
+                WalletDescriptor::new(descriptor.descriptor.as_ref(), descriptor.derivation_index)?
+                    .script_pubkey()
+            },
+            ProtoInputBuilder::multisig(input_multisig) => MultisigScript::from_proto(
+                input_multisig
+                    .multisig
+                    .as_ref()
+                    .ok_or_else(|| Error::from(Proto::Error::Error_invalid_multisig))?,
+            )?
+            .script_pubkey(),
+            ProtoInputBuilder::timelocked_key(_) | ProtoInputBuilder::htlc(_) => {
+                TimelockSpend::from_input(input)?
+                    .expect("timelocked input builder")
+                    .script_pubkey()
+            },
+            // The script that is committed to is the scriptPubkey.
+            _ => return Ok(None),
+        };
+
+        Ok(Some(script_pubkey))
+    }
 }
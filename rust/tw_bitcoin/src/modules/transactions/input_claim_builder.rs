@@ -1,11 +1,19 @@
 use super::brc20::{BRC20TransferInscription, Brc20Ticker};
-use super::OrdinalNftInscription;
+use super::{OrdinalNftInscription, OrdinalsBatch, Rune, RuneCommitment};
 use crate::aliases::*;
+use crate::modules::chain_info::legacy_signature;
+use crate::modules::descriptor::{
+    lock_time_from_proto, x_only_pubkey_from_slice, DescriptorSatisfier, WalletDescriptor,
+};
+use crate::modules::multisig::MultisigScript;
+use crate::modules::timelock::TimelockSpend;
 use crate::{Error, Result};
+use bitcoin::address::WitnessVersion;
 use bitcoin::consensus::Decodable;
 use bitcoin::taproot::{ControlBlock, LeafVersion};
-use bitcoin::{ScriptBuf, Witness};
+use bitcoin::{ScriptBuf, Sequence, Witness};
 use std::borrow::Cow;
+use std::collections::HashMap;
 use tw_coin_entry::coin_entry::SignatureBytes;
 use tw_misc::traits::ToBytesVec;
 use tw_proto::BitcoinV2::Proto;
@@ -15,10 +23,14 @@ pub struct InputClaimBuilder;
 
 impl InputClaimBuilder {
     /// Creates the claim script (_scriptSig_ or _Witness_) to be revealed
-    /// on-chain for a given input.
+    /// on-chain for a given input. The `lock_time` of the transaction is
+    /// required to satisfy absolute timelocks. If `fork_id` is set, the
+    /// `SIGHASH_FORKID` flag is added to the ECDSA signatures.
     pub fn utxo_claim_from_proto(
         input: &Proto::Input<'_>,
         signature: SignatureBytes,
+        lock_time: &Option<UtxoProto::LockTime>,
+        fork_id: bool,
     ) -> Result<UtxoProto::TxInClaim<'static>> {
         let (script_sig, witness) = match &input.to_recipient {
             ProtoInputRecipient::builder(variant) => match &variant.variant {
@@ -27,13 +39,13 @@ impl InputClaimBuilder {
                     Witness::new(),
                 ),
                 ProtoInputBuilder::p2pkh(pubkey) => {
-                    let sig = bitcoin::ecdsa::Signature::from_slice(signature.as_ref())?;
+                    let sig = legacy_signature(signature.as_ref(), fork_id)?;
                     let pubkey = bitcoin::PublicKey::from_slice(pubkey.as_ref())?;
 
                     // The spending script itself.
                     (
                         ScriptBuf::builder()
-                            .push_slice(sig.serialize())
+                            .push_slice(sig)
                             .push_key(&pubkey)
                             .into_script(),
                         Witness::new(),
@@ -46,13 +58,13 @@ impl InputClaimBuilder {
                     (ScriptBuf::new(), witness)
                 },
                 ProtoInputBuilder::p2wpkh(pubkey) => {
-                    let sig = bitcoin::ecdsa::Signature::from_slice(signature.as_ref())?;
+                    let sig = legacy_signature(signature.as_ref(), fork_id)?;
                     let pubkey = bitcoin::PublicKey::from_slice(pubkey.as_ref())?;
 
                     // The spending script itself.
                     (ScriptBuf::new(), {
                         let mut w = Witness::new();
-                        w.push(sig.serialize());
+                        w.push(sig.as_bytes());
                         w.push(pubkey.to_bytes());
                         w
                     })
@@ -144,6 +156,155 @@ impl InputClaimBuilder {
                         w
                     })
                 },
+                ProtoInputBuilder::descriptor(descriptor) => {
+                    let wallet = WalletDescriptor::new(
+                        descriptor.descriptor.as_ref(),
+                        descriptor.derivation_index,
+                    )?;
+
+                    let mut satisfier = DescriptorSatisfier::default()
+                        .with_sequence(Sequence::from_consensus(input.sequence))
+                        .with_lock_time(lock_time_from_proto(lock_time)?);
+
+                    let is_taproot = wallet
+                        .witness_version()
+                        .map_or(false, |version| version != WitnessVersion::V0);
+
+                    if is_taproot && !descriptor.leaf_script.is_empty() {
+                        let leaf_script = ScriptBuf::from_bytes(descriptor.leaf_script.to_vec());
+                        let (leaf_hash, _) = wallet.taproot_leaf(&leaf_script)?;
+                        satisfier = satisfier.with_leaf_hash(leaf_hash);
+                    }
+
+                    // The signature passed on by `sign` or `compile`, followed by
+                    // the signatures collected from other keys.
+                    let own_signature = (!descriptor.public_key.is_empty()
+                        && !signature.is_empty())
+                    .then_some((descriptor.public_key.as_ref(), signature.as_slice()));
+
+                    let collected = descriptor
+                        .signatures
+                        .iter()
+                        .map(|sig| (sig.public_key.as_ref(), sig.signature.as_ref()));
+
+                    for (pubkey, sig) in own_signature.into_iter().chain(collected) {
+                        if !is_taproot {
+                            satisfier.add_ecdsa_sig(
+                                bitcoin::PublicKey::from_slice(pubkey)?,
+                                legacy_signature(sig, fork_id)?,
+                            )?;
+                        } else if descriptor.leaf_script.is_empty() {
+                            satisfier
+                                .set_key_spend_sig(bitcoin::taproot::Signature::from_slice(sig)?);
+                        } else {
+                            satisfier.add_schnorr_sig(
+                                x_only_pubkey_from_slice(pubkey)?,
+                                bitcoin::taproot::Signature::from_slice(sig)?,
+                            );
+                        }
+                    }
+
+                    for preimage in &descriptor.preimages {
+                        satisfier.add_preimage(preimage)?;
+                    }
+
+                    let (script_sig, witness) = wallet.satisfy(&satisfier)?;
+                    (script_sig, Witness::from_slice(&witness))
+                },
+                ProtoInputBuilder::multisig(input_multisig) => {
+                    let multisig = MultisigScript::from_proto(
+                        input_multisig
+                            .multisig
+                            .as_ref()
+                            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_multisig))?,
+                    )?;
+
+                    let mut signatures = HashMap::new();
+
+                    // The signatures collected from other cosigners.
+                    for partial in &input_multisig.signatures {
+                        signatures.insert(
+                            bitcoin::PublicKey::from_slice(partial.public_key.as_ref())?,
+                            legacy_signature(partial.signature.as_ref(), fork_id)?,
+                        );
+                    }
+
+                    // The signature passed on by `sign` or `compile`, if any.
+                    if !input_multisig.public_key.is_empty() && !signature.is_empty() {
+                        signatures.insert(
+                            bitcoin::PublicKey::from_slice(input_multisig.public_key.as_ref())?,
+                            legacy_signature(signature.as_ref(), fork_id)?,
+                        );
+                    }
+
+                    multisig.claim(&signatures)?
+                },
+                ProtoInputBuilder::p2tr_musig2(_) => {
+                    // The aggregated MuSig2 signature is an ordinary Schnorr signature.
+                    let sig = bitcoin::taproot::Signature::from_slice(signature.as_ref())?;
+
+                    (ScriptBuf::new(), {
+                        let mut w = Witness::new();
+                        w.push(sig.to_vec());
+                        w
+                    })
+                },
+                ProtoInputBuilder::rune_commit(commit) => {
+                    let pubkey = bitcoin::PublicKey::from_slice(commit.commit_to.as_ref())?;
+                    let rune = Rune::from_name(commit.rune.as_ref())?;
+
+                    let commitment = RuneCommitment::new(rune, pubkey);
+
+                    // Create a control block for that commitment.
+                    let control_block = commitment
+                        .spend_info()
+                        .control_block(&(
+                            commitment.taproot_program().to_owned(),
+                            LeafVersion::TapScript,
+                        ))
+                        .expect("badly constructed control block");
+
+                    let sig = bitcoin::taproot::Signature::from_slice(signature.as_ref())?;
+
+                    // The spending script itself, revealing the commitment.
+                    (ScriptBuf::new(), {
+                        let mut w = Witness::new();
+                        w.push(sig.to_vec());
+                        w.push(commitment.taproot_program());
+                        w.push(control_block.serialize());
+                        w
+                    })
+                },
+                ProtoInputBuilder::ordinal_batch(ordinal) => {
+                    let pubkey = bitcoin::PublicKey::from_slice(ordinal.inscribe_to.as_ref())?;
+                    let batch = OrdinalsBatch::from_proto(&ordinal.inscriptions, pubkey)?;
+
+                    // Create a control block for that inscription batch.
+                    let control_block = batch
+                        .spend_info()
+                        .control_block(&(
+                            batch.taproot_program().to_owned(),
+                            LeafVersion::TapScript,
+                        ))
+                        .expect("badly constructed control block");
+
+                    let sig = bitcoin::taproot::Signature::from_slice(signature.as_ref())?;
+
+                    // The spending script itself, revealing the inscriptions.
+                    (ScriptBuf::new(), {
+                        let mut w = Witness::new();
+                        w.push(sig.to_vec());
+                        w.push(batch.taproot_program());
+                        w.push(control_block.serialize());
+                        w
+                    })
+                },
+                ProtoInputBuilder::timelocked_key(_) | ProtoInputBuilder::htlc(_) => {
+                    let spend =
+                        TimelockSpend::from_input(input)?.expect("timelocked input builder");
+
+                    (ScriptBuf::new(), spend.claim(signature.as_ref(), fork_id)?)
+                },
                 ProtoInputBuilder::None => {
                     return Err(Error::from(Proto::Error::Error_missing_input_builder))
                 },
@@ -7,13 +7,28 @@ mod input_builder;
 mod input_claim_builder;
 mod ordinals;
 mod output_builder;
+mod runes;
 
 // Re-exports
 pub use brc20::{BRC20TransferInscription, Brc20Ticker};
 pub use input_builder::InputBuilder;
 pub use input_claim_builder::InputClaimBuilder;
-pub use ordinals::{OrdinalNftInscription, OrdinalsInscription};
+pub use ordinals::{
+    Inscription, InscriptionEnvelope, InscriptionId, OrdinalNftInscription, OrdinalsBatch,
+    OrdinalsInscription,
+};
 pub use output_builder::OutputBuilder;
+pub use runes::{
+    Edict, Etching, Rune, RuneCommitment, RuneId, Runestone, SpacedRune, Terms,
+    COMMIT_CONFIRMATIONS,
+};
+
+/// The `H` point with unknown discrete logarithm (BIP-341). Used as internal
+/// key of Taproot outputs which can only be spent by script path.
+pub(crate) const NUMS_INTERNAL_KEY: [u8; 32] = [
+    0x50, 0x92, 0x9b, 0x74, 0xc1, 0xa0, 0x49, 0x54, 0xb7, 0x8b, 0x4b, 0x60, 0x35, 0xe9, 0x7a, 0x5e,
+    0x07, 0x8a, 0x5a, 0x0f, 0x28, 0xec, 0x96, 0xd5, 0x47, 0xbf, 0xee, 0x9a, 0xce, 0x80, 0x3a, 0xc0,
+];
 
 pub struct TaprootScript {
     pub pubkey: PublicKey,
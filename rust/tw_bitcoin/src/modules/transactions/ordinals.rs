@@ -1,9 +1,12 @@
 use super::TaprootProgram;
 use crate::{Error, Result};
-use bitcoin::script::{PushBytesBuf, ScriptBuf};
+use bitcoin::hashes::Hash;
+use bitcoin::script::Instruction;
+use bitcoin::script::{Builder as ScriptBuilder, PushBytesBuf, ScriptBuf};
 use bitcoin::secp256k1::XOnlyPublicKey;
 use bitcoin::taproot::{TaprootBuilder, TaprootSpendInfo};
-use bitcoin::{PublicKey, Script};
+use bitcoin::{PublicKey, Script, Txid};
+use std::str::FromStr;
 use tw_proto::BitcoinV2::Proto;
 
 pub struct OrdinalsInscription {
@@ -102,6 +105,103 @@ fn create_envelope(mime: &[u8], data: &[u8], internal_key: PublicKey) -> Result<
     Ok(TaprootProgram { script, spend_info })
 }
 
+/// The contents of an Ordinals envelope found in a Taproot script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InscriptionEnvelope {
+    pub mime_type: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+impl InscriptionEnvelope {
+    // The field tag identifying the MIME type.
+    const TAG_CONTENT_TYPE: u8 = 1;
+
+    /// Extracts all Ordinals envelopes (`OP_FALSE OP_IF "ord" ... OP_ENDIF`)
+    /// from the given Taproot script. Malformed envelopes are skipped.
+    pub fn parse_envelopes(script: &Script) -> Vec<InscriptionEnvelope> {
+        use bitcoin::opcodes::all::*;
+
+        let Ok(instructions) = script
+            .instructions()
+            .collect::<std::result::Result<Vec<_>, _>>()
+        else {
+            return vec![];
+        };
+
+        let mut envelopes = vec![];
+        let mut idx = 0;
+        while idx + 2 < instructions.len() {
+            let is_envelope_start = matches!(
+                (&instructions[idx], &instructions[idx + 1], &instructions[idx + 2]),
+                (Instruction::PushBytes(empty), Instruction::Op(OP_IF), Instruction::PushBytes(protocol))
+                    if empty.is_empty() && protocol.as_bytes() == b"ord"
+            );
+
+            if !is_envelope_start {
+                idx += 1;
+                continue;
+            }
+
+            // Collect all the pushes until `OP_ENDIF`.
+            let mut pushes = vec![];
+            let mut end = None;
+            for (offset, instruction) in instructions[idx + 3..].iter().enumerate() {
+                match instruction {
+                    Instruction::PushBytes(push) => pushes.push(push.as_bytes().to_vec()),
+                    // Tags might also be pushed as `OP_PUSHNUM_N`.
+                    Instruction::Op(op)
+                        if (OP_PUSHNUM_1.to_u8()..=OP_PUSHNUM_16.to_u8()).contains(&op.to_u8()) =>
+                    {
+                        pushes.push(vec![op.to_u8() - OP_PUSHNUM_1.to_u8() + 1])
+                    },
+                    Instruction::Op(OP_ENDIF) => {
+                        end = Some(idx + 3 + offset);
+                        break;
+                    },
+                    // Any other opcode invalidates the envelope.
+                    Instruction::Op(_) => break,
+                }
+            }
+
+            let Some(end) = end else {
+                idx += 3;
+                continue;
+            };
+
+            if let Some(envelope) = Self::from_pushes(pushes) {
+                envelopes.push(envelope);
+            }
+
+            idx = end + 1;
+        }
+
+        envelopes
+    }
+
+    /// Interprets the pushes of an envelope as `<tag> <value>` pairs followed
+    /// by the body, which is introduced by an empty push.
+    fn from_pushes(pushes: Vec<Vec<u8>>) -> Option<InscriptionEnvelope> {
+        let mut mime_type = vec![];
+        let mut payload = vec![];
+
+        let mut iter = pushes.into_iter();
+        while let Some(tag) = iter.next() {
+            // The body separator, all remaining pushes are content chunks.
+            if tag.is_empty() {
+                iter.by_ref().for_each(|chunk| payload.extend(chunk));
+                break;
+            }
+
+            let value = iter.next()?;
+            if tag == [Self::TAG_CONTENT_TYPE] {
+                mime_type = value;
+            }
+        }
+
+        Some(InscriptionEnvelope { mime_type, payload })
+    }
+}
+
 pub struct OrdinalNftInscription(OrdinalsInscription);
 
 impl OrdinalNftInscription {
@@ -123,3 +223,181 @@ impl OrdinalNftInscription {
         &self.0
     }
 }
+
+/// The ID of an inscription, the ID of its reveal transaction and the index
+/// of the inscription within it, such as `<txid>i0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InscriptionId {
+    pub txid: Txid,
+    pub index: u32,
+}
+
+impl InscriptionId {
+    /// The serialization used by the `parent` field; the transaction ID in
+    /// internal byte order, followed by the little-endian index without
+    /// trailing zeros.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.txid.to_byte_array().to_vec();
+        bytes.extend(trim_le_bytes(&self.index.to_le_bytes()));
+        bytes
+    }
+}
+
+impl FromStr for InscriptionId {
+    type Err = Error;
+
+    fn from_str(id: &str) -> Result<Self> {
+        let invalid = || Error::from(Proto::Error::Error_invalid_inscription_id);
+
+        let (txid, index) = id.split_once('i').ok_or_else(invalid)?;
+
+        Ok(InscriptionId {
+            txid: Txid::from_str(txid).map_err(|_| invalid())?,
+            index: index.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// An Ordinals inscription, including the optional fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inscription {
+    pub mime_type: Vec<u8>,
+    pub payload: Vec<u8>,
+    /// CBOR-encoded metadata.
+    pub metadata: Vec<u8>,
+    pub parent: Option<InscriptionId>,
+    /// The offset of the inscribed satoshi in the outputs.
+    pub pointer: Option<u64>,
+}
+
+impl Inscription {
+    const TAG_CONTENT_TYPE: [u8; 1] = [1];
+    const TAG_POINTER: [u8; 1] = [2];
+    const TAG_PARENT: [u8; 1] = [3];
+    const TAG_METADATA: [u8; 1] = [5];
+
+    pub fn from_proto(proto: &Proto::OrdinalInscription<'_>) -> Result<Inscription> {
+        let parent = if proto.parent.is_empty() {
+            None
+        } else {
+            Some(InscriptionId::from_str(proto.parent.as_ref())?)
+        };
+
+        Ok(Inscription {
+            mime_type: proto.mime_type.as_bytes().to_vec(),
+            payload: proto.payload.to_vec(),
+            metadata: proto.metadata.to_vec(),
+            parent,
+            pointer: (proto.pointer != 0).then_some(proto.pointer),
+        })
+    }
+
+    // Appends the envelope (`OP_FALSE OP_IF "ord" ... OP_ENDIF`) of the
+    // inscription. Content and metadata exceeding the push limit of 520 bytes
+    // are split into multiple pushes.
+    fn append_envelope(&self, builder: ScriptBuilder) -> Result<ScriptBuilder> {
+        use bitcoin::opcodes::all::*;
+        use bitcoin::opcodes::*;
+
+        let mime_type = PushBytesBuf::try_from(self.mime_type.clone())
+            .map_err(|_| Error::from(Proto::Error::Error_ordinal_mime_type_too_large))?;
+
+        let mut builder = builder
+            .push_opcode(OP_FALSE)
+            .push_opcode(OP_IF)
+            .push_slice(b"ord")
+            .push_slice(Self::TAG_CONTENT_TYPE)
+            .push_slice(mime_type);
+
+        if let Some(parent) = self.parent {
+            let parent = PushBytesBuf::try_from(parent.to_bytes())
+                .expect("inscription ID is at most 36 bytes");
+            builder = builder.push_slice(Self::TAG_PARENT).push_slice(parent);
+        }
+
+        if let Some(pointer) = self.pointer {
+            let pointer = PushBytesBuf::try_from(trim_le_bytes(&pointer.to_le_bytes()))
+                .expect("pointer is at most 8 bytes");
+            builder = builder.push_slice(Self::TAG_POINTER).push_slice(pointer);
+        }
+
+        for chunk in self.metadata.chunks(520) {
+            let chunk = PushBytesBuf::try_from(chunk.to_vec()).expect("chunk is at most 520 bytes");
+            builder = builder.push_slice(Self::TAG_METADATA).push_slice(chunk);
+        }
+
+        // The body separator, followed by the content.
+        builder = builder.push_opcode(OP_PUSHBYTES_0);
+        for chunk in self.payload.chunks(520) {
+            let chunk = PushBytesBuf::try_from(chunk.to_vec()).expect("chunk is at most 520 bytes");
+            builder = builder.push_slice(chunk);
+        }
+
+        Ok(builder.push_opcode(OP_ENDIF))
+    }
+}
+
+/// A Taproot script revealing one or more inscriptions in a single input,
+/// which is only spendable by the recipient. The inscriptions are assigned
+/// to the satoshis specified by their pointers, otherwise to the first
+/// satoshi of the input.
+pub struct OrdinalsBatch {
+    envelope: TaprootProgram,
+}
+
+impl OrdinalsBatch {
+    pub fn new(inscriptions: &[Inscription], recipient: PublicKey) -> Result<OrdinalsBatch> {
+        if inscriptions.is_empty() {
+            return Err(Error::from(Proto::Error::Error_missing_inscription));
+        }
+
+        let xonly = XOnlyPublicKey::from(recipient.inner);
+
+        let mut builder = ScriptBuf::builder()
+            .push_x_only_key(&xonly)
+            .push_opcode(bitcoin::opcodes::all::OP_CHECKSIG);
+        for inscription in inscriptions {
+            builder = inscription.append_envelope(builder)?;
+        }
+
+        let script = builder.into_script();
+
+        let spend_info = TaprootBuilder::new()
+            .add_leaf(0, script.clone())
+            .expect("Ordinals batch spending info must always build")
+            .finalize(&secp256k1::Secp256k1::new(), xonly)
+            .expect("Ordinals batch spending info must always build");
+
+        Ok(OrdinalsBatch {
+            envelope: TaprootProgram { script, spend_info },
+        })
+    }
+
+    pub fn from_proto(
+        inscriptions: &[Proto::OrdinalInscription<'_>],
+        recipient: PublicKey,
+    ) -> Result<OrdinalsBatch> {
+        let inscriptions = inscriptions
+            .iter()
+            .map(Inscription::from_proto)
+            .collect::<Result<Vec<_>>>()?;
+
+        OrdinalsBatch::new(&inscriptions, recipient)
+    }
+
+    pub fn taproot_program(&self) -> &Script {
+        self.envelope.script.as_script()
+    }
+    pub fn spend_info(&self) -> &TaprootSpendInfo {
+        &self.envelope.spend_info
+    }
+}
+
+// Strips the trailing zeros of a little-endian integer.
+fn trim_le_bytes(bytes: &[u8]) -> Vec<u8> {
+    let len = bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |pos| pos + 1);
+    bytes[..len].to_vec()
+}
@@ -1,8 +1,13 @@
 use std::str::FromStr;
 
 use super::brc20::{BRC20TransferInscription, Brc20Ticker};
-use super::OrdinalNftInscription;
+use super::{OrdinalNftInscription, OrdinalsBatch, Rune, RuneCommitment, Runestone};
 use crate::aliases::*;
+use crate::modules::descriptor::WalletDescriptor;
+use crate::modules::multisig::MultisigScript;
+use crate::modules::musig2;
+use crate::modules::silent_payments::SilentPaymentAddress;
+use crate::modules::timelock::TimelockScript;
 use crate::{Error, Result};
 use bitcoin::address::{Payload, WitnessVersion};
 use bitcoin::key::TweakedPublicKey;
@@ -170,12 +175,112 @@ impl OutputBuilder {
                         Some(transfer.inscription().taproot_program().to_vec()),
                     )
                 },
+                ProtoOutputBuilder::descriptor(descriptor) => {
+                    let wallet = WalletDescriptor::new(
+                        descriptor.descriptor.as_ref(),
+                        descriptor.derivation_index,
+                    )?;
+
+                    (wallet.script_pubkey(), NO_CONTROL_BLOCK, NO_TAPROOT_PAYLOAD)
+                },
+                ProtoOutputBuilder::multisig(multisig) => {
+                    let multisig = MultisigScript::from_proto(multisig)?;
+
+                    (
+                        multisig.script_pubkey(),
+                        NO_CONTROL_BLOCK,
+                        NO_TAPROOT_PAYLOAD,
+                    )
+                },
+                ProtoOutputBuilder::p2tr_musig2(musig2) => {
+                    let xonly = musig2::internal_key(musig2)?;
+                    (
+                        ScriptBuf::new_v1_p2tr(&secp, xonly, None),
+                        NO_CONTROL_BLOCK,
+                        NO_TAPROOT_PAYLOAD,
+                    )
+                },
+                ProtoOutputBuilder::runestone(runestone) => {
+                    let runestone = Runestone::from_proto(runestone)?;
+                    (runestone.encipher(), NO_CONTROL_BLOCK, NO_TAPROOT_PAYLOAD)
+                },
+                ProtoOutputBuilder::rune_commit(commit) => {
+                    let pubkey = bitcoin::PublicKey::from_slice(commit.commit_to.as_ref())?;
+                    let xonly = XOnlyPublicKey::from(pubkey.inner);
+                    let rune = Rune::from_name(commit.rune.as_ref())?;
+
+                    let commitment = RuneCommitment::new(rune, pubkey);
+
+                    // Construct the control block.
+                    let control_block = commitment
+                        .spend_info()
+                        .control_block(&(
+                            commitment.taproot_program().to_owned(),
+                            LeafVersion::TapScript,
+                        ))
+                        .expect("badly constructed control block");
+
+                    // Construct the merkle root.
+                    let merkle_root = commitment
+                        .spend_info()
+                        .merkle_root()
+                        .expect("badly constructed Taproot merkle root");
+
+                    (
+                        ScriptBuf::new_v1_p2tr(&secp, xonly, Some(merkle_root)),
+                        Some(control_block.serialize()),
+                        Some(commitment.taproot_program().to_vec()),
+                    )
+                },
+                ProtoOutputBuilder::ordinal_batch(ordinal) => {
+                    let pubkey = bitcoin::PublicKey::from_slice(ordinal.inscribe_to.as_ref())?;
+                    let xonly = XOnlyPublicKey::from(pubkey.inner);
+
+                    let batch = OrdinalsBatch::from_proto(&ordinal.inscriptions, pubkey)?;
+
+                    // Construct the control block.
+                    let control_block = batch
+                        .spend_info()
+                        .control_block(&(
+                            batch.taproot_program().to_owned(),
+                            LeafVersion::TapScript,
+                        ))
+                        .expect("badly constructed control block");
+
+                    // Construct the merkle root.
+                    let merkle_root = batch
+                        .spend_info()
+                        .merkle_root()
+                        .expect("badly constructed Taproot merkle root");
+
+                    (
+                        ScriptBuf::new_v1_p2tr(&secp, xonly, Some(merkle_root)),
+                        Some(control_block.serialize()),
+                        Some(batch.taproot_program().to_vec()),
+                    )
+                },
+                ProtoOutputBuilder::timelocked_key(timelocked_key) => {
+                    let script = TimelockScript::from_key_proto(timelocked_key)?;
+                    (script.script_pubkey(), NO_CONTROL_BLOCK, NO_TAPROOT_PAYLOAD)
+                },
+                ProtoOutputBuilder::htlc(htlc) => {
+                    let script = TimelockScript::from_htlc_proto(htlc)?;
+                    (script.script_pubkey(), NO_CONTROL_BLOCK, NO_TAPROOT_PAYLOAD)
+                },
+                // Resolved by the signer, see `Signer::resolve_silent_payments`.
+                ProtoOutputBuilder::silent_payment(_) => {
+                    return Err(Error::from(Proto::Error::Error_unresolved_silent_payment))
+                },
                 ProtoOutputBuilder::None => {
                     return Err(Error::from(Proto::Error::Error_missing_output_builder))
                 },
             },
             // We derive the transaction type from the address.
             ProtoOutputRecipient::from_address(addr) => {
+                if SilentPaymentAddress::from_str(addr.as_ref()).is_ok() {
+                    return Err(Error::from(Proto::Error::Error_unresolved_silent_payment));
+                }
+
                 let proto = output_from_address(output.value, addr.as_ref())?;
 
                 // Recursive call, will initiate the appropraite builder.
@@ -0,0 +1,846 @@
+use super::TaprootProgram;
+use crate::{Error, Result};
+use bitcoin::opcodes::all::*;
+use bitcoin::script::{Instruction, PushBytesBuf};
+use bitcoin::secp256k1::XOnlyPublicKey;
+use bitcoin::taproot::{TaprootBuilder, TaprootSpendInfo};
+use bitcoin::{PublicKey, Script, ScriptBuf, Transaction};
+use std::collections::{BTreeMap, VecDeque};
+use tw_proto::BitcoinV2::Proto;
+
+/// The number of confirmations the commitment output of an etching requires
+/// before the rune can be revealed.
+pub const COMMIT_CONFIRMATIONS: u32 = 6;
+
+/// Names starting from this value are reserved and assigned by the protocol
+/// to etchings without a name.
+const RESERVED: u128 = 6_402_364_363_415_443_603_228_541_259_936_211_926;
+
+const MAX_DIVISIBILITY: u8 = 38;
+const MAX_SPACERS: u32 = 0b0000_0111_1111_1111_1111_1111_1111_1111;
+
+// The field tags of a runestone. Unknown even tags invalidate the runestone,
+// unknown odd tags are ignored.
+const TAG_BODY: u128 = 0;
+const TAG_FLAGS: u128 = 2;
+const TAG_RUNE: u128 = 4;
+const TAG_PREMINE: u128 = 6;
+const TAG_CAP: u128 = 8;
+const TAG_AMOUNT: u128 = 10;
+const TAG_HEIGHT_START: u128 = 12;
+const TAG_HEIGHT_END: u128 = 14;
+const TAG_OFFSET_START: u128 = 16;
+const TAG_OFFSET_END: u128 = 18;
+const TAG_MINT: u128 = 20;
+const TAG_POINTER: u128 = 22;
+const TAG_DIVISIBILITY: u128 = 1;
+const TAG_SPACERS: u128 = 3;
+const TAG_SYMBOL: u128 = 5;
+
+const FLAG_ETCHING: u128 = 1 << 0;
+const FLAG_TERMS: u128 = 1 << 1;
+const FLAG_TURBO: u128 = 1 << 2;
+
+/// The name of a rune, a modified base-26 integer (`A` = 0, `Z` = 25,
+/// `AA` = 26, etc).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rune(pub u128);
+
+impl Rune {
+    /// Parses the name of the rune, spacers are ignored.
+    pub fn from_name(name: &str) -> Result<Rune> {
+        SpacedRune::from_name(name).map(|spaced| spaced.rune)
+    }
+
+    pub fn name(&self) -> String {
+        if self.0 == u128::MAX {
+            return "BCGDENLQRQWDSLRUGSNLBTMFIJAV".to_string();
+        }
+
+        let mut n = self.0 + 1;
+        let mut name = vec![];
+        while n > 0 {
+            name.push(b'A' + ((n - 1) % 26) as u8);
+            n = (n - 1) / 26;
+        }
+
+        name.reverse();
+        String::from_utf8(name).expect("rune names are ASCII")
+    }
+
+    /// The commitment revealed in the Taproot witness of the etching
+    /// transaction; the little-endian encoding without trailing zeros.
+    pub fn commitment(&self) -> Vec<u8> {
+        let bytes = self.0.to_le_bytes();
+        let end = bytes.iter().rposition(|&b| b != 0).map_or(0, |pos| pos + 1);
+        bytes[..end].to_vec()
+    }
+
+    pub fn is_reserved(&self) -> bool {
+        self.0 >= RESERVED
+    }
+}
+
+/// A rune name including the spacers (`•`) between the letters, where bit
+/// `i` of `spacers` denotes a spacer after letter `i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpacedRune {
+    pub rune: Rune,
+    pub spacers: u32,
+}
+
+impl SpacedRune {
+    /// Parses a name such as `UNCOMMON•GOODS`. Both `•` and `.` are accepted
+    /// as spacers.
+    pub fn from_name(name: &str) -> Result<SpacedRune> {
+        let invalid = || Error::from(Proto::Error::Error_invalid_rune_name);
+
+        let mut letters = vec![];
+        let mut spacers = 0u32;
+        for c in name.chars() {
+            match c {
+                'A'..='Z' => letters.push(c as u8 - b'A'),
+                '.' | '•' => {
+                    // A spacer can neither lead nor be repeated.
+                    let position = (letters.len() as u32).checked_sub(1).ok_or_else(invalid)?;
+                    let flag = 1u32.checked_shl(position).ok_or_else(invalid)?;
+                    if spacers & flag != 0 {
+                        return Err(invalid());
+                    }
+
+                    spacers |= flag;
+                },
+                _ => return Err(invalid()),
+            }
+        }
+
+        // Trailing spacer (or empty name).
+        if 32 - spacers.leading_zeros() >= letters.len() as u32 {
+            return Err(invalid());
+        }
+
+        let mut value: u128 = 0;
+        for (i, letter) in letters.into_iter().enumerate() {
+            if i > 0 {
+                value = value.checked_add(1).ok_or_else(invalid)?;
+            }
+            value = value
+                .checked_mul(26)
+                .and_then(|value| value.checked_add(letter.into()))
+                .ok_or_else(invalid)?;
+        }
+
+        Ok(SpacedRune {
+            rune: Rune(value),
+            spacers,
+        })
+    }
+
+    pub fn name(&self) -> String {
+        let letters = self.rune.name();
+
+        let mut name = String::new();
+        for (i, c) in letters.chars().enumerate() {
+            name.push(c);
+            if i + 1 < letters.len() && self.spacers & (1 << i) != 0 {
+                name.push('•');
+            }
+        }
+
+        name
+    }
+}
+
+/// The block height and the transaction index of an etching.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RuneId {
+    pub block: u64,
+    pub tx: u32,
+}
+
+impl RuneId {
+    pub fn new(block: u64, tx: u32) -> Option<RuneId> {
+        // Only the genesis rune has a block height of zero.
+        if block == 0 && tx > 0 {
+            return None;
+        }
+
+        Some(RuneId { block, tx })
+    }
+
+    // The IDs of the edicts are delta-encoded, the transaction index is
+    // absolute if the block height changes.
+    fn delta(self, next: RuneId) -> Option<(u128, u128)> {
+        let block = next.block.checked_sub(self.block)?;
+        let tx = if block == 0 {
+            next.tx.checked_sub(self.tx)?
+        } else {
+            next.tx
+        };
+
+        Some((block.into(), tx.into()))
+    }
+
+    fn next(self, block: u128, tx: u128) -> Option<RuneId> {
+        let block = u64::try_from(block).ok()?;
+        let tx = u32::try_from(tx).ok()?;
+
+        let next_block = self.block.checked_add(block)?;
+        let next_tx = if block == 0 {
+            self.tx.checked_add(tx)?
+        } else {
+            tx
+        };
+
+        RuneId::new(next_block, next_tx)
+    }
+
+    fn from_proto(proto: &Option<Proto::RuneId>) -> Result<RuneId> {
+        let (block, tx) = proto.as_ref().map_or((0, 0), |id| (id.block, id.tx));
+
+        RuneId::new(block, tx).ok_or_else(|| Error::from(Proto::Error::Error_invalid_runestone))
+    }
+
+    fn to_proto(self) -> Proto::RuneId {
+        Proto::RuneId {
+            block: self.block,
+            tx: self.tx,
+        }
+    }
+}
+
+/// Allocates `amount` runes of `id` to the given output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edict {
+    pub id: RuneId,
+    pub amount: u128,
+    pub output: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Terms {
+    pub amount: Option<u128>,
+    pub cap: Option<u128>,
+    pub height: (Option<u64>, Option<u64>),
+    pub offset: (Option<u64>, Option<u64>),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Etching {
+    pub divisibility: Option<u8>,
+    pub premine: Option<u128>,
+    pub rune: Option<Rune>,
+    pub spacers: Option<u32>,
+    pub symbol: Option<char>,
+    pub terms: Option<Terms>,
+    pub turbo: bool,
+}
+
+/// A [Runes protocol](https://docs.ordinals.com/runes.html) message, encoded
+/// as a sequence of LEB128 integers in an `OP_RETURN OP_13` output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Runestone {
+    pub edicts: Vec<Edict>,
+    pub etching: Option<Etching>,
+    pub mint: Option<RuneId>,
+    pub pointer: Option<u32>,
+    /// The runestone is malformed, all runes of the inputs are burned. Only
+    /// the name of the etching and the mint are retained.
+    pub cenotaph: bool,
+}
+
+impl Runestone {
+    /// Creates the `OP_RETURN` script of the runestone.
+    pub fn encipher(&self) -> ScriptBuf {
+        let mut payload = vec![];
+
+        if let Some(etching) = &self.etching {
+            let mut flags = FLAG_ETCHING;
+            if etching.terms.is_some() {
+                flags |= FLAG_TERMS;
+            }
+            if etching.turbo {
+                flags |= FLAG_TURBO;
+            }
+
+            encode_field(&mut payload, TAG_FLAGS, Some(flags));
+            encode_field(&mut payload, TAG_RUNE, etching.rune.map(|rune| rune.0));
+            encode_field(
+                &mut payload,
+                TAG_DIVISIBILITY,
+                etching.divisibility.map(u128::from),
+            );
+            encode_field(&mut payload, TAG_SPACERS, etching.spacers.map(u128::from));
+            encode_field(
+                &mut payload,
+                TAG_SYMBOL,
+                etching.symbol.map(|symbol| u32::from(symbol).into()),
+            );
+            encode_field(&mut payload, TAG_PREMINE, etching.premine);
+
+            if let Some(terms) = &etching.terms {
+                encode_field(&mut payload, TAG_AMOUNT, terms.amount);
+                encode_field(&mut payload, TAG_CAP, terms.cap);
+                encode_field(
+                    &mut payload,
+                    TAG_HEIGHT_START,
+                    terms.height.0.map(u128::from),
+                );
+                encode_field(&mut payload, TAG_HEIGHT_END, terms.height.1.map(u128::from));
+                encode_field(
+                    &mut payload,
+                    TAG_OFFSET_START,
+                    terms.offset.0.map(u128::from),
+                );
+                encode_field(&mut payload, TAG_OFFSET_END, terms.offset.1.map(u128::from));
+            }
+        }
+
+        if let Some(id) = self.mint {
+            encode_field(&mut payload, TAG_MINT, Some(id.block.into()));
+            encode_field(&mut payload, TAG_MINT, Some(id.tx.into()));
+        }
+
+        encode_field(&mut payload, TAG_POINTER, self.pointer.map(u128::from));
+
+        if !self.edicts.is_empty() {
+            encode_varint(&mut payload, TAG_BODY);
+
+            let mut edicts = self.edicts.clone();
+            edicts.sort_by_key(|edict| edict.id);
+
+            let mut previous = RuneId::default();
+            for edict in edicts {
+                let (block, tx) = previous.delta(edict.id).expect("edicts are sorted");
+                encode_varint(&mut payload, block);
+                encode_varint(&mut payload, tx);
+                encode_varint(&mut payload, edict.amount);
+                encode_varint(&mut payload, edict.output.into());
+                previous = edict.id;
+            }
+        }
+
+        let mut builder = ScriptBuf::builder()
+            .push_opcode(OP_RETURN)
+            .push_opcode(OP_PUSHNUM_13);
+
+        for chunk in payload.chunks(520) {
+            let push =
+                PushBytesBuf::try_from(chunk.to_vec()).expect("chunks are at most 520 bytes");
+            builder = builder.push_slice(push);
+        }
+
+        builder.into_script()
+    }
+
+    /// Extracts the runestone of the transaction, which is the first output
+    /// starting with `OP_RETURN OP_13`. Returns `None` if there is no such
+    /// output.
+    pub fn decipher(tx: &Transaction) -> Option<Runestone> {
+        let payload = tx
+            .output
+            .iter()
+            .find_map(|output| runestone_payload(&output.script_pubkey))?;
+
+        let Some(integers) = payload.as_deref().and_then(decode_integers) else {
+            return Some(Runestone::cenotaph(None, None));
+        };
+
+        let mut flaw = false;
+        let mut edicts = vec![];
+        let mut fields: BTreeMap<u128, VecDeque<u128>> = BTreeMap::new();
+
+        let mut idx = 0;
+        while idx < integers.len() {
+            let tag = integers[idx];
+
+            // All remaining integers are edicts.
+            if tag == TAG_BODY {
+                let mut id = RuneId::default();
+                for chunk in integers[idx + 1..].chunks(4) {
+                    let [block, tx_index, amount, output] = chunk else {
+                        flaw = true;
+                        break;
+                    };
+
+                    let Some(next) = id.next(*block, *tx_index) else {
+                        flaw = true;
+                        break;
+                    };
+
+                    // An output index equal to the number of outputs splits
+                    // the runes between all non-`OP_RETURN` outputs.
+                    let Some(output) = u32::try_from(*output)
+                        .ok()
+                        .filter(|output| *output as usize <= tx.output.len())
+                    else {
+                        flaw = true;
+                        break;
+                    };
+
+                    edicts.push(Edict {
+                        id: next,
+                        amount: *amount,
+                        output,
+                    });
+                    id = next;
+                }
+
+                break;
+            }
+
+            let Some(&value) = integers.get(idx + 1) else {
+                flaw = true;
+                break;
+            };
+
+            fields.entry(tag).or_default().push_back(value);
+            idx += 2;
+        }
+
+        let mut flags =
+            take_field(&mut fields, TAG_FLAGS, |[flags]| Some(flags)).unwrap_or_default();
+
+        let etching = take_flag(&mut flags, FLAG_ETCHING).then(|| Etching {
+            divisibility: take_field(&mut fields, TAG_DIVISIBILITY, |[divisibility]| {
+                u8::try_from(divisibility)
+                    .ok()
+                    .filter(|divisibility| *divisibility <= MAX_DIVISIBILITY)
+            }),
+            premine: take_field(&mut fields, TAG_PREMINE, |[premine]| Some(premine)),
+            rune: take_field(&mut fields, TAG_RUNE, |[rune]| Some(Rune(rune))),
+            spacers: take_field(&mut fields, TAG_SPACERS, |[spacers]| {
+                u32::try_from(spacers)
+                    .ok()
+                    .filter(|spacers| *spacers <= MAX_SPACERS)
+            }),
+            symbol: take_field(&mut fields, TAG_SYMBOL, |[symbol]| {
+                char::from_u32(u32::try_from(symbol).ok()?)
+            }),
+            terms: take_flag(&mut flags, FLAG_TERMS).then(|| Terms {
+                amount: take_field(&mut fields, TAG_AMOUNT, |[amount]| Some(amount)),
+                cap: take_field(&mut fields, TAG_CAP, |[cap]| Some(cap)),
+                height: (
+                    take_field(&mut fields, TAG_HEIGHT_START, |[height]| {
+                        height.try_into().ok()
+                    }),
+                    take_field(&mut fields, TAG_HEIGHT_END, |[height]| {
+                        height.try_into().ok()
+                    }),
+                ),
+                offset: (
+                    take_field(&mut fields, TAG_OFFSET_START, |[offset]| {
+                        offset.try_into().ok()
+                    }),
+                    take_field(&mut fields, TAG_OFFSET_END, |[offset]| {
+                        offset.try_into().ok()
+                    }),
+                ),
+            }),
+            turbo: take_flag(&mut flags, FLAG_TURBO),
+        });
+
+        let mint = take_field(&mut fields, TAG_MINT, |[block, tx_index]| {
+            RuneId::new(block.try_into().ok()?, tx_index.try_into().ok()?)
+        });
+
+        let pointer = take_field(&mut fields, TAG_POINTER, |[pointer]| {
+            u32::try_from(pointer)
+                .ok()
+                .filter(|pointer| (*pointer as usize) < tx.output.len())
+        });
+
+        if etching
+            .as_ref()
+            .map_or(false, |etching| etching.supply().is_none())
+        {
+            flaw = true;
+        }
+
+        // Unrecognized flags or even tags.
+        if flags != 0 || fields.keys().any(|tag| tag % 2 == 0) {
+            flaw = true;
+        }
+
+        if flaw {
+            return Some(Runestone::cenotaph(
+                etching.and_then(|etching| etching.rune),
+                mint,
+            ));
+        }
+
+        Some(Runestone {
+            edicts,
+            etching,
+            mint,
+            pointer,
+            cenotaph: false,
+        })
+    }
+
+    pub fn from_proto(proto: &Proto::Runestone<'_>) -> Result<Runestone> {
+        let invalid = || Error::from(Proto::Error::Error_invalid_runestone);
+
+        let etching = proto
+            .etching
+            .as_ref()
+            .map(Etching::from_proto)
+            .transpose()?;
+
+        let mint = proto
+            .mint
+            .as_ref()
+            .map(|id| RuneId::new(id.block, id.tx).ok_or_else(invalid))
+            .transpose()?;
+
+        let edicts = proto
+            .edicts
+            .iter()
+            .map(|edict| {
+                Ok(Edict {
+                    id: RuneId::from_proto(&edict.id)?,
+                    amount: u128_from_proto(edict.amount.as_ref())?,
+                    output: edict.output,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Runestone {
+            edicts,
+            etching,
+            mint,
+            pointer: proto.pointer.as_ref().map(|pointer| pointer.output),
+            cenotaph: false,
+        })
+    }
+
+    pub fn to_proto(&self) -> Proto::Runestone<'static> {
+        Proto::Runestone {
+            etching: self.etching.as_ref().map(Etching::to_proto),
+            mint: self.mint.map(RuneId::to_proto),
+            pointer: self.pointer.map(|output| Proto::RunePointer { output }),
+            edicts: self
+                .edicts
+                .iter()
+                .map(|edict| Proto::RuneEdict {
+                    id: Some(edict.id.to_proto()),
+                    amount: u128_to_proto(edict.amount).into(),
+                    output: edict.output,
+                })
+                .collect(),
+            cenotaph: self.cenotaph,
+        }
+    }
+
+    fn cenotaph(rune: Option<Rune>, mint: Option<RuneId>) -> Runestone {
+        Runestone {
+            etching: rune.map(|rune| Etching {
+                rune: Some(rune),
+                ..Default::default()
+            }),
+            mint,
+            cenotaph: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl Etching {
+    /// The maximum supply of the rune, `None` if it overflows.
+    pub fn supply(&self) -> Option<u128> {
+        let premine = self.premine.unwrap_or_default();
+        let cap = self
+            .terms
+            .as_ref()
+            .and_then(|terms| terms.cap)
+            .unwrap_or_default();
+        let amount = self
+            .terms
+            .as_ref()
+            .and_then(|terms| terms.amount)
+            .unwrap_or_default();
+
+        premine.checked_add(cap.checked_mul(amount)?)
+    }
+
+    fn from_proto(proto: &Proto::RuneEtching<'_>) -> Result<Etching> {
+        let invalid = || Error::from(Proto::Error::Error_invalid_runestone);
+
+        // An empty name lets the protocol assign a reserved name.
+        let (rune, spacers) = if proto.rune.is_empty() {
+            (None, None)
+        } else {
+            let spaced = SpacedRune::from_name(proto.rune.as_ref())?;
+            if spaced.rune.is_reserved() {
+                return Err(Error::from(Proto::Error::Error_invalid_rune_name));
+            }
+
+            (Some(spaced.rune), Some(spaced.spacers).filter(|s| *s != 0))
+        };
+
+        let divisibility = u8::try_from(proto.divisibility)
+            .ok()
+            .filter(|divisibility| *divisibility <= MAX_DIVISIBILITY)
+            .ok_or_else(invalid)?;
+
+        let mut symbol = proto.symbol.chars();
+        let (symbol, None) = (symbol.next(), symbol.next()) else {
+            return Err(invalid());
+        };
+
+        let terms = proto
+            .terms
+            .as_ref()
+            .map(|terms| -> Result<Terms> {
+                Ok(Terms {
+                    amount: non_zero(u128_from_proto(terms.amount.as_ref())?),
+                    cap: non_zero(u128_from_proto(terms.cap.as_ref())?),
+                    height: (non_zero(terms.height_start), non_zero(terms.height_end)),
+                    offset: (non_zero(terms.offset_start), non_zero(terms.offset_end)),
+                })
+            })
+            .transpose()?;
+
+        let etching = Etching {
+            divisibility: non_zero(divisibility),
+            premine: non_zero(u128_from_proto(proto.premine.as_ref())?),
+            rune,
+            spacers,
+            symbol,
+            terms,
+            turbo: proto.turbo,
+        };
+
+        if etching.supply().is_none() {
+            return Err(invalid());
+        }
+
+        Ok(etching)
+    }
+
+    fn to_proto(&self) -> Proto::RuneEtching<'static> {
+        let rune = self
+            .rune
+            .map(|rune| {
+                SpacedRune {
+                    rune,
+                    spacers: self.spacers.unwrap_or_default(),
+                }
+                .name()
+            })
+            .unwrap_or_default();
+
+        Proto::RuneEtching {
+            rune: rune.into(),
+            divisibility: self.divisibility.unwrap_or_default().into(),
+            symbol: self.symbol.map(String::from).unwrap_or_default().into(),
+            premine: u128_to_proto(self.premine.unwrap_or_default()).into(),
+            terms: self.terms.as_ref().map(|terms| Proto::RuneTerms {
+                amount: u128_to_proto(terms.amount.unwrap_or_default()).into(),
+                cap: u128_to_proto(terms.cap.unwrap_or_default()).into(),
+                height_start: terms.height.0.unwrap_or_default(),
+                height_end: terms.height.1.unwrap_or_default(),
+                offset_start: terms.offset.0.unwrap_or_default(),
+                offset_end: terms.offset.1.unwrap_or_default(),
+            }),
+            turbo: self.turbo,
+        }
+    }
+}
+
+/// The Taproot output committing to the name of a rune. Spending it reveals
+/// the commitment in the witness, which the etching transaction requires.
+pub struct RuneCommitment {
+    program: TaprootProgram,
+}
+
+impl RuneCommitment {
+    pub fn new(rune: Rune, recipient: PublicKey) -> RuneCommitment {
+        let xonly = XOnlyPublicKey::from(recipient.inner);
+
+        let commitment =
+            PushBytesBuf::try_from(rune.commitment()).expect("commitment is at most 16 bytes");
+
+        // The commitment is placed in an unexecuted branch, the output is
+        // only spendable by the recipient.
+        let script = ScriptBuf::builder()
+            .push_x_only_key(&xonly)
+            .push_opcode(OP_CHECKSIG)
+            .push_opcode(OP_PUSHBYTES_0)
+            .push_opcode(OP_IF)
+            .push_slice(commitment)
+            .push_opcode(OP_ENDIF)
+            .into_script();
+
+        let spend_info = TaprootBuilder::new()
+            .add_leaf(0, script.clone())
+            .expect("Rune commitment spending info must always build")
+            .finalize(&secp256k1::Secp256k1::new(), xonly)
+            .expect("Rune commitment spending info must always build");
+
+        RuneCommitment {
+            program: TaprootProgram { script, spend_info },
+        }
+    }
+    pub fn taproot_program(&self) -> &Script {
+        self.program.script.as_script()
+    }
+    pub fn spend_info(&self) -> &TaprootSpendInfo {
+        &self.program.spend_info
+    }
+}
+
+// Returns the concatenated pushes of an `OP_RETURN OP_13` script, or `Some(None)`
+// if the script contains other opcodes.
+fn runestone_payload(script: &Script) -> Option<Option<Vec<u8>>> {
+    let mut instructions = script.instructions();
+
+    if !matches!(instructions.next(), Some(Ok(Instruction::Op(OP_RETURN)))) {
+        return None;
+    }
+    if !matches!(
+        instructions.next(),
+        Some(Ok(Instruction::Op(OP_PUSHNUM_13)))
+    ) {
+        return None;
+    }
+
+    let mut payload = vec![];
+    for instruction in instructions {
+        match instruction {
+            Ok(Instruction::PushBytes(push)) => payload.extend_from_slice(push.as_bytes()),
+            Ok(Instruction::Op(_)) | Err(_) => return Some(None),
+        }
+    }
+
+    Some(Some(payload))
+}
+
+fn decode_integers(payload: &[u8]) -> Option<Vec<u128>> {
+    let mut integers = vec![];
+
+    let mut idx = 0;
+    while idx < payload.len() {
+        let (integer, len) = decode_varint(&payload[idx..])?;
+        integers.push(integer);
+        idx += len;
+    }
+
+    Some(integers)
+}
+
+fn encode_field(payload: &mut Vec<u8>, tag: u128, value: Option<u128>) {
+    if let Some(value) = value {
+        encode_varint(payload, tag);
+        encode_varint(payload, value);
+    }
+}
+
+// Encodes the integer as LEB128.
+fn encode_varint(payload: &mut Vec<u8>, mut n: u128) {
+    while n >> 7 > 0 {
+        payload.push((n as u8 & 0b0111_1111) | 0b1000_0000);
+        n >>= 7;
+    }
+    payload.push(n as u8);
+}
+
+// Decodes a LEB128 integer, returning the value and the number of bytes read.
+// Overlong, overflowing and unterminated encodings are rejected.
+fn decode_varint(buf: &[u8]) -> Option<(u128, usize)> {
+    let mut n: u128 = 0;
+
+    for (i, &byte) in buf.iter().enumerate() {
+        if i > 18 {
+            return None;
+        }
+
+        let value = u128::from(byte & 0b0111_1111);
+        if i == 18 && value & 0b0111_1100 != 0 {
+            return None;
+        }
+
+        n |= value << (7 * i);
+
+        if byte & 0b1000_0000 == 0 {
+            return Some((n, i + 1));
+        }
+    }
+
+    None
+}
+
+// Removes `N` values of the given tag, if present and accepted by `with`.
+fn take_field<const N: usize, T>(
+    fields: &mut BTreeMap<u128, VecDeque<u128>>,
+    tag: u128,
+    with: impl Fn([u128; N]) -> Option<T>,
+) -> Option<T> {
+    let field = fields.get_mut(&tag)?;
+
+    let mut values = [0; N];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = *field.get(i)?;
+    }
+
+    let value = with(values)?;
+
+    field.drain(0..N);
+    if field.is_empty() {
+        fields.remove(&tag);
+    }
+
+    Some(value)
+}
+
+fn take_flag(flags: &mut u128, flag: u128) -> bool {
+    let set = *flags & flag != 0;
+    *flags &= !flag;
+    set
+}
+
+fn non_zero<T: Default + PartialEq>(value: T) -> Option<T> {
+    Some(value).filter(|value| *value != T::default())
+}
+
+// Amounts are passed on as big-endian bytes.
+fn u128_from_proto(bytes: &[u8]) -> Result<u128> {
+    if bytes.len() > 16 {
+        return Err(Error::from(Proto::Error::Error_invalid_runestone));
+    }
+
+    let mut buf = [0; 16];
+    buf[16 - bytes.len()..].copy_from_slice(bytes);
+    Ok(u128::from_be_bytes(buf))
+}
+
+fn u128_to_proto(value: u128) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    bytes[start..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varint_encode_decode() {
+        for n in [0, 1, 127, 128, 255, 300, u64::MAX as u128, u128::MAX] {
+            let mut buf = vec![];
+            encode_varint(&mut buf, n);
+            assert_eq!(decode_varint(&buf), Some((n, buf.len())));
+        }
+
+        let mut buf = vec![];
+        encode_varint(&mut buf, 300);
+        assert_eq!(buf, [0xac, 0x02]);
+
+        // Unterminated.
+        assert_eq!(decode_varint(&[0x80]), None);
+        // Overflows 128 bits.
+        let mut buf = vec![0xff; 18];
+        buf.push(0x04);
+        assert_eq!(decode_varint(&buf), None);
+    }
+}
@@ -0,0 +1,372 @@
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_coin_entry::coin_entry_ext::CoinEntryExt;
+use tw_coin_entry::derivation::Derivation;
+use tw_coin_entry::error::AddressError;
+use tw_coin_entry::prefix::{AddressPrefix, BitcoinBase58Prefix};
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_keypair::tw::{PrivateKey, PublicKey, PublicKeyType};
+
+const PRIVATE_KEY: &str = "afeefca74d9a325cf1d6b6911d61a65c32afa8e02bd5e78e2e4ac2910bab45f5";
+
+const MAINNET_P2PKH: &str = "19cAJn4Ms8jodBBGtroBNNpCZiHAWGAq7X";
+const MAINNET_P2SH: &str = "3F4bWvE6GDANhDX9tBzGZzv3KvE7YH82ev";
+const MAINNET_P2WPKH: &str = "bc1qten42eesehw0ktddcp0fws7d3ycsqez3f7d5yt";
+const TESTNET_P2PKH: &str = "mp87bq9LgAB4QHetcRmZCJ2XRhssQX3LLM";
+const TESTNET_P2SH: &str = "2N6coafA7sffiu19hZKc9BwuJYGSHQtWxjT";
+const TESTNET_P2WPKH: &str = "tb1qten42eesehw0ktddcp0fws7d3ycsqez3rck8lc";
+const REGTEST_P2WPKH: &str = "bcrt1qten42eesehw0ktddcp0fws7d3ycsqez3p302g3";
+const LITECOIN_P2PKH: &str = "LTq7ZzNBwnyrsysS4znUePsxmveSaWLyGF";
+const LITECOIN_P2SH: &str = "MMGjpoe4DL1oVio3z4ycPeASecpZbzPYHB";
+const LITECOIN_P2WPKH: &str = "ltc1qten42eesehw0ktddcp0fws7d3ycsqez3dzhsum";
+const DOGECOIN_P2PKH: &str = "DDkFr311AYe6ABMsdSnjv8yoSr1Tppokp8";
+const DOGECOIN_P2SH: &str = "A5orFmHzLH3GbatdJKegp8YR2Vc9ej5mHo";
+const BITCOIN_CASH_P2PKH: &str = "bitcoincash:qp0xw4t8xrxae7ed4hq9a96rekynzqry2ydzeh0jgs";
+const ZCASH_P2PKH: &str = "t1SUmK7UVqTXQDpEAqHcJWBv7pNUFHJRpDx";
+const ZCASH_P2SH: &str = "t3XwCXFeEEXwyHra3pcoPhp1xaaRCLmjbUX";
+
+fn public_key(public_key_type: PublicKeyType) -> PublicKey {
+    let private_key = PrivateKey::new(tw_encoding::hex::decode(PRIVATE_KEY).unwrap()).unwrap();
+    private_key.get_public_key_by_type(public_key_type).unwrap()
+}
+
+fn derive(coin: &TestCoinContext, prefix: Option<AddressPrefix>) -> String {
+    derive_with(coin, Derivation::default(), prefix)
+}
+
+fn derive_with(
+    coin: &TestCoinContext,
+    derivation: Derivation,
+    prefix: Option<AddressPrefix>,
+) -> String {
+    let public_key = public_key(PublicKeyType::Secp256k1);
+    BitcoinEntry
+        .derive_address(coin, public_key, derivation, prefix)
+        .unwrap()
+}
+
+#[test]
+fn address_network_from_coin_context() {
+    let mainnet = TestCoinContext::default().with_hrp("bc");
+    assert_eq!(derive(&mainnet, None), MAINNET_P2PKH);
+    BitcoinEntry
+        .validate_address(&mainnet, MAINNET_P2WPKH, None)
+        .unwrap();
+    BitcoinEntry
+        .validate_address(&mainnet, TESTNET_P2WPKH, None)
+        .unwrap_err();
+
+    let testnet = TestCoinContext::default().with_hrp("tb");
+    assert_eq!(derive(&testnet, None), TESTNET_P2PKH);
+    for address in [TESTNET_P2PKH, TESTNET_P2SH, TESTNET_P2WPKH] {
+        BitcoinEntry
+            .validate_address(&testnet, address, None)
+            .unwrap();
+    }
+    BitcoinEntry
+        .validate_address(&testnet, MAINNET_P2PKH, None)
+        .unwrap_err();
+    BitcoinEntry
+        .validate_address(&testnet, REGTEST_P2WPKH, None)
+        .unwrap_err();
+
+    // Regtest shares the base58 version bytes with testnet.
+    let regtest = TestCoinContext::default().with_hrp("bcrt");
+    assert_eq!(derive(&regtest, None), TESTNET_P2PKH);
+    for address in [TESTNET_P2PKH, TESTNET_P2SH, REGTEST_P2WPKH] {
+        BitcoinEntry
+            .validate_address(&regtest, address, None)
+            .unwrap();
+    }
+    BitcoinEntry
+        .validate_address(&regtest, TESTNET_P2WPKH, None)
+        .unwrap_err();
+
+    let base58_testnet = TestCoinContext::default().with_base58_prefix(111, 196);
+    assert_eq!(derive(&base58_testnet, None), TESTNET_P2PKH);
+
+    // Signet addresses are identical to the testnet ones.
+    let signet_public_key =
+        bitcoin::PublicKey::from_slice(&public_key(PublicKeyType::Secp256k1).to_bytes()).unwrap();
+    let signet = bitcoin::Address::p2wpkh(&signet_public_key, bitcoin::Network::Signet).unwrap();
+    assert_eq!(signet.to_string(), TESTNET_P2WPKH);
+    BitcoinEntry
+        .validate_address(&testnet, &signet.to_string(), None)
+        .unwrap();
+}
+
+#[test]
+fn address_network_empty_coin_context() {
+    // Falls back to mainnet if the coin context doesn't specify any prefix.
+    let coin = TestCoinContext::default();
+    assert_eq!(derive(&coin, None), MAINNET_P2PKH);
+    BitcoinEntry
+        .validate_address(&coin, MAINNET_P2WPKH, None)
+        .unwrap();
+    BitcoinEntry
+        .validate_address(&coin, TESTNET_P2WPKH, None)
+        .unwrap_err();
+}
+
+#[test]
+fn address_network_from_prefix() {
+    // The prefix takes precedence over the coin context.
+    let coin = TestCoinContext::default().with_hrp("bc");
+
+    // A bech32 prefix selects a Segwit address.
+    let testnet_hrp = || Some(AddressPrefix::Hrp("tb".to_string()));
+    assert_eq!(derive(&coin, testnet_hrp()), TESTNET_P2WPKH);
+    assert_eq!(
+        derive_with(&coin, Derivation::Bip44, testnet_hrp()),
+        TESTNET_P2PKH
+    );
+    BitcoinEntry
+        .validate_address(&coin, TESTNET_P2WPKH, testnet_hrp())
+        .unwrap();
+    BitcoinEntry
+        .validate_address(&coin, MAINNET_P2WPKH, testnet_hrp())
+        .unwrap_err();
+
+    let testnet_base58 = || {
+        Some(AddressPrefix::BitcoinBase58(BitcoinBase58Prefix {
+            p2pkh: 111,
+            p2sh: 196,
+        }))
+    };
+    assert_eq!(derive(&coin, testnet_base58()), TESTNET_P2PKH);
+    BitcoinEntry
+        .validate_address(&coin, TESTNET_P2SH, testnet_base58())
+        .unwrap();
+
+    let mainnet_base58 = Some(AddressPrefix::BitcoinBase58(BitcoinBase58Prefix {
+        p2pkh: 0,
+        p2sh: 5,
+    }));
+    assert_eq!(derive(&coin, mainnet_base58), MAINNET_P2PKH);
+
+    // Prefixes of other Bitcoin-based chains select their address formats.
+    let litecoin_hrp = || Some(AddressPrefix::Hrp("ltc".to_string()));
+    assert_eq!(derive(&coin, litecoin_hrp()), LITECOIN_P2WPKH);
+    BitcoinEntry
+        .validate_address(&coin, LITECOIN_P2WPKH, litecoin_hrp())
+        .unwrap();
+    assert_eq!(
+        BitcoinEntry.validate_address(&coin, MAINNET_P2WPKH, litecoin_hrp()),
+        Err(AddressError::InvalidInput)
+    );
+
+    let dogecoin_base58 = || {
+        Some(AddressPrefix::BitcoinBase58(BitcoinBase58Prefix {
+            p2pkh: 30,
+            p2sh: 22,
+        }))
+    };
+    assert_eq!(derive(&coin, dogecoin_base58()), DOGECOIN_P2PKH);
+    BitcoinEntry
+        .validate_address(&coin, DOGECOIN_P2PKH, dogecoin_base58())
+        .unwrap();
+}
+
+#[test]
+fn address_normalize_and_data_any_network() {
+    let coin = TestCoinContext::default();
+
+    for address in [MAINNET_P2PKH, TESTNET_P2PKH, TESTNET_P2WPKH, REGTEST_P2WPKH] {
+        assert_eq!(
+            BitcoinEntry.normalize_address(&coin, address).unwrap(),
+            address
+        );
+        assert_eq!(
+            BitcoinEntry.address_to_data(&coin, address).unwrap(),
+            address.as_bytes()
+        );
+    }
+}
+
+#[test]
+fn address_derivation() {
+    let mainnet = TestCoinContext::default().with_hrp("bc");
+    assert_eq!(
+        derive_with(&mainnet, Derivation::Bip44, None),
+        MAINNET_P2PKH
+    );
+    assert_eq!(derive_with(&mainnet, Derivation::Bip49, None), MAINNET_P2SH);
+    assert_eq!(
+        derive_with(&mainnet, Derivation::Bip84, None),
+        MAINNET_P2WPKH
+    );
+    assert_eq!(
+        derive_with(&mainnet, Derivation::Bip86, None),
+        "bc1p4yq0ttapgxksdtq04njvs2mgc5sg06uuczk6ys6s2mvjkrcvydjqwea8hf"
+    );
+
+    let testnet = TestCoinContext::default().with_hrp("tb");
+    assert_eq!(
+        derive_with(&testnet, Derivation::Bip44, None),
+        TESTNET_P2PKH
+    );
+    assert_eq!(derive_with(&testnet, Derivation::Bip49, None), TESTNET_P2SH);
+    assert_eq!(
+        derive_with(&testnet, Derivation::Bip84, None),
+        TESTNET_P2WPKH
+    );
+    assert_eq!(
+        derive_with(&testnet, Derivation::Bip86, None),
+        "tb1p4yq0ttapgxksdtq04njvs2mgc5sg06uuczk6ys6s2mvjkrcvydjqe3tgdx"
+    );
+
+    let regtest = Some(AddressPrefix::Hrp("bcrt".to_string()));
+    assert_eq!(
+        derive_with(&mainnet, Derivation::Bip84, regtest),
+        REGTEST_P2WPKH
+    );
+}
+
+/// https://github.com/bitcoin/bips/blob/master/bip-0086.mediawiki#test-vectors
+#[test]
+fn address_derivation_bip86_vector() {
+    let coin = TestCoinContext::default().with_hrp("bc");
+    // m/86'/0'/0'/0/0 of the "abandon ... about" mnemonic.
+    let public_key = PublicKey::new(
+        tw_encoding::hex::decode(
+            "02cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115",
+        )
+        .unwrap(),
+        PublicKeyType::Secp256k1,
+    )
+    .unwrap();
+
+    let address = BitcoinEntry
+        .derive_address(&coin, public_key, Derivation::Bip86, None)
+        .unwrap();
+    assert_eq!(
+        address,
+        "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+    );
+}
+
+#[test]
+fn address_derivation_uncompressed_public_key() {
+    let coin = TestCoinContext::default().with_hrp("bc");
+    let public_key = public_key(PublicKeyType::Secp256k1Extended);
+
+    // P2PKH addresses commit to the uncompressed serialization.
+    let address = BitcoinEntry
+        .derive_address(&coin, public_key.clone(), Derivation::Bip44, None)
+        .unwrap();
+    assert_eq!(address, "1E4T9JZ3mq6cdgiRJEWzHqDXb9t322fE6d");
+
+    // Segwit and Taproot addresses require a compressed public key.
+    for derivation in [Derivation::Bip49, Derivation::Bip84, Derivation::Bip86] {
+        assert_eq!(
+            BitcoinEntry.derive_address(&coin, public_key.clone(), derivation, None),
+            Err(AddressError::InvalidInput)
+        );
+    }
+}
+
+#[test]
+fn address_derivation_bitcoin_testnet() {
+    // TWDerivationBitcoinTestnet selects testnet regardless of the coin context.
+    let mainnet = TestCoinContext::default().with_hrp("bc");
+    let derivation = Derivation::from_raw(4).unwrap();
+    assert_eq!(derive_with(&mainnet, derivation, None), TESTNET_P2WPKH);
+}
+
+#[test]
+fn address_fork_litecoin() {
+    let coin = TestCoinContext::default()
+        .with_hrp("ltc")
+        .with_base58_prefix(48, 50);
+
+    assert_eq!(derive(&coin, None), LITECOIN_P2PKH);
+    assert_eq!(derive_with(&coin, Derivation::Bip49, None), LITECOIN_P2SH);
+    assert_eq!(derive_with(&coin, Derivation::Bip84, None), LITECOIN_P2WPKH);
+
+    for address in [LITECOIN_P2PKH, LITECOIN_P2SH, LITECOIN_P2WPKH] {
+        BitcoinEntry.validate_address(&coin, address, None).unwrap();
+    }
+    for address in [MAINNET_P2PKH, MAINNET_P2WPKH, DOGECOIN_P2PKH] {
+        BitcoinEntry
+            .validate_address(&coin, address, None)
+            .unwrap_err();
+    }
+}
+
+#[test]
+fn address_fork_dogecoin() {
+    let coin = TestCoinContext::default().with_base58_prefix(30, 22);
+
+    assert_eq!(derive(&coin, None), DOGECOIN_P2PKH);
+    // Dogecoin has no Segwit addresses.
+    assert_eq!(
+        BitcoinEntry.derive_address(
+            &coin,
+            public_key(PublicKeyType::Secp256k1),
+            Derivation::Bip84,
+            None
+        ),
+        Err(AddressError::UnexpectedAddressPrefix)
+    );
+
+    for address in [DOGECOIN_P2PKH, DOGECOIN_P2SH] {
+        BitcoinEntry.validate_address(&coin, address, None).unwrap();
+    }
+    for address in [MAINNET_P2PKH, LITECOIN_P2PKH, LITECOIN_P2WPKH] {
+        BitcoinEntry
+            .validate_address(&coin, address, None)
+            .unwrap_err();
+    }
+}
+
+#[test]
+fn address_fork_bitcoin_cash() {
+    // The HRP of the coin context is the CashAddr prefix.
+    let coin = TestCoinContext::default()
+        .with_hrp("bitcoincash")
+        .with_base58_prefix(0, 5);
+
+    assert_eq!(derive(&coin, None), BITCOIN_CASH_P2PKH);
+    BitcoinEntry
+        .validate_address(&coin, BITCOIN_CASH_P2PKH, None)
+        .unwrap();
+
+    // The prefix may be omitted, and legacy addresses are accepted too. All
+    // are normalized to CashAddr.
+    let without_prefix = BITCOIN_CASH_P2PKH.trim_start_matches("bitcoincash:");
+    let uppercase = BITCOIN_CASH_P2PKH.to_uppercase();
+    for address in [without_prefix, &uppercase, MAINNET_P2PKH] {
+        assert_eq!(
+            BitcoinEntry.normalize_address(&coin, address).unwrap(),
+            BITCOIN_CASH_P2PKH
+        );
+    }
+
+    for address in [
+        "ecash:qp0xw4t8xrxae7ed4hq9a96rekynzqry2y50du5gw8",
+        MAINNET_P2WPKH,
+        TESTNET_P2PKH,
+    ] {
+        BitcoinEntry
+            .validate_address(&coin, address, None)
+            .unwrap_err();
+    }
+}
+
+#[test]
+fn address_fork_zcash() {
+    // Transparent addresses have two version bytes.
+    let coin = TestCoinContext::default()
+        .with_base58_prefix(0xb8, 0xbd)
+        .with_static_prefix(0x1c);
+
+    assert_eq!(derive(&coin, None), ZCASH_P2PKH);
+
+    for address in [ZCASH_P2PKH, ZCASH_P2SH] {
+        BitcoinEntry.validate_address(&coin, address, None).unwrap();
+    }
+    for address in [MAINNET_P2PKH, MAINNET_P2SH, DOGECOIN_P2PKH] {
+        BitcoinEntry
+            .validate_address(&coin, address, None)
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use common::hex;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::Lightning::Proto;
+
+// Test vectors of the BOLT-11 specification, signed by this node.
+const PAYEE_PUBKEY: &str = "03e7156ae33b0a208d0744199163177e909e80176e55d97a2f221ede0f934dd9ad";
+const PAYMENT_HASH: &str = "0001020304050607080900010203040506070809000102030405060708090102";
+const PAYMENT_SECRET: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+const TIMESTAMP: u64 = 1496314658;
+
+fn decode(invoice: &str, now: u64) -> Proto::DecodingOutput<'static> {
+    let coin = TestCoinContext::default();
+
+    BitcoinEntry.decode_lightning_invoice(
+        &coin,
+        Proto::DecodingInput {
+            invoice: invoice.into(),
+            now,
+        },
+    )
+}
+
+#[test]
+fn decode_invoice_donation() {
+    let invoice = "lnbc1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdpl2pkx2ctnv5sxxmmwwd5kgetjypeh2ursdae8g6twvus8g6rfwvs8qun0dfjkxaq9qrsgq357wnc5r2ueh7ck6q93dj32dlqnls087fxdwk8qakdyafkq3yap9us6v52vjjsrvywa6rt52cm9r9zqt8r2t7mlcwspyetp5h2tztugp9lfyql";
+
+    let output = decode(invoice, 0);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(output.currency, "bc");
+    // Any amount can be paid.
+    assert_eq!(output.amount_msat, 0);
+    assert_eq!(output.timestamp, TIMESTAMP);
+    assert_eq!(output.payment_hash, hex(PAYMENT_HASH));
+    assert_eq!(output.payment_secret, hex(PAYMENT_SECRET));
+    assert_eq!(
+        output.description,
+        "Please consider supporting this project"
+    );
+    assert!(output.description_hash.is_empty());
+    // Defaults.
+    assert_eq!(output.expiry, 3600);
+    assert_eq!(output.expires_at, TIMESTAMP + 3600);
+    assert!(!output.expired);
+    assert_eq!(output.min_final_cltv_expiry_delta, 18);
+    // `var_onion_optin` and `payment_secret` are required.
+    assert_eq!(output.features, vec![8, 14]);
+    // Recovered from the signature.
+    assert_eq!(output.payee_public_key, hex(PAYEE_PUBKEY));
+    assert_eq!(output.signature.len(), 65);
+
+    // Pasted as URI, in uppercase.
+    let uri = format!("lightning:{}", invoice.to_uppercase());
+    assert_eq!(decode(&uri, 0), output);
+}
+
+#[test]
+fn decode_invoice_amount_and_expiry() {
+    let invoice = "lnbc2500u1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdq5xysxxatsyp3k7enxv4jsxqzpu9qrsgquk0rl77nj30yxdy8j9vdx85fkpmdla2087ne0xh8nhedh8w27kyke0lp53ut353s06fv3qfegext0eh0ymjpf39tuven09sam30g4vgpfna3rh";
+
+    let output = decode(invoice, TIMESTAMP + 59);
+    assert_eq!(output.error, Proto::Error::OK);
+    // 2500 micro-bitcoin.
+    assert_eq!(output.amount_msat, 250_000_000);
+    assert_eq!(output.description, "1 cup coffee");
+    assert_eq!(output.expiry, 60);
+    assert!(!output.expired);
+    assert_eq!(output.payee_public_key, hex(PAYEE_PUBKEY));
+
+    let output = decode(invoice, TIMESTAMP + 60);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert!(output.expired);
+}
+
+#[test]
+fn decode_invoice_description_hash_and_fallback() {
+    let invoice = "lntb20m1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygshp58yjmdan79s6qqdhdzgynm4zwqd5d7xmw5fk98klysy043l2ahrqspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqfpp3x9et2e20v6pu37c5d9vax37wxq72un989qrsgqdj545axuxtnfemtpwkc45hx9d2ft7x04mt8q7y6t0k2dge9e7h8kpy9p34ytyslj3yu569aalz2xdk8xkd7ltxqld94u8h2esmsmacgpghe9k8";
+
+    let output = decode(invoice, 0);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(output.currency, "tb");
+    assert_eq!(output.amount_msat, 2_000_000_000);
+    assert!(output.description.is_empty());
+    assert_eq!(
+        output.description_hash,
+        hex("3925b6f67e2c340036ed12093dd44e0368df1b6ea26c53dbe4811f58fd5db8c1")
+    );
+
+    assert_eq!(output.fallbacks.len(), 1);
+    // P2PKH.
+    assert_eq!(output.fallbacks[0].version, 17);
+    assert_eq!(
+        output.fallbacks[0].address,
+        "mk2QpYatsKicvFVuTAQLBryyccRXMUaGHP"
+    );
+    assert_eq!(output.payee_public_key, hex(PAYEE_PUBKEY));
+}
+
+#[test]
+fn decode_invoice_route_hints() {
+    let invoice = "lnbc20m1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqhp58yjmdan79s6qqdhdzgynm4zwqd5d7xmw5fk98klysy043l2ahrqsfpp3qjmp7lwpagxun9pygexvgpjdc4jdj85fr9yq20q82gphp2nflc7jtzrcazrra7wwgzxqc8u7754cdlpfrmccae92qgzqvzq2ps8pqqqqqqpqqqqq9qqqvpeuqafqxu92d8lr6fvg0r5gv0heeeqgcrqlnm6jhphu9y00rrhy4grqszsvpcgpy9qqqqqqgqqqqq7qqzq9qrsgqdfjcdk6w3ak5pca9hwfwfh63zrrz06wwfya0ydlzpgzxkn5xagsqz7x9j4jwe7yj7vaf2k9lqsdk45kts2fd0fkr28am0u4w95tt2nsq76cqw0";
+
+    let output = decode(invoice, 0);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(
+        output.fallbacks[0].address,
+        "1RustyRX2oai4EYYDpQGWvEL62BBGqN9T"
+    );
+
+    assert_eq!(output.route_hints.len(), 1);
+    let hops = &output.route_hints[0].hops;
+    assert_eq!(hops.len(), 2);
+
+    assert_eq!(
+        hops[0].public_key,
+        hex("029e03a901b85534ff1e92c43c74431f7ce72046060fcf7a95c37e148f78c77255")
+    );
+    assert_eq!(hops[0].short_channel_id, 0x0102030405060708);
+    assert_eq!(hops[0].fee_base_msat, 1);
+    assert_eq!(hops[0].fee_proportional_millionths, 20);
+    assert_eq!(hops[0].cltv_expiry_delta, 3);
+
+    assert_eq!(
+        hops[1].public_key,
+        hex("039e03a901b85534ff1e92c43c74431f7ce72046060fcf7a95c37e148f78c77255")
+    );
+    assert_eq!(hops[1].short_channel_id, 0x030405060708090a);
+    assert_eq!(hops[1].fee_base_msat, 2);
+    assert_eq!(hops[1].fee_proportional_millionths, 30);
+    assert_eq!(hops[1].cltv_expiry_delta, 4);
+}
+
+#[test]
+fn decode_invoice_invalid() {
+    // Invalid checksum (last character changed).
+    let output = decode("lnbc2500u1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdq5xysxxatsyp3k7enxv4jsxqzpu9qrsgquk0rl77nj30yxdy8j9vdx85fkpmdla2087ne0xh8nhedh8w27kyke0lp53ut353s06fv3qfegext0eh0ymjpf39tuven09sam30g4vgpfna3rq", 0);
+    assert_eq!(output.error, Proto::Error::Error_invalid_encoding);
+
+    // A Segwit address is not an invoice.
+    let output = decode("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", 0);
+    assert_eq!(output.error, Proto::Error::Error_invalid_hrp);
+}
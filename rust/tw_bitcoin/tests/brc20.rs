@@ -2,8 +2,10 @@ mod common;
 
 use common::hex;
 use tw_bitcoin::aliases::*;
+use tw_bitcoin::modules::plan_builder::BitcoinPlanBuilder;
 use tw_bitcoin::BitcoinEntry;
 use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::modules::plan_builder::PlanBuilder;
 use tw_coin_entry::test_utils::test_context::TestCoinContext;
 use tw_proto::BitcoinV2::Proto;
 use tw_proto::Utxo::Proto as UtxoProto;
@@ -135,3 +137,81 @@ fn coin_entry_sign_brc20_commit_reveal_transfer() {
     assert_eq!(transaction.inputs.len(), 1);
     assert_eq!(transaction.outputs.len(), 1);
 }
+
+#[test]
+fn coin_entry_plan_brc20_commit_reveal_transfer() {
+    let coin = TestCoinContext::default();
+
+    let alice_private_key = hex("e253373989199da27c48680e3a3fc0f648d50f9a727ef17a7fe6a4dc3b159129");
+    let alice_pubkey = hex("030f209b6ada5edb42c77fd2bc64ad650ae38314c8f451f3e36d80bc8e26f132cb");
+
+    let txid: Vec<u8> = hex("8ec895b4d30adb01e38471ca1019bfc8c3e5fbd1f28d9e7b5653260d89989008")
+        .into_iter()
+        .rev()
+        .collect();
+
+    let tx1 = Proto::Input {
+        txid: txid.as_slice().into(),
+        vout: 1,
+        value: 26_400,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2wpkh(alice_pubkey.as_slice().into()),
+        }),
+        ..Default::default()
+    };
+
+    let p2wpkh = |value| Proto::Output {
+        value,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(alice_pubkey.as_slice().into()),
+            }),
+        }),
+    };
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoComposePlan::brc20(Proto::mod_ComposePlan::ComposeBrc20Plan {
+            private_key: alice_private_key.as_slice().into(),
+            inputs: vec![tx1],
+            input_selector: UtxoProto::InputSelector::SelectInOrder,
+            tagged_output: Some(p2wpkh(546)),
+            inscription: Some(Proto::mod_Input::InputBrc20Inscription {
+                one_prevout: false,
+                inscribe_to: alice_pubkey.as_slice().into(),
+                ticker: "oadf".into(),
+                transfer_amount: "20".into(),
+            }),
+            fee_per_vb: 10,
+            change_output: Some(p2wpkh(0)),
+            disable_change_output: false,
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::OK);
+
+    let ProtoTransactionPlan::brc20(brc20) = plan.plan else {
+        panic!("expected a BRC20 plan");
+    };
+    let commit = brc20.commit.unwrap();
+    let reveal = brc20.reveal.unwrap();
+
+    let signed_commit = BitcoinEntry.sign(&coin, commit);
+    assert_eq!(signed_commit.error, Proto::Error::OK);
+
+    // The reveal transaction references the inscription output of the commit
+    // transaction.
+    let commit_txid: Vec<u8> = signed_commit.txid.iter().copied().rev().collect();
+    assert_eq!(reveal.inputs[0].txid.as_ref(), commit_txid.as_slice());
+    assert_eq!(reveal.inputs[0].vout, 0);
+
+    let signed_reveal = BitcoinEntry.sign(&coin, reveal);
+    assert_eq!(signed_reveal.error, Proto::Error::OK);
+    assert!(signed_reveal.fee >= (signed_reveal.weight + 3) / 4 * 10);
+
+    let transaction = signed_reveal.transaction.unwrap();
+    assert_eq!(transaction.inputs.len(), 1);
+    assert_eq!(transaction.outputs.len(), 1);
+    assert_eq!(transaction.outputs[0].value, 546);
+}
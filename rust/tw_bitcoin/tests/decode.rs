@@ -0,0 +1,202 @@
+mod common;
+
+use common::{hex, MINER_FEE, ONE_BTC};
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+// The P2WPKH claim transaction of `coin_entry_sign_input_p2pkh_output_p2wpkh`.
+const P2WPKH_TX: &str = "020000000001016e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e850000000000ffffffff01806de7290100000016001460cda7b50f14c152d7401c28ae773c698db9237302483045022100a9b517de5a5e036d7133df499b5b751db6f9a01576a6c5dc38229ec08b6c45cd02200e42c9f8c707c9bf0ceab4f739ec8d683dc1f1f29e195a8da9bc183584d624a60121025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f00000000";
+
+#[test]
+fn decode_segwit_transaction() {
+    let coin = TestCoinContext::default();
+
+    let input = Proto::DecodingInput {
+        encoded: hex(P2WPKH_TX).into(),
+        network: Proto::Network::Bitcoin,
+        ..Default::default()
+    };
+
+    let decoded = BitcoinEntry.decode_transaction(&coin, input);
+    assert_eq!(decoded.error, Proto::Error::OK);
+
+    assert_eq!(
+        decoded.txid,
+        hex("7553540501d96f986a96564aef9949d284353757f544cc001b60449e1abc52c3")
+    );
+    assert_eq!(
+        decoded.wtxid,
+        hex("37d98e03b7eaeb6d3523628e09f3733ed84db5db817f5e326449a4b72cb4c3c5")
+    );
+    assert_eq!(decoded.weight, 438);
+    assert_eq!(decoded.vsize, 110);
+    assert!(!decoded.rbf_signaling);
+
+    // No prevouts were provided.
+    assert_eq!(decoded.fee, 0);
+    assert_eq!(decoded.inputs.len(), 1);
+    assert_eq!(decoded.inputs[0].script_type, Proto::ScriptType::Unknown);
+
+    let transaction = decoded.transaction.unwrap();
+    assert_eq!(transaction.version, 2);
+    assert_eq!(transaction.lock_time, None);
+    assert_eq!(transaction.inputs.len(), 1);
+    assert_eq!(transaction.inputs[0].witness_items.len(), 2);
+    assert_eq!(transaction.outputs.len(), 1);
+    assert_eq!(
+        transaction.outputs[0].value,
+        ONE_BTC * 50 - MINER_FEE - MINER_FEE
+    );
+
+    assert_eq!(decoded.outputs.len(), 1);
+    assert_eq!(decoded.outputs[0].script_type, Proto::ScriptType::P2WPKH);
+    assert_eq!(
+        decoded.outputs[0].address,
+        "bc1qvrx60dg0znq4946qrs52uaeudxxmjgmnsctylr"
+    );
+}
+
+#[test]
+fn decode_transaction_with_prevouts() {
+    let coin = TestCoinContext::default();
+
+    // The P2WPKH output claimed by the transaction.
+    let prevout = UtxoProto::TxOut {
+        value: ONE_BTC * 50 - MINER_FEE,
+        script_pubkey: hex("00140d0e1cec6c2babe8badde5e9b3dea667da90036d").into(),
+    };
+
+    let input = Proto::DecodingInput {
+        encoded: hex(P2WPKH_TX).into(),
+        network: Proto::Network::Regtest,
+        prevouts: vec![prevout],
+    };
+
+    let decoded = BitcoinEntry.decode_transaction(&coin, input);
+    assert_eq!(decoded.error, Proto::Error::OK);
+
+    assert_eq!(decoded.fee, MINER_FEE);
+    assert_eq!(decoded.fee_rate, MINER_FEE as f64 / 110.0);
+    assert_eq!(decoded.inputs[0].script_type, Proto::ScriptType::P2WPKH);
+    assert_eq!(decoded.inputs[0].value, ONE_BTC * 50 - MINER_FEE);
+    assert_eq!(
+        decoded.outputs[0].address,
+        "bcrt1qvrx60dg0znq4946qrs52uaeudxxmjgmnchf6ne"
+    );
+}
+
+#[test]
+fn decode_ordinal_inscription_reveal() {
+    let coin = TestCoinContext::default();
+
+    let input = Proto::DecodingInput {
+        encoded: hex(common::data::NFT_INSCRIPTION_RAW_HEX).into(),
+        network: Proto::Network::Bitcoin,
+        ..Default::default()
+    };
+
+    let decoded = BitcoinEntry.decode_transaction(&coin, input);
+    assert_eq!(decoded.error, Proto::Error::OK);
+
+    // https://www.blockchain.com/explorer/transactions/btc/173f8350b722243d44cc8db5584de76b432eb6d0888d9e66e662db51584f44ac
+    assert_eq!(
+        decoded.txid,
+        hex("173f8350b722243d44cc8db5584de76b432eb6d0888d9e66e662db51584f44ac")
+    );
+
+    let inscriptions = &decoded.inputs[0].inscriptions;
+    assert_eq!(inscriptions.len(), 1);
+    assert_eq!(inscriptions[0].mime_type, "image/png");
+    assert_eq!(
+        inscriptions[0].payload,
+        hex(common::data::NFT_INSCRIPTION_IMAGE_DATA)
+    );
+
+    assert_eq!(decoded.outputs[0].script_type, Proto::ScriptType::P2WPKH);
+    assert_eq!(
+        decoded.outputs[0].address,
+        "bc1quvgm34kal7zke68f5nsrh3k5leg9p2pa2nlgsp"
+    );
+}
+
+#[test]
+fn decode_op_return_and_invalid_transactions() {
+    let coin = TestCoinContext::default();
+
+    // Invalid encoding.
+    let input = Proto::DecodingInput {
+        encoded: hex("0200000001").into(),
+        ..Default::default()
+    };
+
+    let decoded = BitcoinEntry.decode_transaction(&coin, input);
+    assert_eq!(
+        decoded.error,
+        Proto::Error::Error_invalid_transaction_encoding
+    );
+    assert_eq!(decoded.transaction, None);
+
+    // Mismatching number of prevouts.
+    let input = Proto::DecodingInput {
+        encoded: hex(P2WPKH_TX).into(),
+        prevouts: vec![Default::default(), Default::default()],
+        ..Default::default()
+    };
+
+    let decoded = BitcoinEntry.decode_transaction(&coin, input);
+    assert_eq!(decoded.error, Proto::Error::Error_unmatched_prevout_count);
+
+    // Legacy transaction with an OP_RETURN output and RBF signaling.
+    let encoded = "010000000111111111111111111111111111111111111111111111111111111111111111110000000000fdffffff0100000000000000000d6a0b68656c6c6f20776f726c6400000000";
+    let input = Proto::DecodingInput {
+        encoded: hex(encoded).into(),
+        ..Default::default()
+    };
+
+    let decoded = BitcoinEntry.decode_transaction(&coin, input);
+    assert_eq!(decoded.error, Proto::Error::OK);
+    assert!(decoded.rbf_signaling);
+    assert_eq!(decoded.txid, decoded.wtxid);
+    assert_eq!(decoded.outputs[0].script_type, Proto::ScriptType::OpReturn);
+    assert!(decoded.outputs[0].address.is_empty());
+    assert_eq!(decoded.outputs[0].op_return_data, b"hello world".to_vec());
+}
+
+#[test]
+fn decode_transaction_amount_overflow() {
+    let coin = TestCoinContext::default();
+
+    // Two inputs and two OP_RETURN outputs of `u64::MAX` satoshis each.
+    let encoded = "010000000211111111111111111111111111111111111111111111111111111111111111110000000000ffffffff22222222222222222222222222222222222222222222222222222222222222220000000000ffffffff02ffffffffffffffff016affffffffffffffff016a00000000";
+    let decode = |prevouts: Vec<u64>| {
+        let input = Proto::DecodingInput {
+            encoded: hex(encoded).into(),
+            prevouts: prevouts
+                .into_iter()
+                .map(|value| UtxoProto::TxOut {
+                    value,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        BitcoinEntry.decode_transaction(&coin, input)
+    };
+
+    // The transaction itself decodes, the fee is unknown.
+    assert_eq!(decode(vec![]).error, Proto::Error::OK);
+
+    // The total input amount overflows.
+    assert_eq!(
+        decode(vec![u64::MAX, 1]).error,
+        Proto::Error::Error_invalid_transaction_encoding
+    );
+
+    // The total output amount overflows.
+    assert_eq!(
+        decode(vec![1, 1]).error,
+        Proto::Error::Error_invalid_transaction_encoding
+    );
+}
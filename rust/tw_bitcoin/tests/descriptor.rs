@@ -0,0 +1,286 @@
+mod common;
+
+use common::{hex, MINER_FEE, ONE_BTC};
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const ALICE_PRIVATE_KEY: &str = "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+const BOB_PRIVATE_KEY: &str = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+
+fn descriptor_input(
+    txid: &str,
+    value: u64,
+    descriptor: &str,
+    pubkey: &[u8],
+) -> Proto::Input<'static> {
+    let txid: Vec<u8> = hex(txid).into_iter().rev().collect();
+
+    Proto::Input {
+        txid: txid.into(),
+        vout: 0,
+        value,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::descriptor(Proto::mod_Input::InputDescriptor {
+                descriptor: descriptor.to_string().into(),
+                public_key: pubkey.to_vec().into(),
+                ..Default::default()
+            }),
+        }),
+        ..Default::default()
+    }
+}
+
+fn descriptor_output(value: u64, descriptor: &str) -> Proto::Output<'static> {
+    Proto::Output {
+        value,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::descriptor(Proto::mod_Output::OutputDescriptor {
+                descriptor: descriptor.to_string().into(),
+                derivation_index: 0,
+            }),
+        }),
+    }
+}
+
+// Same transactions as in `coin_entry_sign_input_p2pkh_output_p2wpkh`, but
+// described with `pkh(..)` and `wpkh(..)` descriptors instead.
+#[test]
+fn coin_entry_sign_descriptor_pkh_and_wpkh() {
+    let coin = TestCoinContext::default();
+
+    let alice_pubkey = hex(ALICE_PUBKEY);
+    let bob_pubkey = hex(BOB_PUBKEY);
+
+    let signing = Proto::SigningInput {
+        private_key: hex(ALICE_PRIVATE_KEY).into(),
+        inputs: vec![descriptor_input(
+            "181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911",
+            ONE_BTC * 50,
+            &format!("pkh({ALICE_PUBKEY})"),
+            &alice_pubkey,
+        )],
+        outputs: vec![descriptor_output(
+            ONE_BTC * 50 - MINER_FEE,
+            &format!("wpkh({BOB_PUBKEY})"),
+        )],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(&encoded, "020000000111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c18000000006b483045022100df9ed0b662b759e68b89a42e7144cddf787782a7129d4df05642dd825930e6e6022051a08f577f11cc7390684bbad2951a6374072253ffcf2468d14035ed0d8cd6490121028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28fffffffff01c0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d00000000");
+
+    let signing = Proto::SigningInput {
+        private_key: hex(BOB_PRIVATE_KEY).into(),
+        inputs: vec![descriptor_input(
+            "858e450a1da44397bde05ca2f8a78510d74c623cc2f69736a8b3fbfadc161f6e",
+            ONE_BTC * 50 - MINER_FEE,
+            &format!("wpkh({BOB_PUBKEY})"),
+            &bob_pubkey,
+        )],
+        outputs: vec![descriptor_output(
+            ONE_BTC * 50 - MINER_FEE - MINER_FEE,
+            &format!("wpkh({ALICE_PUBKEY})"),
+        )],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(&encoded, "020000000001016e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e850000000000ffffffff01806de7290100000016001460cda7b50f14c152d7401c28ae773c698db9237302483045022100a9b517de5a5e036d7133df499b5b751db6f9a01576a6c5dc38229ec08b6c45cd02200e42c9f8c707c9bf0ceab4f739ec8d683dc1f1f29e195a8da9bc183584d624a60121025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f00000000");
+}
+
+#[test]
+fn coin_entry_sign_descriptor_sh_wsh_multi() {
+    let coin = TestCoinContext::default();
+
+    // 1-of-2 multisig nested in P2SH, Alice signs.
+    let descriptor = format!("sh(wsh(multi(1,{ALICE_PUBKEY},{BOB_PUBKEY})))");
+
+    let signing = Proto::SigningInput {
+        private_key: hex(ALICE_PRIVATE_KEY).into(),
+        inputs: vec![descriptor_input(
+            "181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911",
+            ONE_BTC * 50,
+            &descriptor,
+            &hex(ALICE_PUBKEY),
+        )],
+        outputs: vec![descriptor_output(
+            ONE_BTC * 50 - MINER_FEE,
+            &format!("wpkh({BOB_PUBKEY})"),
+        )],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(&encoded, "0200000000010111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c180000000023220020ed69fd6c2338aee9dc40151600d94c035502bafa3f4ca2f5b6103d1f02fb2d6affffffff01c0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d030047304402203f7249f6a10e94bb887b44c7bad3b45d1da6c3d91b7623f1361c3d320d551651022013518d4666dc1b44665374e420702e2c4485b54eed35d66eefd886b80b50f7e601475121028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f52ae00000000");
+}
+
+#[test]
+fn coin_entry_sign_descriptor_sh_multi_bitcoin_cash() {
+    let coin = TestCoinContext::default();
+
+    // 1-of-2 multisig in P2SH, Alice signs with the `SIGHASH_FORKID` flag.
+    let descriptor = format!("sh(multi(1,{ALICE_PUBKEY},{BOB_PUBKEY}))");
+
+    let signing = Proto::SigningInput {
+        private_key: hex(ALICE_PRIVATE_KEY).into(),
+        inputs: vec![descriptor_input(
+            "181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911",
+            ONE_BTC * 50,
+            &descriptor,
+            &hex(ALICE_PUBKEY),
+        )],
+        outputs: vec![descriptor_output(
+            ONE_BTC * 50 - MINER_FEE,
+            &format!("pkh({ALICE_PUBKEY})"),
+        )],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        chain_info: Some(Proto::ChainInfo {
+            utxo_chain: Some(UtxoProto::Chain {
+                sighash_algorithm: UtxoProto::mod_Chain::SighashAlgorithm::ForkId,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(&encoded, "020000000111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c18000000009200483045022100edf294f003ab7cad87145da3f9c0594498b5ea7c3e1f6dc8c59b137153465cd102203bd590224456c05c7a17b6616a6b83f321cce9552ac5af5b03306ad692576bd941475121028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f52aeffffffff01c0aff629010000001976a91460cda7b50f14c152d7401c28ae773c698db9237388ac00000000");
+}
+
+#[test]
+fn coin_entry_sign_descriptor_tr_script_path() {
+    let coin = TestCoinContext::default();
+
+    // Alice is the internal key, Bob spends the `pk(BOB)` leaf.
+    let alice_xonly = &ALICE_PUBKEY[2..];
+    let bob_xonly = &BOB_PUBKEY[2..];
+    let descriptor = format!("tr({alice_xonly},{{pk({alice_xonly}),pk({bob_xonly})}})");
+
+    let mut input = descriptor_input(
+        "181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911",
+        ONE_BTC * 50,
+        &descriptor,
+        &hex(BOB_PUBKEY),
+    );
+    if let ProtoInputRecipient::builder(ref mut builder) = input.to_recipient {
+        if let ProtoInputBuilder::descriptor(ref mut descriptor) = builder.variant {
+            descriptor.leaf_script = hex(&format!("20{bob_xonly}ac")).into();
+        }
+    }
+
+    let signing = Proto::SigningInput {
+        private_key: hex(BOB_PRIVATE_KEY).into(),
+        inputs: vec![input],
+        outputs: vec![descriptor_output(
+            ONE_BTC * 50 - MINER_FEE,
+            &format!("wpkh({BOB_PUBKEY})"),
+        )],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        dangerous_use_fixed_schnorr_rng: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing.clone());
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(&encoded, "0200000000010111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c180000000000ffffffff01c0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d034154d3de0a0f2c16bb4ac143ed21de0867f5767e9f41069fb81800f8a2bb0498aa14dc294cede601085c66122c5fe1fc87d612adce981bbba8ef7cf033013a7d370122205a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22fac41c18d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f7f0c7723fcda3c0bcf742e7dfef1f0f8f7e662f9f62a28bea3674c6dabc27a0c00000000");
+
+    // A key-path spend is not supported for descriptors with a script tree.
+    let signing = Proto::SigningInput {
+        inputs: vec![descriptor_input(
+            "181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911",
+            ONE_BTC * 50,
+            &descriptor,
+            &hex(BOB_PUBKEY),
+        )],
+        ..signing
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(
+        signed.error,
+        Proto::Error::Error_invalid_descriptor_leaf_script
+    );
+}
+
+#[test]
+fn coin_entry_sign_descriptor_wsh_multi_missing_signature() {
+    let coin = TestCoinContext::default();
+
+    // Only Alice signs, but both signatures are required.
+    let descriptor = format!("wsh(multi(2,{ALICE_PUBKEY},{BOB_PUBKEY}))");
+
+    let signing = Proto::SigningInput {
+        private_key: hex(ALICE_PRIVATE_KEY).into(),
+        inputs: vec![descriptor_input(
+            "181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911",
+            ONE_BTC * 50,
+            &descriptor,
+            &hex(ALICE_PUBKEY),
+        )],
+        outputs: vec![descriptor_output(
+            ONE_BTC * 50 - MINER_FEE,
+            &format!("wpkh({BOB_PUBKEY})"),
+        )],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_descriptor_unsatisfiable);
+}
+
+#[test]
+fn coin_entry_sign_invalid_descriptor() {
+    let coin = TestCoinContext::default();
+
+    let signing = Proto::SigningInput {
+        private_key: hex(ALICE_PRIVATE_KEY).into(),
+        inputs: vec![descriptor_input(
+            "181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911",
+            ONE_BTC * 50,
+            "wsh(multi(2,invalid))",
+            &hex(ALICE_PUBKEY),
+        )],
+        outputs: vec![descriptor_output(
+            ONE_BTC * 50 - MINER_FEE,
+            &format!("wpkh({BOB_PUBKEY})"),
+        )],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_invalid_descriptor);
+}
@@ -0,0 +1,208 @@
+mod common;
+
+use common::{hex, MINER_FEE, ONE_BTC};
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoInputType = Proto::mod_FeeEstimationInput::InputType;
+type ProtoOutputType = Proto::mod_FeeEstimationInput::OutputType;
+
+const ALICE_PRIVATE_KEY: &str = "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+
+fn input(script_type: Proto::ScriptType) -> ProtoInputType {
+    ProtoInputType {
+        script_type,
+        ..Default::default()
+    }
+}
+
+fn output(script_type: Proto::ScriptType) -> ProtoOutputType {
+    ProtoOutputType {
+        script_type,
+        ..Default::default()
+    }
+}
+
+fn estimate(
+    inputs: Vec<ProtoInputType>,
+    outputs: Vec<ProtoOutputType>,
+) -> Proto::FeeEstimationOutput<'static> {
+    let coin = TestCoinContext::default();
+
+    let proto = Proto::FeeEstimationInput {
+        transaction: Proto::mod_FeeEstimationInput::OneOftransaction::script_types(
+            Proto::mod_FeeEstimationInput::ScriptTypes {
+                inputs,
+                outputs,
+                fee_per_vb: 10,
+            },
+        ),
+    };
+
+    BitcoinEntry.estimate_fee(&coin, proto)
+}
+
+#[test]
+fn estimate_fee_script_types() {
+    use Proto::ScriptType::*;
+
+    // (inputs, outputs, weight, vsize)
+    let cases = vec![
+        (vec![input(P2PKH)], vec![output(P2PKH)], 772, 193),
+        (
+            vec![input(P2WPKH)],
+            vec![output(P2WPKH), output(P2WPKH)],
+            563,
+            141,
+        ),
+        (vec![input(P2TR)], vec![output(P2TR)], 445, 112),
+        // Nested P2WPKH.
+        (vec![input(P2SH)], vec![output(P2WPKH)], 531, 133),
+        // Legacy inputs of a Segwit transaction have an empty witness.
+        (
+            vec![input(P2PKH), input(P2WPKH)],
+            vec![output(P2WPKH)],
+            1036,
+            259,
+        ),
+        // OP_RETURN with 80 bytes of data.
+        (
+            vec![input(P2WPKH)],
+            vec![
+                output(P2WPKH),
+                ProtoOutputType {
+                    script_type: OpReturn,
+                    data_size: 80,
+                },
+            ],
+            807,
+            202,
+        ),
+    ];
+
+    for (inputs, outputs, weight, vsize) in cases {
+        let estimation = estimate(inputs, outputs);
+        assert_eq!(estimation.error, Proto::Error::OK);
+        assert_eq!((estimation.weight, estimation.vsize), (weight, vsize));
+        assert_eq!(estimation.fee, vsize * 10);
+    }
+}
+
+#[test]
+fn estimate_fee_multisig_and_script_path() {
+    use Proto::ScriptType::*;
+
+    let multisig = |script_type| ProtoInputType {
+        script_type,
+        multisig_threshold: 2,
+        multisig_keys: 3,
+        ..Default::default()
+    };
+
+    // 2-of-3 P2WSH multisig.
+    let estimation = estimate(vec![multisig(P2WSH)], vec![output(P2WPKH)]);
+    assert_eq!(estimation.error, Proto::Error::OK);
+    assert_eq!((estimation.weight, estimation.vsize), (586, 147));
+
+    // 2-of-3 P2SH multisig.
+    let estimation = estimate(vec![multisig(P2SH)], vec![output(P2WPKH)]);
+    assert_eq!(estimation.error, Proto::Error::OK);
+    assert_eq!((estimation.weight, estimation.vsize), (1360, 340));
+
+    // Taproot script-path spend of `<xonly> OP_CHECKSIG` at depth one.
+    let script_path = ProtoInputType {
+        script_type: P2TR,
+        tapscript_size: 34,
+        tapscript_depth: 1,
+        ..Default::default()
+    };
+    let estimation = estimate(vec![script_path], vec![output(P2TR)]);
+    assert_eq!(estimation.error, Proto::Error::OK);
+    assert_eq!((estimation.weight, estimation.vsize), (546, 137));
+}
+
+#[test]
+fn estimate_fee_invalid_script_types() {
+    use Proto::ScriptType::*;
+
+    let estimation = estimate(vec![input(Unknown)], vec![output(P2WPKH)]);
+    assert_eq!(
+        estimation.error,
+        Proto::Error::Error_unsupported_script_type
+    );
+
+    let estimation = estimate(vec![input(P2WPKH)], vec![output(Unknown)]);
+    assert_eq!(
+        estimation.error,
+        Proto::Error::Error_unsupported_script_type
+    );
+
+    // P2WSH inputs must be multisigs.
+    let estimation = estimate(vec![input(P2WSH)], vec![output(P2WPKH)]);
+    assert_eq!(estimation.error, Proto::Error::Error_invalid_multisig);
+}
+
+#[test]
+fn estimate_fee_signing_input() {
+    let coin = TestCoinContext::default();
+
+    let txid: Vec<u8> = hex("181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911")
+        .into_iter()
+        .rev()
+        .collect();
+
+    let tx1 = Proto::Input {
+        txid: txid.into(),
+        vout: 0,
+        value: ONE_BTC * 50,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2pkh(hex(ALICE_PUBKEY).into()),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: ONE_BTC * 50 - MINER_FEE,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(hex(BOB_PUBKEY).into()),
+            }),
+        }),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: hex(ALICE_PRIVATE_KEY).into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        fee_per_vb: 10,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let estimation = BitcoinEntry.estimate_fee(
+        &coin,
+        Proto::FeeEstimationInput {
+            transaction: Proto::mod_FeeEstimationInput::OneOftransaction::signing_input(
+                signing.clone(),
+            ),
+        },
+    );
+    assert_eq!(estimation.error, Proto::Error::OK);
+    assert_eq!(
+        (estimation.weight, estimation.vsize, estimation.fee),
+        (760, 190, 1900)
+    );
+
+    // The estimate is never lower than the signed transaction.
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert!(signed.weight <= estimation.weight);
+}
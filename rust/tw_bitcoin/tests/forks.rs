@@ -0,0 +1,351 @@
+mod common;
+
+use common::{hex, MINER_FEE, ONE_BTC};
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::modules::cash_addr::CashAddress;
+use tw_bitcoin::modules::chain_info::script_pubkey_from_address;
+use tw_bitcoin::modules::signer::Signer;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+fn bitcoin_cash_chain_info() -> Proto::ChainInfo<'static> {
+    Proto::ChainInfo {
+        p2pkh_prefix: vec![0x00].into(),
+        p2sh_prefix: vec![0x05].into(),
+        cash_addr_prefix: "bitcoincash".into(),
+        utxo_chain: Some(UtxoProto::Chain {
+            sighash_algorithm: UtxoProto::mod_Chain::SighashAlgorithm::ForkId,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+const BITCOIN_CASH_ENCODED: &str = "0100000001e28c2b955293159898e34c6840d99bf4d390e2ee1c6f606939f18ee1e2000d05020000006b483045022100b70d158b43cbcded60e6977e93f9a84966bc0cec6f2dfd1463d1223a90563f0d02207548d081069de570a494d0967ba388ff02641d91cadb060587ead95a98d4e3534121038eab72ec78e639d02758e7860cdec018b49498c307791f785aa3019622f4ea5bffffffff0258020000000000001976a914769bdff96a02f9135a1d19b749db6a78fe07dc9088ace5100000000000001976a9149e089b6889e032d46e3b915a3392edfd616fb1c488ac00000000";
+
+fn bitcoin_cash_signing_input() -> Proto::SigningInput<'static> {
+    let private_key = hex("7fdafb9db5bc501f2096e7d13d331dc7a75d9594af3d251313ba8b6200f4e384");
+    let pubkey = hex("038eab72ec78e639d02758e7860cdec018b49498c307791f785aa3019622f4ea5b");
+
+    let tx1 = Proto::Input {
+        txid: hex("e28c2b955293159898e34c6840d99bf4d390e2ee1c6f606939f18ee1e2000d05").into(),
+        vout: 2,
+        value: 5151,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2pkh(pubkey.into()),
+        }),
+        ..Default::default()
+    };
+
+    // CashAddr of `1Bp9U1ogV3A14FMvKbRJms7ctyso4Z4Tcx`.
+    let out1 = Proto::Output {
+        value: 600,
+        to_recipient: ProtoOutputRecipient::from_address(
+            "bitcoincash:qpmfhhledgp0jy66r5vmwjwmdfu0up7ujqcp07ha9v".into(),
+        ),
+    };
+
+    // Legacy addresses are supported too.
+    let out2 = Proto::Output {
+        value: 4325,
+        to_recipient: ProtoOutputRecipient::from_address(
+            "1FQc5LdgGHMHEN9nwkjmz6tWkxhPpxBvBU".into(),
+        ),
+    };
+
+    Proto::SigningInput {
+        version: 1,
+        private_key: private_key.into(),
+        inputs: vec![tx1],
+        outputs: vec![out1, out2],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        chain_info: Some(bitcoin_cash_chain_info()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn coin_entry_sign_bitcoin_cash() {
+    let coin = TestCoinContext::default();
+
+    let signed = BitcoinEntry.sign(&coin, bitcoin_cash_signing_input());
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(signed.fee, 226);
+    assert_eq!(
+        tw_encoding::hex::encode(&signed.txid, false),
+        "96ee20002b34e468f9d3c5ee54f6a8ddaa61c118889c4f35395c2cd93ba5bbb4"
+    );
+
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+    assert_eq!(encoded, BITCOIN_CASH_ENCODED);
+}
+
+#[test]
+fn coin_entry_compile_bitcoin_cash() {
+    let coin = TestCoinContext::default();
+    let signing = bitcoin_cash_signing_input();
+
+    let sighashes = BitcoinEntry.preimage_hashes(&coin, signing.clone());
+    assert_eq!(sighashes.error, Proto::Error::OK);
+
+    // The signatures carry the standard sighash type, `compile` adds the
+    // `SIGHASH_FORKID` flag.
+    let signatures = Signer::signatures_from_proto(
+        &sighashes,
+        signing.private_key.to_vec(),
+        Default::default(),
+        false,
+    )
+    .unwrap();
+    assert_eq!(signatures[0].last(), Some(&0x01));
+
+    let compiled = BitcoinEntry.compile(&coin, signing, signatures, vec![]);
+    assert_eq!(compiled.error, Proto::Error::OK);
+
+    let encoded = tw_encoding::hex::encode(compiled.encoded, false);
+    assert_eq!(encoded, BITCOIN_CASH_ENCODED);
+}
+
+#[test]
+fn sign_partial_and_combine_bitcoin_cash_p2sh_multisig() {
+    let coin = TestCoinContext::default();
+
+    let alice_private_key = "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+    let alice_pubkey = hex("028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f");
+    let bob_private_key = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+    let bob_pubkey = hex("025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f");
+
+    let signing_input = |private_key: &str| {
+        let txid: Vec<u8> = hex("181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911")
+            .into_iter()
+            .rev()
+            .collect();
+
+        let tx1 = Proto::Input {
+            txid: txid.into(),
+            vout: 0,
+            value: ONE_BTC * 50,
+            sighash_type: UtxoProto::SighashType::All,
+            to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+                variant: ProtoInputBuilder::multisig(Proto::mod_Input::InputMultisig {
+                    multisig: Some(Proto::Multisig {
+                        threshold: 2,
+                        public_keys: vec![alice_pubkey.clone().into(), bob_pubkey.clone().into()],
+                        multisig_type: Proto::mod_Multisig::MultisigType::P2SH,
+                        disable_key_sorting: false,
+                    }),
+                    ..Default::default()
+                }),
+            }),
+            ..Default::default()
+        };
+
+        let out1 = Proto::Output {
+            value: ONE_BTC * 50 - MINER_FEE,
+            to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+                variant: ProtoOutputBuilder::p2pkh(Proto::ToPublicKeyOrHash {
+                    to_address: ProtoPubkeyOrHash::pubkey(alice_pubkey.clone().into()),
+                }),
+            }),
+        };
+
+        Proto::SigningInput {
+            private_key: hex(private_key).into(),
+            inputs: vec![tx1],
+            outputs: vec![out1],
+            input_selector: UtxoProto::InputSelector::UseAll,
+            disable_change_output: true,
+            chain_info: Some(bitcoin_cash_chain_info()),
+            ..Default::default()
+        }
+    };
+
+    let alice_partial = BitcoinEntry.sign_partial(&coin, signing_input(alice_private_key));
+    let bob_partial = BitcoinEntry.sign_partial(&coin, signing_input(bob_private_key));
+
+    let combining = Proto::CombiningInput {
+        signing_input: Some(signing_input("")),
+        partial_signatures: [alice_partial.inputs, bob_partial.inputs].concat(),
+    };
+    let combined = BitcoinEntry.combine_partial_signatures(&coin, combining);
+    assert_eq!(combined.error, Proto::Error::OK);
+
+    // Both signatures carry the `SIGHASH_FORKID` flag (0x41).
+    let encoded = tw_encoding::hex::encode(combined.encoded, false);
+    assert_eq!(&encoded, "020000000111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c1800000000da0047304402201f11d1c638cf0606218ef4b2361d05888941c8ff43ffc628a3fc312fb9fb2e3102201c80d866823e36615724c74a98911c1baa8a06bd54402a43a1be3b1c5cd267e941483045022100e30d00c9d05e53568d0868619652652f3aa28d1eae954609ec92ad5fe2ea807c02205da37abb7181c1164d470ee7c29d752a1cc264e6701ce687c9ad6d97c4229dca41475221025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f21028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f52aeffffffff01c0aff629010000001976a91460cda7b50f14c152d7401c28ae773c698db9237388ac00000000");
+}
+
+#[test]
+fn coin_entry_sign_zcash_sapling() {
+    let coin = TestCoinContext::default();
+
+    let private_key = hex("a9684f5bebd0e1208aae2e02bc9e9163bd1965ad23d8538644e1df8b99b99559");
+    let pubkey = hex("03b243171fae5516d1dc15f9178cfcc5fdc67b0a883055c117b01ba8af29b953f6");
+
+    let tx1 = Proto::Input {
+        txid: hex("53685b8809efc50dd7d5cb0906b307a1b8aa5157baa5fc1bd6fe2d0344dd193a").into(),
+        vout: 0,
+        value: 494_000,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2pkh(pubkey.as_slice().into()),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: 488_000,
+        to_recipient: ProtoOutputRecipient::from_address(
+            "t1QahNjDdibyE4EdYkawUSKBBcVTSqv64CS".into(),
+        ),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: private_key.as_slice().into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        chain_info: Some(Proto::ChainInfo {
+            p2pkh_prefix: vec![0x1c, 0xb8].into(),
+            p2sh_prefix: vec![0x1c, 0xbd].into(),
+            utxo_chain: Some(UtxoProto::Chain {
+                sighash_algorithm: UtxoProto::mod_Chain::SighashAlgorithm::ZcashSapling,
+                branch_id: 0x76b809bb,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(signed.fee, 6_000);
+
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+    assert_eq!(encoded, "0400008085202f890153685b8809efc50dd7d5cb0906b307a1b8aa5157baa5fc1bd6fe2d0344dd193a000000006b483045022100ca0be9f37a4975432a52bb65b25e483f6f93d577955290bb7fb0060a93bfc92002203e0627dff004d3c72a957dc9f8e4e0e696e69d125e4d8e275d119001924d3b48012103b243171fae5516d1dc15f9178cfcc5fdc67b0a883055c117b01ba8af29b953f6ffffffff0140720700000000001976a91449964a736f3713d64283fd0018626ba50091c7e988ac00000000000000000000000000000000000000");
+}
+
+#[test]
+fn coin_entry_sign_zcash_missing_branch_id() {
+    let coin = TestCoinContext::default();
+
+    let tx1 = Proto::Input {
+        txid: hex("53685b8809efc50dd7d5cb0906b307a1b8aa5157baa5fc1bd6fe2d0344dd193a").into(),
+        vout: 0,
+        value: 494_000,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2pkh(
+                hex("03b243171fae5516d1dc15f9178cfcc5fdc67b0a883055c117b01ba8af29b953f6").into(),
+            ),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: 488_000,
+        to_recipient: ProtoOutputRecipient::custom_script_pubkey(
+            hex("76a91449964a736f3713d64283fd0018626ba50091c7e988ac").into(),
+        ),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: hex("a9684f5bebd0e1208aae2e02bc9e9163bd1965ad23d8538644e1df8b99b99559").into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        chain_info: Some(Proto::ChainInfo {
+            utxo_chain: Some(UtxoProto::Chain {
+                sighash_algorithm: UtxoProto::mod_Chain::SighashAlgorithm::ZcashNu5,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_utxo_missing_branch_id);
+}
+
+#[test]
+fn cash_addr_encode_decode() {
+    // Test vectors of the CashAddr specification.
+    let addr = CashAddress::decode(
+        "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a",
+        "bitcoincash",
+    )
+    .unwrap();
+    assert_eq!(
+        addr.script_pubkey().to_bytes(),
+        hex("76a91476a04053bda0a88bda5177b86a15c3b29f55987388ac")
+    );
+    assert_eq!(
+        addr.encode("bitcoincash"),
+        "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a"
+    );
+
+    // The prefix can be omitted and uppercase addresses are accepted.
+    let addr =
+        CashAddress::decode("PPM2QSZNHKS23Z7629MMS6S4CWEF74VCWVN0H829PQ", "bitcoincash").unwrap();
+    assert_eq!(
+        addr.script_pubkey().to_bytes(),
+        hex("a91476a04053bda0a88bda5177b86a15c3b29f55987387")
+    );
+
+    // Invalid checksum.
+    CashAddress::decode(
+        "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6b",
+        "bitcoincash",
+    )
+    .unwrap_err();
+    // Unexpected prefix.
+    CashAddress::decode(
+        "bchtest:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a",
+        "bitcoincash",
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn fork_address_to_script_pubkey() {
+    let litecoin = Proto::ChainInfo {
+        p2pkh_prefix: vec![0x30].into(),
+        p2sh_prefix: vec![0x32].into(),
+        hrp: "ltc".into(),
+        ..Default::default()
+    };
+
+    let script_pubkey =
+        script_pubkey_from_address(&litecoin, "ltc1qytnqzjknvv03jwfgrsmzt0ycmwqgl0asjnaxwu")
+            .unwrap();
+    assert_eq!(
+        script_pubkey.to_bytes(),
+        hex("001422e6014ad3631f1939281c3625bc98db808fbfb0")
+    );
+
+    let dogecoin = Proto::ChainInfo {
+        p2pkh_prefix: vec![0x1e].into(),
+        p2sh_prefix: vec![0x16].into(),
+        ..Default::default()
+    };
+
+    let script_pubkey =
+        script_pubkey_from_address(&dogecoin, "DLSSSUS3ex7YNDACJDxMER1ZMW579Vy8Zy").unwrap();
+    assert_eq!(
+        script_pubkey.to_bytes(),
+        hex("76a914a7d191ec42aa113e28cd858cceaa7c733ba2f77788ac")
+    );
+
+    // Bitcoin addresses are rejected.
+    script_pubkey_from_address(&dogecoin, "19cAJn4Ms8jodBBGtroBNNpCZiHAWGAq7X").unwrap_err();
+    script_pubkey_from_address(&litecoin, "bc1qten42eesehw0ktddcp0fws7d3ycsqez3f7d5yt")
+        .unwrap_err();
+}
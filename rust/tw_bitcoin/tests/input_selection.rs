@@ -872,3 +872,93 @@ fn input_selection_use_all_without_change_output() {
     assert!(tx.outputs[0].taproot_payload.is_empty());
     assert!(tx.outputs[0].control_block.is_empty());
 }
+
+#[test]
+fn input_selection_transfer_inscription() {
+    let coin = TestCoinContext::default();
+
+    let alice_private_key = hex(ALICE_PRIVATE_KEY);
+    let alice_pubkey = hex(ALICE_PUBKEY);
+    let bob_pubkey = hex(BOB_PUBKEY);
+
+    let input = |txid: u8, value, protection| Proto::Input {
+        txid: vec![txid; 32].into(),
+        vout: 0,
+        value,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2wpkh(alice_pubkey.as_slice().into()),
+        }),
+        protection,
+        ..Default::default()
+    };
+
+    let tx1 = input(1, ONE_BTC, None);
+    // The inscription to send.
+    let tx2 = input(
+        2,
+        546,
+        Some(UtxoProto::InputProtection {
+            inscription_offsets: vec![0],
+            transfer: true,
+            ..Default::default()
+        }),
+    );
+    // Another inscription, which must not be spent.
+    let tx3 = input(
+        3,
+        10_000,
+        Some(UtxoProto::InputProtection {
+            inscription_offsets: vec![0],
+            ..Default::default()
+        }),
+    );
+
+    let out1 = Proto::Output {
+        value: 546,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(bob_pubkey.as_slice().into()),
+            }),
+        }),
+    };
+
+    let change_output = Proto::Output {
+        value: 0,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(alice_pubkey.as_slice().into()),
+            }),
+        }),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: alice_private_key.as_slice().into(),
+        input_selector: UtxoProto::InputSelector::SelectInOrder,
+        inputs: vec![tx3.clone(), tx1.clone(), tx2.clone()],
+        outputs: vec![out1.clone()],
+        fee_per_vb: SAT_VBYTE,
+        change_output: Some(change_output),
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing.clone());
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    // The inscribed input is spent first, so the inscription is sent to the
+    // first output. The other inscription is not spent.
+    let transaction = signed.transaction.unwrap();
+    assert_eq!(transaction.inputs.len(), 2);
+    assert_eq!(transaction.inputs[0].txid, tx2.txid);
+    assert_eq!(transaction.inputs[1].txid, tx1.txid);
+    assert_eq!(transaction.outputs[0].value, 546);
+
+    // Spending all inputs would spend the other inscription.
+    let signing = Proto::SigningInput {
+        input_selector: UtxoProto::InputSelector::UseAll,
+        ..signing
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_utxo_protected_input);
+}
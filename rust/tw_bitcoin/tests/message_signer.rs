@@ -0,0 +1,256 @@
+mod common;
+
+use common::hex;
+use tw_bitcoin::modules::message_signer::BitcoinMessageSigner;
+use tw_coin_entry::modules::message_signer::MessageSigner;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type SignatureFormat = Proto::mod_MessageSigningInput::SignatureFormat;
+
+// Private key of the BIP-322 test vectors.
+const PRIVATE_KEY: &str = "bb051cd0dda0246f33c5a9e133ebd8e7bc02a92af6c41adc131ccd7826c5b004";
+const P2PKH_ADDRESS: &str = "14vV3aCHBeStb5bkenkNHbe2YAFinYdXgc";
+const P2SH_P2WPKH_ADDRESS: &str = "37qyp7jQAzqb2rCBpMvVtLDuuzKAUCVnJb";
+const P2WPKH_ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+const P2TR_ADDRESS: &str = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+
+fn sign(
+    private_key: &str,
+    address: &str,
+    message: &str,
+    signature_format: SignatureFormat,
+) -> Proto::MessageSigningOutput<'static> {
+    let coin = TestCoinContext::default();
+
+    let input = Proto::MessageSigningInput {
+        private_key: hex(private_key).into(),
+        message: message.into(),
+        address: address.into(),
+        signature_format,
+        dangerous_use_fixed_schnorr_rng: true,
+        ..Default::default()
+    };
+
+    BitcoinMessageSigner.sign_message(&coin, input)
+}
+
+fn verify(address: &str, message: &str, signature: &str) -> bool {
+    let coin = TestCoinContext::default();
+
+    let input = Proto::MessageVerifyingInput {
+        message: message.into(),
+        address: address.into(),
+        signature: signature.into(),
+    };
+
+    BitcoinMessageSigner.verify_message(&coin, input)
+}
+
+#[test]
+fn bip322_simple_p2wpkh() {
+    // Test vectors from BIP-322.
+    let tests = [
+        ("", "AkgwRQIhAPkJ1Q4oYS0htvyuSFHLxRQpFAY56b70UvE7Dxazen0ZAiAtZfFz1S6T6I23MWI2lK/pcNTWncuyL8UL+oMdydVgzAEhAsfxIAMZZEKUPYWI4BruhAQjzFT8FSFSajuFwrDL1Yhy"),
+        ("Hello World", "AkgwRQIhAOzyynlqt93lOKJr+wmmxIens//zPzl9tqIOua93wO6MAiBi5n5EyAcPScOjf1lAqIUIQtr3zKNeavYabHyR8eGhowEhAsfxIAMZZEKUPYWI4BruhAQjzFT8FSFSajuFwrDL1Yhy"),
+    ];
+
+    for (message, expected) in tests {
+        let output = sign(
+            PRIVATE_KEY,
+            P2WPKH_ADDRESS,
+            message,
+            SignatureFormat::BIP322_SIMPLE,
+        );
+        assert_eq!(output.error, Proto::Error::OK);
+        assert_eq!(output.signature, expected);
+        assert!(verify(P2WPKH_ADDRESS, message, expected));
+    }
+
+    // Signatures created with a non-deterministic nonce.
+    assert!(verify(P2WPKH_ADDRESS, "", "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="));
+    assert!(verify(P2WPKH_ADDRESS, "Hello World", "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="));
+
+    // Signature of a different message.
+    assert!(!verify(P2WPKH_ADDRESS, "Hello World", "AkgwRQIhAPkJ1Q4oYS0htvyuSFHLxRQpFAY56b70UvE7Dxazen0ZAiAtZfFz1S6T6I23MWI2lK/pcNTWncuyL8UL+oMdydVgzAEhAsfxIAMZZEKUPYWI4BruhAQjzFT8FSFSajuFwrDL1Yhy"));
+    // Different address.
+    assert!(!verify("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4", "", "AkgwRQIhAPkJ1Q4oYS0htvyuSFHLxRQpFAY56b70UvE7Dxazen0ZAiAtZfFz1S6T6I23MWI2lK/pcNTWncuyL8UL+oMdydVgzAEhAsfxIAMZZEKUPYWI4BruhAQjzFT8FSFSajuFwrDL1Yhy"));
+}
+
+#[test]
+fn bip322_simple_p2tr() {
+    let output = sign(
+        PRIVATE_KEY,
+        P2TR_ADDRESS,
+        "Hello World",
+        SignatureFormat::BIP322_SIMPLE,
+    );
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(
+        output.signature,
+        "AUDjpClYFHngjnqQ3F0/3dyrLsOHFNEm4rKaaAc9GsfhC5+DngPJmXTeAmz+yfsVRa61PD2k9/CEQnLDvNUn9Qug"
+    );
+    assert!(verify(P2TR_ADDRESS, "Hello World", &output.signature));
+
+    // Test vector from BIP-322.
+    assert!(verify(P2TR_ADDRESS, "Hello World", "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ=="));
+    assert!(!verify(P2TR_ADDRESS, "Hello", "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ=="));
+}
+
+#[test]
+fn bip322_full() {
+    let output = sign(
+        PRIVATE_KEY,
+        P2SH_P2WPKH_ADDRESS,
+        "Hello World",
+        SignatureFormat::BIP322_FULL,
+    );
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(output.signature, "AAAAAAABAcbRsnNWkk3JpUbXQM8ONPeKRfpyUCEoGI9hMjBvVKIjAAAAABcWABQrBdVk5qejPAh/FuD3MNFEASN5nQAAAAABAAAAAAAAAAABagJHMEQCIEX6thn7/YLhXFwH9r0BCUh1/BT+Troh7NQ0itSNMJP9AiBj86pukDT0wybpItePDkNg2ybV8q6+9bOMWD8KIEFLqQEhAsfxIAMZZEKUPYWI4BruhAQjzFT8FSFSajuFwrDL1YhyAAAAAA==");
+    assert!(verify(
+        P2SH_P2WPKH_ADDRESS,
+        "Hello World",
+        &output.signature
+    ));
+
+    let output = sign(
+        PRIVATE_KEY,
+        P2PKH_ADDRESS,
+        "Hello World",
+        SignatureFormat::BIP322_FULL,
+    );
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(output.signature, "AAAAAAHZIvdvR4fompS+lLTvaKJgjitVabp8CizknOvglZs2XgAAAABqRzBEAiB3hjKYQcm/KGTsalB3I4kixH3+uDyHQzt1PN5cBGJsvQIgJnRxSVWIbijmMST7VnxGpI8OOCU/tky8Pg7UH5HgSt4BIQLH8SADGWRClD2FiOAa7oQEI8xU/BUhUmo7hcKwy9WIcgAAAAABAAAAAAAAAAABagAAAAA=");
+    assert!(verify(P2PKH_ADDRESS, "Hello World", &output.signature));
+    assert!(!verify(P2PKH_ADDRESS, "Hello", &output.signature));
+
+    // The full format is also valid for P2WPKH.
+    let output = sign(
+        PRIVATE_KEY,
+        P2WPKH_ADDRESS,
+        "",
+        SignatureFormat::BIP322_FULL,
+    );
+    assert_eq!(output.error, Proto::Error::OK);
+    assert!(verify(P2WPKH_ADDRESS, "", &output.signature));
+
+    // The simple format requires an empty scriptSig.
+    let output = sign(
+        PRIVATE_KEY,
+        P2SH_P2WPKH_ADDRESS,
+        "Hello World",
+        SignatureFormat::BIP322_SIMPLE,
+    );
+    assert_eq!(
+        output.error,
+        Proto::Error::Error_unsupported_address_recipient
+    );
+}
+
+#[test]
+fn legacy_sign_verify() {
+    let private_key = "1111111111111111111111111111111111111111111111111111111111111111";
+
+    // The header byte indicates the address type (BIP-137).
+    let tests = [
+        ("1Q1pE5vPGEEMqRcVRMbtBK842Y6Pzo6nK9", "IKky6/fatIYKrHVKaklT5+KphBWcxiweV0FyFpGsOhvjPHTyIbkow5AXQxgn99n6scjejxc3cLYwUIx/74GBPe0="),
+        ("3PFpzMLrKWsphFtc8BesF3MGPnimKMuF4x", "JKky6/fatIYKrHVKaklT5+KphBWcxiweV0FyFpGsOhvjPHTyIbkow5AXQxgn99n6scjejxc3cLYwUIx/74GBPe0="),
+        ("bc1ql3e9pgs3mmwuwrh95fecme0s0qtn2880lsvsd5", "KKky6/fatIYKrHVKaklT5+KphBWcxiweV0FyFpGsOhvjPHTyIbkow5AXQxgn99n6scjejxc3cLYwUIx/74GBPe0="),
+    ];
+
+    for (address, expected) in tests {
+        let output = sign(private_key, address, "Hello World", SignatureFormat::LEGACY);
+        assert_eq!(output.error, Proto::Error::OK);
+        assert_eq!(output.signature, expected);
+        assert!(verify(address, "Hello World", expected));
+        assert!(!verify(address, "Hello", expected));
+    }
+
+    // Created by Bitcoin Core `signmessage`.
+    assert!(verify(
+        "mpLQjfK79b7CCV4VMJWEWAj5Mpx8Up5zxB",
+        "This is just a test message",
+        "INbVnW4e6PeRmsv2Qgu8NuopvrVjkcxob+sX8OcZG0SALhWybUjzMLPdAsXI46YZGb0KQTRii+wWIQzRpG/U+S0="
+    ));
+
+    // Taproot addresses are not supported by the legacy format.
+    let output = sign(
+        private_key,
+        P2TR_ADDRESS,
+        "Hello World",
+        SignatureFormat::LEGACY,
+    );
+    assert_eq!(
+        output.error,
+        Proto::Error::Error_unsupported_address_recipient
+    );
+
+    // The private key does not belong to the address.
+    let output = sign(
+        private_key,
+        P2WPKH_ADDRESS,
+        "Hello World",
+        SignatureFormat::LEGACY,
+    );
+    assert_eq!(output.error, Proto::Error::Error_message_address_mismatch);
+}
+
+#[test]
+fn message_preimage_hashes() {
+    let coin = TestCoinContext::default();
+
+    // Without a private key, e.g. for external signing.
+    let input = Proto::MessageSigningInput {
+        message: "Hello World".into(),
+        address: P2WPKH_ADDRESS.into(),
+        signature_format: SignatureFormat::BIP322_SIMPLE,
+        ..Default::default()
+    };
+
+    let output = BitcoinMessageSigner.message_preimage_hashes(&coin, input);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(output.sighashes.len(), 1);
+    assert_eq!(
+        output.sighashes[0].signing_method,
+        UtxoProto::SigningMethod::Segwit
+    );
+
+    // P2SH-P2WPKH requires the public key.
+    let input = Proto::MessageSigningInput {
+        message: "Hello World".into(),
+        address: P2SH_P2WPKH_ADDRESS.into(),
+        signature_format: SignatureFormat::BIP322_FULL,
+        ..Default::default()
+    };
+    let output = BitcoinMessageSigner.message_preimage_hashes(&coin, input);
+    assert_eq!(output.error, Proto::Error::Error_invalid_public_key);
+
+    let input = Proto::MessageSigningInput {
+        message: "Hello World".into(),
+        address: P2SH_P2WPKH_ADDRESS.into(),
+        signature_format: SignatureFormat::BIP322_FULL,
+        public_key: hex("02c7f12003196442943d8588e01aee840423cc54fc1521526a3b85c2b0cbd58872")
+            .into(),
+        ..Default::default()
+    };
+    let output = BitcoinMessageSigner.message_preimage_hashes(&coin, input);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(
+        output.sighashes[0].signing_method,
+        UtxoProto::SigningMethod::Segwit
+    );
+
+    let input = Proto::MessageSigningInput {
+        message: "This is just a test message".into(),
+        address: P2PKH_ADDRESS.into(),
+        signature_format: SignatureFormat::LEGACY,
+        ..Default::default()
+    };
+    let output = BitcoinMessageSigner.message_preimage_hashes(&coin, input);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(
+        output.sighashes[0].signing_method,
+        UtxoProto::SigningMethod::Legacy
+    );
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use common::{hex, MINER_FEE, ONE_BTC};
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const ALICE_PRIVATE_KEY: &str = "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+const BOB_PRIVATE_KEY: &str = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+
+fn multisig(multisig_type: Proto::mod_Multisig::MultisigType) -> Proto::Multisig<'static> {
+    Proto::Multisig {
+        threshold: 2,
+        // Not sorted, BIP-67 sorting puts Bob's key first.
+        public_keys: vec![hex(ALICE_PUBKEY).into(), hex(BOB_PUBKEY).into()],
+        multisig_type,
+        disable_key_sorting: false,
+    }
+}
+
+fn signing_input(
+    multisig_type: Proto::mod_Multisig::MultisigType,
+    private_key: &str,
+) -> Proto::SigningInput<'static> {
+    let txid: Vec<u8> = hex("181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911")
+        .into_iter()
+        .rev()
+        .collect();
+
+    let tx1 = Proto::Input {
+        txid: txid.into(),
+        vout: 0,
+        value: ONE_BTC * 50,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::multisig(Proto::mod_Input::InputMultisig {
+                multisig: Some(multisig(multisig_type)),
+                ..Default::default()
+            }),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: ONE_BTC * 50 - MINER_FEE,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(hex(ALICE_PUBKEY).into()),
+            }),
+        }),
+    };
+
+    Proto::SigningInput {
+        private_key: hex(private_key).into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn derive_multisig_addresses() {
+    let coin = TestCoinContext::default();
+    let network = Proto::Network::Bitcoin;
+
+    let address = BitcoinEntry
+        .derive_multisig_address(
+            &coin,
+            &multisig(Proto::mod_Multisig::MultisigType::P2SH),
+            network,
+        )
+        .unwrap();
+    assert_eq!(address.to_string(), "38XAZfnbr4E9MxyCUpBxHsDx3AHX7WMkVu");
+
+    let address = BitcoinEntry
+        .derive_multisig_address(
+            &coin,
+            &multisig(Proto::mod_Multisig::MultisigType::P2WSH),
+            network,
+        )
+        .unwrap();
+    assert_eq!(
+        address.to_string(),
+        "bc1qztmd66afdjradgnwx6xk2jhywprqg69l29xtpfypqed9k29zr5dqcpp0jp"
+    );
+
+    let address = BitcoinEntry
+        .derive_multisig_address(
+            &coin,
+            &multisig(Proto::mod_Multisig::MultisigType::P2SH_P2WSH),
+            network,
+        )
+        .unwrap();
+    assert_eq!(address.to_string(), "355yqY2L1WZcTCKhpu841yysUzUvA94FxE");
+
+    // The order of the public keys does not matter.
+    let mut reordered = multisig(Proto::mod_Multisig::MultisigType::P2WSH);
+    reordered.public_keys.reverse();
+    let address = BitcoinEntry
+        .derive_multisig_address(&coin, &reordered, network)
+        .unwrap();
+    assert_eq!(
+        address.to_string(),
+        "bc1qztmd66afdjradgnwx6xk2jhywprqg69l29xtpfypqed9k29zr5dqcpp0jp"
+    );
+
+    // Unless sorting is disabled.
+    let mut unsorted = multisig(Proto::mod_Multisig::MultisigType::P2WSH);
+    unsorted.disable_key_sorting = true;
+    let address = BitcoinEntry
+        .derive_multisig_address(&coin, &unsorted, network)
+        .unwrap();
+    assert_ne!(
+        address.to_string(),
+        "bc1qztmd66afdjradgnwx6xk2jhywprqg69l29xtpfypqed9k29zr5dqcpp0jp"
+    );
+
+    // Threshold exceeds the number of public keys.
+    let mut invalid = multisig(Proto::mod_Multisig::MultisigType::P2WSH);
+    invalid.threshold = 3;
+    assert!(BitcoinEntry
+        .derive_multisig_address(&coin, &invalid, network)
+        .is_err());
+}
+
+#[test]
+fn sign_partial_and_combine_p2wsh() {
+    let coin = TestCoinContext::default();
+    let multisig_type = Proto::mod_Multisig::MultisigType::P2WSH;
+
+    let alice_partial =
+        BitcoinEntry.sign_partial(&coin, signing_input(multisig_type, ALICE_PRIVATE_KEY));
+    assert_eq!(alice_partial.error, Proto::Error::OK);
+    assert_eq!(alice_partial.inputs.len(), 1);
+    assert_eq!(
+        alice_partial.inputs[0].signatures[0].public_key,
+        hex(ALICE_PUBKEY)
+    );
+    assert_eq!(alice_partial.inputs[0].signatures[0].signature, hex("304502210097e4c7b95e644ad686569ddbc95b1c9eef1954210203ce028745c3a3207fa4200220264017057f82a221eda15a3fa7074631040d515e0dc2e3b03ff3d5cac920e7ff01"));
+
+    // The threshold is not met with Alice's signature alone.
+    let combining = Proto::CombiningInput {
+        signing_input: Some(signing_input(multisig_type, "")),
+        partial_signatures: alice_partial.inputs.clone(),
+    };
+    let combined = BitcoinEntry.combine_partial_signatures(&coin, combining);
+    assert_eq!(
+        combined.error,
+        Proto::Error::Error_multisig_threshold_not_met
+    );
+
+    let bob_partial =
+        BitcoinEntry.sign_partial(&coin, signing_input(multisig_type, BOB_PRIVATE_KEY));
+    assert_eq!(bob_partial.error, Proto::Error::OK);
+    assert_eq!(bob_partial.inputs.len(), 1);
+
+    let combining = Proto::CombiningInput {
+        signing_input: Some(signing_input(multisig_type, "")),
+        partial_signatures: [alice_partial.inputs, bob_partial.inputs].concat(),
+    };
+    let combined = BitcoinEntry.combine_partial_signatures(&coin, combining);
+    let encoded = tw_encoding::hex::encode(combined.encoded, false);
+
+    assert_eq!(combined.error, Proto::Error::OK);
+    assert_eq!(&encoded, "0200000000010111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c180000000000ffffffff01c0aff6290100000016001460cda7b50f14c152d7401c28ae773c698db9237304004730440220601ebd9b03d0b8b484e1371aea2f8da7d67326a16b322aead1aa3a315bf9be8702201c05e9e080fec8d15a65420ff0ff6efd7b687bcf536c98bb1639244d197832440148304502210097e4c7b95e644ad686569ddbc95b1c9eef1954210203ce028745c3a3207fa4200220264017057f82a221eda15a3fa7074631040d515e0dc2e3b03ff3d5cac920e7ff01475221025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f21028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f52ae00000000");
+}
+
+#[test]
+fn sign_partial_and_combine_p2sh() {
+    let coin = TestCoinContext::default();
+    let multisig_type = Proto::mod_Multisig::MultisigType::P2SH;
+
+    let alice_partial =
+        BitcoinEntry.sign_partial(&coin, signing_input(multisig_type, ALICE_PRIVATE_KEY));
+    let bob_partial =
+        BitcoinEntry.sign_partial(&coin, signing_input(multisig_type, BOB_PRIVATE_KEY));
+
+    let combining = Proto::CombiningInput {
+        signing_input: Some(signing_input(multisig_type, "")),
+        partial_signatures: [bob_partial.inputs, alice_partial.inputs].concat(),
+    };
+    let combined = BitcoinEntry.combine_partial_signatures(&coin, combining);
+    let encoded = tw_encoding::hex::encode(combined.encoded, false);
+
+    assert_eq!(combined.error, Proto::Error::OK);
+    assert_eq!(&encoded, "020000000111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c1800000000db00483045022100f74db4a37232cd9299e42b6f06aa0807175836b0fa65b24bb582507568aef06a022012867196be7bbbe05b74e5ffa9daef77767f3c864e7378b8d95206e14418011301483045022100cb42ff720330d6fbd89f22aeee7d154152af436002ab835d64af3177acf11325022058101a2f4f7f467eb40e25e13331b7761164c8ff8420438f28b84c5ca8c4e13001475221025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f21028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f52aeffffffff01c0aff6290100000016001460cda7b50f14c152d7401c28ae773c698db9237300000000");
+}
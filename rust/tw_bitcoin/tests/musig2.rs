@@ -0,0 +1,203 @@
+mod common;
+
+use common::{hex, MINER_FEE, ONE_BTC};
+use secp256k1::{schnorr, Message, Secp256k1, XOnlyPublicKey};
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::modules::musig2::key_agg_context;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_hash::H256;
+use tw_keypair::ecdsa::secp256k1::PrivateKey;
+use tw_keypair::musig2::{aggregate_nonces, generate_nonce, Session};
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const ALICE_PRIVATE_KEY: &str = "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+const BOB_PRIVATE_KEY: &str = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+
+fn musig2() -> Proto::Musig2<'static> {
+    Proto::Musig2 {
+        public_keys: vec![hex(ALICE_PUBKEY).into(), hex(BOB_PUBKEY).into()],
+        disable_key_sorting: false,
+    }
+}
+
+#[test]
+fn musig2_output_script_pubkey() {
+    let coin = TestCoinContext::default();
+
+    let txid: Vec<u8> = hex("181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911")
+        .into_iter()
+        .rev()
+        .collect();
+
+    let tx1 = Proto::Input {
+        txid: txid.into(),
+        vout: 0,
+        value: ONE_BTC * 50,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2wpkh(hex(ALICE_PUBKEY).into()),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: ONE_BTC * 50 - MINER_FEE,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2tr_musig2(musig2()),
+        }),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: hex(ALICE_PRIVATE_KEY).into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    // The output key is the aggregated key with the Taproot tweak applied.
+    let output_key = key_agg_context(&musig2())
+        .unwrap()
+        .with_taproot_tweak(None)
+        .unwrap()
+        .x_only_public_key();
+
+    let mut expected = hex("5120");
+    expected.extend_from_slice(output_key.as_slice());
+
+    let transaction = signed.transaction.unwrap();
+    assert_eq!(transaction.outputs[0].script_pubkey, expected);
+
+    // The order of the public keys does not matter.
+    let mut reordered = musig2();
+    reordered.public_keys.reverse();
+    let reordered_key = key_agg_context(&reordered)
+        .unwrap()
+        .with_taproot_tweak(None)
+        .unwrap()
+        .x_only_public_key();
+    assert_eq!(reordered_key, output_key);
+
+    // Invalid public key.
+    let mut invalid = musig2();
+    invalid.public_keys.push(hex("02abcd").into());
+    assert!(key_agg_context(&invalid).is_err());
+}
+
+#[test]
+fn musig2_sign_key_path() {
+    let coin = TestCoinContext::default();
+
+    let txid: Vec<u8> = hex("181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911")
+        .into_iter()
+        .rev()
+        .collect();
+
+    let tx1 = Proto::Input {
+        txid: txid.into(),
+        vout: 0,
+        value: ONE_BTC * 50,
+        sighash_type: UtxoProto::SighashType::UseDefault,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2tr_musig2(Proto::mod_Input::InputTaprootMusig2 {
+                one_prevout: false,
+                musig2: Some(musig2()),
+            }),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: ONE_BTC * 50 - MINER_FEE,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(hex(ALICE_PUBKEY).into()),
+            }),
+        }),
+    };
+
+    let signing = Proto::SigningInput {
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    // Generate the sighashes.
+    let sighashes = BitcoinEntry.preimage_hashes(&coin, signing.clone());
+    assert_eq!(sighashes.error, Proto::Error::OK);
+    assert_eq!(
+        sighashes.sighashes[0].signing_method,
+        UtxoProto::SigningMethod::TaprootAll
+    );
+    let sighash = sighashes.sighashes[0].sighash.to_vec();
+
+    // Both signers aggregate the keys and apply the Taproot tweak.
+    let key_agg = key_agg_context(&musig2())
+        .unwrap()
+        .with_taproot_tweak(None)
+        .unwrap();
+    let output_key = key_agg.x_only_public_key();
+
+    let privates: Vec<_> = [ALICE_PRIVATE_KEY, BOB_PRIVATE_KEY]
+        .into_iter()
+        .map(|secret| PrivateKey::try_from(secret).unwrap())
+        .collect();
+
+    // Round 1: exchange the public nonces.
+    let (secnonces, pubnonces): (Vec<_>, Vec<_>) = privates
+        .iter()
+        .enumerate()
+        .map(|(i, private)| {
+            generate_nonce(
+                &H256::from([i as u8 + 1; 32]),
+                Some(private),
+                &private.public(),
+                Some(&output_key),
+                Some(sighash.as_slice()),
+                None,
+            )
+            .unwrap()
+        })
+        .unzip();
+    let agg_nonce = aggregate_nonces(&pubnonces).unwrap();
+
+    // Round 2: exchange the partial signatures.
+    let session = Session::new(&key_agg, &agg_nonce, &sighash);
+    let partials: Vec<_> = privates
+        .iter()
+        .zip(secnonces)
+        .map(|(private, secnonce)| session.sign(secnonce, private).unwrap())
+        .collect();
+    let signature = session.aggregate(&partials).unwrap();
+
+    // The aggregated signature is valid for the output key of the spent output.
+    let utxo_script_pubkey = sighashes.utxo_inputs[0].script_pubkey.to_vec();
+    assert_eq!(&utxo_script_pubkey[2..], output_key.as_slice());
+
+    let secp = Secp256k1::new();
+    secp.verify_schnorr(
+        &schnorr::Signature::from_slice(signature.as_slice()).unwrap(),
+        &Message::from_slice(&sighash).unwrap(),
+        &XOnlyPublicKey::from_slice(output_key.as_slice()).unwrap(),
+    )
+    .unwrap();
+
+    // Construct the final transaction.
+    let signed = BitcoinEntry.compile(&coin, signing, vec![signature.into_vec()], vec![]);
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    let transaction = signed.transaction.unwrap();
+    assert_eq!(transaction.inputs[0].witness_items.len(), 1);
+    assert_eq!(transaction.inputs[0].witness_items[0], signature.as_slice());
+}
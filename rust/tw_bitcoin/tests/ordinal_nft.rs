@@ -3,7 +3,9 @@ mod common;
 use common::hex;
 use tw_bitcoin::aliases::*;
 use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::modules::plan_builder::BitcoinPlanBuilder;
 use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::modules::plan_builder::PlanBuilder;
 use tw_coin_entry::test_utils::test_context::TestCoinContext;
 use tw_proto::BitcoinV2::Proto;
 use tw_proto::Utxo::Proto as UtxoProto;
@@ -128,3 +130,235 @@ fn coin_entry_sign_ordinal_nft_commit_reveal_transfer() {
     assert_eq!(transaction.inputs.len(), 1);
     assert_eq!(transaction.outputs.len(), 1);
 }
+
+fn p2wpkh_output(value: u64, pubkey: &[u8]) -> Proto::Output<'static> {
+    Proto::Output {
+        value,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(pubkey.to_vec().into()),
+            }),
+        }),
+    }
+}
+
+#[test]
+fn coin_entry_plan_ordinal_batch_commit_reveal() {
+    let coin = TestCoinContext::default();
+    let fee_per_vb = 5;
+
+    let alice_private_key = hex("e253373989199da27c48680e3a3fc0f648d50f9a727ef17a7fe6a4dc3b159129");
+    let alice_pubkey = hex("030f209b6ada5edb42c77fd2bc64ad650ae38314c8f451f3e36d80bc8e26f132cb");
+
+    let txid: Vec<u8> = hex("579590c3227253ad423b1e7e3c5b073b8a280d307c68aecd779df2600daa2f99")
+        .into_iter()
+        .rev()
+        .collect();
+
+    let tx1 = Proto::Input {
+        txid: txid.as_slice().into(),
+        vout: 0,
+        value: 32_400,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2wpkh(alice_pubkey.as_slice().into()),
+        }),
+        ..Default::default()
+    };
+
+    // The content exceeds the push limit of 520 bytes.
+    let image = vec![0xab; 1_200];
+    let json = br#"{"name":"batch"}"#.to_vec();
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoComposePlan::ordinal(Proto::mod_ComposePlan::ComposeOrdinalPlan {
+            private_key: alice_private_key.as_slice().into(),
+            inputs: vec![tx1],
+            input_selector: UtxoProto::InputSelector::SelectInOrder,
+            inscribe_to: alice_pubkey.as_slice().into(),
+            inscriptions: vec![
+                Proto::mod_ComposePlan::mod_ComposeOrdinalPlan::Inscription {
+                    inscription: Some(Proto::OrdinalInscription {
+                        mime_type: "image/png".into(),
+                        payload: image.as_slice().into(),
+                        // CBOR: `{"title": "batch"}`
+                        metadata: hex("a1657469746c65656261746368").into(),
+                        ..Default::default()
+                    }),
+                    tagged_output: Some(p2wpkh_output(546, &alice_pubkey)),
+                },
+                Proto::mod_ComposePlan::mod_ComposeOrdinalPlan::Inscription {
+                    inscription: Some(Proto::OrdinalInscription {
+                        mime_type: "application/json".into(),
+                        payload: json.as_slice().into(),
+                        ..Default::default()
+                    }),
+                    tagged_output: Some(p2wpkh_output(546, &alice_pubkey)),
+                },
+            ],
+            fee_per_vb,
+            change_output: Some(p2wpkh_output(0, &alice_pubkey)),
+            disable_change_output: false,
+            ..Default::default()
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::OK);
+
+    let ProtoTransactionPlan::ordinal(ordinal) = plan.plan else {
+        panic!("expected an ordinal plan");
+    };
+    let commit = ordinal.commit.unwrap();
+    let reveal = ordinal.reveal.unwrap();
+
+    // The second inscription is assigned to the first satoshi of the second
+    // output.
+    let Some(ProtoInputRecipient::builder(builder)) =
+        reveal.inputs.first().map(|input| &input.to_recipient)
+    else {
+        panic!("expected an input builder");
+    };
+    let ProtoInputBuilder::ordinal_batch(batch) = &builder.variant else {
+        panic!("expected an ordinal batch input");
+    };
+    assert_eq!(batch.inscriptions[0].pointer, 0);
+    assert_eq!(batch.inscriptions[1].pointer, 546);
+
+    let signed_commit = BitcoinEntry.sign(&coin, commit);
+    assert_eq!(signed_commit.error, Proto::Error::OK);
+
+    let commit_txid: Vec<u8> = signed_commit.txid.iter().copied().rev().collect();
+    assert_eq!(reveal.inputs[0].txid.as_ref(), commit_txid.as_slice());
+
+    let signed_reveal = BitcoinEntry.sign(&coin, reveal);
+    assert_eq!(signed_reveal.error, Proto::Error::OK);
+
+    // The commit output covers the exact fee of the reveal transaction.
+    assert_eq!(
+        signed_reveal.fee,
+        (signed_reveal.weight + 3) / 4 * fee_per_vb
+    );
+
+    let transaction = signed_reveal.transaction.unwrap();
+    assert_eq!(transaction.inputs.len(), 1);
+    assert_eq!(transaction.outputs.len(), 2);
+
+    // Signature, script and control block.
+    assert_eq!(transaction.inputs[0].witness_items.len(), 3);
+
+    let decoded = BitcoinEntry.decode_transaction(
+        &coin,
+        Proto::DecodingInput {
+            encoded: signed_reveal.encoded.clone(),
+            ..Default::default()
+        },
+    );
+    assert_eq!(decoded.error, Proto::Error::OK);
+
+    let inscriptions = &decoded.inputs[0].inscriptions;
+    assert_eq!(inscriptions.len(), 2);
+    assert_eq!(inscriptions[0].mime_type, "image/png");
+    assert_eq!(inscriptions[0].payload, image);
+    assert_eq!(inscriptions[1].mime_type, "application/json");
+    assert_eq!(inscriptions[1].payload, json);
+}
+
+#[test]
+fn coin_entry_plan_ordinal_with_parent() {
+    let coin = TestCoinContext::default();
+
+    let alice_private_key = hex("e253373989199da27c48680e3a3fc0f648d50f9a727ef17a7fe6a4dc3b159129");
+    let alice_pubkey = hex("030f209b6ada5edb42c77fd2bc64ad650ae38314c8f451f3e36d80bc8e26f132cb");
+
+    let funding_txid: Vec<u8> =
+        hex("579590c3227253ad423b1e7e3c5b073b8a280d307c68aecd779df2600daa2f99")
+            .into_iter()
+            .rev()
+            .collect();
+    let parent_txid = "f1ecce4fa8ef1b5c51288bf9a7b8e1a4d0b3f9c9d2b05c5b2fe14b6ae1c44a0e";
+
+    let p2wpkh_input = |txid: Vec<u8>, value| Proto::Input {
+        txid: txid.into(),
+        vout: 0,
+        value,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2wpkh(alice_pubkey.clone().into()),
+        }),
+        ..Default::default()
+    };
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoComposePlan::ordinal(Proto::mod_ComposePlan::ComposeOrdinalPlan {
+            private_key: alice_private_key.as_slice().into(),
+            inputs: vec![p2wpkh_input(funding_txid, 32_400)],
+            input_selector: UtxoProto::InputSelector::SelectInOrder,
+            inscribe_to: alice_pubkey.as_slice().into(),
+            inscriptions: vec![
+                Proto::mod_ComposePlan::mod_ComposeOrdinalPlan::Inscription {
+                    inscription: Some(Proto::OrdinalInscription {
+                        mime_type: "text/plain;charset=utf-8".into(),
+                        payload: b"child".to_vec().into(),
+                        parent: format!("{parent_txid}i0").into(),
+                        ..Default::default()
+                    }),
+                    tagged_output: Some(p2wpkh_output(546, &alice_pubkey)),
+                },
+            ],
+            fee_per_vb: 5,
+            change_output: Some(p2wpkh_output(0, &alice_pubkey)),
+            disable_change_output: false,
+            parent_input: Some(p2wpkh_input(
+                hex(parent_txid).into_iter().rev().collect(),
+                10_000,
+            )),
+            parent_output: Some(p2wpkh_output(0, &alice_pubkey)),
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::OK);
+
+    let ProtoTransactionPlan::ordinal(ordinal) = plan.plan else {
+        panic!("expected an ordinal plan");
+    };
+    let reveal = ordinal.reveal.unwrap();
+
+    // The parent inscription is returned by the first output, the child
+    // inscription follows it.
+    assert_eq!(reveal.inputs.len(), 2);
+    assert_eq!(reveal.outputs.len(), 2);
+    assert_eq!(reveal.outputs[0].value, 10_000);
+
+    let signed_commit = BitcoinEntry.sign(&coin, ordinal.commit.unwrap());
+    assert_eq!(signed_commit.error, Proto::Error::OK);
+
+    let commit_txid: Vec<u8> = signed_commit.txid.iter().copied().rev().collect();
+    assert_eq!(reveal.inputs[1].txid.as_ref(), commit_txid.as_slice());
+
+    let signed_reveal = BitcoinEntry.sign(&coin, reveal);
+    assert_eq!(signed_reveal.error, Proto::Error::OK);
+
+    // Invalid parent.
+    let compose = Proto::ComposePlan {
+        compose: ProtoComposePlan::ordinal(Proto::mod_ComposePlan::ComposeOrdinalPlan {
+            inscribe_to: alice_pubkey.as_slice().into(),
+            inscriptions: vec![
+                Proto::mod_ComposePlan::mod_ComposeOrdinalPlan::Inscription {
+                    inscription: Some(Proto::OrdinalInscription {
+                        mime_type: "text/plain;charset=utf-8".into(),
+                        payload: b"child".to_vec().into(),
+                        parent: parent_txid.into(),
+                        ..Default::default()
+                    }),
+                    tagged_output: Some(p2wpkh_output(546, &alice_pubkey)),
+                },
+            ],
+            ..Default::default()
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::Error_invalid_inscription_id);
+}
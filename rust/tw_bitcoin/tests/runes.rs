@@ -0,0 +1,424 @@
+mod common;
+
+use common::hex;
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::modules::plan_builder::BitcoinPlanBuilder;
+use tw_bitcoin::modules::transactions::{
+    Edict, Etching, Rune, RuneId, Runestone, SpacedRune, Terms,
+};
+use tw_bitcoin::native::{Transaction, TxOut};
+use tw_bitcoin::BitcoinEntry;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::modules::plan_builder::PlanBuilder;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const ALICE_PRIVATE_KEY: &str = "e253373989199da27c48680e3a3fc0f648d50f9a727ef17a7fe6a4dc3b159129";
+const ALICE_PUBKEY: &str = "030f209b6ada5edb42c77fd2bc64ad650ae38314c8f451f3e36d80bc8e26f132cb";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+
+fn funding_input() -> Proto::Input<'static> {
+    let txid: Vec<u8> = hex("8ec895b4d30adb01e38471ca1019bfc8c3e5fbd1f28d9e7b5653260d89989008")
+        .into_iter()
+        .rev()
+        .collect();
+
+    Proto::Input {
+        txid: txid.into(),
+        vout: 1,
+        value: 26_400,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2wpkh(hex(ALICE_PUBKEY).into()),
+        }),
+        ..Default::default()
+    }
+}
+
+fn p2wpkh_output(value: u64, pubkey: &str) -> Proto::Output<'static> {
+    Proto::Output {
+        value,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(hex(pubkey).into()),
+            }),
+        }),
+    }
+}
+
+fn decode(encoded: &[u8]) -> Proto::DecodingOutput<'static> {
+    let coin = TestCoinContext::default();
+
+    let decoded = BitcoinEntry.decode_transaction(
+        &coin,
+        Proto::DecodingInput {
+            encoded: encoded.into(),
+            ..Default::default()
+        },
+    );
+    assert_eq!(decoded.error, Proto::Error::OK);
+    decoded
+}
+
+#[test]
+fn rune_names_and_commitment() {
+    for (value, name) in [
+        (0, "A"),
+        (25, "Z"),
+        (26, "AA"),
+        (701, "ZZ"),
+        (702, "AAA"),
+        (2_055_900_680_524_219_742, "UNCOMMONGOODS"),
+        (u128::MAX, "BCGDENLQRQWDSLRUGSNLBTMFIJAV"),
+    ] {
+        assert_eq!(Rune::from_name(name).unwrap(), Rune(value));
+        assert_eq!(Rune(value).name(), name);
+    }
+
+    let spaced = SpacedRune::from_name("UNCOMMON•GOODS").unwrap();
+    assert_eq!(spaced.rune, Rune(2_055_900_680_524_219_742));
+    assert_eq!(spaced.spacers, 0b1000_0000);
+    assert_eq!(spaced.name(), "UNCOMMON•GOODS");
+    assert_eq!(SpacedRune::from_name("UNCOMMON.GOODS").unwrap(), spaced);
+
+    // The little-endian encoding without trailing zeros.
+    assert_eq!(spaced.rune.commitment(), hex("5e4521bcc606881c"));
+    assert_eq!(Rune(0).commitment(), Vec::<u8>::new());
+
+    // Invalid names.
+    for name in ["", "a", "A•", "•A", "A••B", "BCGDENLQRQWDSLRUGSNLBTMFIJAW"] {
+        Rune::from_name(name).unwrap_err();
+    }
+}
+
+#[test]
+fn runestone_encipher_decipher() {
+    let runestone = Runestone {
+        etching: Some(Etching {
+            divisibility: Some(1),
+            premine: Some(2),
+            rune: Some(Rune(3)),
+            spacers: Some(5),
+            symbol: Some('a'),
+            terms: Some(Terms {
+                cap: Some(6),
+                amount: Some(7),
+                height: (Some(8), Some(9)),
+                offset: (Some(10), Some(11)),
+            }),
+            turbo: true,
+        }),
+        edicts: vec![
+            Edict {
+                id: RuneId::new(5, 6).unwrap(),
+                amount: 4,
+                output: 1,
+            },
+            Edict {
+                id: RuneId::new(2, 3).unwrap(),
+                amount: 1,
+                output: 0,
+            },
+        ],
+        mint: RuneId::new(17, 18),
+        pointer: Some(0),
+        cenotaph: false,
+    };
+
+    // `OP_RETURN OP_13 <payload>`, the edicts are sorted and delta-encoded.
+    let script = runestone.encipher();
+    assert_eq!(
+        script.to_bytes(),
+        hex("6a5d270207040301010305056106020a0708060c080e09100a120b141114121600000203010003060401")
+    );
+
+    let tx = Transaction {
+        version: 2,
+        lock_time: tw_bitcoin::native::absolute::LockTime::ZERO,
+        input: vec![],
+        output: vec![
+            TxOut {
+                value: 0,
+                script_pubkey: script,
+            },
+            TxOut {
+                value: 546,
+                script_pubkey: Default::default(),
+            },
+        ],
+    };
+
+    let mut expected = runestone;
+    expected.edicts.sort_by_key(|edict| edict.id);
+    assert_eq!(Runestone::decipher(&tx).unwrap(), expected);
+}
+
+#[test]
+fn runestone_decipher_cenotaph() {
+    let runestone_tx = |payload: &[u8]| Transaction {
+        version: 2,
+        lock_time: tw_bitcoin::native::absolute::LockTime::ZERO,
+        input: vec![],
+        output: vec![
+            TxOut {
+                value: 0,
+                script_pubkey: [hex("6a5d"), vec![payload.len() as u8], payload.to_vec()]
+                    .concat()
+                    .into(),
+            },
+            TxOut {
+                value: 546,
+                script_pubkey: Default::default(),
+            },
+        ],
+    };
+
+    // Unrecognized odd tags are ignored.
+    let runestone = Runestone::decipher(&runestone_tx(&[127, 0])).unwrap();
+    assert_eq!(runestone, Runestone::default());
+
+    // Unrecognized even tags make the runestone a cenotaph.
+    let runestone = Runestone::decipher(&runestone_tx(&[126, 0])).unwrap();
+    assert!(runestone.cenotaph);
+
+    // Unterminated varint.
+    let runestone = Runestone::decipher(&runestone_tx(&[0x80])).unwrap();
+    assert!(runestone.cenotaph);
+
+    // The edict references a non-existing output.
+    let runestone = Runestone::decipher(&runestone_tx(&[0, 1, 1, 10, 3])).unwrap();
+    assert!(runestone.cenotaph);
+
+    // The etched name is retained.
+    let runestone = Runestone::decipher(&runestone_tx(&[2, 1, 4, 3, 126, 0])).unwrap();
+    assert!(runestone.cenotaph);
+    assert_eq!(runestone.etching.unwrap().rune, Some(Rune(3)));
+
+    // Not a runestone.
+    let mut tx = runestone_tx(&[]);
+    tx.output[0].script_pubkey = hex("6a0568656c6c6f").into();
+    assert_eq!(Runestone::decipher(&tx), None);
+}
+
+#[test]
+fn coin_entry_plan_rune_etch() {
+    let coin = TestCoinContext::default();
+    let fee_per_vb = 10;
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoComposePlan::rune_etch(Proto::mod_ComposePlan::ComposeRuneEtchPlan {
+            private_key: hex(ALICE_PRIVATE_KEY).into(),
+            inputs: vec![funding_input()],
+            input_selector: UtxoProto::InputSelector::SelectInOrder,
+            commit_to: hex(ALICE_PUBKEY).into(),
+            runestone: Some(Proto::Runestone {
+                etching: Some(Proto::RuneEtching {
+                    rune: "UNCOMMON•GOODS".into(),
+                    divisibility: 2,
+                    symbol: "⧉".into(),
+                    premine: hex("03e8").into(),
+                    terms: Some(Proto::RuneTerms {
+                        amount: hex("64").into(),
+                        cap: hex("0a").into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            rune_output: Some(p2wpkh_output(546, ALICE_PUBKEY)),
+            fee_per_vb,
+            change_output: Some(p2wpkh_output(0, ALICE_PUBKEY)),
+            disable_change_output: false,
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::OK);
+
+    let ProtoTransactionPlan::rune_etch(etch) = plan.plan else {
+        panic!("expected a rune etch plan");
+    };
+    let commit = etch.commit.unwrap();
+    let reveal = etch.reveal.unwrap();
+
+    // The reveal transaction spends the commitment output after it matured.
+    assert_eq!(reveal.version, 2);
+    assert_eq!(reveal.inputs.len(), 1);
+    assert_eq!(reveal.inputs[0].sequence, 5);
+    assert_eq!(reveal.inputs[0].value, commit.outputs[0].value);
+    assert_eq!(reveal.outputs.len(), 2);
+    assert_eq!(reveal.outputs[0].value, 546);
+    assert_eq!(reveal.outputs[1].value, 0);
+
+    let signed_commit = BitcoinEntry.sign(&coin, commit);
+    assert_eq!(signed_commit.error, Proto::Error::OK);
+
+    // The reveal transaction references the commit transaction.
+    let commit_txid: Vec<u8> = signed_commit.txid.iter().copied().rev().collect();
+    assert_eq!(reveal.inputs[0].txid.as_ref(), commit_txid.as_slice());
+
+    let signed_reveal = BitcoinEntry.sign(&coin, reveal);
+    assert_eq!(signed_reveal.error, Proto::Error::OK);
+
+    // The commitment output covers the fee of the reveal transaction.
+    assert!(signed_reveal.fee >= (signed_reveal.weight + 3) / 4 * fee_per_vb);
+
+    let transaction = signed_reveal.transaction.unwrap();
+    assert_eq!(transaction.inputs[0].sequence, 5);
+
+    // The tapscript reveals the commitment.
+    let tapscript = transaction.inputs[0].witness_items[1].to_vec();
+    assert!(tapscript
+        .windows(8)
+        .any(|window| window == hex("5e4521bcc606881c").as_slice()));
+
+    let decoded = decode(&signed_reveal.encoded);
+    let runestone = decoded.runestone.unwrap();
+    assert!(!runestone.cenotaph);
+
+    let etching = runestone.etching.unwrap();
+    assert_eq!(etching.rune, "UNCOMMON•GOODS");
+    assert_eq!(etching.divisibility, 2);
+    assert_eq!(etching.symbol, "⧉");
+    assert_eq!(etching.premine, hex("03e8"));
+
+    let terms = etching.terms.unwrap();
+    assert_eq!(terms.amount, hex("64"));
+    assert_eq!(terms.cap, hex("0a"));
+}
+
+#[test]
+fn coin_entry_plan_rune_etch_without_name() {
+    let coin = TestCoinContext::default();
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoComposePlan::rune_etch(Proto::mod_ComposePlan::ComposeRuneEtchPlan {
+            private_key: hex(ALICE_PRIVATE_KEY).into(),
+            inputs: vec![funding_input()],
+            commit_to: hex(ALICE_PUBKEY).into(),
+            runestone: Some(Proto::Runestone {
+                etching: Some(Proto::RuneEtching::default()),
+                ..Default::default()
+            }),
+            rune_output: Some(p2wpkh_output(546, ALICE_PUBKEY)),
+            disable_change_output: true,
+            ..Default::default()
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::Error_invalid_rune_name);
+}
+
+#[test]
+fn coin_entry_plan_rune_mint() {
+    let coin = TestCoinContext::default();
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoComposePlan::rune_mint(Proto::mod_ComposePlan::ComposeRuneMintPlan {
+            private_key: hex(ALICE_PRIVATE_KEY).into(),
+            inputs: vec![funding_input()],
+            input_selector: UtxoProto::InputSelector::SelectInOrder,
+            rune_id: Some(Proto::RuneId {
+                block: 840_000,
+                tx: 3,
+            }),
+            rune_output: Some(p2wpkh_output(546, ALICE_PUBKEY)),
+            fee_per_vb: 10,
+            change_output: Some(p2wpkh_output(0, ALICE_PUBKEY)),
+            disable_change_output: false,
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::OK);
+
+    let ProtoTransactionPlan::rune_mint(signing) = plan.plan else {
+        panic!("expected a rune mint plan");
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    // Rune output, runestone and change output.
+    let transaction = signed.transaction.unwrap();
+    assert_eq!(transaction.outputs.len(), 3);
+    assert_eq!(
+        transaction.outputs[1].script_pubkey,
+        hex("6a5d0614c0a2331403")
+    );
+
+    let runestone = decode(&signed.encoded).runestone.unwrap();
+    assert_eq!(
+        runestone.mint,
+        Some(Proto::RuneId {
+            block: 840_000,
+            tx: 3
+        })
+    );
+}
+
+#[test]
+fn coin_entry_plan_rune_transfer() {
+    let coin = TestCoinContext::default();
+
+    let edict = Proto::RuneEdict {
+        id: Some(Proto::RuneId {
+            block: 840_000,
+            tx: 3,
+        }),
+        amount: hex("03e8").into(),
+        output: 0,
+    };
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoComposePlan::rune_transfer(Proto::mod_ComposePlan::ComposeRuneTransferPlan {
+            private_key: hex(ALICE_PRIVATE_KEY).into(),
+            inputs: vec![funding_input()],
+            input_selector: UtxoProto::InputSelector::UseAll,
+            outputs: vec![p2wpkh_output(546, BOB_PUBKEY)],
+            edicts: vec![edict.clone()],
+            fee_per_vb: 10,
+            change_output: Some(p2wpkh_output(0, ALICE_PUBKEY)),
+            disable_change_output: false,
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::OK);
+
+    let ProtoTransactionPlan::rune_transfer(signing) = plan.plan else {
+        panic!("expected a rune transfer plan");
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    // The remaining runes are allocated to the change output.
+    let transaction = signed.transaction.unwrap();
+    assert_eq!(transaction.outputs.len(), 3);
+    assert_eq!(
+        transaction.outputs[1].script_pubkey,
+        hex("6a5d0a160200c0a23303e80700")
+    );
+
+    let runestone = decode(&signed.encoded).runestone.unwrap();
+    assert_eq!(runestone.pointer, Some(Proto::RunePointer { output: 2 }));
+    assert_eq!(runestone.edicts, vec![edict.clone()]);
+
+    // The edict must reference one of the outputs.
+    let compose = Proto::ComposePlan {
+        compose: ProtoComposePlan::rune_transfer(Proto::mod_ComposePlan::ComposeRuneTransferPlan {
+            inputs: vec![funding_input()],
+            outputs: vec![p2wpkh_output(546, BOB_PUBKEY)],
+            edicts: vec![Proto::RuneEdict { output: 1, ..edict }],
+            disable_change_output: true,
+            ..Default::default()
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::Error_invalid_runestone);
+}
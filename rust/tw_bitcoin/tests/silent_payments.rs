@@ -0,0 +1,491 @@
+mod common;
+
+use bitcoin::key::TweakedPublicKey;
+use bitcoin::{OutPoint, ScriptBuf, TxIn, TxOut, Txid, Witness};
+use common::{hex, MINER_FEE, ONE_BTC};
+use secp256k1::{Parity, Secp256k1, SecretKey, XOnlyPublicKey};
+use std::str::FromStr;
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::modules::silent_payments::{
+    input_public_key, scan_outputs, sender_output_keys, SilentPaymentAddress,
+};
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const ADDRESS: &str = "sp1qqgste7k9hx0qftg6qmwlkqtwuy6cycyavzmzj85c6qdfhjdpdjtdgqjuexzk6murw56suy3e0rd2cgqvycxttddwsvgxe2usfpxumr70xc9pkqwv";
+const SCAN_PUBKEY: &str = "0220bcfac5b99e04ad1a06ddfb016ee13582609d60b6291e98d01a9bc9a16c96d4";
+const SPEND_PUBKEY: &str = "025cc9856d6f8375350e123978daac200c260cb5b5ae83106cab90484dcd8fcf36";
+
+// The receiver keys of the BIP-352 test vectors, matching `ADDRESS`.
+const SCAN_PRIVATE_KEY: &str = "0f694e068028a717f8af6b9411f9a133dd3565258714cc226594b34db90c1f2c";
+const SPEND_PRIVATE_KEY: &str = "9d6ad855ce3417ef84e836892e5a56392bfba05fa5d97ccea30e266f540e08b3";
+const TXID_1: &str = "f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16";
+const TXID_2: &str = "a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d";
+const NUMS_POINT: &str = "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+
+const ALICE_PRIVATE_KEY: &str = "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+
+#[test]
+fn silent_payment_address() {
+    let coin = TestCoinContext::default();
+
+    let address = SilentPaymentAddress::from_str(ADDRESS).unwrap();
+    assert_eq!(address.scan.serialize().to_vec(), hex(SCAN_PUBKEY));
+    assert_eq!(address.spend.serialize().to_vec(), hex(SPEND_PUBKEY));
+    assert_eq!(address.network, bitcoin::Network::Bitcoin);
+    assert_eq!(address.to_string(), ADDRESS);
+
+    let derived = BitcoinEntry
+        .derive_silent_payment_address(
+            &coin,
+            &hex(SCAN_PUBKEY),
+            &hex(SPEND_PUBKEY),
+            Proto::Network::Bitcoin,
+        )
+        .unwrap();
+    assert_eq!(derived, address);
+
+    let testnet = BitcoinEntry
+        .derive_silent_payment_address(
+            &coin,
+            &hex(SCAN_PUBKEY),
+            &hex(SPEND_PUBKEY),
+            Proto::Network::Testnet,
+        )
+        .unwrap();
+    assert!(testnet.to_string().starts_with("tsp1q"));
+    assert_eq!(
+        SilentPaymentAddress::from_str(&testnet.to_string()).unwrap(),
+        testnet
+    );
+}
+
+#[test]
+fn silent_payment_address_invalid() {
+    // Invalid checksum.
+    assert!(SilentPaymentAddress::from_str(&ADDRESS.replace("qwv", "qww")).is_err());
+    // Segwit address.
+    assert!(SilentPaymentAddress::from_str("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").is_err());
+}
+
+#[test]
+fn sign_and_scan_silent_payment() {
+    let coin = TestCoinContext::default();
+    let secp = Secp256k1::new();
+
+    // The keys of the receiver.
+    let scan_key = SecretKey::from_slice(&[3; 32]).unwrap();
+    let spend_key = SecretKey::from_slice(&[4; 32]).unwrap();
+    let address = SilentPaymentAddress::new(
+        scan_key.public_key(&secp),
+        spend_key.public_key(&secp),
+        bitcoin::Network::Bitcoin,
+    );
+
+    let txid: Vec<u8> = hex("858e450a1da44397bde05ca2f8a78510d74c623cc2f69736a8b3fbfadc161f6e")
+        .into_iter()
+        .rev()
+        .collect();
+
+    let tx1 = Proto::Input {
+        txid: txid.as_slice().into(),
+        vout: 0,
+        value: ONE_BTC,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2wpkh(hex(ALICE_PUBKEY).into()),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: ONE_BTC - MINER_FEE,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::silent_payment(address.to_string().into()),
+        }),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: hex(ALICE_PRIVATE_KEY).into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    // The output key is not known before the inputs are selected.
+    let pre_signed = BitcoinEntry.preimage_hashes(&coin, signing.clone());
+    assert_eq!(
+        pre_signed.error,
+        Proto::Error::Error_unresolved_silent_payment
+    );
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    // The receiver finds the output with the keys of the inputs.
+    let prevout = UtxoProto::TxOut {
+        value: ONE_BTC,
+        script_pubkey: {
+            let pubkey = bitcoin::PublicKey::from_slice(&hex(ALICE_PUBKEY)).unwrap();
+            let pubkey_hash = pubkey.wpubkey_hash().unwrap();
+            bitcoin::ScriptBuf::new_v0_p2wpkh(&pubkey_hash)
+                .to_bytes()
+                .into()
+        },
+    };
+
+    let scanned = BitcoinEntry.scan_silent_payments(
+        &coin,
+        Proto::SilentPaymentScanInput {
+            encoded: signed.encoded.clone(),
+            prevouts: vec![prevout.clone()],
+            scan_private_key: scan_key.secret_bytes().to_vec().into(),
+            spend_public_key: spend_key.public_key(&secp).serialize().to_vec().into(),
+        },
+    );
+    assert_eq!(scanned.error, Proto::Error::OK);
+    assert_eq!(scanned.outputs.len(), 1);
+
+    let found = &scanned.outputs[0];
+    assert_eq!(found.vout, 0);
+    assert_eq!(found.value, ONE_BTC - MINER_FEE);
+
+    // The receiver can spend the output with `b_spend + tweak`.
+    let tweak = secp256k1::Scalar::from_be_bytes(found.tweak.as_ref().try_into().unwrap()).unwrap();
+    let output_private_key = spend_key.add_tweak(&tweak).unwrap();
+    let (output_key, _) = output_private_key.x_only_public_key(&secp);
+    assert_eq!(output_key.serialize().as_slice(), found.output_key.as_ref());
+
+    // Anyone else finds nothing.
+    let scanned = BitcoinEntry.scan_silent_payments(
+        &coin,
+        Proto::SilentPaymentScanInput {
+            encoded: signed.encoded,
+            prevouts: vec![prevout],
+            scan_private_key: spend_key.secret_bytes().to_vec().into(),
+            spend_public_key: spend_key.public_key(&secp).serialize().to_vec().into(),
+        },
+    );
+    assert_eq!(scanned.error, Proto::Error::OK);
+    assert!(scanned.outputs.is_empty());
+}
+
+enum VectorInputType {
+    P2wpkh,
+    P2trKeyPath,
+    /// A script-path spend with the NUMS point as internal key, which is not
+    /// eligible.
+    P2trNums,
+}
+
+struct VectorInput {
+    txid: &'static str,
+    vout: u32,
+    private_key: &'static str,
+    input_type: VectorInputType,
+}
+
+/// Checks a BIP-352 send and receive test vector: the sender derives the
+/// `expected` output keys for the recipients, and the receiver finds them
+/// with the expected tweaks.
+///
+/// https://github.com/bitcoin/bips/blob/master/bip-0352/send_and_receive_test_vectors.json
+fn check_vector(inputs: &[VectorInput], recipients: usize, expected: &[(&str, &str)]) {
+    let secp = Secp256k1::new();
+
+    let scan_key = SecretKey::from_slice(&hex(SCAN_PRIVATE_KEY)).unwrap();
+    let spend_key = SecretKey::from_slice(&hex(SPEND_PRIVATE_KEY)).unwrap();
+    let address = SilentPaymentAddress::from_str(ADDRESS).unwrap();
+    assert_eq!(address.scan, scan_key.public_key(&secp));
+    assert_eq!(address.spend, spend_key.public_key(&secp));
+
+    let outpoints: Vec<OutPoint> = inputs
+        .iter()
+        .map(|input| OutPoint::new(Txid::from_str(input.txid).unwrap(), input.vout))
+        .collect();
+
+    // The Taproot keys of the vectors are not tweaked (BIP-86), the private
+    // key of an odd output key is negated.
+    let input_keys: Vec<SecretKey> = inputs
+        .iter()
+        .filter_map(|input| {
+            let private_key = SecretKey::from_slice(&hex(input.private_key)).unwrap();
+            match input.input_type {
+                VectorInputType::P2wpkh => Some(private_key),
+                VectorInputType::P2trKeyPath => match private_key.x_only_public_key(&secp).1 {
+                    Parity::Even => Some(private_key),
+                    Parity::Odd => Some(private_key.negate()),
+                },
+                VectorInputType::P2trNums => None,
+            }
+        })
+        .collect();
+
+    let output_keys =
+        sender_output_keys(&input_keys, &outpoints, &vec![address; recipients]).unwrap();
+    let output_keys: Vec<String> = output_keys
+        .iter()
+        .map(|key| tw_encoding::hex::encode(key.serialize(), false))
+        .collect();
+    let expected_keys: Vec<&str> = expected.iter().map(|(key, _)| *key).collect();
+    assert_eq!(output_keys, expected_keys);
+
+    // The receiver extracts the input public keys from the spending
+    // transaction.
+    let input_public_keys: Vec<_> = inputs
+        .iter()
+        .zip(outpoints.iter())
+        .filter_map(|(input, outpoint)| {
+            let (txin, prevout) = vector_txin(input, *outpoint);
+            input_public_key(&txin, &prevout)
+        })
+        .collect();
+
+    let outputs: Vec<TxOut> = expected_keys
+        .iter()
+        .map(|key| {
+            let key = XOnlyPublicKey::from_slice(&hex(key)).unwrap();
+            TxOut {
+                value: 1_000,
+                script_pubkey: ScriptBuf::new_v1_p2tr_tweaked(
+                    TweakedPublicKey::dangerous_assume_tweaked(key),
+                ),
+            }
+        })
+        .collect();
+
+    let matches = scan_outputs(
+        &scan_key,
+        &spend_key.public_key(&secp),
+        &input_public_keys,
+        &outpoints,
+        &outputs,
+    )
+    .unwrap();
+
+    let matches: Vec<(String, String)> = matches
+        .iter()
+        .map(|found| {
+            (
+                tw_encoding::hex::encode(found.output_key.serialize(), false),
+                tw_encoding::hex::encode(found.tweak, false),
+            )
+        })
+        .collect();
+    let expected: Vec<(String, String)> = expected
+        .iter()
+        .map(|(key, tweak)| (key.to_string(), tweak.to_string()))
+        .collect();
+    assert_eq!(matches, expected);
+}
+
+/// Builds the spending input and the spent output of a test vector input.
+/// The signatures are irrelevant for silent payments.
+fn vector_txin(input: &VectorInput, outpoint: OutPoint) -> (TxIn, TxOut) {
+    let secp = Secp256k1::new();
+    let private_key = SecretKey::from_slice(&hex(input.private_key)).unwrap();
+    let public_key = bitcoin::PublicKey::new(private_key.public_key(&secp));
+    let (x_only, _) = private_key.x_only_public_key(&secp);
+    let signature = [1; 64];
+
+    let (witness, script_pubkey) = match input.input_type {
+        VectorInputType::P2wpkh => (
+            Witness::from_slice(&[&signature[..], &public_key.to_bytes()]),
+            ScriptBuf::new_v0_p2wpkh(&public_key.wpubkey_hash().unwrap()),
+        ),
+        VectorInputType::P2trKeyPath => (
+            Witness::from_slice(&[&signature[..]]),
+            ScriptBuf::new_v1_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(x_only)),
+        ),
+        VectorInputType::P2trNums => {
+            // `<x_only> OP_CHECKSIG`, with the control block of a tree with
+            // a single leaf.
+            let script = [&[0x20][..], &x_only.serialize(), &[0xac]].concat();
+            let control_block = [&[0xc0][..], &hex(NUMS_POINT)].concat();
+            (
+                Witness::from_slice(&[&signature[..], &script, &control_block]),
+                ScriptBuf::new_v1_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(x_only)),
+            )
+        },
+    };
+
+    let txin = TxIn {
+        previous_output: outpoint,
+        witness,
+        ..Default::default()
+    };
+    let prevout = TxOut {
+        value: 1_000,
+        script_pubkey,
+    };
+    (txin, prevout)
+}
+
+#[test]
+fn bip352_vector_simple_send() {
+    let inputs = [
+        VectorInput {
+            txid: TXID_1,
+            vout: 0,
+            private_key: "eadc78165ff1f8ea94ad7cfdc54990738a4c53f6e0507b42154201b8e5dff3b1",
+            input_type: VectorInputType::P2wpkh,
+        },
+        VectorInput {
+            txid: TXID_2,
+            vout: 0,
+            private_key: "93f5ed907ad5b2bdbbdcb5d9116ebc0a4e1f92f910d5260237fa45a9408aad16",
+            input_type: VectorInputType::P2wpkh,
+        },
+    ];
+    check_vector(
+        &inputs,
+        1,
+        &[(
+            "3e9fce73d4e77a4809908e3c3a2e54ee147b9312dc5044a193d1fc85de46e3c1",
+            "f438b40179a3c4262de12986c0e6cce0634007cdc79c1dcd3e20b9ebc2e7eef6",
+        )],
+    );
+}
+
+#[test]
+fn bip352_vector_taproot_even_y() {
+    let inputs = [
+        VectorInput {
+            txid: TXID_1,
+            vout: 0,
+            private_key: "eadc78165ff1f8ea94ad7cfdc54990738a4c53f6e0507b42154201b8e5dff3b1",
+            input_type: VectorInputType::P2trKeyPath,
+        },
+        VectorInput {
+            txid: TXID_2,
+            vout: 0,
+            private_key: "fc8716a97a48ba9a05a98ae47b5cd201a25a7fd5d8b73c203c5f7b6b6b3b6ad7",
+            input_type: VectorInputType::P2trKeyPath,
+        },
+    ];
+    check_vector(
+        &inputs,
+        1,
+        &[(
+            "de88bea8e7ffc9ce1af30d1132f910323c505185aec8eae361670421e749a1fb",
+            "3fb9ce5ce1746ced103c8ed254e81f6690764637ddbc876ec1f9b3ddab776b03",
+        )],
+    );
+}
+
+#[test]
+fn bip352_vector_taproot_mixed_y() {
+    let inputs = [
+        VectorInput {
+            txid: TXID_1,
+            vout: 0,
+            private_key: "eadc78165ff1f8ea94ad7cfdc54990738a4c53f6e0507b42154201b8e5dff3b1",
+            input_type: VectorInputType::P2trKeyPath,
+        },
+        VectorInput {
+            txid: TXID_2,
+            vout: 0,
+            private_key: "1d37787c2b7116ee983e9f9c13269df29091b391c04db94239e0d2bc2182c3bf",
+            input_type: VectorInputType::P2trKeyPath,
+        },
+    ];
+    check_vector(
+        &inputs,
+        1,
+        &[(
+            "77cab7dd12b10259ee82c6ea4b509774e33e7078e7138f568092241bf26b99f1",
+            "f5382508609771068ed079b24e1f72e4a17ee6d1c979066bf1d4e2a5676f09d4",
+        )],
+    );
+}
+
+#[test]
+fn bip352_vector_taproot_and_non_taproot() {
+    let inputs = [
+        VectorInput {
+            txid: TXID_1,
+            vout: 0,
+            private_key: "eadc78165ff1f8ea94ad7cfdc54990738a4c53f6e0507b42154201b8e5dff3b1",
+            input_type: VectorInputType::P2trKeyPath,
+        },
+        VectorInput {
+            txid: TXID_2,
+            vout: 0,
+            private_key: "8d4751f6e8a3586880fb66c19ae277969bd5aa06f61c4ee2f1e2486efdf666d3",
+            input_type: VectorInputType::P2wpkh,
+        },
+    ];
+    check_vector(
+        &inputs,
+        1,
+        &[(
+            "30523cca96b2a9ae3c98beb5e60f7d190ec5bc79b2d11a0b2d4d09a608c448f0",
+            "b40017865c79b1fcbed68896791be93186d08f47e416b289b8c063777e14e8df",
+        )],
+    );
+}
+
+#[test]
+fn bip352_vector_multiple_outputs_same_recipient() {
+    let inputs = [
+        VectorInput {
+            txid: TXID_1,
+            vout: 0,
+            private_key: "eadc78165ff1f8ea94ad7cfdc54990738a4c53f6e0507b42154201b8e5dff3b1",
+            input_type: VectorInputType::P2wpkh,
+        },
+        VectorInput {
+            txid: TXID_2,
+            vout: 0,
+            private_key: "0378e95685b74565fa56751b84a32dfd18545d10d691641b8372e32164fad66a",
+            input_type: VectorInputType::P2wpkh,
+        },
+    ];
+    // The outputs are derived with `k = 0` and `k = 1`.
+    check_vector(
+        &inputs,
+        2,
+        &[
+            (
+                "f207162b1a7abc51c42017bef055e9ec1efc3d3567cb720357e2b84325db33ac",
+                "33ce085c3c11eaad13694aae3c20301a6c83382ec89a7cde96c6799e2f88805a",
+            ),
+            (
+                "e976a58fbd38aeb4e6093d4df02e9c1de0c4513ae0c588cef68cda5b2f8834ca",
+                "d97e442d110c0bdd31161a7bb6e7862e038d02a09b1484dfbb463f2e0f7c9230",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn bip352_vector_skip_nums_internal_key() {
+    let inputs = [
+        VectorInput {
+            txid: TXID_1,
+            vout: 0,
+            private_key: "fc8716a97a48ba9a05a98ae47b5cd201a25a7fd5d8b73c203c5f7b6b6b3b6ad7",
+            input_type: VectorInputType::P2trKeyPath,
+        },
+        VectorInput {
+            txid: TXID_2,
+            vout: 0,
+            private_key: "8d4751f6e8a3586880fb66c19ae277969bd5aa06f61c4ee2f1e2486efdf666d3",
+            input_type: VectorInputType::P2trNums,
+        },
+    ];
+    check_vector(
+        &inputs,
+        1,
+        &[(
+            "79e79897c52935bfd97fc6e076a6431a0c7543ca8c31e0fc3cf719bb572c842d",
+            "3ddec3232609d348d6b8b53123b4f40f6d4f5398ca586f087b0416ec3b851496",
+        )],
+    );
+}
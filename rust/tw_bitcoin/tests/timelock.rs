@@ -0,0 +1,276 @@
+mod common;
+
+use common::{hex, MINER_FEE, ONE_BTC};
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const ALICE_PRIVATE_KEY: &str = "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+const BOB_PRIVATE_KEY: &str = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+
+// SHA256 of the preimage.
+const PREIMAGE: [u8; 32] = [7; 32];
+const PAYMENT_HASH: &str = "4bb06f8e4e3a7715d201d573d0aa423762e55dabd61a2c02278fa56cc6d294e0";
+
+const TXID: &str = "858e450a1da44397bde05ca2f8a78510d74c623cc2f69736a8b3fbfadc161f6e";
+
+fn after_height(height: u32) -> Option<Proto::Timelock> {
+    Some(Proto::Timelock {
+        variant: Proto::mod_Timelock::OneOfvariant::after_height(height),
+    })
+}
+
+fn older_blocks(blocks: u32) -> Option<Proto::Timelock> {
+    Some(Proto::Timelock {
+        variant: Proto::mod_Timelock::OneOfvariant::older_blocks(blocks),
+    })
+}
+
+fn alice_after_height(height: u32) -> Proto::TimelockedKey<'static> {
+    Proto::TimelockedKey {
+        public_key: hex(ALICE_PUBKEY).into(),
+        timelock: after_height(height),
+        script_type: Proto::TimelockScriptType::P2WSH,
+    }
+}
+
+// Bob can claim with the preimage, Alice is refunded after 144 blocks.
+fn htlc(script_type: Proto::TimelockScriptType) -> Proto::Htlc<'static> {
+    Proto::Htlc {
+        payment_hash: hex(PAYMENT_HASH).into(),
+        receiver_public_key: hex(BOB_PUBKEY).into(),
+        refund_public_key: hex(ALICE_PUBKEY).into(),
+        timelock: older_blocks(144),
+        script_type,
+    }
+}
+
+fn txid() -> Vec<u8> {
+    hex(TXID).into_iter().rev().collect()
+}
+
+fn signing_input(
+    private_key: &str,
+    input: ProtoInputBuilder<'static>,
+) -> Proto::SigningInput<'static> {
+    let tx1 = Proto::Input {
+        txid: txid().into(),
+        vout: 0,
+        value: ONE_BTC,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: input,
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: ONE_BTC - MINER_FEE,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(hex(ALICE_PUBKEY).into()),
+            }),
+        }),
+    };
+
+    Proto::SigningInput {
+        private_key: hex(private_key).into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        dangerous_use_fixed_schnorr_rng: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn timelocked_output_script_pubkeys() {
+    let coin = TestCoinContext::default();
+
+    let outputs = vec![
+        ProtoOutputBuilder::timelocked_key(alice_after_height(800_000)),
+        ProtoOutputBuilder::htlc(htlc(Proto::TimelockScriptType::P2WSH)),
+        ProtoOutputBuilder::htlc(htlc(Proto::TimelockScriptType::P2TR)),
+    ];
+
+    let mut signing = signing_input(
+        ALICE_PRIVATE_KEY,
+        ProtoInputBuilder::p2wpkh(hex(ALICE_PUBKEY).into()),
+    );
+    signing.outputs = outputs
+        .into_iter()
+        .map(|variant| Proto::Output {
+            value: ONE_BTC / 4,
+            to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+                variant,
+            }),
+        })
+        .collect();
+
+    let pre_signed = BitcoinEntry.preimage_hashes(&coin, signing);
+    assert_eq!(pre_signed.error, Proto::Error::OK);
+
+    let script_pubkeys: Vec<_> = pre_signed
+        .utxo_outputs
+        .iter()
+        .map(|output| tw_encoding::hex::encode(&output.script_pubkey, false))
+        .collect();
+    assert_eq!(
+        script_pubkeys,
+        vec![
+            // `<800000> OP_CHECKLOCKTIMEVERIFY OP_DROP <alice> OP_CHECKSIG`
+            "002046c219028b98820c8f8ff1dec5622adeaa72fa931c4dc53254eb8ab46ba87512",
+            "002023f8e4d058a06907dd9550d7bed2d4e0be518a131599259ba2547bc4722bdbf9",
+            "51207ee6fb43da905e2985a8975da303078a2a38996b0017b5da677f07ab52d11e44",
+        ]
+    );
+}
+
+#[test]
+fn sign_timelocked_key_sets_lock_time() {
+    let coin = TestCoinContext::default();
+
+    let input = ProtoInputBuilder::timelocked_key(Proto::mod_Input::InputTimelockedKey {
+        one_prevout: false,
+        timelocked_key: Some(alice_after_height(800_000)),
+    });
+
+    let signed = BitcoinEntry.sign(&coin, signing_input(ALICE_PRIVATE_KEY, input.clone()));
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    let transaction = signed.transaction.unwrap();
+    assert_eq!(
+        transaction.lock_time,
+        Some(UtxoProto::LockTime {
+            variant: UtxoProto::mod_LockTime::OneOfvariant::blocks(800_000),
+        })
+    );
+    // Non-final, otherwise the lock time is not enforced.
+    assert_eq!(transaction.inputs[0].sequence, 0xfffffffe);
+
+    // Signature and witness script.
+    let witness = &transaction.inputs[0].witness_items;
+    assert_eq!(witness.len(), 2);
+    assert_eq!(
+        tw_encoding::hex::encode(&witness[1], false),
+        "0300350cb17521028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28fac"
+    );
+
+    // A later lock time also satisfies the timelock.
+    let mut signing = signing_input(ALICE_PRIVATE_KEY, input.clone());
+    signing.lock_time = Some(UtxoProto::LockTime {
+        variant: UtxoProto::mod_LockTime::OneOfvariant::blocks(800_100),
+    });
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    // An earlier one does not.
+    let mut signing = signing_input(ALICE_PRIVATE_KEY, input);
+    signing.lock_time = Some(UtxoProto::LockTime {
+        variant: UtxoProto::mod_LockTime::OneOfvariant::blocks(799_999),
+    });
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_timelock_conflict);
+}
+
+#[test]
+fn sign_htlc_claim_and_refund() {
+    let coin = TestCoinContext::default();
+
+    for script_type in [
+        Proto::TimelockScriptType::P2WSH,
+        Proto::TimelockScriptType::P2TR,
+    ] {
+        // Bob claims the output with the preimage.
+        let claim = ProtoInputBuilder::htlc(Proto::mod_Input::InputHtlc {
+            one_prevout: false,
+            htlc: Some(htlc(script_type)),
+            preimage: PREIMAGE.to_vec().into(),
+        });
+
+        let signed = BitcoinEntry.sign(&coin, signing_input(BOB_PRIVATE_KEY, claim));
+        assert_eq!(signed.error, Proto::Error::OK);
+
+        let transaction = signed.transaction.unwrap();
+        assert_eq!(transaction.inputs[0].sequence, u32::MAX);
+
+        let witness = &transaction.inputs[0].witness_items;
+        assert_eq!(witness[1].as_ref(), PREIMAGE.as_slice());
+        match script_type {
+            // Signature, preimage, `OP_IF` selector and witness script.
+            Proto::TimelockScriptType::P2WSH => {
+                assert_eq!(witness.len(), 4);
+                assert_eq!(witness[2].as_ref(), &[1]);
+            },
+            // Signature, preimage, leaf and control block.
+            Proto::TimelockScriptType::P2TR => {
+                assert_eq!(witness.len(), 4);
+                assert_eq!(witness[0].len(), 65);
+                assert_eq!(witness[3].len(), 65);
+            },
+        }
+
+        // Alice is refunded after 144 blocks.
+        let refund = ProtoInputBuilder::htlc(Proto::mod_Input::InputHtlc {
+            one_prevout: false,
+            htlc: Some(htlc(script_type)),
+            preimage: Default::default(),
+        });
+
+        let signed = BitcoinEntry.sign(&coin, signing_input(ALICE_PRIVATE_KEY, refund));
+        assert_eq!(signed.error, Proto::Error::OK);
+
+        let transaction = signed.transaction.unwrap();
+        assert_eq!(transaction.inputs[0].sequence, 144);
+        assert_eq!(transaction.version, 2);
+
+        let witness = &transaction.inputs[0].witness_items;
+        assert_eq!(witness.len(), 3);
+        if script_type == Proto::TimelockScriptType::P2WSH {
+            // Selects the `OP_ELSE` branch.
+            assert!(witness[1].is_empty());
+        }
+    }
+}
+
+#[test]
+fn sign_htlc_invalid() {
+    let coin = TestCoinContext::default();
+
+    // Wrong preimage.
+    let claim = ProtoInputBuilder::htlc(Proto::mod_Input::InputHtlc {
+        one_prevout: false,
+        htlc: Some(htlc(Proto::TimelockScriptType::P2WSH)),
+        preimage: vec![8; 32].into(),
+    });
+    let signed = BitcoinEntry.sign(&coin, signing_input(BOB_PRIVATE_KEY, claim));
+    assert_eq!(signed.error, Proto::Error::Error_invalid_htlc);
+
+    // The sequence does not satisfy the relative timelock.
+    let refund = ProtoInputBuilder::htlc(Proto::mod_Input::InputHtlc {
+        one_prevout: false,
+        htlc: Some(htlc(Proto::TimelockScriptType::P2WSH)),
+        preimage: Default::default(),
+    });
+    let mut signing = signing_input(ALICE_PRIVATE_KEY, refund);
+    signing.inputs[0].sequence = 100;
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_timelock_conflict);
+
+    // Relative timelocks are limited to 65535 blocks.
+    let mut invalid = htlc(Proto::TimelockScriptType::P2WSH);
+    invalid.timelock = older_blocks(70_000);
+    let refund = ProtoInputBuilder::htlc(Proto::mod_Input::InputHtlc {
+        one_prevout: false,
+        htlc: Some(invalid),
+        preimage: Default::default(),
+    });
+    let signed = BitcoinEntry.sign(&coin, signing_input(ALICE_PRIVATE_KEY, refund));
+    assert_eq!(signed.error, Proto::Error::Error_invalid_timelock);
+}
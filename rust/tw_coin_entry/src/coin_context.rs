@@ -15,4 +15,13 @@ pub trait CoinContext {
 
     /// Optional chain property.
     fn hrp(&self) -> Option<String>;
+
+    /// Optional chain property.
+    fn p2pkh_prefix(&self) -> Option<u8>;
+
+    /// Optional chain property.
+    fn p2sh_prefix(&self) -> Option<u8>;
+
+    /// Optional chain property.
+    fn static_prefix(&self) -> Option<u8>;
 }
@@ -9,13 +9,35 @@ pub enum Derivation {
     /// Default derivation.
     #[default]
     Default = 0,
+    /// BIP-44 derivation, e.g. legacy P2PKH addresses for Bitcoin.
+    Bip44,
+    /// BIP-49 derivation, e.g. nested segwit P2SH-P2WPKH addresses for Bitcoin.
+    Bip49,
+    /// BIP-84 derivation, e.g. native segwit P2WPKH addresses for Bitcoin.
+    Bip84,
+    /// BIP-86 derivation, e.g. key-path only P2TR addresses for Bitcoin.
+    Bip86,
+    /// BIP-84 derivation on the Bitcoin testnet, i.e. `tb1` native segwit addresses.
+    BitcoinTestnet,
 }
 
 impl Derivation {
+    /// Converts a raw `TWDerivation` value.
+    ///
+    /// `TWDerivation` is generated from the named derivations in `registry.json`,
+    /// so the chain specific names are mapped to the purpose of their derivation path.
+    /// The BIP-49 and BIP-86 derivations are not represented by `TWDerivation` yet.
     #[inline]
     pub fn from_raw(derivation: u32) -> Option<Derivation> {
         match derivation {
+            // TWDerivationDefault
             0 => Some(Derivation::Default),
+            // TWDerivationBitcoinSegwit
+            2 => Some(Derivation::Bip84),
+            // TWDerivationBitcoinTestnet
+            4 => Some(Derivation::BitcoinTestnet),
+            // TWDerivationBitcoinLegacy, TWDerivationLitecoinLegacy
+            3 | 5 => Some(Derivation::Bip44),
             _ => None,
         }
     }
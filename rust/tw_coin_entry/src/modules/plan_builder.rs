@@ -7,10 +7,11 @@ use tw_proto::{DummyMessage, MessageRead, MessageWrite, NoMessage};
 
 pub trait PlanBuilder {
     type SigningInput<'a>: MessageRead<'a>;
-    type Plan: MessageWrite;
+    type Plan<'a>: MessageWrite;
 
-    /// Planning, for UTXO chains, in preparation for signing.
-    fn plan(&self, coin: &dyn CoinContext, input: Self::SigningInput<'_>) -> Self::Plan;
+    /// Planning, for UTXO chains, in preparation for signing. The plan may
+    /// reference the data of the input.
+    fn plan<'a>(&self, coin: &dyn CoinContext, input: Self::SigningInput<'a>) -> Self::Plan<'a>;
 }
 
 /// `NoInputBuilder` can't be created since there are no enum variants.
@@ -18,10 +19,10 @@ pub enum NoPlanBuilder {}
 
 impl PlanBuilder for NoPlanBuilder {
     type SigningInput<'a> = DummyMessage;
-    type Plan = NoMessage;
+    type Plan<'a> = NoMessage;
 
     /// [`PlanBuilder::plan`] should never be called.
-    fn plan(&self, _coin: &dyn CoinContext, _input: Self::SigningInput<'_>) -> Self::Plan {
+    fn plan<'a>(&self, _coin: &dyn CoinContext, _input: Self::SigningInput<'a>) -> Self::Plan<'a> {
         panic!("`NoPlanBuilder` should never be constructed and used")
     }
 }
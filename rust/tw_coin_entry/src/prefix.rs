@@ -4,11 +4,20 @@
 
 use crate::error::AddressError;
 
-/// An address prefix. It can contain a bech32 prefix that can be used by `Cosmos` based chains.
+/// An address prefix. It can contain a bech32 prefix that can be used by `Cosmos` based chains,
+/// or base58 version bytes that can be used by `Bitcoin` based chains.
 /// Extend when adding new blockchains.
 #[derive(Clone)]
 pub enum AddressPrefix {
     Hrp(String),
+    BitcoinBase58(BitcoinBase58Prefix),
+}
+
+/// The base58 version bytes of P2PKH and P2SH addresses.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BitcoinBase58Prefix {
+    pub p2pkh: u8,
+    pub p2sh: u8,
 }
 
 /// A blockchain's address prefix should be convertable from an `AddressPrefix`.
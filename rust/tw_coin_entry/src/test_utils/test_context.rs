@@ -12,6 +12,9 @@ pub struct TestCoinContext {
     pub public_key_type: Option<PublicKeyType>,
     pub address_hasher: Option<Hasher>,
     pub hrp: Option<String>,
+    pub p2pkh_prefix: Option<u8>,
+    pub p2sh_prefix: Option<u8>,
+    pub static_prefix: Option<u8>,
 }
 
 impl TestCoinContext {
@@ -24,6 +27,17 @@ impl TestCoinContext {
         self.hrp = Some(hrp.to_string());
         self
     }
+
+    pub fn with_base58_prefix(mut self, p2pkh_prefix: u8, p2sh_prefix: u8) -> TestCoinContext {
+        self.p2pkh_prefix = Some(p2pkh_prefix);
+        self.p2sh_prefix = Some(p2sh_prefix);
+        self
+    }
+
+    pub fn with_static_prefix(mut self, static_prefix: u8) -> TestCoinContext {
+        self.static_prefix = Some(static_prefix);
+        self
+    }
 }
 
 impl CoinContext for TestCoinContext {
@@ -39,4 +53,16 @@ impl CoinContext for TestCoinContext {
     fn hrp(&self) -> Option<String> {
         self.hrp.clone()
     }
+
+    fn p2pkh_prefix(&self) -> Option<u8> {
+        self.p2pkh_prefix
+    }
+
+    fn p2sh_prefix(&self) -> Option<u8> {
+        self.p2sh_prefix
+    }
+
+    fn static_prefix(&self) -> Option<u8> {
+        self.static_prefix
+    }
 }
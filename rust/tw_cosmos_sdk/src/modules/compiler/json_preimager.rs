@@ -5,7 +5,7 @@
 use crate::context::CosmosContext;
 use crate::modules::serializer::json_serializer::JsonSerializer;
 use crate::public_key::JsonPublicKey;
-use crate::transaction::UnsignedTransaction;
+use crate::transaction::{UnsignedMultisigTransaction, UnsignedTransaction};
 use std::marker::PhantomData;
 use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
 use tw_hash::hasher::Hasher;
@@ -38,4 +38,19 @@ where
             tx_hash,
         })
     }
+
+    pub fn preimage_hash_multisig(
+        unsigned: &UnsignedMultisigTransaction<Context>,
+        hasher: Hasher,
+    ) -> SigningResult<JsonTxPreimage> {
+        let tx_to_sign = JsonSerializer::build_unsigned_multisig_tx(unsigned)?;
+        let encoded_tx = serde_json::to_string(&tx_to_sign)
+            .map_err(|_| SigningError(SigningErrorType::Error_internal))?;
+        let tx_hash = hasher.hash(encoded_tx.as_bytes());
+
+        Ok(JsonTxPreimage {
+            encoded_tx,
+            tx_hash,
+        })
+    }
 }
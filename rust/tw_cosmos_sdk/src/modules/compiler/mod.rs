@@ -4,4 +4,5 @@
 
 pub mod json_preimager;
 pub mod protobuf_preimager;
+pub mod textual_preimager;
 pub mod tw_compiler;
@@ -4,7 +4,7 @@
 
 use crate::context::CosmosContext;
 use crate::modules::serializer::protobuf_serializer::{ProtobufSerializer, SignDirectArgs};
-use crate::transaction::UnsignedTransaction;
+use crate::transaction::{UnsignedMultisigTransaction, UnsignedTransaction};
 use std::marker::PhantomData;
 use tw_coin_entry::error::SigningResult;
 use tw_hash::hasher::Hasher;
@@ -35,6 +35,20 @@ impl<Context: CosmosContext> ProtobufPreimager<Context> {
         })
     }
 
+    pub fn preimage_hash_multisig(
+        unsigned: &UnsignedMultisigTransaction<Context>,
+        hasher: Hasher,
+    ) -> SigningResult<ProtobufTxPreimage> {
+        let tx_to_sign = ProtobufSerializer::build_multisig_sign_doc(unsigned)?;
+        let encoded_tx = serialize(&tx_to_sign)?;
+        let tx_hash = hasher.hash(&encoded_tx);
+
+        Ok(ProtobufTxPreimage {
+            encoded_tx,
+            tx_hash,
+        })
+    }
+
     pub fn preimage_hash_direct(
         args: &SignDirectArgs,
         hasher: Hasher,
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::context::CosmosContext;
+use crate::modules::textual::envelope::TextualRenderer;
+use crate::modules::textual::screen::encode_screens;
+use crate::modules::textual::value_renderer::DenomMetadataRegistry;
+use crate::transaction::UnsignedTransaction;
+use std::marker::PhantomData;
+use tw_coin_entry::error::SigningResult;
+use tw_hash::hasher::Hasher;
+use tw_memory::Data;
+
+pub struct TextualTxPreimage {
+    /// CBOR-encoded screens.
+    pub encoded_tx: Data,
+    pub tx_hash: Data,
+}
+
+pub struct TextualPreimager<Context: CosmosContext> {
+    _phantom: PhantomData<Context>,
+}
+
+impl<Context: CosmosContext> TextualPreimager<Context> {
+    pub fn preimage_hash(
+        unsigned: &UnsignedTransaction<Context>,
+        signer_address: &str,
+        registry: &DenomMetadataRegistry,
+        hasher: Hasher,
+    ) -> SigningResult<TextualTxPreimage> {
+        let screens = TextualRenderer::render_tx(unsigned, signer_address, registry)?;
+        let encoded_tx = encode_screens(&screens)?;
+        let tx_hash = hasher.hash(&encoded_tx);
+
+        Ok(TextualTxPreimage {
+            encoded_tx,
+            tx_hash,
+        })
+    }
+}
@@ -6,6 +6,7 @@ use crate::context::CosmosContext;
 use crate::modules::broadcast_msg::{BroadcastMode, BroadcastMsg};
 use crate::modules::compiler::json_preimager::JsonPreimager;
 use crate::modules::compiler::protobuf_preimager::ProtobufPreimager;
+use crate::modules::compiler::textual_preimager::TextualPreimager;
 use crate::modules::serializer::json_serializer::JsonSerializer;
 use crate::modules::serializer::protobuf_serializer::ProtobufSerializer;
 use crate::modules::tx_builder::TxBuilder;
@@ -17,6 +18,7 @@ use tw_coin_entry::coin_entry::{PublicKeyBytes, SignatureBytes};
 use tw_coin_entry::common::compile_input::SingleSignaturePubkey;
 use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
 use tw_coin_entry::signing_output_error;
+use tw_keypair::KeyPairResult;
 use tw_misc::traits::ToBytesVec;
 use tw_proto::Cosmos::Proto;
 use tw_proto::TxCompiler::Proto as CompilerProto;
@@ -34,6 +36,7 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
         match input.signing_mode {
             Proto::SigningMode::JSON => Self::preimage_hashes_as_json(coin, input),
             Proto::SigningMode::Protobuf => Self::preimage_hashes_as_protobuf(coin, input),
+            Proto::SigningMode::Textual => Self::preimage_hashes_as_textual(coin, input),
         }
         .unwrap_or_else(|e| signing_output_error!(CompilerProto::PreSigningOutput, e))
     }
@@ -45,11 +48,19 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
         signatures: Vec<SignatureBytes>,
         public_keys: Vec<PublicKeyBytes>,
     ) -> Proto::SigningOutput<'static> {
+        if input.multisig.is_some() {
+            return Self::compile_multisig(coin, input, signatures, public_keys)
+                .unwrap_or_else(|e| signing_output_error!(Proto::SigningOutput, e));
+        }
+
         match input.signing_mode {
             Proto::SigningMode::JSON => Self::compile_as_json(coin, input, signatures, public_keys),
             Proto::SigningMode::Protobuf => {
                 Self::compile_as_protobuf(coin, input, signatures, public_keys)
             },
+            Proto::SigningMode::Textual => {
+                Self::compile_as_textual(coin, input, signatures, public_keys)
+            },
         }
         .unwrap_or_else(|e| signing_output_error!(Proto::SigningOutput, e))
     }
@@ -64,6 +75,12 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
             Ok(Some(sign_direct_args)) => {
                 ProtobufPreimager::<Context>::preimage_hash_direct(&sign_direct_args, tx_hasher)?
             },
+            // If the transaction is sent from a multisig account, the signer info is built from the members.
+            _ if input.multisig.is_some() => {
+                let unsigned_tx =
+                    TxBuilder::<Context>::unsigned_multisig_tx_from_proto(coin, &input)?;
+                ProtobufPreimager::<Context>::preimage_hash_multisig(&unsigned_tx, tx_hasher)?
+            },
             // Otherwise, generate the tx preimage by using `TxBuilder`.
             _ => {
                 // Please note the [`Proto::SigningInput::public_key`] should be set already.
@@ -83,10 +100,15 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
         coin: &dyn CoinContext,
         input: Proto::SigningInput<'_>,
     ) -> SigningResult<CompilerProto::PreSigningOutput<'static>> {
-        // Please note the [`Proto::SigningInput::public_key`] should be set already.
-        let unsigned_tx = TxBuilder::<Context>::unsigned_tx_from_proto(coin, &input)?;
         let tx_hasher = TxBuilder::<Context>::tx_hasher_from_proto(&input);
-        let preimage = JsonPreimager::preimage_hash(&unsigned_tx, tx_hasher)?;
+        let preimage = if input.multisig.is_some() {
+            let unsigned_tx = TxBuilder::<Context>::unsigned_multisig_tx_from_proto(coin, &input)?;
+            JsonPreimager::preimage_hash_multisig(&unsigned_tx, tx_hasher)?
+        } else {
+            // Please note the [`Proto::SigningInput::public_key`] should be set already.
+            let unsigned_tx = TxBuilder::<Context>::unsigned_tx_from_proto(coin, &input)?;
+            JsonPreimager::preimage_hash(&unsigned_tx, tx_hasher)?
+        };
 
         Ok(CompilerProto::PreSigningOutput {
             data: Cow::from(preimage.encoded_tx.as_bytes().to_vec()),
@@ -95,6 +117,36 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
         })
     }
 
+    pub fn preimage_hashes_as_textual(
+        coin: &dyn CoinContext,
+        input: Proto::SigningInput<'_>,
+    ) -> SigningResult<CompilerProto::PreSigningOutput<'static>> {
+        // The messages of a `SignDirect` transaction cannot be rendered into screens.
+        if TxBuilder::<Context>::try_sign_direct_args(&input)?.is_some() || input.multisig.is_some()
+        {
+            return Err(SigningError(SigningErrorType::Error_not_supported));
+        }
+
+        let tx_hasher = TxBuilder::<Context>::tx_hasher_from_proto(&input);
+        let registry = TxBuilder::<Context>::denom_metadata_from_proto(&input);
+        let signer_address = TxBuilder::<Context>::signer_address_from_proto(coin, &input)?;
+
+        // Please note the [`Proto::SigningInput::public_key`] should be set already.
+        let unsigned_tx = TxBuilder::<Context>::unsigned_tx_from_proto(coin, &input)?;
+        let preimage = TextualPreimager::<Context>::preimage_hash(
+            &unsigned_tx,
+            &signer_address.to_string(),
+            &registry,
+            tx_hasher,
+        )?;
+
+        Ok(CompilerProto::PreSigningOutput {
+            data: Cow::from(preimage.encoded_tx),
+            data_hash: Cow::from(preimage.tx_hash),
+            ..CompilerProto::PreSigningOutput::default()
+        })
+    }
+
     pub fn compile_as_protobuf(
         coin: &dyn CoinContext,
         mut input: Proto::SigningInput<'_>,
@@ -143,6 +195,19 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
         })
     }
 
+    /// The Textual mode produces the same `TxRaw` as the Protobuf mode, but with the `SIGN_MODE_TEXTUAL` mode info.
+    pub fn compile_as_textual(
+        coin: &dyn CoinContext,
+        input: Proto::SigningInput<'_>,
+        signatures: Vec<SignatureBytes>,
+        public_keys: Vec<PublicKeyBytes>,
+    ) -> SigningResult<Proto::SigningOutput<'static>> {
+        if TxBuilder::<Context>::try_sign_direct_args(&input)?.is_some() {
+            return Err(SigningError(SigningErrorType::Error_not_supported));
+        }
+        Self::compile_as_protobuf(coin, input, signatures, public_keys)
+    }
+
     pub fn compile_as_json(
         coin: &dyn CoinContext,
         mut input: Proto::SigningInput<'_>,
@@ -179,6 +244,98 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
         })
     }
 
+    /// Combines the signatures of the multisig members into a signed transaction.
+    /// `signatures` and `public_keys` must have the same length, one signature per member.
+    pub fn compile_multisig(
+        coin: &dyn CoinContext,
+        input: Proto::SigningInput<'_>,
+        signatures: Vec<SignatureBytes>,
+        public_keys: Vec<PublicKeyBytes>,
+    ) -> SigningResult<Proto::SigningOutput<'static>> {
+        if signatures.len() != public_keys.len() {
+            return Err(SigningError(SigningErrorType::Error_signatures_count));
+        }
+
+        let mut unsigned_tx = TxBuilder::<Context>::unsigned_multisig_tx_from_proto(coin, &input)?;
+
+        let members = public_keys
+            .iter()
+            .map(|public_key| {
+                let params = TxBuilder::<Context>::public_key_params_from_proto(&input);
+                Context::PublicKey::from_bytes(coin, public_key, params)
+            })
+            .collect::<KeyPairResult<Vec<_>>>()?;
+        let signatures = signatures
+            .iter()
+            .map(|signature| {
+                Context::Signature::try_from(signature.as_slice()).map(|sig| sig.to_vec())
+            })
+            .collect::<KeyPairResult<Vec<_>>>()?;
+
+        let multisig = &unsigned_tx.signer.public_key;
+        let signers = TxBuilder::<Context>::multisig_signer_positions(multisig, &members)?;
+        if signers.len() < multisig.threshold() as usize {
+            return Err(SigningError(SigningErrorType::Error_signatures_count));
+        }
+
+        // Order the signatures as the members of the multisig account.
+        let mut member_signatures: Vec<_> = members
+            .iter()
+            .map(|member| multisig.position(member))
+            .zip(signatures)
+            .collect();
+        member_signatures.sort_by_key(|(position, _)| *position);
+        let signatures = member_signatures
+            .into_iter()
+            .map(|(_, signature)| signature)
+            .collect();
+
+        match input.signing_mode {
+            // The members have signed the set of signers, so it cannot change.
+            Proto::SigningMode::Protobuf if signers != unsigned_tx.signer.signers => {
+                return Err(SigningError(SigningErrorType::Error_invalid_params));
+            },
+            Proto::SigningMode::Protobuf => (),
+            Proto::SigningMode::JSON => unsigned_tx.signer.signers = signers,
+            Proto::SigningMode::Textual => {
+                return Err(SigningError(SigningErrorType::Error_not_supported))
+            },
+        }
+
+        let signed_tx = unsigned_tx.into_signed(signatures);
+        let signed_tx_raw = ProtobufSerializer::build_signed_multisig_tx(&signed_tx)?;
+        let signed_tx_json = JsonSerializer::build_signed_multisig_tx(&signed_tx)?;
+
+        let broadcast_mode = Self::broadcast_mode(input.mode);
+        let broadcast_tx = BroadcastMsg::raw(broadcast_mode, &signed_tx_raw).to_json_string();
+
+        let signature_json = serde_json::to_string(&signed_tx_json.signatures)
+            .map_err(|_| SigningError(SigningErrorType::Error_internal))?;
+
+        let mut output = Proto::SigningOutput {
+            signature_json: Cow::from(signature_json),
+            serialized: Cow::from(broadcast_tx),
+            ..Proto::SigningOutput::default()
+        };
+
+        match input.signing_mode {
+            Proto::SigningMode::Protobuf | Proto::SigningMode::Textual => {
+                // Serialized `MultiSignature`.
+                output.signature = Cow::from(signed_tx_raw.signatures[0].clone());
+            },
+            Proto::SigningMode::JSON => {
+                let broadcast_mode = Self::broadcast_mode(input.mode);
+                let json_tx = BroadcastMsg::json(broadcast_mode, &signed_tx_json)?.to_json_string();
+
+                // Serialized `AminoMultisignature`.
+                output.signature = Cow::from(signed_tx_json.signatures[0].signature.0.clone());
+                output.json = Cow::from(json_tx);
+            },
+        }
+
+        Ok(output)
+    }
+
     fn broadcast_mode(input: Proto::BroadcastMode) -> BroadcastMode {
         match input {
             Proto::BroadcastMode::BLOCK => BroadcastMode::Block,
@@ -0,0 +1,881 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! Converts the known Protobuf messages into JSON objects.
+//! The format follows the Protobuf JSON mapping with the original field names,
+//! i.e. 64-bit integers are strings, bytes are base64-encoded and enums are their names.
+
+use crate::address::Address;
+use crate::modules::textual::value_renderer::render_timestamp;
+use crate::proto::cosmos::authz::v1beta1 as authz_proto;
+use crate::proto::cosmos::bank::v1beta1 as bank_proto;
+use crate::proto::cosmos::base::v1beta1 as base_proto;
+use crate::proto::cosmos::distribution::v1beta1 as distribution_proto;
+use crate::proto::cosmos::feegrant::v1beta1 as feegrant_proto;
+use crate::proto::cosmos::gov::v1 as gov_v1_proto;
+use crate::proto::cosmos::gov::v1beta1 as gov_v1beta1_proto;
+use crate::proto::cosmos::multisig::v1beta1 as multisig_proto;
+use crate::proto::cosmos::staking::v1beta1 as staking_proto;
+use crate::proto::cosmos::tx::v1beta1 as tx_proto;
+use crate::proto::{
+    cosmos, cosmwasm, ethermint, greenfield, ibc, injective, osmosis, stride, terra, types,
+};
+use serde_json::{json, Value as Json};
+use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
+use tw_encoding::base64;
+use tw_proto::{deserialize, google, type_url};
+
+const TYPE_KEY: &str = "@type";
+
+/// Decodes `google.protobuf.Any` messages of the known types into JSON objects.
+pub struct AnyDecoder {
+    /// Used to render the addresses that are serialized as bytes, e.g. in THORChain messages.
+    hrp: Option<String>,
+}
+
+/// Tries to decode the message as one of the given types.
+macro_rules! decode_known_any {
+    ($decoder:expr, $any:expr, [$($msg:ty),+ $(,)?]) => {
+        $(
+            if $any.type_url == type_url::<$msg>() {
+                let msg: $msg = deserialize(&$any.value)?;
+                return msg.to_json($decoder).map(Some);
+            }
+        )+
+    };
+}
+
+impl AnyDecoder {
+    pub fn new(hrp: Option<String>) -> AnyDecoder {
+        AnyDecoder { hrp }
+    }
+
+    /// Decodes the message into `{"@type": "<type_url>", ...fields}`.
+    /// Unknown messages are represented as `{"@type": "<type_url>", "value": "<base64>"}`.
+    pub fn decode_any(&self, any: &google::protobuf::Any) -> SigningResult<Json> {
+        let mut json = self
+            .decode_known_any(any)?
+            .unwrap_or_else(|| json!({ "value": bytes_json(&any.value) }));
+        if let Json::Object(ref mut fields) = json {
+            fields.insert(TYPE_KEY.to_string(), Json::String(any.type_url.clone()));
+        }
+        Ok(json)
+    }
+
+    /// Returns the fields of the message, or `None` if the message type is unknown.
+    pub fn decode_known_any(&self, any: &google::protobuf::Any) -> SigningResult<Option<Json>> {
+        decode_known_any!(
+            self,
+            any,
+            [
+                // Public keys.
+                cosmos::crypto::secp256k1::PubKey,
+                cosmos::crypto::eth::ethsecp256k1::PubKey,
+                cosmos::crypto::multisig::LegacyAminoPubKey,
+                ethermint::crypto::v1::ethsecp256k1::PubKey,
+                injective::crypto::v1beta1::ethsecp256k1::PubKey,
+                // Messages.
+                authz_proto::MsgExec,
+                authz_proto::MsgGrant,
+                authz_proto::MsgRevoke,
+                bank_proto::MsgSend,
+                cosmwasm::wasm::v1::MsgClearAdmin,
+                cosmwasm::wasm::v1::MsgExecuteContract,
+                cosmwasm::wasm::v1::MsgInstantiateContract,
+                cosmwasm::wasm::v1::MsgInstantiateContract2,
+                cosmwasm::wasm::v1::MsgMigrateContract,
+                cosmwasm::wasm::v1::MsgStoreCode,
+                cosmwasm::wasm::v1::MsgUpdateAdmin,
+                distribution_proto::MsgSetWithdrawAddress,
+                distribution_proto::MsgWithdrawDelegatorReward,
+                feegrant_proto::AllowedMsgAllowance,
+                feegrant_proto::BasicAllowance,
+                feegrant_proto::MsgGrantAllowance,
+                feegrant_proto::MsgRevokeAllowance,
+                feegrant_proto::PeriodicAllowance,
+                gov_v1_proto::MsgDeposit,
+                gov_v1_proto::MsgSubmitProposal,
+                gov_v1_proto::MsgVote,
+                gov_v1_proto::MsgVoteWeighted,
+                gov_v1beta1_proto::MsgDeposit,
+                gov_v1beta1_proto::MsgSubmitProposal,
+                gov_v1beta1_proto::MsgVote,
+                gov_v1beta1_proto::MsgVoteWeighted,
+                gov_v1beta1_proto::TextProposal,
+                greenfield::bridge::MsgTransferOut,
+                ibc::applications::transfer::v1::MsgTransfer,
+                osmosis::concentratedliquidity::v1beta1::MsgAddToPosition,
+                osmosis::concentratedliquidity::v1beta1::MsgCollectSpreadRewards,
+                osmosis::concentratedliquidity::v1beta1::MsgCreatePosition,
+                osmosis::concentratedliquidity::v1beta1::MsgWithdrawPosition,
+                osmosis::poolmanager::v1beta1::MsgSwapExactAmountIn,
+                osmosis::poolmanager::v1beta1::MsgSwapExactAmountOut,
+                staking_proto::MsgBeginRedelegate,
+                staking_proto::MsgDelegate,
+                staking_proto::MsgUndelegate,
+                stride::stakeibc::MsgLiquidStake,
+                stride::stakeibc::MsgRedeemStake,
+                terra::wasm::v1beta1::MsgExecuteContract,
+                types::MsgDeposit,
+                types::MsgSend,
+            ]
+        );
+        Ok(None)
+    }
+
+    fn decode_optional_any(&self, any: &Option<google::protobuf::Any>) -> SigningResult<Json> {
+        match any {
+            Some(any) => self.decode_any(any),
+            None => Ok(Json::Null),
+        }
+    }
+
+    fn decode_any_list(&self, list: &[google::protobuf::Any]) -> SigningResult<Json> {
+        list.iter()
+            .map(|any| self.decode_any(any))
+            .collect::<SigningResult<_>>()
+            .map(Json::Array)
+    }
+
+    /// Renders the address bytes as a bech32 string if the prefix is known.
+    fn address_json(&self, bytes: &[u8]) -> Json {
+        let address = self
+            .hrp
+            .clone()
+            .and_then(|hrp| Address::new(hrp, bytes.to_vec()).ok());
+        match address {
+            Some(address) => Json::String(address.to_string()),
+            None => bytes_json(bytes),
+        }
+    }
+}
+
+pub trait ProtoJson {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json>;
+}
+
+impl<T: ProtoJson> ProtoJson for Option<T> {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        match self {
+            Some(value) => value.to_json(decoder),
+            None => Ok(Json::Null),
+        }
+    }
+}
+
+impl<T: ProtoJson> ProtoJson for Vec<T> {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        self.iter()
+            .map(|value| value.to_json(decoder))
+            .collect::<SigningResult<_>>()
+            .map(Json::Array)
+    }
+}
+
+impl ProtoJson for base_proto::Coin {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount,
+            "denom": self.denom,
+        }))
+    }
+}
+
+impl ProtoJson for google::protobuf::Timestamp {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        let nanos = u32::try_from(self.nanos)
+            .map_err(|_| SigningError(SigningErrorType::Error_input_parse))?;
+        Ok(Json::String(render_timestamp(self.seconds, nanos)))
+    }
+}
+
+impl ProtoJson for google::protobuf::Duration {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        if self.nanos == 0 {
+            return Ok(Json::String(format!("{}s", self.seconds)));
+        }
+        let fraction = format!("{:09}", self.nanos.unsigned_abs());
+        Ok(Json::String(format!(
+            "{}.{}s",
+            self.seconds,
+            fraction.trim_end_matches('0')
+        )))
+    }
+}
+
+// Public keys.
+
+impl ProtoJson for cosmos::crypto::secp256k1::PubKey {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({ "key": bytes_json(&self.key) }))
+    }
+}
+
+impl ProtoJson for cosmos::crypto::eth::ethsecp256k1::PubKey {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({ "key": bytes_json(&self.key) }))
+    }
+}
+
+impl ProtoJson for ethermint::crypto::v1::ethsecp256k1::PubKey {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({ "key": bytes_json(&self.key) }))
+    }
+}
+
+impl ProtoJson for injective::crypto::v1beta1::ethsecp256k1::PubKey {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({ "key": bytes_json(&self.key) }))
+    }
+}
+
+impl ProtoJson for cosmos::crypto::multisig::LegacyAminoPubKey {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "public_keys": decoder.decode_any_list(&self.public_keys)?,
+            "threshold": self.threshold,
+        }))
+    }
+}
+
+// Transaction.
+
+impl ProtoJson for tx_proto::TxBody {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "extension_options": decoder.decode_any_list(&self.extension_options)?,
+            "memo": self.memo,
+            "messages": decoder.decode_any_list(&self.messages)?,
+            "non_critical_extension_options": decoder.decode_any_list(&self.non_critical_extension_options)?,
+            "timeout_height": self.timeout_height.to_string(),
+        }))
+    }
+}
+
+impl ProtoJson for tx_proto::AuthInfo {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "fee": self.fee.to_json(decoder)?,
+            "signer_infos": self.signer_infos.to_json(decoder)?,
+            "tip": self.tip.to_json(decoder)?,
+        }))
+    }
+}
+
+impl ProtoJson for tx_proto::SignerInfo {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "mode_info": self.mode_info.to_json(decoder)?,
+            "public_key": decoder.decode_optional_any(&self.public_key)?,
+            "sequence": self.sequence.to_string(),
+        }))
+    }
+}
+
+impl ProtoJson for tx_proto::ModeInfo {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        use tx_proto::mod_ModeInfo::OneOfsum as SumEnum;
+
+        match self.sum {
+            SumEnum::single(ref single) => Ok(json!({
+                "single": { "mode": format!("{:?}", single.mode) },
+            })),
+            SumEnum::multi(ref multi) => Ok(json!({
+                "multi": {
+                    "bitarray": multi.bitarray.to_json(decoder)?,
+                    "mode_infos": multi.mode_infos.to_json(decoder)?,
+                },
+            })),
+            SumEnum::None => Ok(json!({})),
+        }
+    }
+}
+
+impl ProtoJson for multisig_proto::CompactBitArray {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "elems": bytes_json(&self.elems),
+            "extra_bits_stored": self.extra_bits_stored,
+        }))
+    }
+}
+
+impl ProtoJson for tx_proto::Fee {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount.to_json(decoder)?,
+            "gas_limit": self.gas_limit.to_string(),
+            "granter": self.granter,
+            "payer": self.payer,
+        }))
+    }
+}
+
+impl ProtoJson for tx_proto::Tip {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount.to_json(decoder)?,
+            "tipper": self.tipper,
+        }))
+    }
+}
+
+// Authz.
+
+impl ProtoJson for authz_proto::MsgExec {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "grantee": self.grantee,
+            "msgs": decoder.decode_any_list(&self.msgs)?,
+        }))
+    }
+}
+
+impl ProtoJson for authz_proto::MsgGrant {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        let grant = match self.grant {
+            Some(ref grant) => json!({
+                "authorization": decoder.decode_optional_any(&grant.authorization)?,
+                "expiration": grant.expiration.to_json(decoder)?,
+            }),
+            None => Json::Null,
+        };
+        Ok(json!({
+            "grant": grant,
+            "grantee": self.grantee,
+            "granter": self.granter,
+        }))
+    }
+}
+
+impl ProtoJson for authz_proto::MsgRevoke {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "grantee": self.grantee,
+            "granter": self.granter,
+            "msg_type_url": self.msg_type_url,
+        }))
+    }
+}
+
+// Bank.
+
+impl ProtoJson for bank_proto::MsgSend {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount.to_json(decoder)?,
+            "from_address": self.from_address,
+            "to_address": self.to_address,
+        }))
+    }
+}
+
+// Distribution.
+
+impl ProtoJson for distribution_proto::MsgSetWithdrawAddress {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "delegator_address": self.delegator_address,
+            "withdraw_address": self.withdraw_address,
+        }))
+    }
+}
+
+impl ProtoJson for distribution_proto::MsgWithdrawDelegatorReward {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "delegator_address": self.delegator_address,
+            "validator_address": self.validator_address,
+        }))
+    }
+}
+
+// Feegrant.
+
+impl ProtoJson for feegrant_proto::BasicAllowance {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "expiration": self.expiration.to_json(decoder)?,
+            "spend_limit": self.spend_limit.to_json(decoder)?,
+        }))
+    }
+}
+
+impl ProtoJson for feegrant_proto::PeriodicAllowance {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "basic": self.basic.to_json(decoder)?,
+            "period": self.period.to_json(decoder)?,
+            "period_can_spend": self.period_can_spend.to_json(decoder)?,
+            "period_reset": self.period_reset.to_json(decoder)?,
+            "period_spend_limit": self.period_spend_limit.to_json(decoder)?,
+        }))
+    }
+}
+
+impl ProtoJson for feegrant_proto::AllowedMsgAllowance {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "allowance": decoder.decode_optional_any(&self.allowance)?,
+            "allowed_messages": self.allowed_messages,
+        }))
+    }
+}
+
+impl ProtoJson for feegrant_proto::MsgGrantAllowance {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "allowance": decoder.decode_optional_any(&self.allowance)?,
+            "grantee": self.grantee,
+            "granter": self.granter,
+        }))
+    }
+}
+
+impl ProtoJson for feegrant_proto::MsgRevokeAllowance {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "grantee": self.grantee,
+            "granter": self.granter,
+        }))
+    }
+}
+
+// Gov.
+
+impl ProtoJson for gov_v1beta1_proto::MsgVote {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "option": format!("{:?}", self.option),
+            "proposal_id": self.proposal_id.to_string(),
+            "voter": self.voter,
+        }))
+    }
+}
+
+impl ProtoJson for gov_v1beta1_proto::WeightedVoteOption {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "option": format!("{:?}", self.option),
+            "weight": self.weight,
+        }))
+    }
+}
+
+impl ProtoJson for gov_v1beta1_proto::MsgVoteWeighted {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "options": self.options.to_json(decoder)?,
+            "proposal_id": self.proposal_id.to_string(),
+            "voter": self.voter,
+        }))
+    }
+}
+
+impl ProtoJson for gov_v1beta1_proto::MsgDeposit {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount.to_json(decoder)?,
+            "depositor": self.depositor,
+            "proposal_id": self.proposal_id.to_string(),
+        }))
+    }
+}
+
+impl ProtoJson for gov_v1beta1_proto::MsgSubmitProposal {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "content": decoder.decode_optional_any(&self.content)?,
+            "initial_deposit": self.initial_deposit.to_json(decoder)?,
+            "proposer": self.proposer,
+        }))
+    }
+}
+
+impl ProtoJson for gov_v1beta1_proto::TextProposal {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "description": self.description,
+            "title": self.title,
+        }))
+    }
+}
+
+impl ProtoJson for gov_v1_proto::MsgVote {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "metadata": self.metadata,
+            "option": format!("{:?}", self.option),
+            "proposal_id": self.proposal_id.to_string(),
+            "voter": self.voter,
+        }))
+    }
+}
+
+impl ProtoJson for gov_v1_proto::WeightedVoteOption {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "option": format!("{:?}", self.option),
+            "weight": self.weight,
+        }))
+    }
+}
+
+impl ProtoJson for gov_v1_proto::MsgVoteWeighted {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "metadata": self.metadata,
+            "options": self.options.to_json(decoder)?,
+            "proposal_id": self.proposal_id.to_string(),
+            "voter": self.voter,
+        }))
+    }
+}
+
+impl ProtoJson for gov_v1_proto::MsgDeposit {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount.to_json(decoder)?,
+            "depositor": self.depositor,
+            "proposal_id": self.proposal_id.to_string(),
+        }))
+    }
+}
+
+impl ProtoJson for gov_v1_proto::MsgSubmitProposal {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "expedited": self.expedited,
+            "initial_deposit": self.initial_deposit.to_json(decoder)?,
+            "messages": decoder.decode_any_list(&self.messages)?,
+            "metadata": self.metadata,
+            "proposer": self.proposer,
+            "summary": self.summary,
+            "title": self.title,
+        }))
+    }
+}
+
+// Greenfield.
+
+impl ProtoJson for greenfield::bridge::MsgTransferOut {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount.to_json(decoder)?,
+            "from": self.from,
+            "to": self.to,
+        }))
+    }
+}
+
+// IBC.
+
+impl ProtoJson for ibc::applications::transfer::v1::MsgTransfer {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        let timeout_height = match self.timeout_height {
+            Some(ref height) => json!({
+                "revision_height": height.revision_height.to_string(),
+                "revision_number": height.revision_number.to_string(),
+            }),
+            None => Json::Null,
+        };
+        Ok(json!({
+            "memo": self.memo,
+            "receiver": self.receiver,
+            "sender": self.sender,
+            "source_channel": self.source_channel,
+            "source_port": self.source_port,
+            "timeout_height": timeout_height,
+            "timeout_timestamp": self.timeout_timestamp.to_string(),
+            "token": self.token.to_json(decoder)?,
+        }))
+    }
+}
+
+// Staking.
+
+impl ProtoJson for staking_proto::MsgDelegate {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount.to_json(decoder)?,
+            "delegator_address": self.delegator_address,
+            "validator_address": self.validator_address,
+        }))
+    }
+}
+
+impl ProtoJson for staking_proto::MsgUndelegate {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount.to_json(decoder)?,
+            "delegator_address": self.delegator_address,
+            "validator_address": self.validator_address,
+        }))
+    }
+}
+
+impl ProtoJson for staking_proto::MsgBeginRedelegate {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount.to_json(decoder)?,
+            "delegator_address": self.delegator_address,
+            "validator_dst_address": self.validator_dst_address,
+            "validator_src_address": self.validator_src_address,
+        }))
+    }
+}
+
+// Stride.
+
+impl ProtoJson for stride::stakeibc::MsgLiquidStake {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount,
+            "creator": self.creator,
+            "host_denom": self.host_denom,
+        }))
+    }
+}
+
+impl ProtoJson for stride::stakeibc::MsgRedeemStake {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount,
+            "creator": self.creator,
+            "host_zone": self.host_zone,
+            "receiver": self.receiver,
+        }))
+    }
+}
+
+// THORChain.
+
+impl ProtoJson for types::MsgSend {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount": self.amount.to_json(decoder)?,
+            "from_address": decoder.address_json(&self.from_address),
+            "to_address": decoder.address_json(&self.to_address),
+        }))
+    }
+}
+
+impl ProtoJson for types::MsgDeposit {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "coins": self.coins.to_json(decoder)?,
+            "memo": self.memo,
+            "signer": decoder.address_json(&self.signer),
+        }))
+    }
+}
+
+impl ProtoJson for types::Coin {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        let asset = match self.asset {
+            Some(ref asset) => json!({
+                "chain": asset.chain,
+                "symbol": asset.symbol,
+                "synth": asset.synth,
+                "ticker": asset.ticker,
+            }),
+            None => Json::Null,
+        };
+        Ok(json!({
+            "amount": self.amount,
+            "asset": asset,
+            "decimals": self.decimals.to_string(),
+        }))
+    }
+}
+
+// Osmosis.
+
+impl ProtoJson for osmosis::poolmanager::v1beta1::SwapAmountInRoute {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "pool_id": self.pool_id.to_string(),
+            "token_out_denom": self.token_out_denom,
+        }))
+    }
+}
+
+impl ProtoJson for osmosis::poolmanager::v1beta1::SwapAmountOutRoute {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "pool_id": self.pool_id.to_string(),
+            "token_in_denom": self.token_in_denom,
+        }))
+    }
+}
+
+impl ProtoJson for osmosis::poolmanager::v1beta1::MsgSwapExactAmountIn {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "routes": self.routes.to_json(decoder)?,
+            "sender": self.sender,
+            "token_in": self.token_in.to_json(decoder)?,
+            "token_out_min_amount": self.token_out_min_amount,
+        }))
+    }
+}
+
+impl ProtoJson for osmosis::poolmanager::v1beta1::MsgSwapExactAmountOut {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "routes": self.routes.to_json(decoder)?,
+            "sender": self.sender,
+            "token_in_max_amount": self.token_in_max_amount,
+            "token_out": self.token_out.to_json(decoder)?,
+        }))
+    }
+}
+
+impl ProtoJson for osmosis::concentratedliquidity::v1beta1::MsgCreatePosition {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "lower_tick": self.lower_tick.to_string(),
+            "pool_id": self.pool_id.to_string(),
+            "sender": self.sender,
+            "token_min_amount0": self.token_min_amount0,
+            "token_min_amount1": self.token_min_amount1,
+            "tokens_provided": self.tokens_provided.to_json(decoder)?,
+            "upper_tick": self.upper_tick.to_string(),
+        }))
+    }
+}
+
+impl ProtoJson for osmosis::concentratedliquidity::v1beta1::MsgAddToPosition {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "amount0": self.amount0,
+            "amount1": self.amount1,
+            "position_id": self.position_id.to_string(),
+            "sender": self.sender,
+            "token_min_amount0": self.token_min_amount0,
+            "token_min_amount1": self.token_min_amount1,
+        }))
+    }
+}
+
+impl ProtoJson for osmosis::concentratedliquidity::v1beta1::MsgWithdrawPosition {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "liquidity_amount": self.liquidity_amount,
+            "position_id": self.position_id.to_string(),
+            "sender": self.sender,
+        }))
+    }
+}
+
+impl ProtoJson for osmosis::concentratedliquidity::v1beta1::MsgCollectSpreadRewards {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        let position_ids: Vec<_> = self.position_ids.iter().map(u64::to_string).collect();
+        Ok(json!({
+            "position_ids": position_ids,
+            "sender": self.sender,
+        }))
+    }
+}
+
+// Wasm.
+
+impl ProtoJson for cosmwasm::wasm::v1::MsgExecuteContract {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "contract": self.contract,
+            "funds": self.funds.to_json(decoder)?,
+            "msg": contract_msg_json(&self.msg),
+            "sender": self.sender,
+        }))
+    }
+}
+
+impl ProtoJson for cosmwasm::wasm::v1::MsgInstantiateContract {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "admin": self.admin,
+            "code_id": self.code_id.to_string(),
+            "funds": self.funds.to_json(decoder)?,
+            "label": self.label,
+            "msg": contract_msg_json(&self.msg),
+            "sender": self.sender,
+        }))
+    }
+}
+
+impl ProtoJson for cosmwasm::wasm::v1::MsgInstantiateContract2 {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "admin": self.admin,
+            "code_id": self.code_id.to_string(),
+            "fix_msg": self.fix_msg,
+            "funds": self.funds.to_json(decoder)?,
+            "label": self.label,
+            "msg": contract_msg_json(&self.msg),
+            "salt": bytes_json(&self.salt),
+            "sender": self.sender,
+        }))
+    }
+}
+
+impl ProtoJson for cosmwasm::wasm::v1::MsgMigrateContract {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "code_id": self.code_id.to_string(),
+            "contract": self.contract,
+            "msg": contract_msg_json(&self.msg),
+            "sender": self.sender,
+        }))
+    }
+}
+
+impl ProtoJson for cosmwasm::wasm::v1::MsgUpdateAdmin {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "contract": self.contract,
+            "new_admin": self.new_admin,
+            "sender": self.sender,
+        }))
+    }
+}
+
+impl ProtoJson for cosmwasm::wasm::v1::MsgClearAdmin {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "contract": self.contract,
+            "sender": self.sender,
+        }))
+    }
+}
+
+impl ProtoJson for cosmwasm::wasm::v1::MsgStoreCode {
+    fn to_json(&self, _decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "sender": self.sender,
+            "wasm_byte_code": bytes_json(&self.wasm_byte_code),
+        }))
+    }
+}
+
+impl ProtoJson for terra::wasm::v1beta1::MsgExecuteContract {
+    fn to_json(&self, decoder: &AnyDecoder) -> SigningResult<Json> {
+        Ok(json!({
+            "coins": self.coins.to_json(decoder)?,
+            "contract": self.contract,
+            "execute_msg": contract_msg_json(&self.execute_msg),
+            "sender": self.sender,
+        }))
+    }
+}
+
+fn bytes_json(bytes: &[u8]) -> Json {
+    Json::String(base64::encode(bytes, false))
+}
+
+/// Contract messages are JSON objects serialized as bytes.
+/// Falls back to base64 if the message is not a valid JSON.
+fn contract_msg_json(msg: &[u8]) -> Json {
+    serde_json::from_slice(msg).unwrap_or_else(|_| bytes_json(msg))
+}
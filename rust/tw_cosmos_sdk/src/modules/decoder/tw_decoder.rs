@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::modules::decoder::proto_json::{AnyDecoder, ProtoJson};
+use crate::proto::cosmos::tx::v1beta1 as tx_proto;
+use serde_json::json;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::error::SigningResult;
+use tw_coin_entry::signing_output_error;
+use tw_encoding::base64;
+use tw_proto::Cosmos::Proto;
+use tw_proto::{deserialize, google};
+
+pub struct TWTransactionDecoder;
+
+impl TWTransactionDecoder {
+    /// Decodes a serialized `TxRaw`, `SignDoc`, `AuthInfo` or `TxBody`.
+    #[inline]
+    pub fn decode(
+        coin: &dyn CoinContext,
+        input: Proto::DecodingInput<'_>,
+    ) -> Proto::DecodingOutput<'static> {
+        Self::decode_impl(coin, input)
+            .unwrap_or_else(|e| signing_output_error!(Proto::DecodingOutput, e))
+    }
+
+    fn decode_impl(
+        coin: &dyn CoinContext,
+        input: Proto::DecodingInput<'_>,
+    ) -> SigningResult<Proto::DecodingOutput<'static>> {
+        use Proto::mod_DecodingInput::EncodedType;
+
+        let decoder = AnyDecoder::new(coin.hrp());
+
+        let (json, body) = match input.encoded_type {
+            EncodedType::TxRaw => {
+                let tx_raw: tx_proto::TxRaw = deserialize(&input.encoded)?;
+                let body: tx_proto::TxBody = deserialize(&tx_raw.body_bytes)?;
+                let auth_info: tx_proto::AuthInfo = deserialize(&tx_raw.auth_info_bytes)?;
+
+                let signatures: Vec<_> = tx_raw
+                    .signatures
+                    .iter()
+                    .map(|signature| base64::encode(signature, false))
+                    .collect();
+                let json = json!({
+                    "auth_info": auth_info.to_json(&decoder)?,
+                    "body": body.to_json(&decoder)?,
+                    "signatures": signatures,
+                });
+                (json, Some(body))
+            },
+            EncodedType::SignDoc => {
+                let sign_doc: tx_proto::SignDoc = deserialize(&input.encoded)?;
+                let body: tx_proto::TxBody = deserialize(&sign_doc.body_bytes)?;
+                let auth_info: tx_proto::AuthInfo = deserialize(&sign_doc.auth_info_bytes)?;
+
+                let json = json!({
+                    "account_number": sign_doc.account_number.to_string(),
+                    "auth_info": auth_info.to_json(&decoder)?,
+                    "body": body.to_json(&decoder)?,
+                    "chain_id": sign_doc.chain_id,
+                });
+                (json, Some(body))
+            },
+            EncodedType::AuthInfo => {
+                let auth_info: tx_proto::AuthInfo = deserialize(&input.encoded)?;
+                (auth_info.to_json(&decoder)?, None)
+            },
+            EncodedType::TxBody => {
+                let body: tx_proto::TxBody = deserialize(&input.encoded)?;
+                (body.to_json(&decoder)?, Some(body))
+            },
+        };
+
+        let messages = match body {
+            Some(body) => body
+                .messages
+                .iter()
+                .map(|any| Self::decode_message(&decoder, any))
+                .collect::<SigningResult<_>>()?,
+            None => Vec::default(),
+        };
+
+        Ok(Proto::DecodingOutput {
+            messages,
+            json: json.to_string().into(),
+            ..Proto::DecodingOutput::default()
+        })
+    }
+
+    fn decode_message(
+        decoder: &AnyDecoder,
+        any: &google::protobuf::Any,
+    ) -> SigningResult<Proto::DecodedMessage<'static>> {
+        let json = decoder
+            .decode_known_any(any)?
+            .map(|json| json.to_string())
+            .unwrap_or_default();
+
+        Ok(Proto::DecodedMessage {
+            type_url: any.type_url.clone().into(),
+            value: any.value.clone().into(),
+            json: json.into(),
+        })
+    }
+}
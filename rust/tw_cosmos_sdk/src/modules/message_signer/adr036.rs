@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! [ADR-036](https://github.com/cosmos/cosmos-sdk/blob/main/docs/architecture/adr-036-arbitrary-signature.md)
+//! arbitrary message signing.
+
+use crate::context::CosmosContext;
+use crate::modules::compiler::json_preimager::JsonTxPreimage;
+use crate::modules::serializer::json_serializer::{AnyMsg, FeeJson, UnsignedTxJson};
+use serde_json::json;
+use std::marker::PhantomData;
+use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
+use tw_encoding::base64;
+
+const MSG_SIGN_DATA_TYPE: &str = "sign/MsgSignData";
+
+pub struct Adr036Preimager<Context: CosmosContext> {
+    _phantom: PhantomData<Context>,
+}
+
+impl<Context: CosmosContext> Adr036Preimager<Context> {
+    /// Builds the Amino JSON sign doc of a `sign/MsgSignData` message and hashes it
+    /// with the default transaction hasher of the chain.
+    pub fn preimage_hash(signer: &Context::Address, data: &[u8]) -> SigningResult<JsonTxPreimage> {
+        let sign_doc = Self::build_sign_doc(signer, data);
+        let encoded_tx = serde_json::to_string(&sign_doc)
+            .map_err(|_| SigningError(SigningErrorType::Error_internal))?;
+        let tx_hash = Context::default_tx_hasher().hash(encoded_tx.as_bytes());
+
+        Ok(JsonTxPreimage {
+            encoded_tx,
+            tx_hash,
+        })
+    }
+
+    /// The sign doc must have an empty `chain_id` and `memo`, zero `account_number`,
+    /// `sequence` and `fee`, so it can never be broadcasted as a transaction.
+    fn build_sign_doc(signer: &Context::Address, data: &[u8]) -> UnsignedTxJson {
+        let is_url = false;
+        let msg = AnyMsg {
+            msg_type: MSG_SIGN_DATA_TYPE.to_string(),
+            value: json!({
+                "data": base64::encode(data, is_url),
+                "signer": signer.to_string(),
+            }),
+        };
+
+        UnsignedTxJson {
+            account_number: "0".to_string(),
+            chain_id: String::default(),
+            fee: FeeJson {
+                amount: Vec::default(),
+                gas: "0".to_string(),
+                payer: None,
+                granter: None,
+            },
+            memo: String::default(),
+            msgs: vec![msg],
+            sequence: "0".to_string(),
+            timeout_height: None,
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::{Address, CosmosAddress};
+use crate::context::CosmosContext;
+use crate::modules::message_signer::adr036::Adr036Preimager;
+use crate::modules::serializer::json_serializer::JsonSerializer;
+use crate::private_key::CosmosPrivateKey;
+use crate::public_key::CosmosPublicKey;
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::error::{
+    AddressError, AddressResult, SigningError, SigningErrorType, SigningResult,
+};
+use tw_coin_entry::modules::message_signer::MessageSigner;
+use tw_coin_entry::signing_output_error;
+use tw_encoding::base64;
+use tw_keypair::ecdsa::secp256k1;
+use tw_keypair::tw;
+use tw_misc::traits::ToBytesVec;
+use tw_proto::Cosmos::Proto;
+use tw_proto::TxCompiler::Proto as CompilerProto;
+
+/// An address that ADR-036 messages can be signed by.
+pub trait MessageSignerAddress: CosmosAddress + PartialEq + Sized {
+    /// Parses the address, checking that it belongs to the `coin`.
+    fn from_str_with_coin(coin: &dyn CoinContext, address: &str) -> AddressResult<Self>;
+
+    /// Derives the address of the `coin` from the public key.
+    fn from_public_key_with_coin(
+        coin: &dyn CoinContext,
+        public_key: &tw::PublicKey,
+    ) -> AddressResult<Self>;
+}
+
+impl MessageSignerAddress for Address {
+    fn from_str_with_coin(coin: &dyn CoinContext, address: &str) -> AddressResult<Self> {
+        Address::from_str_with_coin_and_prefix(coin, address.to_string(), None)
+    }
+
+    fn from_public_key_with_coin(
+        coin: &dyn CoinContext,
+        public_key: &tw::PublicKey,
+    ) -> AddressResult<Self> {
+        Address::with_public_key_coin_context(coin, public_key, None)
+    }
+}
+
+/// Signs and verifies arbitrary messages according to ADR-036.
+/// The message is signed with the private key type and the transaction hasher of the `Context`,
+/// so the chains that use `eth_secp256k1` keys hash the sign doc with Keccak256.
+pub struct TWMessageSigner<Context> {
+    _phantom: PhantomData<Context>,
+}
+
+impl<Context> Default for TWMessageSigner<Context> {
+    fn default() -> Self {
+        TWMessageSigner {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Context> MessageSigner for TWMessageSigner<Context>
+where
+    Context: CosmosContext,
+    Context::Address: MessageSignerAddress,
+{
+    type MessageSigningInput<'a> = Proto::MessageSigningInput<'a>;
+    type MessagePreSigningOutput = CompilerProto::PreSigningOutput<'static>;
+    type MessageSigningOutput = Proto::MessageSigningOutput<'static>;
+    type MessageVerifyingInput<'a> = Proto::MessageVerifyingInput<'a>;
+
+    fn message_preimage_hashes(
+        &self,
+        coin: &dyn CoinContext,
+        input: Self::MessageSigningInput<'_>,
+    ) -> Self::MessagePreSigningOutput {
+        Self::message_preimage_hashes_impl(coin, input)
+            .unwrap_or_else(|e| signing_output_error!(CompilerProto::PreSigningOutput, e))
+    }
+
+    fn sign_message(
+        &self,
+        coin: &dyn CoinContext,
+        input: Self::MessageSigningInput<'_>,
+    ) -> Self::MessageSigningOutput {
+        Self::sign_message_impl(coin, input)
+            .unwrap_or_else(|e| signing_output_error!(Proto::MessageSigningOutput, e))
+    }
+
+    fn verify_message(
+        &self,
+        coin: &dyn CoinContext,
+        input: Self::MessageVerifyingInput<'_>,
+    ) -> bool {
+        Self::verify_message_impl(coin, input).unwrap_or_default()
+    }
+}
+
+impl<Context> TWMessageSigner<Context>
+where
+    Context: CosmosContext,
+    Context::Address: MessageSignerAddress,
+{
+    fn message_preimage_hashes_impl(
+        coin: &dyn CoinContext,
+        input: Proto::MessageSigningInput<'_>,
+    ) -> SigningResult<CompilerProto::PreSigningOutput<'static>> {
+        let signer = Context::Address::from_str_with_coin(coin, &input.address)?;
+        let preimage =
+            Adr036Preimager::<Context>::preimage_hash(&signer, input.message.as_bytes())?;
+
+        Ok(CompilerProto::PreSigningOutput {
+            data: Cow::from(preimage.encoded_tx.into_bytes()),
+            data_hash: Cow::from(preimage.tx_hash),
+            ..CompilerProto::PreSigningOutput::default()
+        })
+    }
+
+    fn sign_message_impl(
+        coin: &dyn CoinContext,
+        input: Proto::MessageSigningInput<'_>,
+    ) -> SigningResult<Proto::MessageSigningOutput<'static>> {
+        let private_key = Context::PrivateKey::try_from(&input.private_key)?;
+        let public_key = Context::PublicKey::from_private_key(coin, private_key.as_ref(), None)?;
+
+        let signer_public_key = private_key
+            .as_ref()
+            .get_public_key_by_type(coin.public_key_type())
+            .map_err(|_| AddressError::PublicKeyTypeMismatch)?;
+        let signer = Context::Address::from_public_key_with_coin(coin, &signer_public_key)?;
+        if !input.address.is_empty() {
+            let expected = Context::Address::from_str_with_coin(coin, &input.address)?;
+            if expected != signer {
+                return Err(SigningError(SigningErrorType::Error_invalid_address));
+            }
+        }
+
+        let preimage =
+            Adr036Preimager::<Context>::preimage_hash(&signer, input.message.as_bytes())?;
+        let signature_data = private_key.sign_tx_hash(&preimage.tx_hash)?;
+        // Cosmos signatures do not contain the recovery byte.
+        let signature = Context::Signature::try_from(&signature_data)?.to_vec();
+
+        let signature_json =
+            JsonSerializer::<Context>::serialize_signature(&public_key, signature.clone());
+        let signature_json = serde_json::to_string(&signature_json)
+            .map_err(|_| SigningError(SigningErrorType::Error_internal))?;
+
+        let is_url = false;
+        Ok(Proto::MessageSigningOutput {
+            signature: Cow::from(base64::encode(&signature, is_url)),
+            public_key: Cow::from(public_key.to_bytes()),
+            signature_json: Cow::from(signature_json),
+            ..Proto::MessageSigningOutput::default()
+        })
+    }
+
+    fn verify_message_impl(
+        coin: &dyn CoinContext,
+        input: Proto::MessageVerifyingInput<'_>,
+    ) -> SigningResult<bool> {
+        let signer = Context::Address::from_str_with_coin(coin, &input.address)?;
+
+        // The public key must belong to the signer.
+        let public_key = Self::public_key_from_bytes(coin, &input.public_key)?;
+        if Context::Address::from_public_key_with_coin(coin, &public_key)? != signer {
+            return Ok(false);
+        }
+
+        let is_url = false;
+        let signature = base64::decode(&input.signature, is_url)?;
+
+        let preimage =
+            Adr036Preimager::<Context>::preimage_hash(&signer, input.message.as_bytes())?;
+        Ok(public_key.verify(&signature, &preimage.tx_hash))
+    }
+
+    /// The public key may be compressed even if the chain derives addresses from extended public keys,
+    /// for example, `ethermint/PubKeyEthSecp256k1`.
+    fn public_key_from_bytes(coin: &dyn CoinContext, bytes: &[u8]) -> SigningResult<tw::PublicKey> {
+        let public_key = secp256k1::PublicKey::try_from(bytes)?;
+        match coin.public_key_type() {
+            tw::PublicKeyType::Secp256k1 => Ok(tw::PublicKey::Secp256k1(public_key)),
+            tw::PublicKeyType::Secp256k1Extended => {
+                Ok(tw::PublicKey::Secp256k1Extended(public_key))
+            },
+            _ => Err(SigningError(SigningErrorType::Error_not_supported)),
+        }
+    }
+}
@@ -4,6 +4,10 @@
 
 pub mod broadcast_msg;
 pub mod compiler;
+pub mod decoder;
+pub mod message_signer;
 pub mod serializer;
 pub mod signer;
+pub mod textual;
 pub mod tx_builder;
+pub mod wallet_connect;
@@ -3,21 +3,27 @@
 // Copyright © 2017 Trust Wallet.
 
 use crate::context::CosmosContext;
+use crate::modules::serializer::protobuf_serializer::ProtobufSerializer;
 use crate::private_key::SignatureData;
+use crate::public_key::multisig::MultisigPublicKey;
 use crate::public_key::{CosmosPublicKey, JsonPublicKey};
-use crate::transaction::{Coin, Fee, SignedTransaction, UnsignedTransaction};
+use crate::transaction::{
+    Coin, Fee, SignedMultisigTransaction, SignedTransaction, TxBody, UnsignedMultisigTransaction,
+    UnsignedTransaction,
+};
 use serde::Serialize;
 use serde_json::Value as Json;
 use std::marker::PhantomData;
 use tw_coin_entry::error::SigningResult;
 use tw_encoding::base64::Base64Encoded;
+use tw_proto::serialize;
 
 #[derive(Serialize)]
-pub struct SignedTxJson {
+pub struct SignedTxJson<Signature = SignatureJson> {
     pub fee: FeeJson,
     pub memo: String,
     pub msg: Vec<AnyMsg<Json>>,
-    pub signatures: Vec<SignatureJson>,
+    pub signatures: Vec<Signature>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub timeout_height: Option<String>,
@@ -40,6 +46,11 @@ pub struct UnsignedTxJson {
 pub struct FeeJson {
     pub amount: Vec<Coin>,
     pub gas: String,
+    // Amino JSON sorts the keys, so `granter` must come before `payer`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub granter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
 }
 
 #[derive(Clone, Serialize)]
@@ -55,6 +66,19 @@ pub struct SignatureJson {
     pub signature: Base64Encoded,
 }
 
+#[derive(Clone, Serialize)]
+pub struct MultisigPublicKeyJson {
+    pub threshold: String,
+    pub pubkeys: Vec<AnyMsg<Base64Encoded>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct MultisigSignatureJson {
+    pub pub_key: AnyMsg<MultisigPublicKeyJson>,
+    /// Amino binary encoded `AminoMultisignature`.
+    pub signature: Base64Encoded,
+}
+
 /// `JsonSerializer` serializes transaction to JSON in Cosmos specific way.
 pub struct JsonSerializer<Context: CosmosContext> {
     _phantom: PhantomData<Context>,
@@ -66,14 +90,31 @@ where
     Context::PublicKey: JsonPublicKey,
 {
     pub fn build_signed_tx(signed: &SignedTransaction<Context>) -> SigningResult<SignedTxJson> {
-        let msg = signed
-            .tx_body
+        let signature =
+            Self::serialize_signature(&signed.signer.public_key, signed.signature.clone());
+        Self::build_signed_tx_impl(&signed.tx_body, &signed.fee, signature)
+    }
+
+    pub fn build_signed_multisig_tx(
+        signed: &SignedMultisigTransaction<Context>,
+    ) -> SigningResult<SignedTxJson<MultisigSignatureJson>> {
+        let amino_multisignature = ProtobufSerializer::build_amino_multisignature(signed);
+        let signature = serialize(&amino_multisignature)?;
+
+        let signature = Self::serialize_multisig_signature(&signed.signer.public_key, signature);
+        Self::build_signed_tx_impl(&signed.tx_body, &signed.fee, signature)
+    }
+
+    fn build_signed_tx_impl<Signature>(
+        tx_body: &TxBody,
+        fee: &Fee<Context::Address>,
+        signature: Signature,
+    ) -> SigningResult<SignedTxJson<Signature>> {
+        let msg = tx_body
             .messages
             .iter()
             .map(|msg| msg.to_json())
             .collect::<SigningResult<_>>()?;
-        let signature =
-            Self::serialize_signature(&signed.signer.public_key, signed.signature.clone());
 
         let convert = |value: u64| {
             if value == 0 {
@@ -83,19 +124,48 @@ where
             }
         };
         Ok(SignedTxJson {
-            fee: Self::build_fee(&signed.fee),
-            memo: signed.tx_body.memo.clone(),
+            fee: Self::build_fee(fee),
+            memo: tx_body.memo.clone(),
             msg,
             signatures: vec![signature],
-            timeout_height: convert(signed.tx_body.timeout_height),
+            timeout_height: convert(tx_body.timeout_height),
         })
     }
 
     pub fn build_unsigned_tx(
         unsigned: &UnsignedTransaction<Context>,
     ) -> SigningResult<UnsignedTxJson> {
-        let msgs = unsigned
-            .tx_body
+        Self::build_unsigned_tx_impl(
+            &unsigned.tx_body,
+            &unsigned.fee,
+            &unsigned.chain_id,
+            unsigned.account_number,
+            unsigned.signer.sequence,
+        )
+    }
+
+    /// The Amino JSON sign doc does not depend on the signers,
+    /// so each of the multisig members signs the same document.
+    pub fn build_unsigned_multisig_tx(
+        unsigned: &UnsignedMultisigTransaction<Context>,
+    ) -> SigningResult<UnsignedTxJson> {
+        Self::build_unsigned_tx_impl(
+            &unsigned.tx_body,
+            &unsigned.fee,
+            &unsigned.chain_id,
+            unsigned.account_number,
+            unsigned.signer.sequence,
+        )
+    }
+
+    fn build_unsigned_tx_impl(
+        tx_body: &TxBody,
+        fee: &Fee<Context::Address>,
+        chain_id: &str,
+        account_number: u64,
+        sequence: u64,
+    ) -> SigningResult<UnsignedTxJson> {
+        let msgs = tx_body
             .messages
             .iter()
             .map(|msg| msg.to_json())
@@ -109,13 +179,13 @@ where
             }
         };
         Ok(UnsignedTxJson {
-            account_number: unsigned.account_number.to_string(),
-            chain_id: unsigned.chain_id.clone(),
-            fee: Self::build_fee(&unsigned.fee),
-            memo: unsigned.tx_body.memo.clone(),
+            account_number: account_number.to_string(),
+            chain_id: chain_id.to_string(),
+            fee: Self::build_fee(fee),
+            memo: tx_body.memo.clone(),
             msgs,
-            sequence: unsigned.signer.sequence.to_string(),
-            timeout_height: convert(unsigned.tx_body.timeout_height),
+            sequence: sequence.to_string(),
+            timeout_height: convert(tx_body.timeout_height),
         })
     }
 
@@ -136,10 +206,38 @@ where
         }
     }
 
+    pub fn serialize_multisig_signature(
+        public_key: &MultisigPublicKey<Context::PublicKey>,
+        signature: SignatureData,
+    ) -> MultisigSignatureJson {
+        MultisigSignatureJson {
+            pub_key: Self::serialize_multisig_public_key(public_key),
+            signature: Base64Encoded(signature),
+        }
+    }
+
+    pub fn serialize_multisig_public_key(
+        public_key: &MultisigPublicKey<Context::PublicKey>,
+    ) -> AnyMsg<MultisigPublicKeyJson> {
+        AnyMsg {
+            msg_type: public_key.public_key_type(),
+            value: MultisigPublicKeyJson {
+                threshold: public_key.threshold().to_string(),
+                pubkeys: public_key
+                    .public_keys()
+                    .iter()
+                    .map(Self::serialize_public_key)
+                    .collect(),
+            },
+        }
+    }
+
     pub fn build_fee(fee: &Fee<Context::Address>) -> FeeJson {
         FeeJson {
             gas: fee.gas_limit.to_string(),
             amount: fee.amounts.clone(),
+            granter: fee.granter.as_ref().map(|granter| granter.to_string()),
+            payer: fee.payer.as_ref().map(|payer| payer.to_string()),
         }
     }
 }
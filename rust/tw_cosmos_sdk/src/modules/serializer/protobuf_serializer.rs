@@ -4,11 +4,13 @@
 
 use crate::context::CosmosContext;
 use crate::proto::cosmos::base::v1beta1 as base_proto;
+use crate::proto::cosmos::multisig::v1beta1 as multisig_proto;
 use crate::proto::cosmos::signing::v1beta1 as signing_proto;
 use crate::proto::cosmos::tx::v1beta1 as tx_proto;
 use crate::public_key::ProtobufPublicKey;
 use crate::transaction::{
-    Coin, Fee, SignMode, SignedTransaction, SignerInfo, TxBody, UnsignedTransaction,
+    Coin, Fee, MultisigSignerInfo, SignMode, SignedMultisigTransaction, SignedTransaction,
+    SignerInfo, TxBody, UnsignedMultisigTransaction, UnsignedTransaction,
 };
 use std::marker::PhantomData;
 use tw_coin_entry::error::SigningResult;
@@ -52,6 +54,31 @@ impl<Context: CosmosContext> ProtobufSerializer<Context> {
         })
     }
 
+    /// Serializes a signed multisig transaction into the Cosmos [`tx_proto::TxRaw`] message.
+    /// The member signatures are wrapped into a single [`multisig_proto::MultiSignature`].
+    pub fn build_signed_multisig_tx(
+        signed: &SignedMultisigTransaction<Context>,
+    ) -> SigningResult<tx_proto::TxRaw> {
+        let tx_body = Self::build_tx_body(&signed.tx_body)?;
+        let body_bytes = serialize(&tx_body).expect("Unexpected error on tx_body serialization");
+
+        let auth_info = Self::build_multisig_auth_info(&signed.signer, &signed.fee);
+        let auth_info_bytes =
+            serialize(&auth_info).expect("Unexpected error on auth_info serialization");
+
+        let multi_signature = multisig_proto::MultiSignature {
+            signatures: signed.signatures.clone(),
+        };
+        let signature =
+            serialize(&multi_signature).expect("Unexpected error on multi_signature serialization");
+
+        Ok(tx_proto::TxRaw {
+            body_bytes,
+            auth_info_bytes,
+            signatures: vec![signature],
+        })
+    }
+
     pub fn build_direct_signed_tx(args: &SignDirectArgs, signature: Data) -> tx_proto::TxRaw {
         tx_proto::TxRaw {
             body_bytes: args.tx_body.clone(),
@@ -80,6 +107,25 @@ impl<Context: CosmosContext> ProtobufSerializer<Context> {
         })
     }
 
+    /// Serializes an unsigned multisig transaction into the Cosmos [`tx_proto::SignDoc`] message.
+    pub fn build_multisig_sign_doc(
+        unsigned: &UnsignedMultisigTransaction<Context>,
+    ) -> SigningResult<tx_proto::SignDoc> {
+        let tx_body = Self::build_tx_body(&unsigned.tx_body)?;
+        let body_bytes = serialize(&tx_body).expect("Unexpected error on tx_body serialization");
+
+        let auth_info = Self::build_multisig_auth_info(&unsigned.signer, &unsigned.fee);
+        let auth_info_bytes =
+            serialize(&auth_info).expect("Unexpected error on auth_info serialization");
+
+        Ok(tx_proto::SignDoc {
+            body_bytes,
+            auth_info_bytes,
+            chain_id: unsigned.chain_id.clone(),
+            account_number: unsigned.account_number,
+        })
+    }
+
     pub fn build_direct_sign_doc(args: &SignDirectArgs) -> tx_proto::SignDoc {
         tx_proto::SignDoc {
             body_bytes: args.tx_body.clone(),
@@ -101,6 +147,18 @@ impl<Context: CosmosContext> ProtobufSerializer<Context> {
         }
     }
 
+    pub fn build_multisig_auth_info(
+        signer: &MultisigSignerInfo<Context::PublicKey>,
+        fee: &Fee<Context::Address>,
+    ) -> tx_proto::AuthInfo {
+        tx_proto::AuthInfo {
+            signer_infos: vec![Self::build_multisig_signer_info(signer)],
+            fee: Some(Self::build_fee(fee)),
+            // At this moment, we do not support transaction tip.
+            tip: None,
+        }
+    }
+
     pub fn build_tx_body(tx_body: &TxBody) -> SigningResult<tx_proto::TxBody> {
         let messages: Vec<_> = tx_body
             .messages
@@ -135,19 +193,82 @@ impl<Context: CosmosContext> ProtobufSerializer<Context> {
         }
     }
 
+    pub fn build_multisig_signer_info(
+        signer: &MultisigSignerInfo<Context::PublicKey>,
+    ) -> tx_proto::SignerInfo {
+        use tx_proto::mod_ModeInfo::{self as mode_info, OneOfsum as SumEnum};
+
+        let member_mode_info = tx_proto::ModeInfo {
+            sum: SumEnum::single(mode_info::Single {
+                mode: Self::build_sign_mode(signer.sign_mode),
+            }),
+        };
+
+        // Multi is the mode info for a multisig public key.
+        // Each of the signers uses the same signing mode.
+        let mode_info = tx_proto::ModeInfo {
+            sum: SumEnum::multi(mode_info::Multi {
+                bitarray: Some(Self::build_signers_bit_array(signer)),
+                mode_infos: vec![member_mode_info; signer.signers.len()],
+            }),
+        };
+
+        tx_proto::SignerInfo {
+            public_key: Some(signer.public_key.to_proto()),
+            mode_info: Some(mode_info),
+            sequence: signer.sequence,
+        }
+    }
+
+    /// Builds the legacy Amino multisig signature used in the Amino JSON transactions.
+    pub fn build_amino_multisignature(
+        signed: &SignedMultisigTransaction<Context>,
+    ) -> multisig_proto::AminoMultisignature {
+        multisig_proto::AminoMultisignature {
+            bit_array: Some(Self::build_signers_bit_array(&signed.signer)),
+            sigs: signed.signatures.clone(),
+        }
+    }
+
+    /// Builds a bit array of the multisig members, where the bits of the signers are set.
+    /// https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/crypto/types/compact_bit_array.go
+    fn build_signers_bit_array(
+        signer: &MultisigSignerInfo<Context::PublicKey>,
+    ) -> multisig_proto::CompactBitArray {
+        let bits = signer.public_key.public_keys().len();
+
+        let mut elems = vec![0_u8; bits.div_ceil(8)];
+        for position in signer.signers.iter() {
+            elems[position / 8] |= 1 << (7 - position % 8);
+        }
+
+        multisig_proto::CompactBitArray {
+            extra_bits_stored: (bits % 8) as u32,
+            elems,
+        }
+    }
+
     fn build_fee(fee: &Fee<Context::Address>) -> tx_proto::Fee {
         tx_proto::Fee {
             amount: fee.amounts.iter().map(build_coin).collect(),
             gas_limit: fee.gas_limit,
-            // Ignore `payer` and `granter` even if they set.
-            payer: String::default(),
-            granter: String::default(),
+            payer: fee
+                .payer
+                .as_ref()
+                .map(|payer| payer.to_string())
+                .unwrap_or_default(),
+            granter: fee
+                .granter
+                .as_ref()
+                .map(|granter| granter.to_string())
+                .unwrap_or_default(),
         }
     }
 
     fn build_sign_mode(sign_mode: SignMode) -> signing_proto::SignMode {
         match sign_mode {
             SignMode::Direct => signing_proto::SignMode::SIGN_MODE_DIRECT,
+            SignMode::Textual => signing_proto::SignMode::SIGN_MODE_TEXTUAL,
             SignMode::Other(other) => signing_proto::SignMode::from(other),
         }
     }
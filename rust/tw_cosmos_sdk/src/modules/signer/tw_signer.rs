@@ -4,14 +4,16 @@
 
 use crate::context::CosmosContext;
 use crate::modules::compiler::tw_compiler::TWTransactionCompiler;
+use crate::modules::serializer::json_serializer::JsonSerializer;
 use crate::modules::tx_builder::TxBuilder;
-use crate::private_key::CosmosPrivateKey;
+use crate::private_key::{CosmosPrivateKey, SignatureData};
 use crate::public_key::CosmosPublicKey;
 use std::borrow::Cow;
 use std::marker::PhantomData;
 use tw_coin_entry::coin_context::CoinContext;
 use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
 use tw_coin_entry::signing_output_error;
+use tw_misc::traits::ToBytesVec;
 use tw_proto::Cosmos::Proto;
 
 pub struct TWSigner<Context> {
@@ -47,6 +49,10 @@ impl<Context: CosmosContext> TWSigner<Context> {
         }
 
         let signature_data = private_key.sign_tx_hash(&preimage_output.data_hash)?;
+        if input.multisig.is_some() {
+            return Self::partial_signature_output(coin, &input, &public_key, signature_data);
+        }
+
         let compile_output = TWTransactionCompiler::<Context>::compile(
             coin,
             input,
@@ -60,4 +66,39 @@ impl<Context: CosmosContext> TWSigner<Context> {
 
         Ok(compile_output)
     }
+
+    /// Returns the signature of a multisig member.
+    /// The signatures of the members should be combined by [`TWTransactionCompiler::compile`].
+    fn partial_signature_output(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput<'_>,
+        public_key: &Context::PublicKey,
+        signature: SignatureData,
+    ) -> SigningResult<Proto::SigningOutput<'static>> {
+        let signer = TxBuilder::<Context>::multisig_signer_info_from_proto(coin, input)?;
+        let position = signer
+            .public_key
+            .position(public_key)
+            .ok_or(SigningError(SigningErrorType::Error_invalid_params))?;
+
+        // The set of signers is a part of the Protobuf sign doc.
+        if input.signing_mode == Proto::SigningMode::Protobuf && !signer.signers.contains(&position)
+        {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+
+        // Strip the recovery byte, as `compile` embeds the signatures without it.
+        let signature = Context::Signature::try_from(&signature)?.to_vec();
+
+        let signature_json =
+            JsonSerializer::<Context>::serialize_signature(public_key, signature.clone());
+        let signature_json = serde_json::to_string(&[signature_json])
+            .map_err(|_| SigningError(SigningErrorType::Error_internal))?;
+
+        Ok(Proto::SigningOutput {
+            signature: Cow::from(signature),
+            signature_json: Cow::from(signature_json),
+            ..Proto::SigningOutput::default()
+        })
+    }
 }
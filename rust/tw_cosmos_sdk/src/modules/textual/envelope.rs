@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::context::CosmosContext;
+use crate::modules::serializer::protobuf_serializer::ProtobufSerializer;
+use crate::modules::textual::message_renderer::render_any;
+use crate::modules::textual::screen::Screen;
+use crate::modules::textual::value_renderer::{
+    render_bytes, render_coins, render_int, DenomMetadataRegistry,
+};
+use crate::public_key::{CosmosPublicKey, ProtobufPublicKey};
+use crate::transaction::message::CosmosMessageBox;
+use crate::transaction::UnsignedTransaction;
+use std::marker::PhantomData;
+use tw_coin_entry::error::SigningResult;
+use tw_encoding::hex::ToHex;
+use tw_hash::sha2::sha256;
+
+/// The size of the length prefix of the raw bytes in the hash.
+const LEN_PREFIX_SIZE: usize = 8;
+
+/// Renders a transaction into the screens of the SIGN_MODE_TEXTUAL envelope.
+/// https://docs.cosmos.network/main/build/architecture/adr-050-sign-mode-textual#envelope-screens
+pub struct TextualRenderer<Context: CosmosContext> {
+    _phantom: PhantomData<Context>,
+}
+
+impl<Context: CosmosContext> TextualRenderer<Context> {
+    /// `signer_address` is the address derived from [`UnsignedTransaction::signer`] public key.
+    pub fn render_tx(
+        unsigned: &UnsignedTransaction<Context>,
+        signer_address: &str,
+        registry: &DenomMetadataRegistry,
+    ) -> SigningResult<Vec<Screen>> {
+        let public_key = unsigned.signer.public_key.to_proto();
+        let tx_body = &unsigned.tx_body;
+        let fee = &unsigned.fee;
+
+        let mut screens = vec![
+            Screen::new("Chain id", unsigned.chain_id.clone()),
+            Screen::new(
+                "Account number",
+                render_int(&unsigned.account_number.to_string()),
+            ),
+            Screen::new(
+                "Sequence",
+                render_int(&unsigned.signer.sequence.to_string()),
+            ),
+            Screen::new("Address", signer_address),
+            Screen::new("Public key", public_key.type_url).expert(),
+            Screen::new("Key", render_bytes(&unsigned.signer.public_key.to_bytes()))
+                .with_indent(1)
+                .expert(),
+        ];
+
+        let messages_count = tx_body.messages.len();
+        let plural = if messages_count == 1 { "" } else { "s" };
+        screens.push(Screen::content(format!(
+            "This transaction has {messages_count} Message{plural}"
+        )));
+        for (i, message) in tx_body.messages.iter().enumerate() {
+            Self::render_message(&mut screens, message, i + 1, messages_count, registry)?;
+        }
+
+        if !tx_body.memo.is_empty() {
+            screens.push(Screen::new("Memo", tx_body.memo.clone()));
+        }
+        screens.push(Screen::new("Fees", render_coins(&fee.amounts, registry)));
+        if let Some(ref payer) = fee.payer {
+            screens.push(Screen::new("Fee payer", payer.to_string()).expert());
+        }
+        if let Some(ref granter) = fee.granter {
+            screens.push(Screen::new("Fee granter", granter.to_string()).expert());
+        }
+        screens.push(Screen::new("Gas limit", render_int(&fee.gas_limit.to_string())).expert());
+        if tx_body.timeout_height > 0 {
+            let timeout_height = render_int(&tx_body.timeout_height.to_string());
+            screens.push(Screen::new("Timeout height", timeout_height).expert());
+        }
+
+        // The hash of the raw bytes guarantees that the signed screens correspond to the broadcasted transaction.
+        let sign_doc = ProtobufSerializer::build_sign_doc(unsigned)?;
+        let hash = hash_of_raw_bytes(&sign_doc.body_bytes, &sign_doc.auth_info_bytes);
+        screens.push(Screen::new("Hash of raw bytes", hash).expert());
+
+        Ok(screens)
+    }
+
+    fn render_message(
+        screens: &mut Vec<Screen>,
+        message: &CosmosMessageBox,
+        number: usize,
+        count: usize,
+        registry: &DenomMetadataRegistry,
+    ) -> SigningResult<()> {
+        let title = format!("Message ({number}/{count})");
+        render_any(screens, &title, &message.to_proto()?, 1, registry)?;
+        screens.push(Screen::content("End of Message"));
+        Ok(())
+    }
+}
+
+/// `HEX(sha256(len(body_bytes) ++ body_bytes ++ len(auth_info_bytes) ++ auth_info_bytes))`,
+/// where the lengths are encoded as big-endian `u64`.
+fn hash_of_raw_bytes(body_bytes: &[u8], auth_info_bytes: &[u8]) -> String {
+    let mut raw_bytes =
+        Vec::with_capacity(2 * LEN_PREFIX_SIZE + body_bytes.len() + auth_info_bytes.len());
+    for bytes in [body_bytes, auth_info_bytes] {
+        raw_bytes.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+        raw_bytes.extend_from_slice(bytes);
+    }
+    sha256(&raw_bytes).to_hex().to_uppercase()
+}
@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! Renders Protobuf messages field by field, in the order of their field numbers.
+//! https://docs.cosmos.network/main/build/architecture/adr-050-sign-mode-textual-annex1#message
+
+use crate::modules::textual::screen::Screen;
+use crate::modules::textual::value_renderer::{
+    render_bytes, render_coin, render_coins, render_int, DenomMetadataRegistry,
+};
+use crate::proto::{cosmos, cosmwasm, ibc};
+use crate::transaction::Coin;
+use quick_protobuf::MessageInfo;
+use std::str::FromStr;
+use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
+use tw_number::U256;
+use tw_proto::{google, MessageRead};
+
+/// A Protobuf message that can be rendered into SIGN_MODE_TEXTUAL screens.
+pub trait TextualMessage: MessageInfo {
+    /// Renders the fields in the order of their field numbers.
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()>;
+}
+
+/// Renders the fields of a message at the same indentation level.
+/// Fields that have default values are omitted.
+pub struct FieldsRenderer<'a> {
+    screens: &'a mut Vec<Screen>,
+    indent: u64,
+    registry: &'a DenomMetadataRegistry,
+}
+
+impl<'a> FieldsRenderer<'a> {
+    pub fn new(
+        screens: &'a mut Vec<Screen>,
+        indent: u64,
+        registry: &'a DenomMetadataRegistry,
+    ) -> Self {
+        FieldsRenderer {
+            screens,
+            indent,
+            registry,
+        }
+    }
+
+    pub fn string(&mut self, name: &str, value: &str) {
+        if !value.is_empty() {
+            self.push(name, value);
+        }
+    }
+
+    pub fn uint(&mut self, name: &str, value: u64) {
+        if value != 0 {
+            self.push(name, render_int(&value.to_string()));
+        }
+    }
+
+    pub fn bytes(&mut self, name: &str, value: &[u8]) {
+        if !value.is_empty() {
+            self.push(name, render_bytes(value));
+        }
+    }
+
+    /// Enums are rendered as the name of their value, e.g. `VOTE_OPTION_YES`.
+    pub fn enumeration(&mut self, name: &str, value: i32, value_name: &str) {
+        if value != 0 {
+            self.push(name, value_name);
+        }
+    }
+
+    pub fn coin(
+        &mut self,
+        name: &str,
+        value: &Option<cosmos::base::v1beta1::Coin>,
+    ) -> SigningResult<()> {
+        if let Some(coin) = value {
+            let content = render_coin(&coin_from_proto(coin)?, self.registry);
+            self.push(name, content);
+        }
+        Ok(())
+    }
+
+    pub fn coins(
+        &mut self,
+        name: &str,
+        value: &[cosmos::base::v1beta1::Coin],
+    ) -> SigningResult<()> {
+        if !value.is_empty() {
+            let coins = value
+                .iter()
+                .map(coin_from_proto)
+                .collect::<SigningResult<Vec<_>>>()?;
+            let content = render_coins(&coins, self.registry);
+            self.push(name, content);
+        }
+        Ok(())
+    }
+
+    /// A nested message is rendered as `<type name> object` followed by its fields.
+    pub fn message<M: TextualMessage>(
+        &mut self,
+        name: &str,
+        value: &Option<M>,
+    ) -> SigningResult<()> {
+        let Some(message) = value else {
+            return Ok(());
+        };
+
+        let header = format!("{} object", type_name::<M>());
+        self.push(name, header);
+
+        let mut fields = FieldsRenderer::new(self.screens, self.indent + 1, self.registry);
+        message.render_fields(&mut fields)
+    }
+
+    fn push(&mut self, name: &str, content: impl Into<String>) {
+        self.screens
+            .push(Screen::new(&field_title(name), content).with_indent(self.indent));
+    }
+}
+
+/// Renders a message packed into `google.protobuf.Any` as its type URL followed by the message fields.
+/// Returns an error if the message type is not supported.
+pub fn render_any(
+    screens: &mut Vec<Screen>,
+    title: &str,
+    any: &google::protobuf::Any,
+    indent: u64,
+    registry: &DenomMetadataRegistry,
+) -> SigningResult<()> {
+    use cosmos::bank::v1beta1 as bank;
+    use cosmos::distribution::v1beta1 as distribution;
+    use cosmos::gov::v1beta1 as gov;
+    use cosmos::staking::v1beta1 as staking;
+    use cosmwasm::wasm::v1 as wasm;
+    use ibc::applications::transfer::v1 as transfer;
+
+    screens.push(Screen::new(title, any.type_url.clone()).with_indent(indent));
+    let mut fields = FieldsRenderer::new(screens, indent + 1, registry);
+
+    let path = any.type_url.strip_prefix('/').unwrap_or_default();
+    match path {
+        bank::MsgSend::PATH => render_packed::<bank::MsgSend>(&any.value, &mut fields),
+        distribution::MsgSetWithdrawAddress::PATH => {
+            render_packed::<distribution::MsgSetWithdrawAddress>(&any.value, &mut fields)
+        },
+        distribution::MsgWithdrawDelegatorReward::PATH => {
+            render_packed::<distribution::MsgWithdrawDelegatorReward>(&any.value, &mut fields)
+        },
+        gov::MsgDeposit::PATH => render_packed::<gov::MsgDeposit>(&any.value, &mut fields),
+        gov::MsgVote::PATH => render_packed::<gov::MsgVote>(&any.value, &mut fields),
+        staking::MsgBeginRedelegate::PATH => {
+            render_packed::<staking::MsgBeginRedelegate>(&any.value, &mut fields)
+        },
+        staking::MsgDelegate::PATH => {
+            render_packed::<staking::MsgDelegate>(&any.value, &mut fields)
+        },
+        staking::MsgUndelegate::PATH => {
+            render_packed::<staking::MsgUndelegate>(&any.value, &mut fields)
+        },
+        transfer::MsgTransfer::PATH => {
+            render_packed::<transfer::MsgTransfer>(&any.value, &mut fields)
+        },
+        wasm::MsgExecuteContract::PATH => {
+            render_packed::<wasm::MsgExecuteContract>(&any.value, &mut fields)
+        },
+        _ => Err(SigningError(SigningErrorType::Error_not_supported)),
+    }
+}
+
+fn render_packed<'a, M>(bytes: &'a [u8], fields: &mut FieldsRenderer) -> SigningResult<()>
+where
+    M: TextualMessage + MessageRead<'a>,
+{
+    let message: M = tw_proto::deserialize(bytes)
+        .map_err(|_| SigningError(SigningErrorType::Error_invalid_params))?;
+    message.render_fields(fields)
+}
+
+/// Converts a field name into a screen title, e.g. `from_address` into `From address`.
+fn field_title(name: &str) -> String {
+    let title = name.replace('_', " ");
+    let mut chars = title.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => title,
+    }
+}
+
+/// The message name without the package, e.g. `MsgSend`.
+fn type_name<M: MessageInfo>() -> &'static str {
+    M::PATH.rsplit('.').next().unwrap_or(M::PATH)
+}
+
+fn coin_from_proto(coin: &cosmos::base::v1beta1::Coin) -> SigningResult<Coin> {
+    Ok(Coin {
+        amount: U256::from_str(&coin.amount)?,
+        denom: coin.denom.clone(),
+    })
+}
+
+impl TextualMessage for cosmos::bank::v1beta1::MsgSend {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.string("from_address", &self.from_address);
+        fields.string("to_address", &self.to_address);
+        fields.coins("amount", &self.amount)
+    }
+}
+
+impl TextualMessage for cosmos::distribution::v1beta1::MsgSetWithdrawAddress {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.string("delegator_address", &self.delegator_address);
+        fields.string("withdraw_address", &self.withdraw_address);
+        Ok(())
+    }
+}
+
+impl TextualMessage for cosmos::distribution::v1beta1::MsgWithdrawDelegatorReward {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.string("delegator_address", &self.delegator_address);
+        fields.string("validator_address", &self.validator_address);
+        Ok(())
+    }
+}
+
+impl TextualMessage for cosmos::gov::v1beta1::MsgDeposit {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.uint("proposal_id", self.proposal_id);
+        fields.string("depositor", &self.depositor);
+        fields.coins("amount", &self.amount)
+    }
+}
+
+impl TextualMessage for cosmos::gov::v1beta1::MsgVote {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.uint("proposal_id", self.proposal_id);
+        fields.string("voter", &self.voter);
+        fields.enumeration("option", self.option as i32, &format!("{:?}", self.option));
+        Ok(())
+    }
+}
+
+impl TextualMessage for cosmos::staking::v1beta1::MsgBeginRedelegate {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.string("delegator_address", &self.delegator_address);
+        fields.string("validator_src_address", &self.validator_src_address);
+        fields.string("validator_dst_address", &self.validator_dst_address);
+        fields.coin("amount", &self.amount)
+    }
+}
+
+impl TextualMessage for cosmos::staking::v1beta1::MsgDelegate {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.string("delegator_address", &self.delegator_address);
+        fields.string("validator_address", &self.validator_address);
+        fields.coin("amount", &self.amount)
+    }
+}
+
+impl TextualMessage for cosmos::staking::v1beta1::MsgUndelegate {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.string("delegator_address", &self.delegator_address);
+        fields.string("validator_address", &self.validator_address);
+        fields.coin("amount", &self.amount)
+    }
+}
+
+impl TextualMessage for cosmwasm::wasm::v1::MsgExecuteContract {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.string("sender", &self.sender);
+        fields.string("contract", &self.contract);
+        fields.bytes("msg", &self.msg);
+        fields.coins("funds", &self.funds)
+    }
+}
+
+impl TextualMessage for ibc::applications::transfer::v1::MsgTransfer {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.string("source_port", &self.source_port);
+        fields.string("source_channel", &self.source_channel);
+        fields.coin("token", &self.token)?;
+        fields.string("sender", &self.sender);
+        fields.string("receiver", &self.receiver);
+        fields.message("timeout_height", &self.timeout_height)?;
+        fields.uint("timeout_timestamp", self.timeout_timestamp);
+        fields.string("memo", &self.memo);
+        Ok(())
+    }
+}
+
+impl TextualMessage for ibc::core::client::v1::Height {
+    fn render_fields(&self, fields: &mut FieldsRenderer) -> SigningResult<()> {
+        fields.uint("revision_number", self.revision_number);
+        fields.uint("revision_height", self.revision_height);
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! SIGN_MODE_TEXTUAL (ADR-050) - the transaction is signed as a list of human-readable screens.
+//! https://docs.cosmos.network/main/build/architecture/adr-050-sign-mode-textual
+
+pub mod envelope;
+pub mod message_renderer;
+pub mod screen;
+pub mod value_renderer;
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
+use tw_encoding::cbor;
+use tw_memory::Data;
+
+const TITLE_KEY: u64 = 1;
+const CONTENT_KEY: u64 = 2;
+const INDENT_KEY: u64 = 3;
+const EXPERT_KEY: u64 = 4;
+const SCREENS_KEY: u64 = 1;
+
+/// A screen of a SIGN_MODE_TEXTUAL sign doc, as shown on the device.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Screen {
+    pub title: String,
+    pub content: String,
+    pub indent: u64,
+    /// Whether the screen is shown in the expert mode only.
+    pub expert: bool,
+}
+
+impl Screen {
+    pub fn new(title: &str, content: impl Into<String>) -> Screen {
+        Screen {
+            title: title.to_string(),
+            content: content.into(),
+            ..Screen::default()
+        }
+    }
+
+    /// Creates a screen without a title.
+    pub fn content(content: impl Into<String>) -> Screen {
+        Screen {
+            content: content.into(),
+            ..Screen::default()
+        }
+    }
+
+    pub fn with_indent(mut self, indent: u64) -> Screen {
+        self.indent = indent;
+        self
+    }
+
+    pub fn expert(mut self) -> Screen {
+        self.expert = true;
+        self
+    }
+}
+
+/// Screens are encoded as CBOR maps with integer keys, omitting the fields that have default values.
+impl Serialize for Screen {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let len = usize::from(!self.title.is_empty())
+            + usize::from(!self.content.is_empty())
+            + usize::from(self.indent > 0)
+            + usize::from(self.expert);
+
+        let mut map = serializer.serialize_map(Some(len))?;
+        if !self.title.is_empty() {
+            map.serialize_entry(&TITLE_KEY, &self.title)?;
+        }
+        if !self.content.is_empty() {
+            map.serialize_entry(&CONTENT_KEY, &self.content)?;
+        }
+        if self.indent > 0 {
+            map.serialize_entry(&INDENT_KEY, &self.indent)?;
+        }
+        if self.expert {
+            map.serialize_entry(&EXPERT_KEY, &self.expert)?;
+        }
+        map.end()
+    }
+}
+
+/// Encodes the screens into the SIGN_MODE_TEXTUAL sign bytes: `{1: [screen, ...]}`.
+pub fn encode_screens(screens: &[Screen]) -> SigningResult<Data> {
+    let sign_doc = BTreeMap::from([(SCREENS_KEY, screens)]);
+    cbor::encode(&sign_doc).map_err(|_| SigningError(SigningErrorType::Error_internal))
+}
@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! Value renderers of SIGN_MODE_TEXTUAL.
+//! https://docs.cosmos.network/main/build/architecture/adr-050-sign-mode-textual-annex1
+
+use crate::transaction::Coin;
+use std::collections::HashMap;
+use tw_encoding::hex::ToHex;
+use tw_hash::sha2::sha256;
+
+/// Byte arrays longer than this are rendered as their SHA-256 hash.
+const MAX_RENDERED_BYTES_LEN: usize = 35;
+const SECONDS_PER_MINUTE: u64 = 60;
+const SECONDS_PER_HOUR: u64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+const NANOS_PER_SECOND: u32 = 1_000_000_000;
+
+pub struct DenomUnit {
+    pub denom: String,
+    pub exponent: u32,
+}
+
+pub struct DenomMetadata {
+    pub base: String,
+    pub display: String,
+    pub denom_units: Vec<DenomUnit>,
+}
+
+impl DenomMetadata {
+    /// Returns the exponent of the display unit.
+    pub fn display_exponent(&self) -> Option<u32> {
+        self.denom_units
+            .iter()
+            .find(|unit| unit.denom == self.display)
+            .map(|unit| unit.exponent)
+    }
+}
+
+/// Denomination metadata by the base denomination.
+#[derive(Default)]
+pub struct DenomMetadataRegistry {
+    metadata: HashMap<String, DenomMetadata>,
+}
+
+impl DenomMetadataRegistry {
+    pub fn new(metadata: Vec<DenomMetadata>) -> Self {
+        let metadata = metadata
+            .into_iter()
+            .map(|metadata| (metadata.base.clone(), metadata))
+            .collect();
+        DenomMetadataRegistry { metadata }
+    }
+
+    pub fn get(&self, base: &str) -> Option<&DenomMetadata> {
+        self.metadata.get(base)
+    }
+}
+
+/// Renders an unsigned integer with the `'` thousands separator, e.g. `1'000'000`.
+/// `value` must be a decimal string.
+pub fn render_int(value: &str) -> String {
+    let len = value.len();
+    let mut rendered = String::with_capacity(len + len / 3);
+    for (i, digit) in value.chars().enumerate() {
+        if i > 0 && (len - i).is_multiple_of(3) {
+            rendered.push('\'');
+        }
+        rendered.push(digit);
+    }
+    rendered
+}
+
+/// Renders a decimal with the `'` thousands separator and without trailing zeros, e.g. `1'000.5`.
+/// `value` must be a decimal string with an optional fractional part.
+pub fn render_dec(value: &str) -> String {
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let integer = integer.trim_start_matches('0');
+    let integer = if integer.is_empty() { "0" } else { integer };
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        render_int(integer)
+    } else {
+        format!("{}.{fraction}", render_int(integer))
+    }
+}
+
+/// Renders a coin in the display denomination if its metadata is known, e.g. `1.5 atom`.
+/// Otherwise, the coin is rendered in the base denomination, e.g. `1'500'000 uatom`.
+pub fn render_coin(coin: &Coin, registry: &DenomMetadataRegistry) -> String {
+    let amount = coin.amount.to_string();
+
+    let display = registry.get(&coin.denom).and_then(|metadata| {
+        let exponent = metadata.display_exponent()?;
+        Some((shift_decimal_point(&amount, exponent), &metadata.display))
+    });
+
+    match display {
+        Some((amount, denom)) => format!("{} {denom}", render_dec(&amount)),
+        None => format!("{} {}", render_int(&amount), coin.denom),
+    }
+}
+
+/// Renders coins sorted by their display denomination and separated by `, `.
+/// An empty list is rendered as `zero`.
+pub fn render_coins(coins: &[Coin], registry: &DenomMetadataRegistry) -> String {
+    if coins.is_empty() {
+        return "zero".to_string();
+    }
+
+    let display_denom = |coin: &Coin| {
+        registry
+            .get(&coin.denom)
+            .map(|metadata| metadata.display.clone())
+            .unwrap_or_else(|| coin.denom.clone())
+    };
+
+    let mut coins: Vec<_> = coins.iter().collect();
+    coins.sort_by_cached_key(|coin| display_denom(coin));
+    coins
+        .into_iter()
+        .map(|coin| render_coin(coin, registry))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Renders bytes as uppercase hex split into groups of 4 characters, e.g. `0102 03FF`.
+/// Byte arrays longer than 35 bytes are rendered as `SHA-256=` followed by their hash.
+pub fn render_bytes(bytes: &[u8]) -> String {
+    if bytes.len() > MAX_RENDERED_BYTES_LEN {
+        return format!("SHA-256={}", render_hex(&sha256(bytes)));
+    }
+    render_hex(bytes)
+}
+
+/// Renders a timestamp in the RFC 3339 format in UTC, e.g. `2006-01-02T15:04:05.7Z`.
+/// Trailing zeros of the fractional seconds are omitted.
+pub fn render_timestamp(seconds: i64, nanos: u32) -> String {
+    let days = seconds.div_euclid(SECONDS_PER_DAY as i64);
+    let seconds_of_day = seconds.rem_euclid(SECONDS_PER_DAY as i64) as u64;
+    let (year, month, day) = civil_from_days(days);
+
+    let hour = seconds_of_day / SECONDS_PER_HOUR;
+    let minute = seconds_of_day % SECONDS_PER_HOUR / SECONDS_PER_MINUTE;
+    let second = seconds_of_day % SECONDS_PER_MINUTE;
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}{}Z",
+        render_nanos(nanos)
+    )
+}
+
+/// Renders a duration in days, hours, minutes and seconds, e.g. `1 day, 2 hours, 3.5 seconds`.
+/// Zero components are omitted.
+pub fn render_duration(seconds: i64, nanos: i32) -> String {
+    let negative = seconds < 0 || nanos < 0;
+    let seconds = seconds.unsigned_abs();
+    let nanos = nanos.unsigned_abs();
+
+    let days = seconds / SECONDS_PER_DAY;
+    let hours = seconds % SECONDS_PER_DAY / SECONDS_PER_HOUR;
+    let minutes = seconds % SECONDS_PER_HOUR / SECONDS_PER_MINUTE;
+    let seconds = seconds % SECONDS_PER_MINUTE;
+
+    let mut components = Vec::new();
+    for (value, unit) in [(days, "day"), (hours, "hour"), (minutes, "minute")] {
+        if value > 0 {
+            components.push(pluralize(&value.to_string(), value == 1, unit));
+        }
+    }
+    if seconds > 0 || nanos > 0 || components.is_empty() {
+        let value = format!("{seconds}{}", render_nanos(nanos));
+        components.push(pluralize(&value, seconds == 1 && nanos == 0, "second"));
+    }
+
+    let rendered = components.join(", ");
+    if negative {
+        format!("-{rendered}")
+    } else {
+        rendered
+    }
+}
+
+pub fn render_bool(value: bool) -> String {
+    if value { "True" } else { "False" }.to_string()
+}
+
+fn render_hex(bytes: &[u8]) -> String {
+    bytes
+        .chunks(2)
+        .map(|chunk| chunk.to_hex().to_uppercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Renders fractional seconds as `.NNN` without trailing zeros, or an empty string if `nanos` is zero.
+fn render_nanos(nanos: u32) -> String {
+    if nanos == 0 {
+        return String::default();
+    }
+    let fraction = format!("{:09}", nanos % NANOS_PER_SECOND);
+    format!(".{}", fraction.trim_end_matches('0'))
+}
+
+fn pluralize(value: &str, singular: bool, unit: &str) -> String {
+    if singular {
+        format!("{value} {unit}")
+    } else {
+        format!("{value} {unit}s")
+    }
+}
+
+/// Moves the decimal point of an integer `exponent` digits to the left.
+fn shift_decimal_point(integer: &str, exponent: u32) -> String {
+    let exponent = exponent as usize;
+    if exponent == 0 {
+        return integer.to_string();
+    }
+
+    let padded = format!("{integer:0>width$}", width = exponent + 1);
+    let (integer, fraction) = padded.split_at(padded.len() - exponent);
+    format!("{integer}.{fraction}")
+}
+
+/// Converts days since the Unix epoch into a `(year, month, day)` date.
+/// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
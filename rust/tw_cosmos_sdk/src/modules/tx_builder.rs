@@ -5,10 +5,18 @@
 use crate::address::Address;
 use crate::context::CosmosContext;
 use crate::modules::serializer::protobuf_serializer::SignDirectArgs;
+use crate::modules::textual::value_renderer::{DenomMetadata, DenomMetadataRegistry, DenomUnit};
+use crate::proto::cosmos::signing::v1beta1 as signing_proto;
+use crate::public_key::multisig::MultisigPublicKey;
 use crate::public_key::{CosmosPublicKey, PublicKeyParams};
+use crate::transaction::message::cosmos_feegrant_message::BasicAllowance;
 use crate::transaction::message::cosmos_generic_message::JsonRawMessage;
+use crate::transaction::message::cosmos_gov_message::{GovVersion, VoteOption};
 use crate::transaction::message::{CosmosMessage, CosmosMessageBox};
-use crate::transaction::{Coin, Fee, SignMode, SignerInfo, TxBody, UnsignedTransaction};
+use crate::transaction::{
+    Coin, Fee, MultisigSignerInfo, SignMode, SignerInfo, TxBody, UnsignedMultisigTransaction,
+    UnsignedTransaction,
+};
 use std::marker::PhantomData;
 use std::str::FromStr;
 use tw_coin_entry::coin_context::CoinContext;
@@ -56,14 +64,150 @@ where
         let params = Self::public_key_params_from_proto(input);
         let public_key = Context::PublicKey::from_bytes(coin, &input.public_key, params)?;
 
+        let sign_mode = match input.signing_mode {
+            Proto::SigningMode::Textual => SignMode::Textual,
+            // The sign mode is not a part of the JSON transaction.
+            Proto::SigningMode::JSON | Proto::SigningMode::Protobuf => SignMode::Direct,
+        };
+
         Ok(SignerInfo {
             public_key,
             sequence: input.sequence,
-            // At this moment, we support the Direct signing mode only.
-            sign_mode: SignMode::Direct,
+            sign_mode,
+        })
+    }
+
+    /// Derives the signer address from [`Proto::SigningInput::public_key`].
+    pub fn signer_address_from_proto(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput,
+    ) -> SigningResult<Address> {
+        let public_key_type = match Self::public_key_params_from_proto(input) {
+            Some(params) => params.public_key_type,
+            None => coin.public_key_type(),
+        };
+        let public_key = tw::PublicKey::new(input.public_key.to_vec(), public_key_type)?;
+        Address::with_public_key_coin_context(coin, &public_key, None).map_err(SigningError::from)
+    }
+
+    pub fn denom_metadata_from_proto(input: &Proto::SigningInput) -> DenomMetadataRegistry {
+        let metadata = input
+            .denom_metadata
+            .iter()
+            .map(|metadata| DenomMetadata {
+                base: metadata.base.to_string(),
+                display: metadata.display.to_string(),
+                denom_units: metadata
+                    .denom_units
+                    .iter()
+                    .map(|unit| DenomUnit {
+                        denom: unit.denom.to_string(),
+                        exponent: unit.exponent,
+                    })
+                    .collect(),
+            })
+            .collect();
+        DenomMetadataRegistry::new(metadata)
+    }
+
+    /// Please note that [`Proto::SigningInput::multisig`] must be set.
+    pub fn unsigned_multisig_tx_from_proto(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput<'_>,
+    ) -> SigningResult<UnsignedMultisigTransaction<Context>> {
+        let fee = input
+            .fee
+            .as_ref()
+            .ok_or(SigningError(SigningErrorType::Error_wrong_fee))?;
+        let signer = Self::multisig_signer_info_from_proto(coin, input)?;
+
+        Ok(UnsignedMultisigTransaction {
+            signer,
+            fee: Self::fee_from_proto(fee)?,
+            chain_id: input.chain_id.to_string(),
+            account_number: input.account_number,
+            tx_body: Self::tx_body_from_proto(coin, input)?,
+        })
+    }
+
+    pub fn multisig_signer_info_from_proto(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput,
+    ) -> SigningResult<MultisigSignerInfo<Context::PublicKey>> {
+        let multisig = input
+            .multisig
+            .as_ref()
+            .ok_or(SigningError(SigningErrorType::Error_invalid_params))?;
+
+        let public_keys = multisig
+            .public_keys
+            .iter()
+            .map(|public_key| Self::public_key_from_proto(coin, input, public_key))
+            .collect::<SigningResult<_>>()?;
+        let public_key = MultisigPublicKey::new(multisig.threshold, public_keys)?;
+
+        let signers = multisig
+            .signers
+            .iter()
+            .map(|signer| Self::public_key_from_proto(coin, input, signer))
+            .collect::<SigningResult<Vec<_>>>()?;
+        let signers = Self::multisig_signer_positions(&public_key, &signers)?;
+
+        let sign_mode = match input.signing_mode {
+            Proto::SigningMode::Protobuf => {
+                // Every member signs the `AuthInfo` that contains the set of signers.
+                if signers.len() < multisig.threshold as usize {
+                    return Err(SigningError(SigningErrorType::Error_invalid_params));
+                }
+                SignMode::Direct
+            },
+            Proto::SigningMode::JSON => {
+                SignMode::Other(signing_proto::SignMode::SIGN_MODE_LEGACY_AMINO_JSON as i32)
+            },
+            Proto::SigningMode::Textual => {
+                return Err(SigningError(SigningErrorType::Error_not_supported));
+            },
+        };
+
+        Ok(MultisigSignerInfo {
+            public_key,
+            signers,
+            sequence: input.sequence,
+            sign_mode,
         })
     }
 
+    /// Returns the positions of the given members in ascending order.
+    /// Returns an error if any of the public keys is not a member, or is duplicated.
+    pub fn multisig_signer_positions(
+        multisig: &MultisigPublicKey<Context::PublicKey>,
+        signers: &[Context::PublicKey],
+    ) -> SigningResult<Vec<usize>> {
+        let mut positions = signers
+            .iter()
+            .map(|signer| {
+                multisig
+                    .position(signer)
+                    .ok_or(SigningError(SigningErrorType::Error_invalid_params))
+            })
+            .collect::<SigningResult<Vec<_>>>()?;
+        positions.sort_unstable();
+
+        if positions.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+        Ok(positions)
+    }
+
+    fn public_key_from_proto(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput,
+        public_key: &[u8],
+    ) -> SigningResult<Context::PublicKey> {
+        let params = Self::public_key_params_from_proto(input);
+        Context::PublicKey::from_bytes(coin, public_key, params).map_err(SigningError::from)
+    }
+
     pub fn public_key_params_from_proto(input: &Proto::SigningInput) -> Option<PublicKeyParams> {
         input.signer_info.clone().map(|params| PublicKeyParams {
             public_key_type: match params.public_key_type {
@@ -91,11 +235,24 @@ where
             .iter()
             .map(Self::coin_from_proto)
             .collect::<SigningResult<_>>()?;
+        let payer = input
+            .payer
+            .to_string()
+            .empty_or_some()
+            .map(|payer| Context::Address::from_str(&payer))
+            .transpose()?;
+        let granter = input
+            .granter
+            .to_string()
+            .empty_or_some()
+            .map(|granter| Context::Address::from_str(&granter))
+            .transpose()?;
+
         Ok(Fee {
             amounts,
             gas_limit: input.gas,
-            payer: None,
-            granter: None,
+            payer,
+            granter,
         })
     }
 
@@ -215,6 +372,49 @@ where
             MessageEnum::thorchain_deposit_message(ref deposit) => {
                 Self::thorchain_deposit_msg_from_proto(coin, deposit)
             },
+            MessageEnum::auth_exec(ref exec) => Self::auth_exec_msg_from_proto(coin, exec),
+            MessageEnum::fee_grant(ref grant) => Self::fee_grant_msg_from_proto(coin, grant),
+            MessageEnum::fee_revoke(ref revoke) => Self::fee_revoke_msg_from_proto(coin, revoke),
+            MessageEnum::msg_vote_weighted(ref vote) => {
+                Self::vote_weighted_msg_from_proto(coin, vote)
+            },
+            MessageEnum::msg_deposit(ref deposit) => Self::deposit_msg_from_proto(coin, deposit),
+            MessageEnum::msg_submit_proposal(ref proposal) => {
+                Self::submit_proposal_msg_from_proto(coin, proposal)
+            },
+            MessageEnum::wasm_instantiate_contract(ref instantiate) => {
+                Self::wasm_instantiate_contract_msg_from_proto(coin, instantiate)
+            },
+            MessageEnum::wasm_migrate_contract(ref migrate) => {
+                Self::wasm_migrate_contract_msg_from_proto(coin, migrate)
+            },
+            MessageEnum::wasm_update_admin(ref update) => {
+                Self::wasm_update_admin_msg_from_proto(coin, update)
+            },
+            MessageEnum::wasm_clear_admin(ref clear) => {
+                Self::wasm_clear_admin_msg_from_proto(coin, clear)
+            },
+            MessageEnum::wasm_store_code(ref store) => {
+                Self::wasm_store_code_msg_from_proto(coin, store)
+            },
+            MessageEnum::msg_osmosis_swap_exact_amount_in(ref swap) => {
+                Self::osmosis_swap_exact_amount_in_msg_from_proto(coin, swap)
+            },
+            MessageEnum::msg_osmosis_swap_exact_amount_out(ref swap) => {
+                Self::osmosis_swap_exact_amount_out_msg_from_proto(coin, swap)
+            },
+            MessageEnum::msg_osmosis_create_position(ref create) => {
+                Self::osmosis_create_position_msg_from_proto(coin, create)
+            },
+            MessageEnum::msg_osmosis_add_to_position(ref add) => {
+                Self::osmosis_add_to_position_msg_from_proto(coin, add)
+            },
+            MessageEnum::msg_osmosis_withdraw_position(ref withdraw) => {
+                Self::osmosis_withdraw_position_msg_from_proto(coin, withdraw)
+            },
+            MessageEnum::msg_osmosis_collect_spread_rewards(ref collect) => {
+                Self::osmosis_collect_spread_rewards_msg_from_proto(coin, collect)
+            },
             MessageEnum::None => Err(SigningError(SigningErrorType::Error_invalid_params)),
         }
     }
@@ -245,15 +445,36 @@ where
     ) -> SigningResult<CosmosMessageBox> {
         use crate::transaction::message::ibc_message::{Height, TransferTokensMessage};
 
+        const NANOS_IN_SECOND: u64 = 1_000_000_000;
+
         let token = transfer
             .token
             .as_ref()
             .ok_or(SigningError(SigningErrorType::Error_invalid_params))?;
         let token = Self::coin_from_proto(token)?;
-        let height = transfer
+        let timeout_height = transfer
             .timeout_height
             .as_ref()
-            .ok_or(SigningError(SigningErrorType::Error_invalid_params))?;
+            .map(|height| Height {
+                revision_number: height.revision_number,
+                revision_height: height.revision_height,
+            })
+            .unwrap_or_default();
+
+        let timeout_timestamp = if transfer.now_timestamp != 0 && transfer.timeout_seconds != 0 {
+            transfer
+                .now_timestamp
+                .checked_add(transfer.timeout_seconds)
+                .and_then(|timeout| timeout.checked_mul(NANOS_IN_SECOND))
+                .ok_or(SigningError(SigningErrorType::Error_invalid_params))?
+        } else {
+            transfer.timeout_timestamp
+        };
+
+        // Either timeout height or timestamp should be set.
+        if timeout_height.revision_height == 0 && timeout_timestamp == 0 {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
 
         let msg = TransferTokensMessage {
             source_port: transfer.source_port.to_string(),
@@ -261,15 +482,45 @@ where
             token,
             sender: Address::from_str(&transfer.sender)?,
             receiver: Address::from_str(&transfer.receiver)?,
-            timeout_height: Height {
-                revision_number: height.revision_number,
-                revision_height: height.revision_height,
-            },
-            timeout_timestamp: transfer.timeout_timestamp,
+            timeout_height,
+            timeout_timestamp,
+            memo: Self::transfer_memo_from_proto(transfer)?,
         };
         Ok(msg.into_boxed())
     }
 
+    /// Wraps the memo into packet-forward-middleware metadata if `forward` hops are set.
+    fn transfer_memo_from_proto(
+        transfer: &Proto::mod_Message::Transfer<'_>,
+    ) -> SigningResult<String> {
+        use crate::transaction::message::ibc_message::{ForwardHop, ForwardMemoBuilder};
+
+        if transfer.forward.is_empty() {
+            return Ok(transfer.memo.to_string());
+        }
+
+        let mut builder = ForwardMemoBuilder::default();
+        for hop in transfer.forward.iter() {
+            let mut forward_hop =
+                ForwardHop::new(hop.receiver.to_string(), hop.channel.to_string());
+            if !hop.port.is_empty() {
+                forward_hop.port = hop.port.to_string();
+            }
+            forward_hop.timeout = hop.timeout.to_string().empty_or_some();
+            forward_hop.retries = (hop.retries != 0).then_some(hop.retries);
+            builder = builder.hop(forward_hop);
+        }
+
+        if !transfer.memo.is_empty() {
+            let next = serde_json::from_str(&transfer.memo)
+                .map_err(|_| SigningError(SigningErrorType::Error_invalid_params))?;
+            builder = builder.next(next);
+        }
+
+        let memo = builder.build()?;
+        serde_json::to_string(&memo).map_err(|_| SigningError(SigningErrorType::Error_internal))
+    }
+
     pub fn delegate_msg_from_proto(
         _coin: &dyn CoinContext,
         delegate: &Proto::mod_Message::Delegate<'_>,
@@ -512,6 +763,94 @@ where
         Ok(msg.into_boxed())
     }
 
+    pub fn wasm_instantiate_contract_msg_from_proto(
+        _coin: &dyn CoinContext,
+        instantiate: &Proto::mod_Message::WasmInstantiateContract<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::wasm_message::{
+            ExecuteMsg, Instantiate2Salt, WasmInstantiateContractMessage,
+        };
+
+        let coins = instantiate
+            .coins
+            .iter()
+            .map(Self::coin_from_proto)
+            .collect::<SigningResult<_>>()?;
+        let admin = instantiate
+            .admin_address
+            .to_string()
+            .empty_or_some()
+            .map(|admin| Address::from_str(&admin))
+            .transpose()?;
+        let salt = (!instantiate.salt.is_empty())
+            .then(|| Instantiate2Salt::new(instantiate.salt.to_vec(), instantiate.fix_msg))
+            .transpose()?;
+
+        let msg = WasmInstantiateContractMessage {
+            sender: Address::from_str(&instantiate.sender_address)?,
+            admin,
+            code_id: instantiate.code_id,
+            label: instantiate.label.to_string(),
+            msg: ExecuteMsg::String(instantiate.instantiate_msg.to_string()),
+            coins,
+            salt,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn wasm_migrate_contract_msg_from_proto(
+        _coin: &dyn CoinContext,
+        migrate: &Proto::mod_Message::WasmMigrateContract<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::wasm_message::{ExecuteMsg, WasmMigrateContractMessage};
+
+        let msg = WasmMigrateContractMessage {
+            sender: Address::from_str(&migrate.sender_address)?,
+            contract: Address::from_str(&migrate.contract_address)?,
+            code_id: migrate.code_id,
+            msg: ExecuteMsg::String(migrate.migrate_msg.to_string()),
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn wasm_update_admin_msg_from_proto(
+        _coin: &dyn CoinContext,
+        update: &Proto::mod_Message::WasmUpdateAdmin<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::wasm_message::WasmUpdateAdminMessage;
+
+        let msg = WasmUpdateAdminMessage {
+            sender: Address::from_str(&update.sender_address)?,
+            new_admin: Address::from_str(&update.new_admin_address)?,
+            contract: Address::from_str(&update.contract_address)?,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn wasm_clear_admin_msg_from_proto(
+        _coin: &dyn CoinContext,
+        clear: &Proto::mod_Message::WasmClearAdmin<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::wasm_message::WasmClearAdminMessage;
+
+        let msg = WasmClearAdminMessage {
+            sender: Address::from_str(&clear.sender_address)?,
+            contract: Address::from_str(&clear.contract_address)?,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn wasm_store_code_msg_from_proto(
+        _coin: &dyn CoinContext,
+        store: &Proto::mod_Message::WasmStoreCode<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::wasm_message::WasmStoreCodeMessage;
+
+        let sender = Address::from_str(&store.sender_address)?;
+        let msg = WasmStoreCodeMessage::new(sender, store.wasm_byte_code.to_vec())?;
+        Ok(msg.into_boxed())
+    }
+
     pub fn thorchain_send_msg_from_proto(
         _coin: &dyn CoinContext,
         send: &Proto::mod_Message::THORChainSend<'_>,
@@ -575,27 +914,292 @@ where
         Ok(msg.into_boxed())
     }
 
+    pub fn auth_exec_msg_from_proto(
+        coin: &dyn CoinContext,
+        exec: &Proto::mod_Message::AuthExec<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_auth_message::AuthExecMessage;
+
+        if exec.msgs.is_empty() {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+
+        let msgs = exec
+            .msgs
+            .iter()
+            .map(|msg| Self::tx_message(coin, msg))
+            .collect::<SigningResult<_>>()?;
+
+        let msg = AuthExecMessage {
+            grantee: Address::from_str(&exec.grantee)?,
+            msgs,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn fee_grant_msg_from_proto(
+        _coin: &dyn CoinContext,
+        grant: &Proto::mod_Message::FeeGrant<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_feegrant_message::{
+            FeeAllowance, GrantAllowanceMessage, PeriodicAllowance,
+        };
+        use Proto::mod_Message::mod_FeeGrant::OneOfallowance as ProtoAllowance;
+
+        let allowance = match grant.allowance {
+            ProtoAllowance::basic(ref basic) => {
+                FeeAllowance::Basic(Self::basic_allowance_from_proto(basic)?)
+            },
+            ProtoAllowance::periodic(ref periodic) => {
+                let basic = periodic
+                    .basic
+                    .as_ref()
+                    .ok_or(SigningError(SigningErrorType::Error_invalid_params))?;
+                if periodic.period <= 0 {
+                    return Err(SigningError(SigningErrorType::Error_invalid_params));
+                }
+                let period_spend_limit = periodic
+                    .period_spend_limit
+                    .iter()
+                    .map(Self::coin_from_proto)
+                    .collect::<SigningResult<_>>()?;
+
+                FeeAllowance::Periodic(PeriodicAllowance {
+                    basic: Self::basic_allowance_from_proto(basic)?,
+                    period_secs: periodic.period,
+                    period_spend_limit,
+                })
+            },
+            ProtoAllowance::None => {
+                return Err(SigningError(SigningErrorType::Error_invalid_params))
+            },
+        };
+
+        let msg = GrantAllowanceMessage {
+            granter: Address::from_str(&grant.granter)?,
+            grantee: Address::from_str(&grant.grantee)?,
+            allowance,
+            allowed_messages: grant
+                .allowed_messages
+                .iter()
+                .map(|msg_type| msg_type.to_string())
+                .collect(),
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn fee_revoke_msg_from_proto(
+        _coin: &dyn CoinContext,
+        revoke: &Proto::mod_Message::FeeRevoke<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_feegrant_message::RevokeAllowanceMessage;
+
+        let msg = RevokeAllowanceMessage {
+            granter: Address::from_str(&revoke.granter)?,
+            grantee: Address::from_str(&revoke.grantee)?,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    fn basic_allowance_from_proto(
+        basic: &Proto::mod_Message::BasicAllowance<'_>,
+    ) -> SigningResult<BasicAllowance> {
+        let spend_limit = basic
+            .spend_limit
+            .iter()
+            .map(Self::coin_from_proto)
+            .collect::<SigningResult<_>>()?;
+        Ok(BasicAllowance {
+            spend_limit,
+            expiration_secs: (basic.expiration != 0).then_some(basic.expiration),
+        })
+    }
+
     pub fn vote_msg_from_proto(
         _coin: &dyn CoinContext,
         vote: &Proto::mod_Message::MsgVote<'_>,
     ) -> SigningResult<CosmosMessageBox> {
-        use crate::transaction::message::cosmos_gov_message::{VoteMessage, VoteOption};
+        use crate::transaction::message::cosmos_gov_message::VoteMessage;
+
+        let version = Self::gov_version_from_proto(vote.version);
+        Self::check_gov_metadata(version, &vote.metadata)?;
+
+        let msg = VoteMessage {
+            version,
+            proposal_id: vote.proposal_id,
+            voter: Address::from_str(&vote.voter)?,
+            option: Self::vote_option_from_proto(vote.option),
+            metadata: vote.metadata.to_string(),
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn vote_weighted_msg_from_proto(
+        _coin: &dyn CoinContext,
+        vote: &Proto::mod_Message::MsgVoteWeighted<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_gov_message::{
+            VoteWeight, VoteWeightedMessage, WeightedVoteOption,
+        };
+
+        let version = Self::gov_version_from_proto(vote.version);
+        Self::check_gov_metadata(version, &vote.metadata)?;
+
+        let options = vote
+            .options
+            .iter()
+            .map(|option| {
+                Ok(WeightedVoteOption {
+                    option: Self::vote_option_from_proto(option.option),
+                    weight: VoteWeight::from_str(&option.weight)?,
+                })
+            })
+            .collect::<SigningResult<Vec<_>>>()?;
+
+        // Weights of the options must sum up to 1.
+        let total_weight = options.iter().try_fold(0_u64, |total, option| {
+            total.checked_add(option.weight.atomics())
+        });
+        if total_weight != Some(VoteWeight::ONE) {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+
+        let msg = VoteWeightedMessage {
+            version,
+            proposal_id: vote.proposal_id,
+            voter: Address::from_str(&vote.voter)?,
+            options,
+            metadata: vote.metadata.to_string(),
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn deposit_msg_from_proto(
+        _coin: &dyn CoinContext,
+        deposit: &Proto::mod_Message::MsgDeposit<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_gov_message::DepositMessage;
+
+        let amount = deposit
+            .amounts
+            .iter()
+            .map(Self::coin_from_proto)
+            .collect::<SigningResult<_>>()?;
+
+        let msg = DepositMessage {
+            version: Self::gov_version_from_proto(deposit.version),
+            proposal_id: deposit.proposal_id,
+            depositor: Address::from_str(&deposit.depositor)?,
+            amount,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn submit_proposal_msg_from_proto(
+        _coin: &dyn CoinContext,
+        proposal: &Proto::mod_Message::MsgSubmitProposal<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_generic_message::RawAnyMessage;
+        use crate::transaction::message::cosmos_gov_message::{
+            SubmitProposalMessage, TextProposal,
+        };
+        use crate::transaction::message::JsonMessage;
+
+        let version = Self::gov_version_from_proto(proposal.version);
+        Self::check_gov_metadata(version, &proposal.metadata)?;
+
+        let mut messages = proposal
+            .messages
+            .iter()
+            .map(|any| {
+                let json = match any.json {
+                    Some(ref raw) => Some(JsonMessage {
+                        msg_type: raw.type_pb.to_string(),
+                        value: serde_json::from_str(&raw.value)?,
+                    }),
+                    None => None,
+                };
+                let msg = RawAnyMessage {
+                    proto: google::protobuf::Any {
+                        type_url: any.type_url.to_string(),
+                        value: any.value.to_vec(),
+                    },
+                    json,
+                };
+                Ok(msg.into_boxed())
+            })
+            .collect::<SigningResult<Vec<_>>>()?;
+
+        let (title, summary) = match version {
+            GovVersion::V1Beta1 => {
+                if proposal.expedited {
+                    return Err(SigningError(SigningErrorType::Error_invalid_params));
+                }
+                // `v1beta1` proposals have exactly one content, which is a text proposal by default.
+                match (messages.len(), proposal.title.is_empty()) {
+                    (0, false) => {
+                        let content = TextProposal {
+                            description: proposal.summary.to_string(),
+                            title: proposal.title.to_string(),
+                        };
+                        messages.push(content.into_boxed());
+                    },
+                    (1, true) if proposal.summary.is_empty() => (),
+                    _ => return Err(SigningError(SigningErrorType::Error_invalid_params)),
+                }
+                (String::default(), String::default())
+            },
+            GovVersion::V1 => (proposal.title.to_string(), proposal.summary.to_string()),
+        };
+
+        let initial_deposit = proposal
+            .initial_deposit
+            .iter()
+            .map(Self::coin_from_proto)
+            .collect::<SigningResult<_>>()?;
+
+        let msg = SubmitProposalMessage {
+            version,
+            messages,
+            initial_deposit,
+            proposer: Address::from_str(&proposal.proposer)?,
+            metadata: proposal.metadata.to_string(),
+            title,
+            summary,
+            expedited: proposal.expedited,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    fn gov_version_from_proto(version: Proto::mod_Message::GovVersion) -> GovVersion {
+        use Proto::mod_Message::GovVersion as ProtoGovVersion;
+
+        match version {
+            ProtoGovVersion::V1Beta1 => GovVersion::V1Beta1,
+            ProtoGovVersion::V1 => GovVersion::V1,
+        }
+    }
+
+    fn vote_option_from_proto(option: Proto::mod_Message::VoteOption) -> VoteOption {
         use Proto::mod_Message::VoteOption as ProtoVoteOption;
 
-        let option = match vote.option {
+        match option {
             ProtoVoteOption::_UNSPECIFIED => VoteOption::Unspecified,
             ProtoVoteOption::YES => VoteOption::Yes,
             ProtoVoteOption::ABSTAIN => VoteOption::Abstain,
             ProtoVoteOption::NO => VoteOption::No,
             ProtoVoteOption::NO_WITH_VETO => VoteOption::NoWithVeto,
-        };
+        }
+    }
 
-        let msg = VoteMessage {
-            proposal_id: vote.proposal_id,
-            voter: Address::from_str(&vote.voter)?,
-            option,
-        };
-        Ok(msg.into_boxed())
+    /// `v1beta1` messages don't have metadata.
+    fn check_gov_metadata(version: GovVersion, metadata: &str) -> SigningResult<()> {
+        match version {
+            GovVersion::V1Beta1 if !metadata.is_empty() => {
+                Err(SigningError(SigningErrorType::Error_invalid_params))
+            },
+            _ => Ok(()),
+        }
     }
 
     pub fn stride_stake_msg_from_proto(
@@ -627,6 +1231,160 @@ where
         Ok(msg.into_boxed())
     }
 
+    pub fn osmosis_swap_exact_amount_in_msg_from_proto(
+        _coin: &dyn CoinContext,
+        swap: &Proto::mod_Message::MsgOsmosisSwapExactAmountIn<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::osmosis_message::{
+            OsmosisSwapExactAmountInMessage, SwapAmountInRoute,
+        };
+
+        if swap.routes.is_empty() {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+        let routes = swap
+            .routes
+            .iter()
+            .map(|route| SwapAmountInRoute {
+                pool_id: route.pool_id,
+                token_out_denom: route.token_out_denom.to_string(),
+            })
+            .collect();
+        let token_in = swap
+            .token_in
+            .as_ref()
+            .ok_or(SigningError(SigningErrorType::Error_invalid_params))?;
+
+        let msg = OsmosisSwapExactAmountInMessage {
+            sender: Address::from_str(&swap.sender)?,
+            routes,
+            token_in: Self::coin_from_proto(token_in)?,
+            token_out_min_amount: U256::from_str(&swap.token_out_min_amount)?,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn osmosis_swap_exact_amount_out_msg_from_proto(
+        _coin: &dyn CoinContext,
+        swap: &Proto::mod_Message::MsgOsmosisSwapExactAmountOut<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::osmosis_message::{
+            OsmosisSwapExactAmountOutMessage, SwapAmountOutRoute,
+        };
+
+        if swap.routes.is_empty() {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+        let routes = swap
+            .routes
+            .iter()
+            .map(|route| SwapAmountOutRoute {
+                pool_id: route.pool_id,
+                token_in_denom: route.token_in_denom.to_string(),
+            })
+            .collect();
+        let token_out = swap
+            .token_out
+            .as_ref()
+            .ok_or(SigningError(SigningErrorType::Error_invalid_params))?;
+
+        let msg = OsmosisSwapExactAmountOutMessage {
+            sender: Address::from_str(&swap.sender)?,
+            routes,
+            token_in_max_amount: U256::from_str(&swap.token_in_max_amount)?,
+            token_out: Self::coin_from_proto(token_out)?,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn osmosis_create_position_msg_from_proto(
+        _coin: &dyn CoinContext,
+        create: &Proto::mod_Message::MsgOsmosisCreatePosition<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::osmosis_message::OsmosisCreatePositionMessage;
+
+        // A position is either single sided or straddles the current tick.
+        let tokens_provided_len = create.tokens_provided.len();
+        if tokens_provided_len == 0 || tokens_provided_len > 2 {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+        if create.lower_tick >= create.upper_tick {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+        let tokens_provided = create
+            .tokens_provided
+            .iter()
+            .map(Self::coin_from_proto)
+            .collect::<SigningResult<_>>()?;
+
+        let msg = OsmosisCreatePositionMessage {
+            pool_id: create.pool_id,
+            sender: Address::from_str(&create.sender)?,
+            lower_tick: create.lower_tick,
+            upper_tick: create.upper_tick,
+            tokens_provided,
+            token_min_amount0: Self::optional_amount_from_proto(&create.token_min_amount0)?,
+            token_min_amount1: Self::optional_amount_from_proto(&create.token_min_amount1)?,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn osmosis_add_to_position_msg_from_proto(
+        _coin: &dyn CoinContext,
+        add: &Proto::mod_Message::MsgOsmosisAddToPosition<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::osmosis_message::OsmosisAddToPositionMessage;
+
+        let msg = OsmosisAddToPositionMessage {
+            position_id: add.position_id,
+            sender: Address::from_str(&add.sender)?,
+            amount0: U256::from_str(&add.amount0)?,
+            amount1: U256::from_str(&add.amount1)?,
+            token_min_amount0: Self::optional_amount_from_proto(&add.token_min_amount0)?,
+            token_min_amount1: Self::optional_amount_from_proto(&add.token_min_amount1)?,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn osmosis_withdraw_position_msg_from_proto(
+        _coin: &dyn CoinContext,
+        withdraw: &Proto::mod_Message::MsgOsmosisWithdrawPosition<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::osmosis_message::{Dec, OsmosisWithdrawPositionMessage};
+
+        let msg = OsmosisWithdrawPositionMessage {
+            position_id: withdraw.position_id,
+            sender: Address::from_str(&withdraw.sender)?,
+            liquidity_amount: Dec::from_str(&withdraw.liquidity_amount)?,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn osmosis_collect_spread_rewards_msg_from_proto(
+        _coin: &dyn CoinContext,
+        collect: &Proto::mod_Message::MsgOsmosisCollectSpreadRewards<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::osmosis_message::OsmosisCollectSpreadRewardsMessage;
+
+        if collect.position_ids.is_empty() {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+
+        let msg = OsmosisCollectSpreadRewardsMessage {
+            position_ids: collect.position_ids.clone(),
+            sender: Address::from_str(&collect.sender)?,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    /// Parses an amount that is zero if not specified.
+    fn optional_amount_from_proto(amount: &str) -> SigningResult<U256> {
+        if amount.is_empty() {
+            return Ok(U256::zero());
+        }
+        Ok(U256::from_str(amount)?)
+    }
+
     pub fn thorchain_deposit_msg_from_proto(
         _coin: &dyn CoinContext,
         deposit: &Proto::mod_Message::THORChainDeposit<'_>,
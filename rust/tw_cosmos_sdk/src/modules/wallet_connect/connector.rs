@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::modules::wallet_connect::types::{
+    AminoFee, AminoSignDoc, SignAminoRequest, SignDirectRequest,
+};
+use std::borrow::Cow;
+use std::str::FromStr;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
+use tw_coin_entry::modules::wallet_connector::WalletConnector;
+use tw_coin_entry::signing_output_error;
+use tw_encoding::base64;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+use tw_proto::WalletConnect::Proto::{
+    self as WCProto, mod_ParseRequestOutput::OneOfsigning_input_oneof as SigningInputEnum,
+};
+
+/// Parses `cosmos_signAmino` and `cosmos_signDirect` requests into a Cosmos `SigningInput`.
+/// The private key is not set and has to be completed before signing.
+pub struct CosmosWalletConnector;
+
+impl WalletConnector for CosmosWalletConnector {
+    fn parse_request(
+        &self,
+        coin: &dyn CoinContext,
+        request: WCProto::ParseRequestInput<'_>,
+    ) -> WCProto::ParseRequestOutput<'static> {
+        Self::parse_request_impl(coin, request)
+            .unwrap_or_else(|e| signing_output_error!(WCProto::ParseRequestOutput, e))
+    }
+}
+
+impl CosmosWalletConnector {
+    fn parse_request_impl(
+        coin: &dyn CoinContext,
+        request: WCProto::ParseRequestInput<'_>,
+    ) -> SigningResult<WCProto::ParseRequestOutput<'static>> {
+        let signing_input = match request.method {
+            WCProto::Method::CosmosSignAmino => Self::parse_sign_amino_request(coin, &request)?,
+            WCProto::Method::CosmosSignDirect => Self::parse_sign_direct_request(coin, &request)?,
+            _ => return Err(SigningError(SigningErrorType::Error_not_supported)),
+        };
+
+        Ok(WCProto::ParseRequestOutput {
+            signing_input_oneof: SigningInputEnum::cosmos(signing_input),
+            ..WCProto::ParseRequestOutput::default()
+        })
+    }
+
+    /// Parses a `SigningInput` in the JSON mode from the given `signDoc`.
+    /// The messages are passed as is, see [`Proto::mod_Message::RawJSON`].
+    pub fn parse_sign_amino_request(
+        coin: &dyn CoinContext,
+        request: &WCProto::ParseRequestInput<'_>,
+    ) -> SigningResult<Proto::SigningInput<'static>> {
+        let amino_req: SignAminoRequest = serde_json::from_str(&request.payload)
+            .map_err(|_| SigningError(SigningErrorType::Error_input_parse))?;
+        Address::from_str_with_coin_and_prefix(coin, amino_req.signer_address, None)?;
+
+        let AminoSignDoc {
+            account_number,
+            chain_id,
+            fee,
+            memo,
+            msgs,
+            sequence,
+            timeout_height,
+        } = amino_req.sign_doc;
+
+        let messages = msgs
+            .into_iter()
+            .map(|msg| Proto::Message {
+                message_oneof: MessageEnum::raw_json_message(Proto::mod_Message::RawJSON {
+                    type_pb: msg.ty.into(),
+                    value: msg.value.to_string().into(),
+                }),
+            })
+            .collect();
+
+        let timeout_height = match timeout_height {
+            Some(height) => u64::from_str(&height)
+                .map_err(|_| SigningError(SigningErrorType::Error_input_parse))?,
+            None => 0,
+        };
+
+        Ok(Proto::SigningInput {
+            signing_mode: Proto::SigningMode::JSON,
+            account_number,
+            chain_id: chain_id.into(),
+            fee: Some(Self::fee_to_proto(fee)),
+            memo: memo.into(),
+            sequence,
+            messages,
+            timeout_height,
+            ..Proto::SigningInput::default()
+        })
+    }
+
+    /// Parses a `SigningInput` in the Protobuf mode with a single `SignDirect` message.
+    /// The fee and the sequence are already encoded in `authInfoBytes`.
+    pub fn parse_sign_direct_request(
+        coin: &dyn CoinContext,
+        request: &WCProto::ParseRequestInput<'_>,
+    ) -> SigningResult<Proto::SigningInput<'static>> {
+        let direct_req: SignDirectRequest = serde_json::from_str(&request.payload)
+            .map_err(|_| SigningError(SigningErrorType::Error_input_parse))?;
+        Address::from_str_with_coin_and_prefix(coin, direct_req.signer_address, None)?;
+
+        let sign_doc = direct_req.sign_doc;
+        let is_url = false;
+        let body_bytes = base64::decode(&sign_doc.body_bytes, is_url)?;
+        let auth_info_bytes = base64::decode(&sign_doc.auth_info_bytes, is_url)?;
+
+        let sign_direct = Proto::mod_Message::SignDirect {
+            body_bytes: body_bytes.into(),
+            auth_info_bytes: auth_info_bytes.into(),
+        };
+
+        Ok(Proto::SigningInput {
+            signing_mode: Proto::SigningMode::Protobuf,
+            account_number: sign_doc.account_number,
+            chain_id: sign_doc.chain_id.into(),
+            messages: vec![Proto::Message {
+                message_oneof: MessageEnum::sign_direct_message(sign_direct),
+            }],
+            ..Proto::SigningInput::default()
+        })
+    }
+
+    fn fee_to_proto(fee: AminoFee) -> Proto::Fee<'static> {
+        let amounts = fee
+            .amount
+            .into_iter()
+            .map(|coin| Proto::Amount {
+                denom: coin.denom.into(),
+                amount: coin.amount.into(),
+            })
+            .collect();
+
+        Proto::Fee {
+            amounts,
+            gas: fee.gas,
+            payer: fee.payer.map(Cow::from).unwrap_or_default(),
+            granter: fee.granter.map(Cow::from).unwrap_or_default(),
+        }
+    }
+}
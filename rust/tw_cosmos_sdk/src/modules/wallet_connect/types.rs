@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use serde::Deserialize;
+use serde_json::Value as Json;
+use tw_misc::serde::{as_string, Typed};
+
+/// `cosmos_signAmino` request payload.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignAminoRequest {
+    pub signer_address: String,
+    pub sign_doc: AminoSignDoc,
+}
+
+/// Amino JSON sign doc, `StdSignDoc`.
+#[derive(Deserialize)]
+pub struct AminoSignDoc {
+    #[serde(with = "as_string")]
+    pub account_number: u64,
+    pub chain_id: String,
+    pub fee: AminoFee,
+    #[serde(default)]
+    pub memo: String,
+    pub msgs: Vec<Typed<Json>>,
+    #[serde(with = "as_string")]
+    pub sequence: u64,
+    #[serde(default)]
+    pub timeout_height: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AminoFee {
+    pub amount: Vec<AminoCoin>,
+    #[serde(with = "as_string")]
+    pub gas: u64,
+    #[serde(default)]
+    pub payer: Option<String>,
+    #[serde(default)]
+    pub granter: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AminoCoin {
+    pub denom: String,
+    pub amount: String,
+}
+
+/// `cosmos_signDirect` request payload.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignDirectRequest {
+    pub signer_address: String,
+    pub sign_doc: DirectSignDoc,
+}
+
+/// Protobuf sign doc with Base64-encoded `bodyBytes` and `authInfoBytes`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectSignDoc {
+    pub chain_id: String,
+    #[serde(with = "as_string")]
+    pub account_number: u64,
+    pub body_bytes: String,
+    pub auth_info_bytes: String,
+}
@@ -7,6 +7,7 @@ use tw_keypair::{tw, KeyPairResult};
 use tw_memory::Data;
 use tw_proto::google;
 
+pub mod multisig;
 pub mod secp256k1;
 
 pub struct PublicKeyParams {
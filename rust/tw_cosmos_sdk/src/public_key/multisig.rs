@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::proto::cosmos;
+use crate::public_key::{CosmosPublicKey, JsonPublicKey, ProtobufPublicKey};
+use quick_protobuf::Writer;
+use tw_hash::sha2::sha256;
+use tw_hash::H160;
+use tw_keypair::{KeyPairError, KeyPairResult};
+use tw_memory::Data;
+use tw_proto::{google, to_any};
+
+pub const MULTISIG_JSON_PUBLIC_KEY_TYPE: &str = "tendermint/PubKeyMultisigThreshold";
+
+/// `LegacyAminoPubKey` - a multisig public key that requires `threshold` of `public_keys` to sign.
+pub struct MultisigPublicKey<PublicKey> {
+    threshold: u32,
+    public_keys: Vec<PublicKey>,
+}
+
+impl<PublicKey: CosmosPublicKey> MultisigPublicKey<PublicKey> {
+    pub fn new(threshold: u32, public_keys: Vec<PublicKey>) -> KeyPairResult<Self> {
+        if threshold == 0 || threshold as usize > public_keys.len() {
+            return Err(KeyPairError::InvalidPublicKey);
+        }
+        Ok(MultisigPublicKey {
+            threshold,
+            public_keys,
+        })
+    }
+
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    pub fn public_keys(&self) -> &[PublicKey] {
+        &self.public_keys
+    }
+
+    /// Returns the position of the given member public key.
+    pub fn position(&self, public_key: &PublicKey) -> Option<usize> {
+        let public_key = public_key.to_bytes();
+        self.public_keys
+            .iter()
+            .position(|member| member.to_bytes() == public_key)
+    }
+
+    /// Returns the Amino binary encoding of the public key.
+    /// It is used to derive the multisig address.
+    pub fn to_amino_bytes(&self) -> Data {
+        let mut amino = amino_prefix(MULTISIG_JSON_PUBLIC_KEY_TYPE);
+
+        {
+            let mut writer = Writer::new(&mut amino);
+            writer
+                .write_with_tag(8, |w| w.write_uint32(self.threshold))
+                .expect("Writing into a vector should never fail");
+
+            for public_key in self.public_keys.iter() {
+                let mut member = amino_prefix(&public_key.public_key_type());
+                Writer::new(&mut member)
+                    .write_bytes(&public_key.to_bytes())
+                    .expect("Writing into a vector should never fail");
+
+                writer
+                    .write_with_tag(18, |w| w.write_bytes(&member))
+                    .expect("Writing into a vector should never fail");
+            }
+        }
+
+        amino
+    }
+
+    /// Returns the address key hash: the first 20 bytes of `SHA256(amino_bytes)`.
+    pub fn address_hash(&self) -> Data {
+        let mut hash = sha256(&self.to_amino_bytes());
+        hash.truncate(H160::LEN);
+        hash
+    }
+}
+
+impl<PublicKey: ProtobufPublicKey> ProtobufPublicKey for MultisigPublicKey<PublicKey> {
+    fn to_proto(&self) -> google::protobuf::Any {
+        let proto = cosmos::crypto::multisig::LegacyAminoPubKey {
+            threshold: self.threshold,
+            public_keys: self.public_keys.iter().map(|key| key.to_proto()).collect(),
+        };
+        to_any(&proto)
+    }
+}
+
+impl<PublicKey> JsonPublicKey for MultisigPublicKey<PublicKey> {
+    fn public_key_type(&self) -> String {
+        MULTISIG_JSON_PUBLIC_KEY_TYPE.to_string()
+    }
+}
+
+/// Computes the prefix bytes of an Amino registered type.
+/// https://github.com/tendermint/go-amino/blob/v0.16.0/codec.go#L775-L792
+fn amino_prefix(name: &str) -> Data {
+    sha256(name.as_bytes())
+        .into_iter()
+        .skip_while(|byte| *byte == 0)
+        // Skip the disambiguation bytes.
+        .skip(3)
+        .skip_while(|byte| *byte == 0)
+        .take(4)
+        .collect()
+}
@@ -4,10 +4,15 @@
 
 use crate::address::CosmosAddress;
 use crate::proto::cosmos;
-use crate::transaction::message::{CosmosMessage, ProtobufMessage};
+use crate::transaction::message::{
+    message_to_json, CosmosMessage, CosmosMessageBox, JsonMessage, ProtobufMessage,
+};
+use serde::Serialize;
 use tw_coin_entry::error::SigningResult;
 use tw_proto::{google, to_any};
 
+const DEFAULT_JSON_EXEC_TYPE: &str = "cosmos-sdk/MsgExec";
+
 /// Supports Protobuf serialization only.
 pub struct AuthGrantMessage<Address: CosmosAddress> {
     pub granter: Address,
@@ -53,3 +58,46 @@ impl<Address: CosmosAddress> CosmosMessage for AuthRevokeMessage<Address> {
         Ok(to_any(&proto_msg))
     }
 }
+
+/// cosmos-sdk/MsgExec
+pub struct AuthExecMessage<Address: CosmosAddress> {
+    pub grantee: Address,
+    /// Messages to execute on behalf of their signers (granters).
+    pub msgs: Vec<CosmosMessageBox>,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for AuthExecMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let msgs = self
+            .msgs
+            .iter()
+            .map(|msg| msg.to_proto())
+            .collect::<SigningResult<_>>()?;
+
+        let proto_msg = cosmos::authz::v1beta1::MsgExec {
+            grantee: self.grantee.to_string(),
+            msgs,
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        #[derive(Serialize)]
+        struct AuthExecJson {
+            grantee: String,
+            msgs: Vec<JsonMessage>,
+        }
+
+        let msgs = self
+            .msgs
+            .iter()
+            .map(|msg| msg.to_json())
+            .collect::<SigningResult<_>>()?;
+
+        let json = AuthExecJson {
+            grantee: self.grantee.to_string(),
+            msgs,
+        };
+        message_to_json(DEFAULT_JSON_EXEC_TYPE, &json)
+    }
+}
@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::CosmosAddress;
+use crate::modules::serializer::protobuf_serializer::build_coin;
+use crate::modules::textual::value_renderer::render_timestamp;
+use crate::proto::cosmos::feegrant::v1beta1 as feegrant_proto;
+use crate::transaction::message::{message_to_json, CosmosMessage, JsonMessage, ProtobufMessage};
+use crate::transaction::Coin;
+use serde::Serialize;
+use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
+use tw_proto::{google, to_any};
+
+const DEFAULT_JSON_GRANT_ALLOWANCE_TYPE: &str = "cosmos-sdk/MsgGrantAllowance";
+const DEFAULT_JSON_REVOKE_ALLOWANCE_TYPE: &str = "cosmos-sdk/MsgRevokeAllowance";
+const JSON_BASIC_ALLOWANCE_TYPE: &str = "cosmos-sdk/BasicAllowance";
+const JSON_PERIODIC_ALLOWANCE_TYPE: &str = "cosmos-sdk/PeriodicAllowance";
+const JSON_ALLOWED_MSG_ALLOWANCE_TYPE: &str = "cosmos-sdk/AllowedMsgAllowance";
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+pub struct BasicAllowance {
+    /// Unlimited if empty.
+    pub spend_limit: Vec<Coin>,
+    pub expiration_secs: Option<i64>,
+}
+
+impl BasicAllowance {
+    fn to_proto(&self) -> feegrant_proto::BasicAllowance {
+        feegrant_proto::BasicAllowance {
+            spend_limit: self.spend_limit.iter().map(build_coin).collect(),
+            expiration: self
+                .expiration_secs
+                .map(|seconds| google::protobuf::Timestamp {
+                    seconds,
+                    ..google::protobuf::Timestamp::default()
+                }),
+        }
+    }
+
+    fn to_json(&self) -> BasicAllowanceJson {
+        BasicAllowanceJson {
+            expiration: self
+                .expiration_secs
+                .map(|seconds| render_timestamp(seconds, 0)),
+            spend_limit: self.spend_limit.clone(),
+        }
+    }
+}
+
+pub struct PeriodicAllowance {
+    pub basic: BasicAllowance,
+    pub period_secs: i64,
+    pub period_spend_limit: Vec<Coin>,
+}
+
+pub enum FeeAllowance {
+    Basic(BasicAllowance),
+    Periodic(PeriodicAllowance),
+}
+
+impl FeeAllowance {
+    fn to_proto(&self) -> google::protobuf::Any {
+        match self {
+            FeeAllowance::Basic(basic) => to_any(&basic.to_proto()),
+            FeeAllowance::Periodic(periodic) => {
+                let period_spend_limit: Vec<_> =
+                    periodic.period_spend_limit.iter().map(build_coin).collect();
+                let proto = feegrant_proto::PeriodicAllowance {
+                    basic: Some(periodic.basic.to_proto()),
+                    period: Some(google::protobuf::Duration {
+                        seconds: periodic.period_secs,
+                        ..google::protobuf::Duration::default()
+                    }),
+                    // The whole limit can be spent within the first period.
+                    period_can_spend: period_spend_limit.clone(),
+                    period_spend_limit,
+                    // Set by the chain when the allowance is granted.
+                    period_reset: None,
+                };
+                to_any(&proto)
+            },
+        }
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        match self {
+            FeeAllowance::Basic(basic) => {
+                message_to_json(JSON_BASIC_ALLOWANCE_TYPE, &basic.to_json())
+            },
+            FeeAllowance::Periodic(periodic) => {
+                // Amino JSON encodes durations in nanoseconds.
+                let period_nanos = periodic
+                    .period_secs
+                    .checked_mul(NANOS_PER_SECOND)
+                    .ok_or(SigningError(SigningErrorType::Error_invalid_params))?;
+                let json = PeriodicAllowanceJson {
+                    basic: periodic.basic.to_json(),
+                    period: period_nanos.to_string(),
+                    period_can_spend: periodic.period_spend_limit.clone(),
+                    period_spend_limit: periodic.period_spend_limit.clone(),
+                };
+                message_to_json(JSON_PERIODIC_ALLOWANCE_TYPE, &json)
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BasicAllowanceJson {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expiration: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    spend_limit: Vec<Coin>,
+}
+
+#[derive(Serialize)]
+struct PeriodicAllowanceJson {
+    basic: BasicAllowanceJson,
+    period: String,
+    period_can_spend: Vec<Coin>,
+    period_spend_limit: Vec<Coin>,
+}
+
+#[derive(Serialize)]
+struct AllowedMsgAllowanceJson {
+    allowance: JsonMessage,
+    allowed_messages: Vec<String>,
+}
+
+/// cosmos-sdk/MsgGrantAllowance
+pub struct GrantAllowanceMessage<Address: CosmosAddress> {
+    pub granter: Address,
+    pub grantee: Address,
+    pub allowance: FeeAllowance,
+    /// If not empty, the allowance can only be used to pay for the given message types.
+    pub allowed_messages: Vec<String>,
+}
+
+impl<Address: CosmosAddress> GrantAllowanceMessage<Address> {
+    fn allowance_to_proto(&self) -> google::protobuf::Any {
+        let allowance = self.allowance.to_proto();
+        if self.allowed_messages.is_empty() {
+            return allowance;
+        }
+
+        to_any(&feegrant_proto::AllowedMsgAllowance {
+            allowance: Some(allowance),
+            allowed_messages: self.allowed_messages.clone(),
+        })
+    }
+
+    fn allowance_to_json(&self) -> SigningResult<JsonMessage> {
+        let allowance = self.allowance.to_json()?;
+        if self.allowed_messages.is_empty() {
+            return Ok(allowance);
+        }
+
+        let json = AllowedMsgAllowanceJson {
+            allowance,
+            allowed_messages: self.allowed_messages.clone(),
+        };
+        message_to_json(JSON_ALLOWED_MSG_ALLOWANCE_TYPE, &json)
+    }
+}
+
+impl<Address: CosmosAddress> CosmosMessage for GrantAllowanceMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = feegrant_proto::MsgGrantAllowance {
+            granter: self.granter.to_string(),
+            grantee: self.grantee.to_string(),
+            allowance: Some(self.allowance_to_proto()),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        #[derive(Serialize)]
+        struct GrantAllowanceJson {
+            allowance: JsonMessage,
+            grantee: String,
+            granter: String,
+        }
+
+        let json = GrantAllowanceJson {
+            allowance: self.allowance_to_json()?,
+            grantee: self.grantee.to_string(),
+            granter: self.granter.to_string(),
+        };
+        message_to_json(DEFAULT_JSON_GRANT_ALLOWANCE_TYPE, &json)
+    }
+}
+
+/// cosmos-sdk/MsgRevokeAllowance
+#[derive(Serialize)]
+pub struct RevokeAllowanceMessage<Address: CosmosAddress> {
+    pub grantee: Address,
+    pub granter: Address,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for RevokeAllowanceMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = feegrant_proto::MsgRevokeAllowance {
+            granter: self.granter.to_string(),
+            grantee: self.grantee.to_string(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        message_to_json(DEFAULT_JSON_REVOKE_ALLOWANCE_TYPE, self)
+    }
+}
@@ -2,9 +2,9 @@
 //
 // Copyright © 2017 Trust Wallet.
 
-use crate::transaction::message::{CosmosMessage, JsonMessage};
+use crate::transaction::message::{CosmosMessage, JsonMessage, ProtobufMessage};
 use serde_json::Value as Json;
-use tw_coin_entry::error::SigningResult;
+use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
 
 /// Any raw JSON message.
 /// Supports JSON serialization only.
@@ -21,3 +21,22 @@ impl CosmosMessage for JsonRawMessage {
         })
     }
 }
+
+/// Any raw message packed into `google.protobuf.Any`.
+/// Supports JSON serialization only if the Amino JSON representation is provided.
+pub struct RawAnyMessage {
+    pub proto: ProtobufMessage,
+    pub json: Option<JsonMessage>,
+}
+
+impl CosmosMessage for RawAnyMessage {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        Ok(self.proto.clone())
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        self.json
+            .clone()
+            .ok_or(SigningError(SigningErrorType::Error_not_supported))
+    }
+}
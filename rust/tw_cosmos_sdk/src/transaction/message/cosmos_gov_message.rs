@@ -3,11 +3,40 @@
 // Copyright © 2017 Trust Wallet.
 
 use crate::address::CosmosAddress;
+use crate::modules::serializer::protobuf_serializer::build_coin;
 use crate::proto::cosmos;
-use crate::transaction::message::{CosmosMessage, ProtobufMessage};
-use tw_coin_entry::error::SigningResult;
+use crate::transaction::message::{
+    message_to_json, CosmosMessage, CosmosMessageBox, JsonMessage, ProtobufMessage,
+};
+use crate::transaction::Coin;
+use serde::{Serialize, Serializer};
+use std::str::FromStr;
+use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
 use tw_proto::to_any;
 
+const DEFAULT_JSON_VOTE_TYPE: &str = "cosmos-sdk/MsgVote";
+const DEFAULT_JSON_VOTE_WEIGHTED_TYPE: &str = "cosmos-sdk/MsgVoteWeighted";
+const DEFAULT_JSON_DEPOSIT_TYPE: &str = "cosmos-sdk/MsgDeposit";
+const DEFAULT_JSON_SUBMIT_PROPOSAL_TYPE: &str = "cosmos-sdk/MsgSubmitProposal";
+const DEFAULT_JSON_V1_VOTE_TYPE: &str = "cosmos-sdk/v1/MsgVote";
+const DEFAULT_JSON_V1_VOTE_WEIGHTED_TYPE: &str = "cosmos-sdk/v1/MsgVoteWeighted";
+const DEFAULT_JSON_V1_DEPOSIT_TYPE: &str = "cosmos-sdk/v1/MsgDeposit";
+const DEFAULT_JSON_V1_SUBMIT_PROPOSAL_TYPE: &str = "cosmos-sdk/v1/MsgSubmitProposal";
+const DEFAULT_JSON_TEXT_PROPOSAL_TYPE: &str = "cosmos-sdk/TextProposal";
+
+/// The number of decimals of `cosmos.Dec`.
+const DEC_PRECISION: usize = 18;
+
+/// Version of the `x/gov` module messages.
+#[derive(Clone, Copy)]
+pub enum GovVersion {
+    /// `cosmos.gov.v1beta1`
+    V1Beta1,
+    /// `cosmos.gov.v1`, since cosmos-sdk 0.46.
+    V1,
+}
+
+#[derive(Clone, Copy)]
 pub enum VoteOption {
     Unspecified,
     Yes,
@@ -16,29 +45,430 @@ pub enum VoteOption {
     NoWithVeto,
 }
 
+impl VoteOption {
+    fn to_v1beta1_proto(self) -> cosmos::gov::v1beta1::VoteOption {
+        use cosmos::gov::v1beta1::VoteOption as ProtoVoteOption;
+
+        match self {
+            VoteOption::Unspecified => ProtoVoteOption::VOTE_OPTION_UNSPECIFIED,
+            VoteOption::Yes => ProtoVoteOption::VOTE_OPTION_YES,
+            VoteOption::Abstain => ProtoVoteOption::VOTE_OPTION_ABSTAIN,
+            VoteOption::No => ProtoVoteOption::VOTE_OPTION_NO,
+            VoteOption::NoWithVeto => ProtoVoteOption::VOTE_OPTION_NO_WITH_VETO,
+        }
+    }
+
+    fn to_v1_proto(self) -> cosmos::gov::v1::VoteOption {
+        use cosmos::gov::v1::VoteOption as ProtoVoteOption;
+
+        match self {
+            VoteOption::Unspecified => ProtoVoteOption::VOTE_OPTION_UNSPECIFIED,
+            VoteOption::Yes => ProtoVoteOption::VOTE_OPTION_YES,
+            VoteOption::Abstain => ProtoVoteOption::VOTE_OPTION_ABSTAIN,
+            VoteOption::No => ProtoVoteOption::VOTE_OPTION_NO,
+            VoteOption::NoWithVeto => ProtoVoteOption::VOTE_OPTION_NO_WITH_VETO,
+        }
+    }
+}
+
+/// Amino JSON encodes the vote option as a number.
+impl Serialize for VoteOption {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i32(self.to_v1beta1_proto() as i32)
+    }
+}
+
+/// A vote weight in the range `(0, 1]`, stored as `cosmos.Dec` atomics, i.e. multiplied by 10^18.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VoteWeight(u64);
+
+impl VoteWeight {
+    pub const ONE: u64 = 1_000_000_000_000_000_000;
+
+    pub fn atomics(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the decimal string with 18 decimals, e.g. `0.500000000000000000`.
+    pub fn to_dec_string(self) -> String {
+        format!(
+            "{}.{:0width$}",
+            self.0 / Self::ONE,
+            self.0 % Self::ONE,
+            width = DEC_PRECISION
+        )
+    }
+}
+
+impl FromStr for VoteWeight {
+    type Err = SigningError;
+
+    /// Parses a decimal string, e.g. `0.5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SigningError(SigningErrorType::Error_invalid_params);
+
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let is_digits = |part: &str| part.chars().all(|ch| ch.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !is_digits(integer)
+            || !is_digits(fraction)
+            || fraction.len() > DEC_PRECISION
+        {
+            return Err(invalid());
+        }
+
+        let integer: u64 = if integer.is_empty() {
+            0
+        } else {
+            integer.parse().map_err(|_| invalid())?
+        };
+        let fraction: u64 = format!("{fraction:0<width$}", width = DEC_PRECISION)
+            .parse()
+            .map_err(|_| invalid())?;
+
+        let atomics = integer
+            .checked_mul(Self::ONE)
+            .and_then(|integer| integer.checked_add(fraction))
+            .ok_or_else(invalid)?;
+        if atomics == 0 || atomics > Self::ONE {
+            return Err(invalid());
+        }
+        Ok(VoteWeight(atomics))
+    }
+}
+
+#[derive(Serialize)]
+struct WeightedVoteOptionJson {
+    option: VoteOption,
+    weight: String,
+}
+
+pub struct WeightedVoteOption {
+    pub option: VoteOption,
+    pub weight: VoteWeight,
+}
+
+impl WeightedVoteOption {
+    fn to_json(&self) -> WeightedVoteOptionJson {
+        WeightedVoteOptionJson {
+            option: self.option,
+            weight: self.weight.to_dec_string(),
+        }
+    }
+}
+
+/// cosmos-sdk/MsgVote
 pub struct VoteMessage<Address: CosmosAddress> {
+    pub version: GovVersion,
     pub proposal_id: u64,
     pub voter: Address,
     pub option: VoteOption,
+    /// Supported by [`GovVersion::V1`] only.
+    pub metadata: String,
 }
 
 impl<Address: CosmosAddress> CosmosMessage for VoteMessage<Address> {
     fn to_proto(&self) -> SigningResult<ProtobufMessage> {
-        use cosmos::gov::v1beta1::VoteOption as ProtoVoteOption;
+        match self.version {
+            GovVersion::V1Beta1 => {
+                let proto_msg = cosmos::gov::v1beta1::MsgVote {
+                    proposal_id: self.proposal_id,
+                    voter: self.voter.to_string(),
+                    option: self.option.to_v1beta1_proto(),
+                };
+                Ok(to_any(&proto_msg))
+            },
+            GovVersion::V1 => {
+                let proto_msg = cosmos::gov::v1::MsgVote {
+                    proposal_id: self.proposal_id,
+                    voter: self.voter.to_string(),
+                    option: self.option.to_v1_proto(),
+                    metadata: self.metadata.clone(),
+                };
+                Ok(to_any(&proto_msg))
+            },
+        }
+    }
 
-        let option = match self.option {
-            VoteOption::Unspecified => ProtoVoteOption::VOTE_OPTION_UNSPECIFIED,
-            VoteOption::Yes => ProtoVoteOption::VOTE_OPTION_YES,
-            VoteOption::Abstain => ProtoVoteOption::VOTE_OPTION_ABSTAIN,
-            VoteOption::No => ProtoVoteOption::VOTE_OPTION_NO,
-            VoteOption::NoWithVeto => ProtoVoteOption::VOTE_OPTION_NO_WITH_VETO,
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        #[derive(Serialize)]
+        struct VoteJson {
+            #[serde(skip_serializing_if = "String::is_empty")]
+            metadata: String,
+            option: VoteOption,
+            proposal_id: String,
+            voter: String,
+        }
+
+        let json = VoteJson {
+            metadata: self.metadata.clone(),
+            option: self.option,
+            proposal_id: self.proposal_id.to_string(),
+            voter: self.voter.to_string(),
+        };
+        let msg_type = match self.version {
+            GovVersion::V1Beta1 => DEFAULT_JSON_VOTE_TYPE,
+            GovVersion::V1 => DEFAULT_JSON_V1_VOTE_TYPE,
         };
+        message_to_json(msg_type, &json)
+    }
+}
+
+/// cosmos-sdk/MsgVoteWeighted
+pub struct VoteWeightedMessage<Address: CosmosAddress> {
+    pub version: GovVersion,
+    pub proposal_id: u64,
+    pub voter: Address,
+    pub options: Vec<WeightedVoteOption>,
+    /// Supported by [`GovVersion::V1`] only.
+    pub metadata: String,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for VoteWeightedMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        match self.version {
+            GovVersion::V1Beta1 => {
+                let options = self
+                    .options
+                    .iter()
+                    .map(|option| cosmos::gov::v1beta1::WeightedVoteOption {
+                        option: option.option.to_v1beta1_proto(),
+                        // `v1beta1` encodes `cosmos.Dec` as its atomics.
+                        weight: option.weight.atomics().to_string(),
+                    })
+                    .collect();
+                let proto_msg = cosmos::gov::v1beta1::MsgVoteWeighted {
+                    proposal_id: self.proposal_id,
+                    voter: self.voter.to_string(),
+                    options,
+                };
+                Ok(to_any(&proto_msg))
+            },
+            GovVersion::V1 => {
+                let options = self
+                    .options
+                    .iter()
+                    .map(|option| cosmos::gov::v1::WeightedVoteOption {
+                        option: option.option.to_v1_proto(),
+                        weight: option.weight.to_dec_string(),
+                    })
+                    .collect();
+                let proto_msg = cosmos::gov::v1::MsgVoteWeighted {
+                    proposal_id: self.proposal_id,
+                    voter: self.voter.to_string(),
+                    options,
+                    metadata: self.metadata.clone(),
+                };
+                Ok(to_any(&proto_msg))
+            },
+        }
+    }
 
-        let proto_msg = cosmos::gov::v1beta1::MsgVote {
-            proposal_id: self.proposal_id,
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        #[derive(Serialize)]
+        struct VoteWeightedJson {
+            #[serde(skip_serializing_if = "String::is_empty")]
+            metadata: String,
+            options: Vec<WeightedVoteOptionJson>,
+            proposal_id: String,
+            voter: String,
+        }
+
+        let json = VoteWeightedJson {
+            metadata: self.metadata.clone(),
+            options: self
+                .options
+                .iter()
+                .map(WeightedVoteOption::to_json)
+                .collect(),
+            proposal_id: self.proposal_id.to_string(),
             voter: self.voter.to_string(),
-            option,
+        };
+        let msg_type = match self.version {
+            GovVersion::V1Beta1 => DEFAULT_JSON_VOTE_WEIGHTED_TYPE,
+            GovVersion::V1 => DEFAULT_JSON_V1_VOTE_WEIGHTED_TYPE,
+        };
+        message_to_json(msg_type, &json)
+    }
+}
+
+/// cosmos-sdk/MsgDeposit
+pub struct DepositMessage<Address: CosmosAddress> {
+    pub version: GovVersion,
+    pub proposal_id: u64,
+    pub depositor: Address,
+    pub amount: Vec<Coin>,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for DepositMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let amount = self.amount.iter().map(build_coin).collect();
+        match self.version {
+            GovVersion::V1Beta1 => Ok(to_any(&cosmos::gov::v1beta1::MsgDeposit {
+                proposal_id: self.proposal_id,
+                depositor: self.depositor.to_string(),
+                amount,
+            })),
+            GovVersion::V1 => Ok(to_any(&cosmos::gov::v1::MsgDeposit {
+                proposal_id: self.proposal_id,
+                depositor: self.depositor.to_string(),
+                amount,
+            })),
+        }
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        #[derive(Serialize)]
+        struct DepositJson {
+            amount: Vec<Coin>,
+            depositor: String,
+            proposal_id: String,
+        }
+
+        let json = DepositJson {
+            amount: self.amount.clone(),
+            depositor: self.depositor.to_string(),
+            proposal_id: self.proposal_id.to_string(),
+        };
+        let msg_type = match self.version {
+            GovVersion::V1Beta1 => DEFAULT_JSON_DEPOSIT_TYPE,
+            GovVersion::V1 => DEFAULT_JSON_V1_DEPOSIT_TYPE,
+        };
+        message_to_json(msg_type, &json)
+    }
+}
+
+/// cosmos-sdk/TextProposal
+/// The content of a [`GovVersion::V1Beta1`] text proposal.
+#[derive(Serialize)]
+pub struct TextProposal {
+    pub description: String,
+    pub title: String,
+}
+
+impl CosmosMessage for TextProposal {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = cosmos::gov::v1beta1::TextProposal {
+            title: self.title.clone(),
+            description: self.description.clone(),
         };
         Ok(to_any(&proto_msg))
     }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        message_to_json(DEFAULT_JSON_TEXT_PROPOSAL_TYPE, self)
+    }
+}
+
+/// cosmos-sdk/MsgSubmitProposal
+pub struct SubmitProposalMessage<Address: CosmosAddress> {
+    pub version: GovVersion,
+    /// [`GovVersion::V1`]: messages to be executed if the proposal passes.
+    /// [`GovVersion::V1Beta1`]: exactly one proposal content.
+    pub messages: Vec<CosmosMessageBox>,
+    pub initial_deposit: Vec<Coin>,
+    pub proposer: Address,
+    /// The following fields are supported by [`GovVersion::V1`] only.
+    pub metadata: String,
+    pub title: String,
+    pub summary: String,
+    pub expedited: bool,
+}
+
+impl<Address: CosmosAddress> SubmitProposalMessage<Address> {
+    fn content(&self) -> SigningResult<&CosmosMessageBox> {
+        match self.messages.as_slice() {
+            [content] => Ok(content),
+            _ => Err(SigningError(SigningErrorType::Error_invalid_params)),
+        }
+    }
+}
+
+impl<Address: CosmosAddress> CosmosMessage for SubmitProposalMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let initial_deposit = self.initial_deposit.iter().map(build_coin).collect();
+        match self.version {
+            GovVersion::V1Beta1 => {
+                let proto_msg = cosmos::gov::v1beta1::MsgSubmitProposal {
+                    content: Some(self.content()?.to_proto()?),
+                    initial_deposit,
+                    proposer: self.proposer.to_string(),
+                };
+                Ok(to_any(&proto_msg))
+            },
+            GovVersion::V1 => {
+                let messages = self
+                    .messages
+                    .iter()
+                    .map(|msg| msg.to_proto())
+                    .collect::<SigningResult<_>>()?;
+                let proto_msg = cosmos::gov::v1::MsgSubmitProposal {
+                    messages,
+                    initial_deposit,
+                    proposer: self.proposer.to_string(),
+                    metadata: self.metadata.clone(),
+                    title: self.title.clone(),
+                    summary: self.summary.clone(),
+                    expedited: self.expedited,
+                };
+                Ok(to_any(&proto_msg))
+            },
+        }
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        #[derive(Serialize)]
+        struct SubmitProposalJson {
+            content: JsonMessage,
+            initial_deposit: Vec<Coin>,
+            proposer: String,
+        }
+
+        #[derive(Serialize)]
+        struct SubmitProposalV1Json {
+            #[serde(skip_serializing_if = "std::ops::Not::not")]
+            expedited: bool,
+            initial_deposit: Vec<Coin>,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            messages: Vec<JsonMessage>,
+            #[serde(skip_serializing_if = "String::is_empty")]
+            metadata: String,
+            proposer: String,
+            #[serde(skip_serializing_if = "String::is_empty")]
+            summary: String,
+            #[serde(skip_serializing_if = "String::is_empty")]
+            title: String,
+        }
+
+        match self.version {
+            GovVersion::V1Beta1 => {
+                let json = SubmitProposalJson {
+                    content: self.content()?.to_json()?,
+                    initial_deposit: self.initial_deposit.clone(),
+                    proposer: self.proposer.to_string(),
+                };
+                message_to_json(DEFAULT_JSON_SUBMIT_PROPOSAL_TYPE, &json)
+            },
+            GovVersion::V1 => {
+                let messages = self
+                    .messages
+                    .iter()
+                    .map(|msg| msg.to_json())
+                    .collect::<SigningResult<_>>()?;
+                let json = SubmitProposalV1Json {
+                    expedited: self.expedited,
+                    initial_deposit: self.initial_deposit.clone(),
+                    messages,
+                    metadata: self.metadata.clone(),
+                    proposer: self.proposer.to_string(),
+                    summary: self.summary.clone(),
+                    title: self.title.clone(),
+                };
+                message_to_json(DEFAULT_JSON_V1_SUBMIT_PROPOSAL_TYPE, &json)
+            },
+        }
+    }
 }
@@ -7,9 +7,14 @@ use crate::modules::serializer::protobuf_serializer::build_coin;
 use crate::proto::ibc;
 use crate::transaction::message::{CosmosMessage, ProtobufMessage};
 use crate::transaction::Coin;
-use tw_coin_entry::error::SigningResult;
+use serde::Serialize;
+use serde_json::{json, Value as Json};
+use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
 use tw_proto::to_any;
 
+const DEFAULT_TRANSFER_PORT: &str = "transfer";
+
+#[derive(Default)]
 pub struct Height {
     pub revision_number: u64,
     pub revision_height: u64,
@@ -28,6 +33,8 @@ pub struct TransferTokensMessage<Address: CosmosAddress> {
     pub timeout_height: Height,
     // Timeout timestamp (in nanoseconds) relative to the current block timestamp.  Either timeout height or timestamp should be set.
     pub timeout_timestamp: u64,
+    /// ICS-20 packet memo, e.g. a packet-forward-middleware or IBC-hooks JSON.
+    pub memo: String,
 }
 
 impl<Address: CosmosAddress> CosmosMessage for TransferTokensMessage<Address> {
@@ -45,7 +52,80 @@ impl<Address: CosmosAddress> CosmosMessage for TransferTokensMessage<Address> {
             receiver: self.receiver.to_string(),
             timeout_height: Some(height),
             timeout_timestamp: self.timeout_timestamp,
+            memo: self.memo.clone(),
         };
         Ok(to_any(&proto_msg))
     }
 }
+
+/// A packet-forward-middleware hop.
+/// https://github.com/cosmos/ibc-apps/tree/main/middleware/packet-forward-middleware
+#[derive(Clone, Serialize)]
+pub struct ForwardHop {
+    pub receiver: String,
+    pub port: String,
+    pub channel: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+impl ForwardHop {
+    pub fn new(receiver: String, channel: String) -> ForwardHop {
+        ForwardHop {
+            receiver,
+            port: DEFAULT_TRANSFER_PORT.to_string(),
+            channel,
+            timeout: None,
+            retries: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ForwardMetadata {
+    #[serde(flatten)]
+    hop: ForwardHop,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<Json>,
+}
+
+/// Builds a multi-hop packet-forward-middleware memo:
+/// `{"forward": {"receiver", "port", "channel", "next": {"forward": {...}}}}`.
+#[derive(Default)]
+pub struct ForwardMemoBuilder {
+    hops: Vec<ForwardHop>,
+    next: Option<Json>,
+}
+
+impl ForwardMemoBuilder {
+    /// Appends a hop. The hops are forwarded in the order they were added.
+    pub fn hop(mut self, hop: ForwardHop) -> Self {
+        self.hops.push(hop);
+        self
+    }
+
+    /// Sets a memo to be passed to the final destination, e.g. an IBC-hooks payload.
+    pub fn next(mut self, next: Json) -> Self {
+        self.next = Some(next);
+        self
+    }
+
+    pub fn build(self) -> SigningResult<Json> {
+        if self.hops.is_empty() {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+        if matches!(self.next, Some(ref next) if !next.is_object()) {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+
+        let mut next = self.next;
+        for hop in self.hops.into_iter().rev() {
+            let forward = serde_json::to_value(ForwardMetadata { hop, next })
+                .map_err(|_| SigningError(SigningErrorType::Error_internal))?;
+            next = Some(json!({ "forward": forward }));
+        }
+        Ok(next.expect("There should be at least one hop"))
+    }
+}
@@ -10,10 +10,12 @@ use tw_proto::google;
 
 pub mod cosmos_auth_message;
 pub mod cosmos_bank_message;
+pub mod cosmos_feegrant_message;
 pub mod cosmos_generic_message;
 pub mod cosmos_gov_message;
 pub mod cosmos_staking_message;
 pub mod ibc_message;
+pub mod osmosis_message;
 pub mod stride_message;
 pub mod terra_wasm_message;
 pub mod thorchain_message;
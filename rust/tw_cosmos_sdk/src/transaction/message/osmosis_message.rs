@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::CosmosAddress;
+use crate::modules::serializer::protobuf_serializer::build_coin;
+use crate::proto::osmosis;
+use crate::transaction::message::{message_to_json, CosmosMessage, JsonMessage, ProtobufMessage};
+use crate::transaction::Coin;
+use serde::{Serialize, Serializer};
+use std::str::FromStr;
+use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
+use tw_misc::serde::as_string;
+use tw_number::U256;
+use tw_proto::to_any;
+
+const DEFAULT_JSON_SWAP_EXACT_AMOUNT_IN_TYPE: &str = "osmosis/poolmanager/swap-exact-amount-in";
+const DEFAULT_JSON_SWAP_EXACT_AMOUNT_OUT_TYPE: &str = "osmosis/poolmanager/swap-exact-amount-out";
+const DEFAULT_JSON_CREATE_POSITION_TYPE: &str = "osmosis/cl-create-position";
+const DEFAULT_JSON_ADD_TO_POSITION_TYPE: &str = "osmosis/cl-add-to-position";
+const DEFAULT_JSON_WITHDRAW_POSITION_TYPE: &str = "osmosis/cl-withdraw-position";
+const DEFAULT_JSON_COLLECT_SPREAD_REWARDS_TYPE: &str = "osmosis/cl-col-sp-rewards";
+
+/// The number of decimals of `cosmos.Dec`.
+const DEC_PRECISION: usize = 18;
+
+/// A non-negative `cosmossdk.io/math.LegacyDec` of an arbitrary size.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dec {
+    integer: String,
+    /// Always contains [`DEC_PRECISION`] digits.
+    fraction: String,
+}
+
+impl Dec {
+    /// Returns the atomics, i.e. the value multiplied by 10^18, as it's encoded in Protobuf.
+    pub fn to_atomics_string(&self) -> String {
+        let atomics = format!("{}{}", self.integer, self.fraction);
+        match atomics.trim_start_matches('0') {
+            "" => "0".to_string(),
+            atomics => atomics.to_string(),
+        }
+    }
+
+    /// Returns the decimal string with 18 decimals, e.g. `1000.500000000000000000`, as it's encoded in Amino JSON.
+    pub fn to_dec_string(&self) -> String {
+        format!("{}.{}", self.integer, self.fraction)
+    }
+}
+
+impl FromStr for Dec {
+    type Err = SigningError;
+
+    /// Parses a decimal string, e.g. `1000.5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let is_digits = |part: &str| part.chars().all(|ch| ch.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !is_digits(integer)
+            || !is_digits(fraction)
+            || fraction.len() > DEC_PRECISION
+        {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+
+        let integer = match integer.trim_start_matches('0') {
+            "" => "0",
+            integer => integer,
+        };
+        Ok(Dec {
+            integer: integer.to_string(),
+            fraction: format!("{fraction:0<width$}", width = DEC_PRECISION),
+        })
+    }
+}
+
+impl Serialize for Dec {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_dec_string().serialize(serializer)
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct SwapAmountInRoute {
+    #[serde(serialize_with = "as_string::serialize")]
+    pub pool_id: u64,
+    pub token_out_denom: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SwapAmountOutRoute {
+    #[serde(serialize_with = "as_string::serialize")]
+    pub pool_id: u64,
+    pub token_in_denom: String,
+}
+
+/// osmosis/poolmanager/swap-exact-amount-in
+#[derive(Serialize)]
+pub struct OsmosisSwapExactAmountInMessage<Address: CosmosAddress> {
+    pub sender: Address,
+    pub routes: Vec<SwapAmountInRoute>,
+    pub token_in: Coin,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub token_out_min_amount: U256,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for OsmosisSwapExactAmountInMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let routes = self
+            .routes
+            .iter()
+            .map(|route| osmosis::poolmanager::v1beta1::SwapAmountInRoute {
+                pool_id: route.pool_id,
+                token_out_denom: route.token_out_denom.clone(),
+            })
+            .collect();
+
+        let proto_msg = osmosis::poolmanager::v1beta1::MsgSwapExactAmountIn {
+            sender: self.sender.to_string(),
+            routes,
+            token_in: Some(build_coin(&self.token_in)),
+            token_out_min_amount: self.token_out_min_amount.to_string(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        message_to_json(DEFAULT_JSON_SWAP_EXACT_AMOUNT_IN_TYPE, self)
+    }
+}
+
+/// osmosis/poolmanager/swap-exact-amount-out
+#[derive(Serialize)]
+pub struct OsmosisSwapExactAmountOutMessage<Address: CosmosAddress> {
+    pub sender: Address,
+    pub routes: Vec<SwapAmountOutRoute>,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub token_in_max_amount: U256,
+    pub token_out: Coin,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for OsmosisSwapExactAmountOutMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let routes = self
+            .routes
+            .iter()
+            .map(|route| osmosis::poolmanager::v1beta1::SwapAmountOutRoute {
+                pool_id: route.pool_id,
+                token_in_denom: route.token_in_denom.clone(),
+            })
+            .collect();
+
+        let proto_msg = osmosis::poolmanager::v1beta1::MsgSwapExactAmountOut {
+            sender: self.sender.to_string(),
+            routes,
+            token_in_max_amount: self.token_in_max_amount.to_string(),
+            token_out: Some(build_coin(&self.token_out)),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        message_to_json(DEFAULT_JSON_SWAP_EXACT_AMOUNT_OUT_TYPE, self)
+    }
+}
+
+/// osmosis/cl-create-position
+#[derive(Serialize)]
+pub struct OsmosisCreatePositionMessage<Address: CosmosAddress> {
+    #[serde(serialize_with = "as_string::serialize")]
+    pub pool_id: u64,
+    pub sender: Address,
+    #[serde(serialize_with = "as_string::serialize")]
+    pub lower_tick: i64,
+    #[serde(serialize_with = "as_string::serialize")]
+    pub upper_tick: i64,
+    pub tokens_provided: Vec<Coin>,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub token_min_amount0: U256,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub token_min_amount1: U256,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for OsmosisCreatePositionMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = osmosis::concentratedliquidity::v1beta1::MsgCreatePosition {
+            pool_id: self.pool_id,
+            sender: self.sender.to_string(),
+            lower_tick: self.lower_tick,
+            upper_tick: self.upper_tick,
+            tokens_provided: self.tokens_provided.iter().map(build_coin).collect(),
+            token_min_amount0: self.token_min_amount0.to_string(),
+            token_min_amount1: self.token_min_amount1.to_string(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        message_to_json(DEFAULT_JSON_CREATE_POSITION_TYPE, self)
+    }
+}
+
+/// osmosis/cl-add-to-position
+#[derive(Serialize)]
+pub struct OsmosisAddToPositionMessage<Address: CosmosAddress> {
+    #[serde(serialize_with = "as_string::serialize")]
+    pub position_id: u64,
+    pub sender: Address,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub amount0: U256,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub amount1: U256,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub token_min_amount0: U256,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub token_min_amount1: U256,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for OsmosisAddToPositionMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = osmosis::concentratedliquidity::v1beta1::MsgAddToPosition {
+            position_id: self.position_id,
+            sender: self.sender.to_string(),
+            amount0: self.amount0.to_string(),
+            amount1: self.amount1.to_string(),
+            token_min_amount0: self.token_min_amount0.to_string(),
+            token_min_amount1: self.token_min_amount1.to_string(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        message_to_json(DEFAULT_JSON_ADD_TO_POSITION_TYPE, self)
+    }
+}
+
+/// osmosis/cl-withdraw-position
+#[derive(Serialize)]
+pub struct OsmosisWithdrawPositionMessage<Address: CosmosAddress> {
+    #[serde(serialize_with = "as_string::serialize")]
+    pub position_id: u64,
+    pub sender: Address,
+    pub liquidity_amount: Dec,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for OsmosisWithdrawPositionMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = osmosis::concentratedliquidity::v1beta1::MsgWithdrawPosition {
+            position_id: self.position_id,
+            sender: self.sender.to_string(),
+            liquidity_amount: self.liquidity_amount.to_atomics_string(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        message_to_json(DEFAULT_JSON_WITHDRAW_POSITION_TYPE, self)
+    }
+}
+
+/// osmosis/cl-col-sp-rewards
+pub struct OsmosisCollectSpreadRewardsMessage<Address: CosmosAddress> {
+    pub position_ids: Vec<u64>,
+    pub sender: Address,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for OsmosisCollectSpreadRewardsMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = osmosis::concentratedliquidity::v1beta1::MsgCollectSpreadRewards {
+            position_ids: self.position_ids.clone(),
+            sender: self.sender.to_string(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        #[derive(Serialize)]
+        struct CollectSpreadRewardsJson<'a, Address: CosmosAddress> {
+            position_ids: Vec<String>,
+            sender: &'a Address,
+        }
+
+        let json = CollectSpreadRewardsJson {
+            position_ids: self.position_ids.iter().map(u64::to_string).collect(),
+            sender: &self.sender,
+        };
+        message_to_json(DEFAULT_JSON_COLLECT_SPREAD_REWARDS_TYPE, &json)
+    }
+}
@@ -2,19 +2,33 @@
 //
 // Copyright © 2017 Trust Wallet.
 
-use crate::address::CosmosAddress;
+use crate::address::{Address as Bech32Address, CosmosAddress};
 use crate::modules::serializer::protobuf_serializer::build_coin;
 use crate::proto::cosmwasm;
-use crate::transaction::message::{CosmosMessage, JsonMessage, ProtobufMessage};
+use crate::transaction::message::{message_to_json, CosmosMessage, JsonMessage, ProtobufMessage};
 use crate::transaction::Coin;
 use serde::Serialize;
 use serde_json::{json, Value as Json};
 use tw_coin_entry::error::{SigningError, SigningErrorType, SigningResult};
+use tw_encoding::base64;
+use tw_hash::sha2::sha256;
 use tw_memory::Data;
 use tw_number::U256;
 use tw_proto::to_any;
 
 const DEFAULT_JSON_MSG_TYPE: &str = "wasm/MsgExecuteContract";
+const INSTANTIATE_JSON_MSG_TYPE: &str = "wasm/MsgInstantiateContract";
+const INSTANTIATE2_JSON_MSG_TYPE: &str = "wasm/MsgInstantiateContract2";
+const MIGRATE_JSON_MSG_TYPE: &str = "wasm/MsgMigrateContract";
+const UPDATE_ADMIN_JSON_MSG_TYPE: &str = "wasm/MsgUpdateAdmin";
+const CLEAR_ADMIN_JSON_MSG_TYPE: &str = "wasm/MsgClearAdmin";
+const STORE_CODE_JSON_MSG_TYPE: &str = "wasm/MsgStoreCode";
+
+const WASM_MODULE_NAME: &str = "wasm";
+const CHECKSUM_LEN: usize = 32;
+const MAX_SALT_LEN: usize = 64;
+const WASM_MAGIC: &[u8] = b"\0asm";
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
 
 #[derive(Clone, Serialize)]
 #[serde(untagged)]
@@ -101,3 +115,246 @@ pub enum WasmExecutePayload {
         msg: String,
     },
 }
+
+/// Salt of `MsgInstantiateContract2` used to make the contract address predictable.
+pub struct Instantiate2Salt {
+    pub salt: Data,
+    /// Whether the instantiate message is included into the predictable address.
+    pub fix_msg: bool,
+}
+
+impl Instantiate2Salt {
+    pub fn new(salt: Data, fix_msg: bool) -> SigningResult<Instantiate2Salt> {
+        check_salt(&salt)?;
+        Ok(Instantiate2Salt { salt, fix_msg })
+    }
+}
+
+pub struct WasmInstantiateContractMessage<Address: CosmosAddress> {
+    pub sender: Address,
+    pub admin: Option<Address>,
+    pub code_id: u64,
+    pub label: String,
+    pub msg: ExecuteMsg,
+    pub coins: Vec<Coin>,
+    /// If set, the message is encoded as `MsgInstantiateContract2`.
+    pub salt: Option<Instantiate2Salt>,
+}
+
+impl<Address: CosmosAddress> WasmInstantiateContractMessage<Address> {
+    fn admin_str(&self) -> String {
+        self.admin
+            .as_ref()
+            .map(|admin| admin.to_string())
+            .unwrap_or_default()
+    }
+}
+
+impl<Address: CosmosAddress> CosmosMessage for WasmInstantiateContractMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let funds = self.coins.iter().map(build_coin).collect();
+
+        let Some(ref salt) = self.salt else {
+            let proto_msg = cosmwasm::wasm::v1::MsgInstantiateContract {
+                sender: self.sender.to_string(),
+                admin: self.admin_str(),
+                code_id: self.code_id,
+                label: self.label.clone(),
+                msg: self.msg.to_bytes(),
+                funds,
+            };
+            return Ok(to_any(&proto_msg));
+        };
+
+        let proto_msg = cosmwasm::wasm::v1::MsgInstantiateContract2 {
+            sender: self.sender.to_string(),
+            admin: self.admin_str(),
+            code_id: self.code_id,
+            label: self.label.clone(),
+            msg: self.msg.to_bytes(),
+            funds,
+            salt: salt.salt.clone(),
+            fix_msg: salt.fix_msg,
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        let mut value = json!({
+            "code_id": self.code_id.to_string(),
+            "funds": self.coins,
+            "label": self.label,
+            "msg": self.msg.try_to_json(),
+            "sender": self.sender,
+        });
+        if let Some(ref admin) = self.admin {
+            value["admin"] = json!(admin);
+        }
+
+        let Some(ref salt) = self.salt else {
+            return Ok(JsonMessage {
+                msg_type: INSTANTIATE_JSON_MSG_TYPE.to_string(),
+                value,
+            });
+        };
+
+        if salt.fix_msg {
+            value["fix_msg"] = Json::Bool(true);
+        }
+        value["salt"] = Json::String(base64::encode(&salt.salt, false));
+        Ok(JsonMessage {
+            msg_type: INSTANTIATE2_JSON_MSG_TYPE.to_string(),
+            value,
+        })
+    }
+}
+
+pub struct WasmMigrateContractMessage<Address: CosmosAddress> {
+    pub sender: Address,
+    pub contract: Address,
+    pub code_id: u64,
+    pub msg: ExecuteMsg,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for WasmMigrateContractMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = cosmwasm::wasm::v1::MsgMigrateContract {
+            sender: self.sender.to_string(),
+            contract: self.contract.to_string(),
+            code_id: self.code_id,
+            msg: self.msg.to_bytes(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        let value = json!({
+            "code_id": self.code_id.to_string(),
+            "contract": self.contract,
+            "msg": self.msg.try_to_json(),
+            "sender": self.sender,
+        });
+        Ok(JsonMessage {
+            msg_type: MIGRATE_JSON_MSG_TYPE.to_string(),
+            value,
+        })
+    }
+}
+
+#[derive(Serialize)]
+pub struct WasmUpdateAdminMessage<Address: CosmosAddress> {
+    pub sender: Address,
+    pub new_admin: Address,
+    pub contract: Address,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for WasmUpdateAdminMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = cosmwasm::wasm::v1::MsgUpdateAdmin {
+            sender: self.sender.to_string(),
+            new_admin: self.new_admin.to_string(),
+            contract: self.contract.to_string(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        message_to_json(UPDATE_ADMIN_JSON_MSG_TYPE, self)
+    }
+}
+
+#[derive(Serialize)]
+pub struct WasmClearAdminMessage<Address: CosmosAddress> {
+    pub sender: Address,
+    pub contract: Address,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for WasmClearAdminMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = cosmwasm::wasm::v1::MsgClearAdmin {
+            sender: self.sender.to_string(),
+            contract: self.contract.to_string(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        message_to_json(CLEAR_ADMIN_JSON_MSG_TYPE, self)
+    }
+}
+
+pub struct WasmStoreCodeMessage<Address: CosmosAddress> {
+    pub sender: Address,
+    /// Either raw or gzip-compressed WASM byte code.
+    pub wasm_byte_code: Data,
+}
+
+impl<Address: CosmosAddress> WasmStoreCodeMessage<Address> {
+    pub fn new(sender: Address, wasm_byte_code: Data) -> SigningResult<Self> {
+        if !wasm_byte_code.starts_with(WASM_MAGIC) && !wasm_byte_code.starts_with(GZIP_MAGIC) {
+            return Err(SigningError(SigningErrorType::Error_invalid_params));
+        }
+        Ok(WasmStoreCodeMessage {
+            sender,
+            wasm_byte_code,
+        })
+    }
+}
+
+impl<Address: CosmosAddress> CosmosMessage for WasmStoreCodeMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = cosmwasm::wasm::v1::MsgStoreCode {
+            sender: self.sender.to_string(),
+            wasm_byte_code: self.wasm_byte_code.clone(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        let value = json!({
+            "sender": self.sender,
+            "wasm_byte_code": base64::encode(&self.wasm_byte_code, false),
+        });
+        Ok(JsonMessage {
+            msg_type: STORE_CODE_JSON_MSG_TYPE.to_string(),
+            value,
+        })
+    }
+}
+
+/// Predicts the address of a contract instantiated with `MsgInstantiateContract2`.
+/// `checksum` is SHA256 of the uncompressed WASM byte code,
+/// `msg` is the instantiate message if `fix_msg` is set, otherwise empty.
+///
+/// https://github.com/CosmWasm/wasmd/blob/main/x/wasm/keeper/addresses.go
+pub fn instantiate2_address(
+    checksum: &[u8],
+    creator: &Bech32Address,
+    salt: &[u8],
+    msg: &[u8],
+) -> SigningResult<Bech32Address> {
+    check_salt(salt)?;
+    if checksum.len() != CHECKSUM_LEN {
+        return Err(SigningError(SigningErrorType::Error_invalid_params));
+    }
+
+    // `address.Module(moduleName, key)` hashes `moduleName || 0x00 || key` with the `module` type.
+    let mut key = WASM_MODULE_NAME.as_bytes().to_vec();
+    key.push(0);
+    for field in [checksum, creator.key_hash(), salt, msg] {
+        key.extend_from_slice(&(field.len() as u64).to_be_bytes());
+        key.extend_from_slice(field);
+    }
+
+    let mut preimage = sha256(b"module");
+    preimage.extend_from_slice(&key);
+    let address = Bech32Address::new(creator.hrp().to_string(), sha256(&preimage))?;
+    Ok(address)
+}
+
+fn check_salt(salt: &[u8]) -> SigningResult<()> {
+    if salt.is_empty() || salt.len() > MAX_SALT_LEN {
+        return Err(SigningError(SigningErrorType::Error_invalid_params));
+    }
+    Ok(())
+}
@@ -4,6 +4,7 @@
 
 use crate::context::CosmosContext;
 use crate::private_key::SignatureData;
+use crate::public_key::multisig::MultisigPublicKey;
 use serde::Serialize;
 use tw_number::U256;
 
@@ -11,10 +12,12 @@ pub mod message;
 
 use message::CosmosMessageBox;
 
-/// At this moment, TW only supports the Direct signing mode.
+/// At this moment, TW only supports the Direct and Textual signing modes.
 #[derive(Clone, Copy)]
 pub enum SignMode {
     Direct,
+    /// SIGN_MODE_TEXTUAL, see [ADR-050](https://docs.cosmos.network/main/build/architecture/adr-050-sign-mode-textual).
+    Textual,
     Other(i32),
 }
 
@@ -39,6 +42,14 @@ pub struct SignerInfo<PublicKey> {
     pub sign_mode: SignMode,
 }
 
+pub struct MultisigSignerInfo<PublicKey> {
+    pub public_key: MultisigPublicKey<PublicKey>,
+    /// Positions of the members that sign the transaction, in ascending order.
+    pub signers: Vec<usize>,
+    pub sequence: u64,
+    pub sign_mode: SignMode,
+}
+
 pub struct TxBody {
     pub messages: Vec<CosmosMessageBox>,
     pub memo: String,
@@ -70,3 +81,30 @@ pub struct SignedTransaction<Context: CosmosContext> {
     pub tx_body: TxBody,
     pub signature: SignatureData,
 }
+
+pub struct UnsignedMultisigTransaction<Context: CosmosContext> {
+    pub signer: MultisigSignerInfo<Context::PublicKey>,
+    pub fee: Fee<Context::Address>,
+    pub chain_id: String,
+    pub account_number: u64,
+    pub tx_body: TxBody,
+}
+
+impl<Context: CosmosContext> UnsignedMultisigTransaction<Context> {
+    /// `signatures` must be ordered as [`MultisigSignerInfo::signers`].
+    pub fn into_signed(self, signatures: Vec<SignatureData>) -> SignedMultisigTransaction<Context> {
+        SignedMultisigTransaction {
+            signer: self.signer,
+            fee: self.fee,
+            tx_body: self.tx_body,
+            signatures,
+        }
+    }
+}
+
+pub struct SignedMultisigTransaction<Context: CosmosContext> {
+    pub signer: MultisigSignerInfo<Context::PublicKey>,
+    pub fee: Fee<Context::Address>,
+    pub tx_body: TxBody,
+    pub signatures: Vec<SignatureData>,
+}
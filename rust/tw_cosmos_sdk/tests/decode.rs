@@ -0,0 +1,259 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use serde_json::{json, Value as Json};
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::modules::decoder::tw_decoder::TWTransactionDecoder;
+use tw_cosmos_sdk::proto::cosmos::authz::v1beta1 as authz_proto;
+use tw_cosmos_sdk::proto::cosmos::base::v1beta1 as base_proto;
+use tw_cosmos_sdk::proto::cosmos::staking::v1beta1 as staking_proto;
+use tw_cosmos_sdk::proto::cosmos::tx::v1beta1 as tx_proto;
+use tw_cosmos_sdk::proto::cosmwasm::wasm::v1 as wasm_proto;
+use tw_cosmos_sdk::proto::types as thorchain_proto;
+use tw_encoding::base64;
+use tw_encoding::hex::DecodeHex;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_DecodingInput::EncodedType;
+use tw_proto::{google, serialize, to_any};
+
+const DELEGATOR: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+const GRANTEE: &str = "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573";
+const VALIDATOR: &str = "cosmosvaloper1gjtvly9lel6zskvwtvlg5vhwpu9c9waw7sxzwx";
+
+fn decode(
+    hrp: &str,
+    encoded_type: EncodedType,
+    encoded: Vec<u8>,
+) -> Proto::DecodingOutput<'static> {
+    let coin = TestCoinContext::default().with_hrp(hrp);
+    let input = Proto::DecodingInput {
+        encoded_type,
+        encoded: encoded.into(),
+    };
+    TWTransactionDecoder::decode(&coin, input)
+}
+
+fn decode_json(output: &Proto::DecodingOutput<'_>) -> Json {
+    assert_eq!(output.error, SigningError::OK, "{}", output.error_message);
+    serde_json::from_str(&output.json).unwrap()
+}
+
+fn make_body(messages: Vec<google::protobuf::Any>) -> tx_proto::TxBody {
+    tx_proto::TxBody {
+        messages,
+        memo: "Decoded".to_string(),
+        ..tx_proto::TxBody::default()
+    }
+}
+
+#[test]
+fn test_decode_tx_raw() {
+    // Successfully broadcasted https://www.mintscan.io/cosmos/txs/2EFA054B842B1641B131137B13360F95164C6C1D51BB4A4AC6DE8F75F504AA4C
+    let tx_bytes = base64::decode("ClQKUgobL2Nvc21vcy5nb3YudjFiZXRhMS5Nc2dWb3RlEjMITRItY29zbW9zMW1yeTQ3cGtnYTV0ZHN3dGx1eTBtOHRlc2xwYWxrZHEwN3Bzd3U0GAESZQpOCkYKHy9jb3Ntb3MuY3J5cHRvLnNlY3AyNTZrMS5QdWJLZXkSIwohAsv9teRyiTMiKU5gzwiD1D30MeEInSnstEep5tVQRarlEgQKAggBEhMKDQoFdWF0b20SBDI0MTgQkfsFGkA+Nb3NULc38quGC1x+8ZXry4w9mMX3IA7wUjFboTv7kVOwPlleIc8UqIsjVvKTUFnUuW8dlGQzNR1KkvbvZ1NA", false).unwrap();
+
+    let output = decode("cosmos", EncodedType::TxRaw, tx_bytes);
+    assert_eq!(
+        decode_json(&output),
+        json!({
+            "auth_info": {
+                "fee": {
+                    "amount": [{"amount": "2418", "denom": "uatom"}],
+                    "gas_limit": "97681",
+                    "granter": "",
+                    "payer": ""
+                },
+                "signer_infos": [{
+                    "mode_info": {"single": {"mode": "SIGN_MODE_DIRECT"}},
+                    "public_key": {
+                        "@type": "/cosmos.crypto.secp256k1.PubKey",
+                        "key": "Asv9teRyiTMiKU5gzwiD1D30MeEInSnstEep5tVQRarl"
+                    },
+                    "sequence": "0"
+                }],
+                "tip": null
+            },
+            "body": {
+                "extension_options": [],
+                "memo": "",
+                "messages": [{
+                    "@type": "/cosmos.gov.v1beta1.MsgVote",
+                    "option": "VOTE_OPTION_YES",
+                    "proposal_id": "77",
+                    "voter": "cosmos1mry47pkga5tdswtluy0m8teslpalkdq07pswu4"
+                }],
+                "non_critical_extension_options": [],
+                "timeout_height": "0"
+            },
+            "signatures": ["PjW9zVC3N/KrhgtcfvGV68uMPZjF9yAO8FIxW6E7+5FTsD5ZXiHPFKiLI1byk1BZ1LlvHZRkMzUdSpL272dTQA=="]
+        })
+    );
+
+    assert_eq!(output.messages.len(), 1);
+    let message = &output.messages[0];
+    assert_eq!(message.type_url, "/cosmos.gov.v1beta1.MsgVote");
+    let message_json: Json = serde_json::from_str(&message.json).unwrap();
+    assert_eq!(
+        message_json,
+        json!({
+            "option": "VOTE_OPTION_YES",
+            "proposal_id": "77",
+            "voter": "cosmos1mry47pkga5tdswtluy0m8teslpalkdq07pswu4"
+        })
+    );
+}
+
+#[test]
+fn test_decode_sign_doc() {
+    let delegate = staking_proto::MsgDelegate {
+        delegator_address: DELEGATOR.to_string(),
+        validator_address: VALIDATOR.to_string(),
+        amount: Some(base_proto::Coin {
+            denom: "uatom".to_string(),
+            amount: "1000".to_string(),
+        }),
+    };
+    let exec = authz_proto::MsgExec {
+        grantee: GRANTEE.to_string(),
+        msgs: vec![to_any(&delegate)],
+    };
+    let auth_info_bytes = "0a500a460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a210257286ec3f37d33557bbbaa000b27744ac9023aa9967cae75a181d1ff91fa9dc512040a020801180812110a0b0a046d756f6e120332303010c09a0c".decode_hex().unwrap();
+    let sign_doc = tx_proto::SignDoc {
+        body_bytes: serialize(&make_body(vec![to_any(&exec)])).unwrap(),
+        auth_info_bytes,
+        chain_id: "cosmoshub-4".to_string(),
+        account_number: 1037,
+    };
+
+    let output = decode(
+        "cosmos",
+        EncodedType::SignDoc,
+        serialize(&sign_doc).unwrap(),
+    );
+    let json = decode_json(&output);
+    assert_eq!(json["chain_id"], "cosmoshub-4");
+    assert_eq!(json["account_number"], "1037");
+    assert_eq!(json["body"]["memo"], "Decoded");
+    assert_eq!(
+        json["auth_info"]["fee"],
+        json!({
+            "amount": [{"amount": "200", "denom": "muon"}],
+            "gas_limit": "200000",
+            "granter": "",
+            "payer": ""
+        })
+    );
+    assert_eq!(json["auth_info"]["signer_infos"][0]["sequence"], "8");
+
+    let expected_exec = json!({
+        "grantee": GRANTEE,
+        "msgs": [{
+            "@type": "/cosmos.staking.v1beta1.MsgDelegate",
+            "amount": {"amount": "1000", "denom": "uatom"},
+            "delegator_address": DELEGATOR,
+            "validator_address": VALIDATOR
+        }]
+    });
+    let message = &output.messages[0];
+    assert_eq!(message.type_url, "/cosmos.authz.v1beta1.MsgExec");
+    assert_eq!(
+        serde_json::from_str::<Json>(&message.json).unwrap(),
+        expected_exec
+    );
+}
+
+#[test]
+fn test_decode_tx_body_unknown_message() {
+    let unknown = google::protobuf::Any {
+        type_url: "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn".to_string(),
+        value: vec![0x0a, 0x01, 0x01],
+    };
+    let execute = wasm_proto::MsgExecuteContract {
+        sender: DELEGATOR.to_string(),
+        contract: GRANTEE.to_string(),
+        msg: br#"{"claim":{}}"#.to_vec(),
+        funds: Vec::default(),
+    };
+    let body = make_body(vec![unknown, to_any(&execute)]);
+
+    let output = decode("cosmos", EncodedType::TxBody, serialize(&body).unwrap());
+    assert_eq!(
+        decode_json(&output)["messages"],
+        json!([
+            {
+                "@type": "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn",
+                "value": "CgEB"
+            },
+            {
+                "@type": "/cosmwasm.wasm.v1.MsgExecuteContract",
+                "contract": GRANTEE,
+                "funds": [],
+                "msg": {"claim": {}},
+                "sender": DELEGATOR
+            }
+        ])
+    );
+
+    // Unknown messages are returned as is.
+    let unknown = &output.messages[0];
+    assert_eq!(
+        unknown.type_url,
+        "/osmosis.gamm.v1beta1.MsgSwapExactAmountIn"
+    );
+    assert_eq!(unknown.value.as_ref(), [0x0a, 0x01, 0x01]);
+    assert!(unknown.json.is_empty());
+}
+
+#[test]
+fn test_decode_thorchain_addresses() {
+    let send = thorchain_proto::MsgSend {
+        from_address: (1..=20).collect(),
+        to_address: (21..=40).collect(),
+        amount: vec![base_proto::Coin {
+            denom: "rune".to_string(),
+            amount: "10000000".to_string(),
+        }],
+    };
+    let body = make_body(vec![to_any(&send)]);
+
+    let output = decode("thor", EncodedType::TxBody, serialize(&body).unwrap());
+    assert_eq!(
+        decode_json(&output)["messages"][0],
+        json!({
+            "@type": "/types.MsgSend",
+            "amount": [{"amount": "10000000", "denom": "rune"}],
+            "from_address": "thor1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5e949nr",
+            "to_address": "thor1z5tpwxqergd3c8g7ruszzg3rysjjvfegwqptm4"
+        })
+    );
+}
+
+#[test]
+fn test_decode_auth_info() {
+    let auth_info_bytes = "0a500a460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a210257286ec3f37d33557bbbaa000b27744ac9023aa9967cae75a181d1ff91fa9dc512040a020801180812110a0b0a046d756f6e120332303010c09a0c".decode_hex().unwrap();
+
+    let output = decode("cosmos", EncodedType::AuthInfo, auth_info_bytes);
+    let json = decode_json(&output);
+    assert_eq!(
+        json["signer_infos"][0]["public_key"]["@type"],
+        "/cosmos.crypto.secp256k1.PubKey"
+    );
+    assert_eq!(json["fee"]["gas_limit"], "200000");
+    assert!(output.messages.is_empty());
+}
+
+#[test]
+fn test_decode_invalid() {
+    // A truncated length-delimited field.
+    let output = decode("cosmos", EncodedType::TxRaw, vec![0x0a, 0x05, 0x01]);
+    assert_eq!(output.error, SigningError::Error_input_parse);
+
+    // A known message with invalid contents.
+    let body = make_body(vec![google::protobuf::Any {
+        type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+        value: vec![0x0a, 0x05, 0x01],
+    }]);
+    let output = decode("cosmos", EncodedType::TxBody, serialize(&body).unwrap());
+    assert_eq!(output.error, SigningError::Error_input_parse);
+}
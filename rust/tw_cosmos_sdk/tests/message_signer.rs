@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use std::borrow::Cow;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::modules::message_signer::MessageSigner;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::address::Address;
+use tw_cosmos_sdk::context::{CosmosContext, StandardCosmosContext};
+use tw_cosmos_sdk::modules::message_signer::tw_message_signer::TWMessageSigner;
+use tw_cosmos_sdk::private_key::secp256k1::Secp256PrivateKey;
+use tw_cosmos_sdk::public_key::secp256k1::Secp256PublicKey;
+use tw_cosmos_sdk::signature::secp256k1::Secp256k1Signature;
+use tw_encoding::base64;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_hash::hasher::Hasher;
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+
+const COSMOS_PRIVATE_KEY: &str = "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005";
+const COSMOS_ADDRESS: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+const COSMOS_PUBLIC_KEY: &str = "AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F";
+const COSMOS_SIGNATURE: &str =
+    "YEqPmiO3yR0/G1/3szKjsK/GCAzuHp1/m2L7t+4BDMFoBGkIrp+Ib685p8kjfMoCM+7sye9f8RtGNLmNUZHFbw==";
+
+const EVMOS_PRIVATE_KEY: &str = "727513ec3c54eb6fae24f2ff756bbc4c89b82945c6538bbd173613ae3de719d3";
+const EVMOS_ADDRESS: &str = "evmos1d0jkrsd09c7pule43y3ylrul43lwwcqa7vpy0g";
+const EVMOS_COMPRESSED_PUBLIC_KEY: &str = "AgiKwpGZh9knNoyyvireRM0O02FnRalpnK4mSz/Fp8Ng";
+const EVMOS_SIGNATURE: &str =
+    "URDg7ccaSNgJgMRrEbeXllQ5WMPiH7YBk5gVW4h/VoFzIe3FYCkoxg9CNXrEn2gMu3v816vWUNaBjyT7FDLQlQ==";
+
+const MESSAGE: &str = "Hello, Cosmos!";
+
+/// Mimics an `eth_secp256k1` chain like `NativeEvmos`.
+struct KeccakCosmosContext;
+
+impl CosmosContext for KeccakCosmosContext {
+    type Address = Address;
+    type PrivateKey = Secp256PrivateKey;
+    type PublicKey = Secp256PublicKey;
+    type Signature = Secp256k1Signature;
+
+    fn default_tx_hasher() -> Hasher {
+        Hasher::Keccak256
+    }
+}
+
+fn cosmos_coin() -> TestCoinContext {
+    TestCoinContext {
+        address_hasher: Some(Hasher::Sha256ripemd),
+        ..TestCoinContext::default()
+    }
+    .with_hrp("cosmos")
+    .with_public_key_type(PublicKeyType::Secp256k1)
+}
+
+fn evmos_coin() -> TestCoinContext {
+    TestCoinContext {
+        address_hasher: Some(Hasher::Keccak256),
+        ..TestCoinContext::default()
+    }
+    .with_hrp("evmos")
+    .with_public_key_type(PublicKeyType::Secp256k1Extended)
+}
+
+fn signing_input<'a>(private_key: &str, address: &'a str) -> Proto::MessageSigningInput<'a> {
+    Proto::MessageSigningInput {
+        private_key: private_key.decode_hex().unwrap().into(),
+        message: MESSAGE.into(),
+        address: address.into(),
+    }
+}
+
+fn verifying_input<'a>(
+    address: &'a str,
+    signature: &'a str,
+    public_key: &str,
+) -> Proto::MessageVerifyingInput<'a> {
+    Proto::MessageVerifyingInput {
+        message: MESSAGE.into(),
+        address: address.into(),
+        signature: signature.into(),
+        public_key: base64::decode(public_key, false).unwrap().into(),
+    }
+}
+
+fn verify<Context>(coin: &dyn CoinContext, input: Proto::MessageVerifyingInput<'_>) -> bool
+where
+    Context: CosmosContext<Address = Address>,
+{
+    TWMessageSigner::<Context>::default().verify_message(coin, input)
+}
+
+#[test]
+fn test_adr036_preimage_hashes() {
+    let coin = cosmos_coin();
+    let input = Proto::MessageSigningInput {
+        private_key: Cow::default(),
+        ..signing_input(COSMOS_PRIVATE_KEY, COSMOS_ADDRESS)
+    };
+
+    let output =
+        TWMessageSigner::<StandardCosmosContext>::default().message_preimage_hashes(&coin, input);
+    assert_eq!(output.error, SigningError::OK, "{}", output.error_message);
+    assert_eq!(
+        String::from_utf8(output.data.to_vec()).unwrap(),
+        r#"{"account_number":"0","chain_id":"","fee":{"amount":[],"gas":"0"},"memo":"","msgs":[{"type":"sign/MsgSignData","value":{"data":"SGVsbG8sIENvc21vcyE=","signer":"cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02"}}],"sequence":"0"}"#
+    );
+    assert_eq!(
+        output.data_hash.to_hex(),
+        "783fc490188f0872daaafc466d326cb5f47c0af0171351a5f26c6bb6318483f7"
+    );
+
+    // The signer address is required to build the sign doc.
+    let input = signing_input(COSMOS_PRIVATE_KEY, "");
+    let output =
+        TWMessageSigner::<StandardCosmosContext>::default().message_preimage_hashes(&coin, input);
+    assert_eq!(output.error, SigningError::Error_invalid_address);
+}
+
+#[test]
+fn test_adr036_sign_message() {
+    let coin = cosmos_coin();
+    let signer = TWMessageSigner::<StandardCosmosContext>::default();
+
+    // The signer address is derived from the private key if not specified.
+    for address in [COSMOS_ADDRESS, ""] {
+        let output = signer.sign_message(&coin, signing_input(COSMOS_PRIVATE_KEY, address));
+        assert_eq!(output.error, SigningError::OK, "{}", output.error_message);
+        assert_eq!(output.signature, COSMOS_SIGNATURE);
+        assert_eq!(base64::encode(&output.public_key, false), COSMOS_PUBLIC_KEY);
+        assert_eq!(
+            output.signature_json,
+            format!(
+                r#"{{"pub_key":{{"type":"tendermint/PubKeySecp256k1","value":"{COSMOS_PUBLIC_KEY}"}},"signature":"{COSMOS_SIGNATURE}"}}"#
+            )
+        );
+    }
+
+    let output = signer.sign_message(
+        &coin,
+        signing_input(
+            COSMOS_PRIVATE_KEY,
+            "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+        ),
+    );
+    assert_eq!(output.error, SigningError::Error_invalid_address);
+}
+
+#[test]
+fn test_adr036_verify_message() {
+    let coin = cosmos_coin();
+
+    let input = verifying_input(COSMOS_ADDRESS, COSMOS_SIGNATURE, COSMOS_PUBLIC_KEY);
+    assert!(verify::<StandardCosmosContext>(&coin, input));
+
+    let input = Proto::MessageVerifyingInput {
+        message: "Hello, Evmos!".into(),
+        ..verifying_input(COSMOS_ADDRESS, COSMOS_SIGNATURE, COSMOS_PUBLIC_KEY)
+    };
+    assert!(!verify::<StandardCosmosContext>(&coin, input));
+
+    // The public key does not belong to the address.
+    let input = verifying_input(
+        "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573",
+        COSMOS_SIGNATURE,
+        COSMOS_PUBLIC_KEY,
+    );
+    assert!(!verify::<StandardCosmosContext>(&coin, input));
+
+    // The sign doc is hashed with Keccak256 instead of SHA256.
+    let input = verifying_input(COSMOS_ADDRESS, COSMOS_SIGNATURE, COSMOS_PUBLIC_KEY);
+    assert!(!verify::<KeccakCosmosContext>(&coin, input));
+
+    let input = verifying_input(COSMOS_ADDRESS, "invalid", COSMOS_PUBLIC_KEY);
+    assert!(!verify::<StandardCosmosContext>(&coin, input));
+}
+
+#[test]
+fn test_adr036_eth_secp256k1() {
+    let coin = evmos_coin();
+    let signer = TWMessageSigner::<KeccakCosmosContext>::default();
+
+    let output = signer.sign_message(&coin, signing_input(EVMOS_PRIVATE_KEY, ""));
+    assert_eq!(output.error, SigningError::OK, "{}", output.error_message);
+    assert_eq!(output.signature, EVMOS_SIGNATURE);
+
+    // Both compressed and extended public keys are accepted.
+    let extended_public_key = base64::encode(&output.public_key, false);
+    for public_key in [EVMOS_COMPRESSED_PUBLIC_KEY, extended_public_key.as_str()] {
+        let input = verifying_input(EVMOS_ADDRESS, EVMOS_SIGNATURE, public_key);
+        assert!(verify::<KeccakCosmosContext>(&coin, input));
+    }
+}
@@ -41,6 +41,7 @@ fn make_fee(gas: u64, amount: Proto::Amount<'_>) -> Proto::Fee<'_> {
     Proto::Fee {
         amounts: vec![amount],
         gas,
+        ..Proto::Fee::default()
     }
 }
 
@@ -337,6 +338,7 @@ fn test_sign_vote() {
         proposal_id: 77,
         voter: "cosmos1mry47pkga5tdswtluy0m8teslpalkdq07pswu4".into(),
         option: Proto::mod_Message::VoteOption::YES,
+        ..Proto::mod_Message::MsgVote::default()
     };
     let input = Proto::SigningInput {
         account_number: 1366160,
@@ -351,18 +353,11 @@ fn test_sign_vote() {
     // Successfully broadcasted https://www.mintscan.io/cosmos/txs/2EFA054B842B1641B131137B13360F95164C6C1D51BB4A4AC6DE8F75F504AA4C
     test_sign_protobuf::<StandardCosmosContext>(TestInput {
             coin: &coin,
-            input: input.clone(),
+            input,
             tx: r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"ClQKUgobL2Nvc21vcy5nb3YudjFiZXRhMS5Nc2dWb3RlEjMITRItY29zbW9zMW1yeTQ3cGtnYTV0ZHN3dGx1eTBtOHRlc2xwYWxrZHEwN3Bzd3U0GAESZQpOCkYKHy9jb3Ntb3MuY3J5cHRvLnNlY3AyNTZrMS5QdWJLZXkSIwohAsv9teRyiTMiKU5gzwiD1D30MeEInSnstEep5tVQRarlEgQKAggBEhMKDQoFdWF0b20SBDI0MTgQkfsFGkA+Nb3NULc38quGC1x+8ZXry4w9mMX3IA7wUjFboTv7kVOwPlleIc8UqIsjVvKTUFnUuW8dlGQzNR1KkvbvZ1NA"}"#,
             signature: "3e35bdcd50b737f2ab860b5c7ef195ebcb8c3d98c5f7200ef052315ba13bfb9153b03e595e21cf14a88b2356f2935059d4b96f1d946433351d4a92f6ef675340",
             signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"Asv9teRyiTMiKU5gzwiD1D30MeEInSnstEep5tVQRarl"},"signature":"PjW9zVC3N/KrhgtcfvGV68uMPZjF9yAO8FIxW6E7+5FTsD5ZXiHPFKiLI1byk1BZ1LlvHZRkMzUdSpL272dTQA=="}]"#,
         });
-
-    // `MsgVote` doesn't support JSON serialization and signing.
-    test_sign_json_error::<StandardCosmosContext>(TestErrorInput {
-        coin: &coin,
-        input,
-        error: SigningError::Error_not_supported,
-    });
 }
 
 #[test]
@@ -375,6 +370,7 @@ fn test_vote_payload() {
         proposal_id: 123,
         voter: "cosmos1mry47pkga5tdswtluy0m8teslpalkdq07pswu4".into(),
         option: Proto::mod_Message::VoteOption::_UNSPECIFIED,
+        ..Proto::mod_Message::MsgVote::default()
     };
 
     let tests = [
@@ -0,0 +1,357 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use serde_json::{json, Value as Json};
+use std::borrow::Cow;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::context::StandardCosmosContext;
+use tw_cosmos_sdk::modules::signer::tw_signer::TWSigner;
+use tw_cosmos_sdk::proto::cosmos::authz::v1beta1 as authz_proto;
+use tw_cosmos_sdk::proto::cosmos::feegrant::v1beta1 as feegrant_proto;
+use tw_cosmos_sdk::proto::cosmos::staking::v1beta1 as staking_proto;
+use tw_cosmos_sdk::proto::cosmos::tx::v1beta1 as tx_proto;
+use tw_cosmos_sdk::test_utils::proto_utils::{make_amount, make_fee, make_message};
+use tw_encoding::base64;
+use tw_encoding::hex::DecodeHex;
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+use tw_proto::{deserialize, google};
+
+const GRANTER: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+const GRANTEE: &str = "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573";
+const VALIDATOR: &str = "cosmosvaloper1gjtvly9lel6zskvwtvlg5vhwpu9c9waw7sxzwx";
+
+fn account_1037_private_key() -> Cow<'static, [u8]> {
+    "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005"
+        .decode_hex()
+        .unwrap()
+        .into()
+}
+
+fn make_input(message: MessageEnum<'static>) -> Proto::SigningInput<'static> {
+    Proto::SigningInput {
+        account_number: 1037,
+        chain_id: "cosmoshub-4".into(),
+        sequence: 8,
+        fee: Some(make_fee(200000, make_amount("uatom", "5000"))),
+        private_key: account_1037_private_key(),
+        messages: vec![make_message(message)],
+        ..Proto::SigningInput::default()
+    }
+}
+
+fn sign(
+    signing_mode: Proto::SigningMode,
+    mut input: Proto::SigningInput<'_>,
+) -> Proto::SigningOutput<'static> {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    input.signing_mode = signing_mode;
+    TWSigner::<StandardCosmosContext>::sign(&coin, input)
+}
+
+/// Signs the input in the Protobuf mode and returns the decoded `TxRaw`.
+fn sign_protobuf(input: Proto::SigningInput<'_>) -> tx_proto::TxRaw {
+    let output = sign(Proto::SigningMode::Protobuf, input);
+    assert_eq!(output.error, SigningError::OK);
+
+    let broadcast: Json = serde_json::from_str(&output.serialized).unwrap();
+    let tx_bytes = base64::decode(broadcast["tx_bytes"].as_str().unwrap(), false).unwrap();
+    deserialize(&tx_bytes).unwrap()
+}
+
+/// Signs the input in the JSON mode and returns the broadcasted transaction.
+fn sign_json(input: Proto::SigningInput<'_>) -> Json {
+    let output = sign(Proto::SigningMode::JSON, input);
+    assert_eq!(output.error, SigningError::OK);
+
+    let broadcast: Json = serde_json::from_str(&output.json).unwrap();
+    broadcast["tx"].clone()
+}
+
+fn single_message(tx_raw: &tx_proto::TxRaw) -> google::protobuf::Any {
+    let tx_body: tx_proto::TxBody = deserialize(&tx_raw.body_bytes).unwrap();
+    assert_eq!(tx_body.messages.len(), 1);
+    tx_body.messages[0].clone()
+}
+
+fn restake_exec_message() -> MessageEnum<'static> {
+    let delegate = Proto::mod_Message::Delegate {
+        delegator_address: GRANTER.into(),
+        validator_address: VALIDATOR.into(),
+        amount: Some(make_amount("uatom", "1000")),
+        ..Proto::mod_Message::Delegate::default()
+    };
+    let withdraw = Proto::mod_Message::WithdrawDelegationReward {
+        delegator_address: GRANTER.into(),
+        validator_address: VALIDATOR.into(),
+        ..Proto::mod_Message::WithdrawDelegationReward::default()
+    };
+
+    MessageEnum::auth_exec(Proto::mod_Message::AuthExec {
+        grantee: GRANTEE.into(),
+        msgs: vec![
+            make_message(MessageEnum::withdraw_stake_reward_message(withdraw)),
+            make_message(MessageEnum::stake_message(delegate)),
+        ],
+    })
+}
+
+#[test]
+fn test_sign_auth_exec() {
+    let input = make_input(restake_exec_message());
+
+    let message = single_message(&sign_protobuf(input.clone()));
+    assert_eq!(message.type_url, "/cosmos.authz.v1beta1.MsgExec");
+
+    let exec: authz_proto::MsgExec = deserialize(&message.value).unwrap();
+    assert_eq!(exec.grantee, GRANTEE);
+    let msg_types: Vec<_> = exec.msgs.iter().map(|msg| msg.type_url.as_str()).collect();
+    assert_eq!(
+        msg_types,
+        [
+            "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward",
+            "/cosmos.staking.v1beta1.MsgDelegate"
+        ]
+    );
+    let delegate: staking_proto::MsgDelegate = deserialize(&exec.msgs[1].value).unwrap();
+    assert_eq!(delegate.delegator_address, GRANTER);
+    assert_eq!(delegate.amount.unwrap().amount, "1000");
+
+    let tx = sign_json(input);
+    assert_eq!(
+        tx["msg"][0],
+        json!({
+            "type": "cosmos-sdk/MsgExec",
+            "value": {
+                "grantee": GRANTEE,
+                "msgs": [
+                    {
+                        "type": "cosmos-sdk/MsgWithdrawDelegationReward",
+                        "value": {
+                            "delegator_address": GRANTER,
+                            "validator_address": VALIDATOR
+                        }
+                    },
+                    {
+                        "type": "cosmos-sdk/MsgDelegate",
+                        "value": {
+                            "amount": {"amount": "1000", "denom": "uatom"},
+                            "delegator_address": GRANTER,
+                            "validator_address": VALIDATOR
+                        }
+                    }
+                ]
+            }
+        })
+    );
+}
+
+#[test]
+fn test_sign_auth_exec_empty() {
+    let input = make_input(MessageEnum::auth_exec(Proto::mod_Message::AuthExec {
+        grantee: GRANTEE.into(),
+        msgs: Vec::default(),
+    }));
+
+    let output = sign(Proto::SigningMode::Protobuf, input);
+    assert_eq!(output.error, SigningError::Error_invalid_params);
+}
+
+#[test]
+fn test_sign_fee_grant_basic() {
+    use Proto::mod_Message::mod_FeeGrant::OneOfallowance as ProtoAllowance;
+
+    let basic = Proto::mod_Message::BasicAllowance {
+        spend_limit: vec![make_amount("uatom", "100000")],
+        expiration: 1700000000,
+    };
+    let input = make_input(MessageEnum::fee_grant(Proto::mod_Message::FeeGrant {
+        granter: GRANTER.into(),
+        grantee: GRANTEE.into(),
+        allowance: ProtoAllowance::basic(basic),
+        allowed_messages: Vec::default(),
+    }));
+
+    let message = single_message(&sign_protobuf(input.clone()));
+    assert_eq!(
+        message.type_url,
+        "/cosmos.feegrant.v1beta1.MsgGrantAllowance"
+    );
+
+    let grant: feegrant_proto::MsgGrantAllowance = deserialize(&message.value).unwrap();
+    assert_eq!(grant.granter, GRANTER);
+    assert_eq!(grant.grantee, GRANTEE);
+    let allowance = grant.allowance.unwrap();
+    assert_eq!(
+        allowance.type_url,
+        "/cosmos.feegrant.v1beta1.BasicAllowance"
+    );
+    let basic: feegrant_proto::BasicAllowance = deserialize(&allowance.value).unwrap();
+    assert_eq!(basic.spend_limit[0].amount, "100000");
+    assert_eq!(basic.expiration.unwrap().seconds, 1700000000);
+
+    let tx = sign_json(input);
+    assert_eq!(
+        tx["msg"][0],
+        json!({
+            "type": "cosmos-sdk/MsgGrantAllowance",
+            "value": {
+                "allowance": {
+                    "type": "cosmos-sdk/BasicAllowance",
+                    "value": {
+                        "expiration": "2023-11-14T22:13:20Z",
+                        "spend_limit": [{"amount": "100000", "denom": "uatom"}]
+                    }
+                },
+                "grantee": GRANTEE,
+                "granter": GRANTER
+            }
+        })
+    );
+}
+
+#[test]
+fn test_sign_fee_grant_periodic_allowed_messages() {
+    use Proto::mod_Message::mod_FeeGrant::OneOfallowance as ProtoAllowance;
+
+    let periodic = Proto::mod_Message::PeriodicAllowance {
+        basic: Some(Proto::mod_Message::BasicAllowance::default()),
+        period: 86400,
+        period_spend_limit: vec![make_amount("uatom", "1000")],
+    };
+    let input = make_input(MessageEnum::fee_grant(Proto::mod_Message::FeeGrant {
+        granter: GRANTER.into(),
+        grantee: GRANTEE.into(),
+        allowance: ProtoAllowance::periodic(periodic),
+        allowed_messages: vec!["/cosmos.authz.v1beta1.MsgExec".into()],
+    }));
+
+    let message = single_message(&sign_protobuf(input.clone()));
+    let grant: feegrant_proto::MsgGrantAllowance = deserialize(&message.value).unwrap();
+
+    let allowance = grant.allowance.unwrap();
+    assert_eq!(
+        allowance.type_url,
+        "/cosmos.feegrant.v1beta1.AllowedMsgAllowance"
+    );
+    let allowed: feegrant_proto::AllowedMsgAllowance = deserialize(&allowance.value).unwrap();
+    assert_eq!(allowed.allowed_messages, ["/cosmos.authz.v1beta1.MsgExec"]);
+
+    let allowance = allowed.allowance.unwrap();
+    assert_eq!(
+        allowance.type_url,
+        "/cosmos.feegrant.v1beta1.PeriodicAllowance"
+    );
+    let periodic: feegrant_proto::PeriodicAllowance = deserialize(&allowance.value).unwrap();
+    assert_eq!(periodic.period.unwrap().seconds, 86400);
+    assert_eq!(periodic.period_spend_limit, periodic.period_can_spend);
+    assert!(periodic.basic.unwrap().spend_limit.is_empty());
+
+    let tx = sign_json(input);
+    assert_eq!(
+        tx["msg"][0]["value"]["allowance"],
+        json!({
+            "type": "cosmos-sdk/AllowedMsgAllowance",
+            "value": {
+                "allowance": {
+                    "type": "cosmos-sdk/PeriodicAllowance",
+                    "value": {
+                        "basic": {},
+                        "period": "86400000000000",
+                        "period_can_spend": [{"amount": "1000", "denom": "uatom"}],
+                        "period_spend_limit": [{"amount": "1000", "denom": "uatom"}]
+                    }
+                },
+                "allowed_messages": ["/cosmos.authz.v1beta1.MsgExec"]
+            }
+        })
+    );
+}
+
+#[test]
+fn test_sign_fee_revoke() {
+    let input = make_input(MessageEnum::fee_revoke(Proto::mod_Message::FeeRevoke {
+        granter: GRANTER.into(),
+        grantee: GRANTEE.into(),
+    }));
+
+    let message = single_message(&sign_protobuf(input.clone()));
+    assert_eq!(
+        message.type_url,
+        "/cosmos.feegrant.v1beta1.MsgRevokeAllowance"
+    );
+    let revoke: feegrant_proto::MsgRevokeAllowance = deserialize(&message.value).unwrap();
+    assert_eq!(revoke.granter, GRANTER);
+    assert_eq!(revoke.grantee, GRANTEE);
+
+    let tx = sign_json(input);
+    assert_eq!(
+        tx["msg"][0],
+        json!({
+            "type": "cosmos-sdk/MsgRevokeAllowance",
+            "value": {
+                "grantee": GRANTEE,
+                "granter": GRANTER
+            }
+        })
+    );
+}
+
+#[test]
+fn test_sign_fee_granter() {
+    let mut input = make_input(restake_exec_message());
+    if let Some(ref mut fee) = input.fee {
+        fee.granter = GRANTER.into();
+    }
+
+    let tx_raw = sign_protobuf(input.clone());
+    let auth_info: tx_proto::AuthInfo = deserialize(&tx_raw.auth_info_bytes).unwrap();
+    let fee = auth_info.fee.unwrap();
+    assert_eq!(fee.granter, GRANTER);
+    assert!(fee.payer.is_empty());
+
+    let tx = sign_json(input);
+    assert_eq!(
+        tx["fee"],
+        json!({
+            "amount": [{"amount": "5000", "denom": "uatom"}],
+            "gas": "200000",
+            "granter": GRANTER
+        })
+    );
+}
+
+#[test]
+fn test_sign_fee_granter_and_payer_json() {
+    let mut input = make_input(restake_exec_message());
+    if let Some(ref mut fee) = input.fee {
+        fee.granter = GRANTER.into();
+        fee.payer = GRANTEE.into();
+    }
+
+    let output = sign(Proto::SigningMode::JSON, input);
+    assert_eq!(output.error, SigningError::OK);
+
+    // The keys of the fee must be sorted, as in the Amino JSON sign doc.
+    let expected_fee = format!(
+        r#""fee":{{"amount":[{{"amount":"5000","denom":"uatom"}}],"gas":"200000","granter":"{GRANTER}","payer":"{GRANTEE}"}}"#
+    );
+    assert!(output.json.contains(&expected_fee), "{}", output.json);
+}
+
+#[test]
+fn test_sign_fee_payer_invalid() {
+    let mut input = make_input(restake_exec_message());
+    if let Some(ref mut fee) = input.fee {
+        fee.payer = "cosmos1invalid".into();
+    }
+
+    let output = sign(Proto::SigningMode::Protobuf, input);
+    assert_eq!(output.error, SigningError::Error_invalid_address);
+}
@@ -0,0 +1,466 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use serde_json::{json, Value as Json};
+use std::borrow::Cow;
+use std::str::FromStr;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::context::StandardCosmosContext;
+use tw_cosmos_sdk::modules::signer::tw_signer::TWSigner;
+use tw_cosmos_sdk::proto::cosmos::bank::v1beta1 as bank_proto;
+use tw_cosmos_sdk::proto::cosmos::base::v1beta1 as base_proto;
+use tw_cosmos_sdk::proto::cosmos::gov::v1 as gov_v1_proto;
+use tw_cosmos_sdk::proto::cosmos::gov::v1beta1 as gov_v1beta1_proto;
+use tw_cosmos_sdk::proto::cosmos::tx::v1beta1 as tx_proto;
+use tw_cosmos_sdk::test_utils::proto_utils::{make_amount, make_fee, make_message};
+use tw_cosmos_sdk::transaction::message::cosmos_gov_message::VoteWeight;
+use tw_encoding::base64;
+use tw_encoding::hex::DecodeHex;
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+use tw_proto::Cosmos::Proto::mod_Message::{GovVersion, VoteOption};
+use tw_proto::{deserialize, google, serialize};
+
+const VOTER: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+const GOV_MODULE: &str = "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn";
+
+fn account_1037_private_key() -> Cow<'static, [u8]> {
+    "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005"
+        .decode_hex()
+        .unwrap()
+        .into()
+}
+
+fn make_input(message: MessageEnum<'static>) -> Proto::SigningInput<'static> {
+    Proto::SigningInput {
+        account_number: 1037,
+        chain_id: "cosmoshub-4".into(),
+        sequence: 8,
+        fee: Some(make_fee(200000, make_amount("uatom", "5000"))),
+        private_key: account_1037_private_key(),
+        messages: vec![make_message(message)],
+        ..Proto::SigningInput::default()
+    }
+}
+
+fn sign(
+    signing_mode: Proto::SigningMode,
+    mut input: Proto::SigningInput<'_>,
+) -> Proto::SigningOutput<'static> {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    input.signing_mode = signing_mode;
+    TWSigner::<StandardCosmosContext>::sign(&coin, input)
+}
+
+/// Signs the input in the Protobuf mode and returns the only message of the transaction.
+fn sign_protobuf(input: Proto::SigningInput<'_>) -> google::protobuf::Any {
+    let output = sign(Proto::SigningMode::Protobuf, input);
+    assert_eq!(output.error, SigningError::OK);
+
+    let broadcast: Json = serde_json::from_str(&output.serialized).unwrap();
+    let tx_bytes = base64::decode(broadcast["tx_bytes"].as_str().unwrap(), false).unwrap();
+    let tx_raw: tx_proto::TxRaw = deserialize(&tx_bytes).unwrap();
+    let tx_body: tx_proto::TxBody = deserialize(&tx_raw.body_bytes).unwrap();
+    assert_eq!(tx_body.messages.len(), 1);
+    tx_body.messages[0].clone()
+}
+
+/// Signs the input in the JSON mode and returns the only message of the transaction.
+fn sign_json(input: Proto::SigningInput<'_>) -> Json {
+    let output = sign(Proto::SigningMode::JSON, input);
+    assert_eq!(output.error, SigningError::OK);
+
+    let broadcast: Json = serde_json::from_str(&output.json).unwrap();
+    broadcast["tx"]["msg"][0].clone()
+}
+
+fn make_weighted_vote(
+    version: GovVersion,
+    weights: &[(VoteOption, &'static str)],
+) -> MessageEnum<'static> {
+    let options = weights
+        .iter()
+        .map(|(option, weight)| Proto::mod_Message::WeightedVoteOption {
+            option: *option,
+            weight: (*weight).into(),
+        })
+        .collect();
+    MessageEnum::msg_vote_weighted(Proto::mod_Message::MsgVoteWeighted {
+        proposal_id: 77,
+        voter: VOTER.into(),
+        options,
+        version,
+        ..Proto::mod_Message::MsgVoteWeighted::default()
+    })
+}
+
+#[test]
+fn test_vote_weight() {
+    let valid = [
+        ("1", "1.000000000000000000", 1_000_000_000_000_000_000),
+        ("1.0", "1.000000000000000000", 1_000_000_000_000_000_000),
+        ("0.5", "0.500000000000000000", 500_000_000_000_000_000),
+        (".25", "0.250000000000000000", 250_000_000_000_000_000),
+        ("0.000000000000000001", "0.000000000000000001", 1),
+    ];
+    for (input, dec_string, atomics) in valid {
+        let weight = VoteWeight::from_str(input).unwrap();
+        assert_eq!(weight.to_dec_string(), dec_string, "{input}");
+        assert_eq!(weight.atomics(), atomics, "{input}");
+    }
+
+    let invalid = [
+        "",
+        ".",
+        "0",
+        "0.0",
+        "1.000000000000000001",
+        "2",
+        "-0.5",
+        "0.5e1",
+        "0.0000000000000000001",
+    ];
+    for input in invalid {
+        VoteWeight::from_str(input).unwrap_err();
+    }
+}
+
+#[test]
+fn test_sign_vote_v1beta1_json() {
+    let input = make_input(MessageEnum::msg_vote(Proto::mod_Message::MsgVote {
+        proposal_id: 77,
+        voter: VOTER.into(),
+        option: VoteOption::NO_WITH_VETO,
+        ..Proto::mod_Message::MsgVote::default()
+    }));
+
+    assert_eq!(
+        sign_json(input),
+        json!({
+            "type": "cosmos-sdk/MsgVote",
+            "value": {
+                "option": 4,
+                "proposal_id": "77",
+                "voter": VOTER
+            }
+        })
+    );
+}
+
+#[test]
+fn test_sign_vote_v1() {
+    let input = make_input(MessageEnum::msg_vote(Proto::mod_Message::MsgVote {
+        proposal_id: 77,
+        voter: VOTER.into(),
+        option: VoteOption::YES,
+        version: GovVersion::V1,
+        metadata: "ipfs://CID".into(),
+    }));
+
+    let message = sign_protobuf(input.clone());
+    assert_eq!(message.type_url, "/cosmos.gov.v1.MsgVote");
+    let vote: gov_v1_proto::MsgVote = deserialize(&message.value).unwrap();
+    assert_eq!(vote.proposal_id, 77);
+    assert_eq!(vote.voter, VOTER);
+    assert_eq!(vote.option, gov_v1_proto::VoteOption::VOTE_OPTION_YES);
+    assert_eq!(vote.metadata, "ipfs://CID");
+
+    assert_eq!(
+        sign_json(input),
+        json!({
+            "type": "cosmos-sdk/v1/MsgVote",
+            "value": {
+                "metadata": "ipfs://CID",
+                "option": 1,
+                "proposal_id": "77",
+                "voter": VOTER
+            }
+        })
+    );
+}
+
+#[test]
+fn test_sign_vote_v1beta1_metadata_error() {
+    let input = make_input(MessageEnum::msg_vote(Proto::mod_Message::MsgVote {
+        proposal_id: 77,
+        voter: VOTER.into(),
+        option: VoteOption::YES,
+        version: GovVersion::V1Beta1,
+        metadata: "ipfs://CID".into(),
+    }));
+
+    let output = sign(Proto::SigningMode::Protobuf, input);
+    assert_eq!(output.error, SigningError::Error_invalid_params);
+}
+
+#[test]
+fn test_sign_vote_weighted_v1beta1() {
+    let input = make_input(make_weighted_vote(
+        GovVersion::V1Beta1,
+        &[(VoteOption::YES, "0.7"), (VoteOption::ABSTAIN, "0.3")],
+    ));
+
+    let message = sign_protobuf(input.clone());
+    assert_eq!(message.type_url, "/cosmos.gov.v1beta1.MsgVoteWeighted");
+    let vote: gov_v1beta1_proto::MsgVoteWeighted = deserialize(&message.value).unwrap();
+    assert_eq!(vote.options.len(), 2);
+    assert_eq!(
+        vote.options[0].option,
+        gov_v1beta1_proto::VoteOption::VOTE_OPTION_YES
+    );
+    // `v1beta1` encodes weights as `cosmos.Dec` atomics.
+    assert_eq!(vote.options[0].weight, "700000000000000000");
+    assert_eq!(vote.options[1].weight, "300000000000000000");
+
+    assert_eq!(
+        sign_json(input),
+        json!({
+            "type": "cosmos-sdk/MsgVoteWeighted",
+            "value": {
+                "options": [
+                    {"option": 1, "weight": "0.700000000000000000"},
+                    {"option": 2, "weight": "0.300000000000000000"}
+                ],
+                "proposal_id": "77",
+                "voter": VOTER
+            }
+        })
+    );
+}
+
+#[test]
+fn test_sign_vote_weighted_v1() {
+    let input = make_input(make_weighted_vote(GovVersion::V1, &[(VoteOption::NO, "1")]));
+
+    let message = sign_protobuf(input.clone());
+    assert_eq!(message.type_url, "/cosmos.gov.v1.MsgVoteWeighted");
+    let vote: gov_v1_proto::MsgVoteWeighted = deserialize(&message.value).unwrap();
+    assert_eq!(
+        vote.options[0].option,
+        gov_v1_proto::VoteOption::VOTE_OPTION_NO
+    );
+    assert_eq!(vote.options[0].weight, "1.000000000000000000");
+
+    assert_eq!(
+        sign_json(input)["type"],
+        json!("cosmos-sdk/v1/MsgVoteWeighted")
+    );
+}
+
+#[test]
+fn test_sign_vote_weighted_invalid_weights() {
+    let tests: [&[(VoteOption, &str)]; 4] = [
+        &[],
+        &[(VoteOption::YES, "0.5"), (VoteOption::NO, "0.4")],
+        &[(VoteOption::YES, "0.5"), (VoteOption::NO, "0.6")],
+        &[(VoteOption::YES, "half")],
+    ];
+
+    for weights in tests {
+        let input = make_input(make_weighted_vote(GovVersion::V1, weights));
+        let output = sign(Proto::SigningMode::Protobuf, input);
+        assert_eq!(output.error, SigningError::Error_invalid_params);
+    }
+}
+
+#[test]
+fn test_sign_deposit() {
+    for (version, type_url, json_type) in [
+        (
+            GovVersion::V1Beta1,
+            "/cosmos.gov.v1beta1.MsgDeposit",
+            "cosmos-sdk/MsgDeposit",
+        ),
+        (
+            GovVersion::V1,
+            "/cosmos.gov.v1.MsgDeposit",
+            "cosmos-sdk/v1/MsgDeposit",
+        ),
+    ] {
+        let input = make_input(MessageEnum::msg_deposit(Proto::mod_Message::MsgDeposit {
+            proposal_id: 10,
+            depositor: VOTER.into(),
+            amounts: vec![make_amount("uatom", "1000000")],
+            version,
+        }));
+
+        let message = sign_protobuf(input.clone());
+        assert_eq!(message.type_url, type_url);
+
+        assert_eq!(
+            sign_json(input),
+            json!({
+                "type": json_type,
+                "value": {
+                    "amount": [{"amount": "1000000", "denom": "uatom"}],
+                    "depositor": VOTER,
+                    "proposal_id": "10"
+                }
+            })
+        );
+    }
+}
+
+#[test]
+fn test_sign_submit_text_proposal_v1beta1() {
+    let input = make_input(MessageEnum::msg_submit_proposal(
+        Proto::mod_Message::MsgSubmitProposal {
+            initial_deposit: vec![make_amount("uatom", "250000000")],
+            proposer: VOTER.into(),
+            version: GovVersion::V1Beta1,
+            title: "Signaling proposal".into(),
+            summary: "Description of the proposal".into(),
+            ..Proto::mod_Message::MsgSubmitProposal::default()
+        },
+    ));
+
+    let message = sign_protobuf(input.clone());
+    assert_eq!(message.type_url, "/cosmos.gov.v1beta1.MsgSubmitProposal");
+    let proposal: gov_v1beta1_proto::MsgSubmitProposal = deserialize(&message.value).unwrap();
+    assert_eq!(proposal.proposer, VOTER);
+    let content = proposal.content.unwrap();
+    assert_eq!(content.type_url, "/cosmos.gov.v1beta1.TextProposal");
+    let text: gov_v1beta1_proto::TextProposal = deserialize(&content.value).unwrap();
+    assert_eq!(text.title, "Signaling proposal");
+    assert_eq!(text.description, "Description of the proposal");
+
+    assert_eq!(
+        sign_json(input),
+        json!({
+            "type": "cosmos-sdk/MsgSubmitProposal",
+            "value": {
+                "content": {
+                    "type": "cosmos-sdk/TextProposal",
+                    "value": {
+                        "description": "Description of the proposal",
+                        "title": "Signaling proposal"
+                    }
+                },
+                "initial_deposit": [{"amount": "250000000", "denom": "uatom"}],
+                "proposer": VOTER
+            }
+        })
+    );
+}
+
+#[test]
+fn test_sign_submit_proposal_v1() {
+    let community_spend = bank_proto::MsgSend {
+        from_address: GOV_MODULE.to_string(),
+        to_address: VOTER.to_string(),
+        amount: vec![base_proto::Coin {
+            denom: "uatom".to_string(),
+            amount: "1000".to_string(),
+        }],
+    };
+    let amino_json = json!({
+        "amount": [{"amount": "1000", "denom": "uatom"}],
+        "from_address": GOV_MODULE,
+        "to_address": VOTER
+    });
+
+    let mut proposal = Proto::mod_Message::MsgSubmitProposal {
+        messages: vec![Proto::mod_Message::AnyMessage {
+            type_url: "/cosmos.bank.v1beta1.MsgSend".into(),
+            value: serialize(&community_spend).unwrap().into(),
+            json: Some(Proto::mod_Message::RawJSON {
+                type_pb: "cosmos-sdk/MsgSend".into(),
+                value: amino_json.to_string().into(),
+            }),
+        }],
+        initial_deposit: vec![make_amount("uatom", "250000000")],
+        proposer: VOTER.into(),
+        version: GovVersion::V1,
+        metadata: "ipfs://CID".into(),
+        title: "Community spend".into(),
+        summary: "Fund the voter".into(),
+        expedited: true,
+    };
+    let input = make_input(MessageEnum::msg_submit_proposal(proposal.clone()));
+
+    let message = sign_protobuf(input.clone());
+    assert_eq!(message.type_url, "/cosmos.gov.v1.MsgSubmitProposal");
+    let decoded: gov_v1_proto::MsgSubmitProposal = deserialize(&message.value).unwrap();
+    assert_eq!(decoded.messages.len(), 1);
+    assert_eq!(decoded.messages[0].type_url, "/cosmos.bank.v1beta1.MsgSend");
+    let decoded_send: bank_proto::MsgSend = deserialize(&decoded.messages[0].value).unwrap();
+    assert_eq!(decoded_send, community_spend);
+    assert_eq!(decoded.metadata, "ipfs://CID");
+    assert_eq!(decoded.title, "Community spend");
+    assert_eq!(decoded.summary, "Fund the voter");
+    assert!(decoded.expedited);
+
+    assert_eq!(
+        sign_json(input),
+        json!({
+            "type": "cosmos-sdk/v1/MsgSubmitProposal",
+            "value": {
+                "expedited": true,
+                "initial_deposit": [{"amount": "250000000", "denom": "uatom"}],
+                "messages": [{"type": "cosmos-sdk/MsgSend", "value": amino_json}],
+                "metadata": "ipfs://CID",
+                "proposer": VOTER,
+                "summary": "Fund the voter",
+                "title": "Community spend"
+            }
+        })
+    );
+
+    // The Amino JSON representation of the proposal messages is required in the JSON mode only.
+    proposal.messages[0].json = None;
+    let input = make_input(MessageEnum::msg_submit_proposal(proposal));
+    sign_protobuf(input.clone());
+    let output = sign(Proto::SigningMode::JSON, input);
+    assert_eq!(output.error, SigningError::Error_not_supported);
+}
+
+#[test]
+fn test_sign_submit_proposal_v1beta1_invalid() {
+    let content = Proto::mod_Message::AnyMessage {
+        type_url: "/cosmos.gov.v1beta1.TextProposal".into(),
+        ..Proto::mod_Message::AnyMessage::default()
+    };
+    let valid = Proto::mod_Message::MsgSubmitProposal {
+        proposer: VOTER.into(),
+        version: GovVersion::V1Beta1,
+        ..Proto::mod_Message::MsgSubmitProposal::default()
+    };
+
+    let tests = [
+        // Neither a content nor a title.
+        valid.clone(),
+        // Several contents.
+        Proto::mod_Message::MsgSubmitProposal {
+            messages: vec![content.clone(), content.clone()],
+            ..valid.clone()
+        },
+        // Both a content and a title.
+        Proto::mod_Message::MsgSubmitProposal {
+            messages: vec![content],
+            title: "Title".into(),
+            ..valid.clone()
+        },
+        Proto::mod_Message::MsgSubmitProposal {
+            title: "Title".into(),
+            metadata: "ipfs://CID".into(),
+            ..valid.clone()
+        },
+        Proto::mod_Message::MsgSubmitProposal {
+            title: "Title".into(),
+            expedited: true,
+            ..valid
+        },
+    ];
+
+    for proposal in tests {
+        let input = make_input(MessageEnum::msg_submit_proposal(proposal));
+        let output = sign(Proto::SigningMode::Protobuf, input);
+        assert_eq!(output.error, SigningError::Error_invalid_params);
+    }
+}
@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use serde_json::{json, Value as Json};
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::context::StandardCosmosContext;
+use tw_cosmos_sdk::modules::tx_builder::TxBuilder;
+use tw_cosmos_sdk::proto::ibc::applications::transfer::v1 as transfer_proto;
+use tw_cosmos_sdk::test_utils::proto_utils::make_amount;
+use tw_cosmos_sdk::transaction::message::ibc_message::{ForwardHop, ForwardMemoBuilder};
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::deserialize;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+
+const OSMOSIS_RECEIVER: &str = "osmo18s0hdnsllgcclweu9aymw4ngktr2k0rkvn7jmn";
+const NOBLE_RECEIVER: &str = "noble18s0hdnsllgcclweu9aymw4ngktr2k0rkvtc240";
+const STRIDE_RECEIVER: &str = "stride18s0hdnsllgcclweu9aymw4ngktr2k0rk8rd7ed";
+
+fn make_height(revision_number: u64, revision_height: u64) -> Proto::Height {
+    Proto::Height {
+        revision_number,
+        revision_height,
+    }
+}
+
+fn make_transfer() -> Proto::mod_Message::Transfer<'static> {
+    Proto::mod_Message::Transfer {
+        source_port: "transfer".into(),
+        source_channel: "channel-141".into(),
+        token: Some(make_amount("uatom", "100000")),
+        sender: "cosmos1mky69cn8ektwy0845vec9upsdphktxt03gkwlx".into(),
+        receiver: OSMOSIS_RECEIVER.into(),
+        timeout_height: Some(make_height(1, 8800000)),
+        ..Proto::mod_Message::Transfer::default()
+    }
+}
+
+fn make_forward(receiver: &str, channel: &str) -> Proto::mod_Message::PacketForward<'static> {
+    Proto::mod_Message::PacketForward {
+        receiver: receiver.to_string().into(),
+        channel: channel.to_string().into(),
+        ..Proto::mod_Message::PacketForward::default()
+    }
+}
+
+fn build_transfer(
+    transfer: &Proto::mod_Message::Transfer<'_>,
+) -> Result<transfer_proto::MsgTransfer, SigningError> {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let msg = TxBuilder::<StandardCosmosContext>::transfer_tokens_msg_from_proto(&coin, transfer)
+        .map_err(|e| e.0)?;
+    let any = msg.to_proto().unwrap();
+    assert_eq!(any.type_url, "/ibc.applications.transfer.v1.MsgTransfer");
+    Ok(deserialize(&any.value).unwrap())
+}
+
+#[test]
+fn test_forward_memo_builder() {
+    let wasm_hook = json!({
+        "wasm": {
+            "contract": "osmo1contract",
+            "msg": { "swap": {} }
+        }
+    });
+
+    let memo = ForwardMemoBuilder::default()
+        .hop(ForwardHop::new(
+            NOBLE_RECEIVER.to_string(),
+            "channel-4".to_string(),
+        ))
+        .hop(ForwardHop {
+            timeout: Some("10m".to_string()),
+            retries: Some(2),
+            ..ForwardHop::new(STRIDE_RECEIVER.to_string(), "channel-39".to_string())
+        })
+        .next(wasm_hook.clone())
+        .build()
+        .unwrap();
+
+    let expected = json!({
+        "forward": {
+            "receiver": NOBLE_RECEIVER,
+            "port": "transfer",
+            "channel": "channel-4",
+            "next": {
+                "forward": {
+                    "receiver": STRIDE_RECEIVER,
+                    "port": "transfer",
+                    "channel": "channel-39",
+                    "timeout": "10m",
+                    "retries": 2,
+                    "next": wasm_hook
+                }
+            }
+        }
+    });
+    assert_eq!(memo, expected);
+
+    // At least one hop is required.
+    ForwardMemoBuilder::default().build().unwrap_err();
+    // `next` must be a JSON object.
+    ForwardMemoBuilder::default()
+        .hop(ForwardHop::new(
+            NOBLE_RECEIVER.to_string(),
+            "channel-4".to_string(),
+        ))
+        .next(Json::String("memo".to_string()))
+        .build()
+        .unwrap_err();
+}
+
+#[test]
+fn test_ibc_transfer_memo() {
+    // The memo is passed as is if there are no forward hops.
+    let transfer = Proto::mod_Message::Transfer {
+        memo: r#"{"wasm":{"contract":"osmo1contract","msg":{}}}"#.into(),
+        ..make_transfer()
+    };
+    let msg = build_transfer(&transfer).unwrap();
+    assert_eq!(
+        msg.memo,
+        r#"{"wasm":{"contract":"osmo1contract","msg":{}}}"#
+    );
+
+    let transfer = Proto::mod_Message::Transfer {
+        memo: r#"{"wasm":{"contract":"stride1contract","msg":{}}}"#.into(),
+        forward: vec![
+            make_forward(NOBLE_RECEIVER, "channel-4"),
+            Proto::mod_Message::PacketForward {
+                port: "custom".into(),
+                timeout: "10m".into(),
+                retries: 2,
+                ..make_forward(STRIDE_RECEIVER, "channel-39")
+            },
+        ],
+        ..make_transfer()
+    };
+    let msg = build_transfer(&transfer).unwrap();
+    assert_eq!(
+        msg.memo,
+        r#"{"forward":{"channel":"channel-4","next":{"forward":{"channel":"channel-39","next":{"wasm":{"contract":"stride1contract","msg":{}}},"port":"custom","receiver":"stride18s0hdnsllgcclweu9aymw4ngktr2k0rk8rd7ed","retries":2,"timeout":"10m"}},"port":"transfer","receiver":"noble18s0hdnsllgcclweu9aymw4ngktr2k0rkvtc240"}}"#
+    );
+
+    // The memo must be a JSON object if there are forward hops.
+    let transfer = Proto::mod_Message::Transfer {
+        memo: "not a json".into(),
+        forward: vec![make_forward(NOBLE_RECEIVER, "channel-4")],
+        ..make_transfer()
+    };
+    assert_eq!(
+        build_transfer(&transfer).unwrap_err(),
+        SigningError::Error_invalid_params
+    );
+}
+
+#[test]
+fn test_ibc_transfer_timeout() {
+    // `timeout_timestamp` is computed relative to `now_timestamp`.
+    let transfer = Proto::mod_Message::Transfer {
+        timeout_height: None,
+        timeout_timestamp: 1,
+        now_timestamp: 1_700_000_000,
+        timeout_seconds: 600,
+        ..make_transfer()
+    };
+    let msg = build_transfer(&transfer).unwrap();
+    assert_eq!(msg.timeout_timestamp, 1_700_000_600_000_000_000);
+    assert_eq!(msg.timeout_height, Some(Default::default()));
+
+    // `timeout_timestamp` is used as is if `timeout_seconds` is not set.
+    let transfer = Proto::mod_Message::Transfer {
+        timeout_timestamp: 1_700_000_600_000_000_000,
+        now_timestamp: 1_700_000_000,
+        ..make_transfer()
+    };
+    let msg = build_transfer(&transfer).unwrap();
+    assert_eq!(msg.timeout_timestamp, 1_700_000_600_000_000_000);
+
+    // Either timeout height or timestamp should be set.
+    let transfer = Proto::mod_Message::Transfer {
+        timeout_height: None,
+        ..make_transfer()
+    };
+    assert_eq!(
+        build_transfer(&transfer).unwrap_err(),
+        SigningError::Error_invalid_params
+    );
+
+    // The timeout timestamp in nanoseconds overflows `u64`.
+    let transfer = Proto::mod_Message::Transfer {
+        now_timestamp: u64::MAX / 1_000_000_000,
+        timeout_seconds: 1,
+        ..make_transfer()
+    };
+    assert_eq!(
+        build_transfer(&transfer).unwrap_err(),
+        SigningError::Error_invalid_params
+    );
+}
@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use std::borrow::Cow;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::address::Address;
+use tw_cosmos_sdk::context::StandardCosmosContext;
+use tw_cosmos_sdk::modules::compiler::tw_compiler::TWTransactionCompiler;
+use tw_cosmos_sdk::modules::signer::tw_signer::TWSigner;
+use tw_cosmos_sdk::proto::cosmos::multisig::v1beta1 as multisig_proto;
+use tw_cosmos_sdk::proto::cosmos::signing::v1beta1 as signing_proto;
+use tw_cosmos_sdk::proto::cosmos::tx::v1beta1 as tx_proto;
+use tw_cosmos_sdk::public_key::multisig::MultisigPublicKey;
+use tw_cosmos_sdk::public_key::secp256k1::Secp256PublicKey;
+use tw_cosmos_sdk::public_key::CosmosPublicKey;
+use tw_cosmos_sdk::test_utils::proto_utils::{make_amount, make_fee, make_message};
+use tw_encoding::base64;
+use tw_encoding::hex::DecodeHex;
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::deserialize;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+
+const PRIVATE_KEY_1: &str = "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005";
+const PUBLIC_KEY_1: &str = "0257286ec3f37d33557bbbaa000b27744ac9023aa9967cae75a181d1ff91fa9dc5";
+const PRIVATE_KEY_2: &str = "8bbec3772ddb4df68f3186440380c301af116d1422001c1877d6f5e4dba8c8af";
+const PUBLIC_KEY_2: &str = "02ecef5ce437a302c67f95468de4b31f36e911f467d7e6a52b41c1e13e1d563649";
+const PRIVATE_KEY_3: &str = "a498a9ee41af9bab5ef2a8be63d5c970135c3c109e70efc8c56c534e6636b433";
+const PUBLIC_KEY_3: &str = "02cbfdb5e472893322294e60cf0883d43df431e1089d29ecb447a9e6d55045aae5";
+
+const MULTISIG_ADDRESS: &str = "cosmos1ffm9u6tn7q85xek5jsg25gfkzyr7hlmuye5cwk";
+
+fn bytes(hex: &str) -> Cow<'static, [u8]> {
+    hex.decode_hex().unwrap().into()
+}
+
+fn multisig_input(signers: &[&str]) -> Proto::SigningInput<'static> {
+    let send_msg = Proto::mod_Message::Send {
+        from_address: MULTISIG_ADDRESS.into(),
+        to_address: "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573".into(),
+        amounts: vec![make_amount("uatom", "1000")],
+        ..Proto::mod_Message::Send::default()
+    };
+
+    Proto::SigningInput {
+        account_number: 1234,
+        chain_id: "cosmoshub-4".into(),
+        sequence: 5,
+        fee: Some(make_fee(200000, make_amount("uatom", "500"))),
+        messages: vec![make_message(MessageEnum::send_coins_message(send_msg))],
+        multisig: Some(Proto::Multisig {
+            threshold: 2,
+            public_keys: vec![
+                bytes(PUBLIC_KEY_1),
+                bytes(PUBLIC_KEY_2),
+                bytes(PUBLIC_KEY_3),
+            ],
+            signers: signers.iter().copied().map(bytes).collect(),
+        }),
+        ..Proto::SigningInput::default()
+    }
+}
+
+fn sign_partial(
+    coin: &dyn CoinContext,
+    input: &Proto::SigningInput<'static>,
+    private_key: &str,
+) -> Vec<u8> {
+    let mut input = input.clone();
+    input.private_key = bytes(private_key);
+
+    let output = TWSigner::<StandardCosmosContext>::sign(coin, input);
+    assert_eq!(output.error, SigningError::OK);
+    // Partial signing does not produce a transaction.
+    assert!(output.serialized.is_empty());
+    assert!(output.json.is_empty());
+    output.signature.to_vec()
+}
+
+fn decode_tx_raw(serialized: &str) -> tx_proto::TxRaw {
+    let broadcast: serde_json::Value = serde_json::from_str(serialized).unwrap();
+    let tx_bytes = base64::decode(broadcast["tx_bytes"].as_str().unwrap(), false).unwrap();
+    deserialize(&tx_bytes).unwrap()
+}
+
+fn multi_mode_info(auth_info: &tx_proto::AuthInfo) -> tx_proto::mod_ModeInfo::Multi {
+    use tx_proto::mod_ModeInfo::OneOfsum as SumEnum;
+
+    match auth_info.signer_infos[0].mode_info.clone().unwrap().sum {
+        SumEnum::multi(multi) => multi,
+        _ => panic!("Expected a multisig mode info"),
+    }
+}
+
+#[test]
+fn test_multisig_address() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let public_keys = [PUBLIC_KEY_1, PUBLIC_KEY_2, PUBLIC_KEY_3]
+        .iter()
+        .map(|key| Secp256PublicKey::from_bytes(&coin, &bytes(key), None).unwrap())
+        .collect();
+    let multisig = MultisigPublicKey::new(2, public_keys).unwrap();
+
+    let address = Address::new("cosmos".to_string(), multisig.address_hash()).unwrap();
+    assert_eq!(address.to_string(), MULTISIG_ADDRESS);
+
+    // The threshold must be within the number of members.
+    let public_keys =
+        vec![Secp256PublicKey::from_bytes(&coin, &bytes(PUBLIC_KEY_1), None).unwrap()];
+    assert!(MultisigPublicKey::new(2, public_keys).is_err());
+}
+
+#[test]
+fn test_multisig_sign_direct() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let mut input = multisig_input(&[PUBLIC_KEY_1, PUBLIC_KEY_3]);
+    input.signing_mode = Proto::SigningMode::Protobuf;
+
+    let signature_1 = sign_partial(&coin, &input, PRIVATE_KEY_1);
+    let signature_3 = sign_partial(&coin, &input, PRIVATE_KEY_3);
+
+    // The second member is not one of the signers.
+    let mut not_signer = input.clone();
+    not_signer.private_key = bytes(PRIVATE_KEY_2);
+    let output = TWSigner::<StandardCosmosContext>::sign(&coin, not_signer);
+    assert_eq!(output.error, SigningError::Error_invalid_params);
+
+    // The signatures may be passed in any order.
+    let output = TWTransactionCompiler::<StandardCosmosContext>::compile(
+        &coin,
+        input.clone(),
+        vec![signature_3.clone(), signature_1.clone()],
+        vec![bytes(PUBLIC_KEY_3).to_vec(), bytes(PUBLIC_KEY_1).to_vec()],
+    );
+    assert_eq!(output.error, SigningError::OK);
+    assert!(output.json.is_empty());
+
+    let tx_raw = decode_tx_raw(&output.serialized);
+    assert_eq!(tx_raw.signatures.len(), 1);
+    assert_eq!(output.signature.as_ref(), tx_raw.signatures[0].as_slice());
+
+    let multi_signature: multisig_proto::MultiSignature =
+        deserialize(&tx_raw.signatures[0]).unwrap();
+    assert_eq!(multi_signature.signatures, vec![signature_1, signature_3]);
+
+    let auth_info: tx_proto::AuthInfo = deserialize(&tx_raw.auth_info_bytes).unwrap();
+    let signer_info = &auth_info.signer_infos[0];
+    assert_eq!(signer_info.sequence, 5);
+    assert_eq!(
+        signer_info.public_key.as_ref().unwrap().type_url,
+        "/cosmos.crypto.multisig.LegacyAminoPubKey"
+    );
+
+    let multi = multi_mode_info(&auth_info);
+    assert_eq!(
+        multi.bitarray,
+        Some(multisig_proto::CompactBitArray {
+            extra_bits_stored: 3,
+            elems: vec![0b1010_0000],
+        })
+    );
+    assert_eq!(multi.mode_infos.len(), 2);
+
+    let signature_json: serde_json::Value = serde_json::from_str(&output.signature_json).unwrap();
+    assert_eq!(
+        signature_json[0]["pub_key"]["type"],
+        "tendermint/PubKeyMultisigThreshold"
+    );
+    assert_eq!(signature_json[0]["pub_key"]["value"]["threshold"], "2");
+}
+
+#[test]
+fn test_multisig_compile_direct_invalid() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let mut input = multisig_input(&[PUBLIC_KEY_1, PUBLIC_KEY_2]);
+    input.signing_mode = Proto::SigningMode::Protobuf;
+
+    let signature_1 = sign_partial(&coin, &input, PRIVATE_KEY_1);
+    let signature_2 = sign_partial(&coin, &input, PRIVATE_KEY_2);
+
+    // Not enough signatures.
+    let output = TWTransactionCompiler::<StandardCosmosContext>::compile(
+        &coin,
+        input.clone(),
+        vec![signature_1.clone()],
+        vec![bytes(PUBLIC_KEY_1).to_vec()],
+    );
+    assert_eq!(output.error, SigningError::Error_signatures_count);
+
+    // The same member twice.
+    let output = TWTransactionCompiler::<StandardCosmosContext>::compile(
+        &coin,
+        input.clone(),
+        vec![signature_1.clone(), signature_1.clone()],
+        vec![bytes(PUBLIC_KEY_1).to_vec(), bytes(PUBLIC_KEY_1).to_vec()],
+    );
+    assert_eq!(output.error, SigningError::Error_invalid_params);
+
+    // The members have signed another set of signers.
+    let mut other_signers = input.clone();
+    other_signers.multisig.as_mut().unwrap().signers =
+        vec![bytes(PUBLIC_KEY_1), bytes(PUBLIC_KEY_3)];
+    let output = TWTransactionCompiler::<StandardCosmosContext>::compile(
+        &coin,
+        other_signers,
+        vec![signature_1, signature_2],
+        vec![bytes(PUBLIC_KEY_1).to_vec(), bytes(PUBLIC_KEY_2).to_vec()],
+    );
+    assert_eq!(output.error, SigningError::Error_invalid_params);
+
+    // The set of signers must be known to produce a Direct sign doc.
+    let mut no_signers = input;
+    no_signers.multisig.as_mut().unwrap().signers.clear();
+    no_signers.private_key = bytes(PRIVATE_KEY_1);
+    let output = TWSigner::<StandardCosmosContext>::sign(&coin, no_signers);
+    assert_eq!(output.error, SigningError::Error_invalid_params);
+}
+
+#[test]
+fn test_multisig_sign_amino_json() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    // The Amino JSON sign doc does not depend on the signers.
+    let mut input = multisig_input(&[]);
+    input.signing_mode = Proto::SigningMode::JSON;
+
+    let signature_2 = sign_partial(&coin, &input, PRIVATE_KEY_2);
+    let signature_3 = sign_partial(&coin, &input, PRIVATE_KEY_3);
+
+    let output = TWTransactionCompiler::<StandardCosmosContext>::compile(
+        &coin,
+        input,
+        vec![signature_2.clone(), signature_3.clone()],
+        vec![bytes(PUBLIC_KEY_2).to_vec(), bytes(PUBLIC_KEY_3).to_vec()],
+    );
+    assert_eq!(output.error, SigningError::OK);
+
+    let bit_array = multisig_proto::CompactBitArray {
+        extra_bits_stored: 3,
+        elems: vec![0b0110_0000],
+    };
+
+    // Amino JSON transaction.
+    let json: serde_json::Value = serde_json::from_str(&output.json).unwrap();
+    let signature = &json["tx"]["signatures"][0];
+    assert_eq!(
+        signature["pub_key"]["type"],
+        "tendermint/PubKeyMultisigThreshold"
+    );
+    assert_eq!(
+        signature["pub_key"]["value"]["pubkeys"][2]["value"],
+        base64::encode(&bytes(PUBLIC_KEY_3), false)
+    );
+
+    let amino_signature = base64::decode(signature["signature"].as_str().unwrap(), false).unwrap();
+    assert_eq!(output.signature.as_ref(), amino_signature.as_slice());
+    let amino_multisignature: multisig_proto::AminoMultisignature =
+        deserialize(&amino_signature).unwrap();
+    assert_eq!(amino_multisignature.bit_array, Some(bit_array.clone()));
+    assert_eq!(
+        amino_multisignature.sigs,
+        vec![signature_2.clone(), signature_3.clone()]
+    );
+
+    // Protobuf transaction.
+    let tx_raw = decode_tx_raw(&output.serialized);
+    let multi_signature: multisig_proto::MultiSignature =
+        deserialize(&tx_raw.signatures[0]).unwrap();
+    assert_eq!(multi_signature.signatures, vec![signature_2, signature_3]);
+
+    let auth_info: tx_proto::AuthInfo = deserialize(&tx_raw.auth_info_bytes).unwrap();
+    let multi = multi_mode_info(&auth_info);
+    assert_eq!(multi.bitarray, Some(bit_array));
+
+    let member_mode_info = tx_proto::ModeInfo {
+        sum: tx_proto::mod_ModeInfo::OneOfsum::single(tx_proto::mod_ModeInfo::Single {
+            mode: signing_proto::SignMode::SIGN_MODE_LEGACY_AMINO_JSON,
+        }),
+    };
+    assert_eq!(
+        multi.mode_infos,
+        vec![member_mode_info.clone(), member_mode_info]
+    );
+}
@@ -0,0 +1,289 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use serde_json::json;
+use std::str::FromStr;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::context::StandardCosmosContext;
+use tw_cosmos_sdk::modules::tx_builder::TxBuilder;
+use tw_cosmos_sdk::test_utils::proto_utils::make_amount;
+use tw_cosmos_sdk::transaction::message::osmosis_message::Dec;
+use tw_encoding::hex::ToHex;
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+
+const SENDER: &str = "osmo1qr7dhmvcqm4fnleaqel3gel4u20nk5rp9rwsae";
+const DENOM_A: &str = "ibc/1DC495FCEFDA068A3820F903EDBD78B942FBD204D7E93D3BA2B432E9669D1A59";
+const DENOM_B: &str = "ibc/573FCD90FACEE750F55A8864EF7D38265F07E5A9273FA0E8DAFD39951332B580";
+
+type Builder = TxBuilder<StandardCosmosContext>;
+
+fn osmosis_coin() -> TestCoinContext {
+    TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("osmo")
+}
+
+fn make_create_position() -> Proto::mod_Message::MsgOsmosisCreatePosition<'static> {
+    Proto::mod_Message::MsgOsmosisCreatePosition {
+        pool_id: 1066,
+        sender: SENDER.into(),
+        lower_tick: -108000000,
+        upper_tick: 342000000,
+        tokens_provided: vec![make_amount("uosmo", "1000"), make_amount(DENOM_B, "500")],
+        ..Proto::mod_Message::MsgOsmosisCreatePosition::default()
+    }
+}
+
+#[test]
+fn test_osmosis_swap_exact_amount_in() {
+    let coin = osmosis_coin();
+
+    let swap = Proto::mod_Message::MsgOsmosisSwapExactAmountIn {
+        sender: SENDER.into(),
+        routes: vec![
+            Proto::mod_Message::OsmosisSwapAmountInRoute {
+                pool_id: 463,
+                token_out_denom: DENOM_A.into(),
+            },
+            Proto::mod_Message::OsmosisSwapAmountInRoute {
+                pool_id: 916,
+                token_out_denom: DENOM_B.into(),
+            },
+        ],
+        token_in: Some(make_amount("uosmo", "70000")),
+        token_out_min_amount: "885297".into(),
+    };
+    let payload = Builder::osmosis_swap_exact_amount_in_msg_from_proto(&coin, &swap).unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(
+        actual.type_url,
+        "/osmosis.poolmanager.v1beta1.MsgSwapExactAmountIn"
+    );
+    assert_eq!(actual.value.to_hex(), "0a2b6f736d6f3171723764686d7663716d34666e6c656171656c3367656c347532306e6b357270397277736165124908cf0312446962632f31444334393546434546444130363841333832304639303345444244373842393432464244323034443745393344334241324234333245393636394431413539124908940712446962632f353733464344393046414345453735304635354138383634454637443338323635463037453541393237334641304538444146443339393531333332423538301a0e0a05756f736d6f120537303030302206383835323937");
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "osmosis/poolmanager/swap-exact-amount-in");
+    let expected = json!({
+        "routes": [
+            {"pool_id": "463", "token_out_denom": DENOM_A},
+            {"pool_id": "916", "token_out_denom": DENOM_B}
+        ],
+        "sender": SENDER,
+        "token_in": {"amount": "70000", "denom": "uosmo"},
+        "token_out_min_amount": "885297"
+    });
+    assert_eq!(actual.value, expected);
+
+    // At least one route is required.
+    let swap = Proto::mod_Message::MsgOsmosisSwapExactAmountIn {
+        routes: Vec::default(),
+        ..swap
+    };
+    let err = Builder::osmosis_swap_exact_amount_in_msg_from_proto(&coin, &swap)
+        .err()
+        .unwrap();
+    assert_eq!(err.0, SigningError::Error_invalid_params);
+}
+
+#[test]
+fn test_osmosis_swap_exact_amount_out() {
+    let coin = osmosis_coin();
+
+    let swap = Proto::mod_Message::MsgOsmosisSwapExactAmountOut {
+        sender: SENDER.into(),
+        routes: vec![Proto::mod_Message::OsmosisSwapAmountOutRoute {
+            pool_id: 1,
+            token_in_denom: "uosmo".into(),
+        }],
+        token_in_max_amount: "1000".into(),
+        token_out: Some(make_amount(DENOM_B, "500")),
+    };
+    let payload = Builder::osmosis_swap_exact_amount_out_msg_from_proto(&coin, &swap).unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(
+        actual.type_url,
+        "/osmosis.poolmanager.v1beta1.MsgSwapExactAmountOut"
+    );
+    assert_eq!(actual.value.to_hex(), "0a2b6f736d6f3171723764686d7663716d34666e6c656171656c3367656c347532306e6b357270397277736165120908011205756f736d6f1a0431303030224b0a446962632f353733464344393046414345453735304635354138383634454637443338323635463037453541393237334641304538444146443339393531333332423538301203353030");
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "osmosis/poolmanager/swap-exact-amount-out");
+    let expected = json!({
+        "routes": [{"pool_id": "1", "token_in_denom": "uosmo"}],
+        "sender": SENDER,
+        "token_in_max_amount": "1000",
+        "token_out": {"amount": "500", "denom": DENOM_B}
+    });
+    assert_eq!(actual.value, expected);
+}
+
+#[test]
+fn test_osmosis_create_position() {
+    let coin = osmosis_coin();
+
+    let payload =
+        Builder::osmosis_create_position_msg_from_proto(&coin, &make_create_position()).unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(
+        actual.type_url,
+        "/osmosis.concentratedliquidity.v1beta1.MsgCreatePosition"
+    );
+    assert_eq!(actual.value.to_hex(), "08aa08122b6f736d6f3171723764686d7663716d34666e6c656171656c3367656c347532306e6b35727039727773616518809ac0ccffffffffff012080838aa3012a0d0a05756f736d6f1204313030302a4b0a446962632f3537334643443930464143454537353046353541383836344546374433383236354630374535413932373346413045384441464433393935313333324235383012033530303201303a0130");
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "osmosis/cl-create-position");
+    let expected = json!({
+        "lower_tick": "-108000000",
+        "pool_id": "1066",
+        "sender": SENDER,
+        "token_min_amount0": "0",
+        "token_min_amount1": "0",
+        "tokens_provided": [
+            {"amount": "1000", "denom": "uosmo"},
+            {"amount": "500", "denom": DENOM_B}
+        ],
+        "upper_tick": "342000000"
+    });
+    assert_eq!(actual.value, expected);
+
+    // The lower tick must be less than the upper tick.
+    let create = Proto::mod_Message::MsgOsmosisCreatePosition {
+        lower_tick: 342000000,
+        ..make_create_position()
+    };
+    let err = Builder::osmosis_create_position_msg_from_proto(&coin, &create)
+        .err()
+        .unwrap();
+    assert_eq!(err.0, SigningError::Error_invalid_params);
+
+    // One or two tokens must be provided.
+    let create = Proto::mod_Message::MsgOsmosisCreatePosition {
+        tokens_provided: Vec::default(),
+        ..make_create_position()
+    };
+    let err = Builder::osmosis_create_position_msg_from_proto(&coin, &create)
+        .err()
+        .unwrap();
+    assert_eq!(err.0, SigningError::Error_invalid_params);
+}
+
+#[test]
+fn test_osmosis_add_to_position() {
+    let coin = osmosis_coin();
+
+    let add = Proto::mod_Message::MsgOsmosisAddToPosition {
+        position_id: 123,
+        sender: SENDER.into(),
+        amount0: "1000".into(),
+        amount1: "500".into(),
+        ..Proto::mod_Message::MsgOsmosisAddToPosition::default()
+    };
+    let payload = Builder::osmosis_add_to_position_msg_from_proto(&coin, &add).unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(
+        actual.type_url,
+        "/osmosis.concentratedliquidity.v1beta1.MsgAddToPosition"
+    );
+    assert_eq!(actual.value.to_hex(), "087b122b6f736d6f3171723764686d7663716d34666e6c656171656c3367656c347532306e6b3572703972777361651a043130303022033530302a0130320130");
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "osmosis/cl-add-to-position");
+    let expected = json!({
+        "amount0": "1000",
+        "amount1": "500",
+        "position_id": "123",
+        "sender": SENDER,
+        "token_min_amount0": "0",
+        "token_min_amount1": "0"
+    });
+    assert_eq!(actual.value, expected);
+}
+
+#[test]
+fn test_osmosis_withdraw_position() {
+    let coin = osmosis_coin();
+
+    let withdraw = Proto::mod_Message::MsgOsmosisWithdrawPosition {
+        position_id: 123,
+        sender: SENDER.into(),
+        liquidity_amount: "1000.5".into(),
+    };
+    let payload = Builder::osmosis_withdraw_position_msg_from_proto(&coin, &withdraw).unwrap();
+
+    // `cosmos.Dec` is encoded as atomics in Protobuf.
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(
+        actual.type_url,
+        "/osmosis.concentratedliquidity.v1beta1.MsgWithdrawPosition"
+    );
+    assert_eq!(actual.value.to_hex(), "087b122b6f736d6f3171723764686d7663716d34666e6c656171656c3367656c347532306e6b3572703972777361651a1631303030353030303030303030303030303030303030");
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "osmosis/cl-withdraw-position");
+    let expected = json!({
+        "liquidity_amount": "1000.500000000000000000",
+        "position_id": "123",
+        "sender": SENDER
+    });
+    assert_eq!(actual.value, expected);
+}
+
+#[test]
+fn test_osmosis_collect_spread_rewards() {
+    let coin = osmosis_coin();
+
+    let collect = Proto::mod_Message::MsgOsmosisCollectSpreadRewards {
+        position_ids: vec![123, 124],
+        sender: SENDER.into(),
+    };
+    let payload = Builder::osmosis_collect_spread_rewards_msg_from_proto(&coin, &collect).unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(
+        actual.type_url,
+        "/osmosis.concentratedliquidity.v1beta1.MsgCollectSpreadRewards"
+    );
+    assert_eq!(
+        actual.value.to_hex(),
+        "0a027b7c122b6f736d6f3171723764686d7663716d34666e6c656171656c3367656c347532306e6b357270397277736165"
+    );
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "osmosis/cl-col-sp-rewards");
+    let expected = json!({
+        "position_ids": ["123", "124"],
+        "sender": SENDER
+    });
+    assert_eq!(actual.value, expected);
+}
+
+#[test]
+fn test_osmosis_dec() {
+    let tests = [
+        (
+            "1000.5",
+            "1000500000000000000000",
+            "1000.500000000000000000",
+        ),
+        ("0.000000000000000001", "1", "0.000000000000000001"),
+        ("007", "7000000000000000000", "7.000000000000000000"),
+        (".5", "500000000000000000", "0.500000000000000000"),
+        ("0", "0", "0.000000000000000000"),
+    ];
+    for (input, atomics, dec) in tests {
+        let actual = Dec::from_str(input).unwrap();
+        assert_eq!(actual.to_atomics_string(), atomics, "{input}");
+        assert_eq!(actual.to_dec_string(), dec, "{input}");
+    }
+
+    for input in ["", ".", "-1", "1.2.3", "1e18", "0.0000000000000000001"] {
+        Dec::from_str(input).unwrap_err();
+    }
+}
@@ -0,0 +1,465 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use std::borrow::Cow;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::context::StandardCosmosContext;
+use tw_cosmos_sdk::modules::compiler::tw_compiler::TWTransactionCompiler;
+use tw_cosmos_sdk::modules::signer::tw_signer::TWSigner;
+use tw_cosmos_sdk::modules::textual::envelope::TextualRenderer;
+use tw_cosmos_sdk::modules::textual::screen::{encode_screens, Screen};
+use tw_cosmos_sdk::modules::textual::value_renderer::{
+    render_bool, render_bytes, render_coins, render_dec, render_duration, render_int,
+    render_timestamp, DenomMetadata, DenomMetadataRegistry, DenomUnit,
+};
+use tw_cosmos_sdk::modules::tx_builder::TxBuilder;
+use tw_cosmos_sdk::proto::cosmos::signing::v1beta1 as signing_proto;
+use tw_cosmos_sdk::proto::cosmos::tx::v1beta1 as tx_proto;
+use tw_cosmos_sdk::test_utils::proto_utils::{make_amount, make_fee, make_message};
+use tw_cosmos_sdk::transaction::Coin;
+use tw_encoding::base64;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_hash::hasher::Hasher;
+use tw_hash::sha2::sha256;
+use tw_keypair::tw::PublicKeyType;
+use tw_number::U256;
+use tw_proto::deserialize;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+
+const PRIVATE_KEY: &str = "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005";
+const PUBLIC_KEY: &str = "0257286ec3f37d33557bbbaa000b27744ac9023aa9967cae75a181d1ff91fa9dc5";
+const ADDRESS: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+
+fn coin(amount: u64, denom: &str) -> Coin {
+    Coin {
+        amount: U256::from(amount),
+        denom: denom.to_string(),
+    }
+}
+
+fn atom_registry() -> DenomMetadataRegistry {
+    DenomMetadataRegistry::new(vec![atom_metadata()])
+}
+
+fn atom_metadata() -> DenomMetadata {
+    DenomMetadata {
+        base: "uatom".to_string(),
+        display: "atom".to_string(),
+        denom_units: vec![
+            DenomUnit {
+                denom: "uatom".to_string(),
+                exponent: 0,
+            },
+            DenomUnit {
+                denom: "atom".to_string(),
+                exponent: 6,
+            },
+        ],
+    }
+}
+
+fn textual_input() -> Proto::SigningInput<'static> {
+    let send_msg = Proto::mod_Message::Send {
+        from_address: ADDRESS.into(),
+        to_address: "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573".into(),
+        amounts: vec![make_amount("uatom", "1500000")],
+        ..Proto::mod_Message::Send::default()
+    };
+
+    Proto::SigningInput {
+        signing_mode: Proto::SigningMode::Textual,
+        account_number: 1037,
+        chain_id: "cosmoshub-4".into(),
+        sequence: 8,
+        memo: "textual".into(),
+        fee: Some(make_fee(200000, make_amount("uatom", "2000"))),
+        messages: vec![make_message(MessageEnum::send_coins_message(send_msg))],
+        denom_metadata: vec![Proto::DenomMetadata {
+            base: "uatom".into(),
+            display: "atom".into(),
+            denom_units: vec![
+                Proto::DenomUnit {
+                    denom: "uatom".into(),
+                    exponent: 0,
+                },
+                Proto::DenomUnit {
+                    denom: "atom".into(),
+                    exponent: 6,
+                },
+            ],
+        }],
+        ..Proto::SigningInput::default()
+    }
+}
+
+fn test_coin_context() -> TestCoinContext {
+    let mut coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+    coin.address_hasher = Some(Hasher::Sha256ripemd);
+    coin
+}
+
+#[test]
+fn test_render_numbers() {
+    assert_eq!(render_int("0"), "0");
+    assert_eq!(render_int("999"), "999");
+    assert_eq!(render_int("1000"), "1'000");
+    assert_eq!(render_int("1234567"), "1'234'567");
+
+    assert_eq!(render_dec("1000.50"), "1'000.5");
+    assert_eq!(render_dec("0.000001"), "0.000001");
+    assert_eq!(render_dec("2.000"), "2");
+    assert_eq!(render_dec("0012"), "12");
+
+    assert_eq!(render_bool(true), "True");
+    assert_eq!(render_bool(false), "False");
+}
+
+#[test]
+fn test_render_coins() {
+    let registry = atom_registry();
+
+    assert_eq!(
+        render_coins(&[coin(1_500_000, "uatom")], &registry),
+        "1.5 atom"
+    );
+    assert_eq!(
+        render_coins(&[coin(1, "uatom")], &registry),
+        "0.000001 atom"
+    );
+    assert_eq!(
+        render_coins(&[coin(2_000_000_000_000, "uatom")], &registry),
+        "2'000'000 atom"
+    );
+    // No metadata for the denomination.
+    assert_eq!(
+        render_coins(&[coin(1000, "uluna")], &registry),
+        "1'000 uluna"
+    );
+    // Sorted by the display denomination.
+    assert_eq!(
+        render_coins(&[coin(1_000_000, "uatom"), coin(5, "abc")], &registry),
+        "5 abc, 1 atom"
+    );
+    assert_eq!(render_coins(&[], &registry), "zero");
+}
+
+#[test]
+fn test_render_bytes() {
+    assert_eq!(render_bytes(&[]), "");
+    assert_eq!(render_bytes(&[0x01, 0x02, 0x03]), "0102 03");
+    assert_eq!(
+        render_bytes(&"deadbeef00".decode_hex().unwrap()),
+        "DEAD BEEF 00"
+    );
+    // Byte arrays longer than 35 bytes are rendered as the hash.
+    assert_eq!(
+        render_bytes(&[0; 36]),
+        "SHA-256=6DB6 5FD5 9FD3 56F6 7291 4057 1B5B CD6B B3B8 3492 A16E 1BF0 A388 4442 FC3C 8A0E"
+    );
+}
+
+#[test]
+fn test_render_timestamp() {
+    assert_eq!(render_timestamp(0, 0), "1970-01-01T00:00:00Z");
+    assert_eq!(
+        render_timestamp(1136214245, 700_000_000),
+        "2006-01-02T15:04:05.7Z"
+    );
+    assert_eq!(
+        render_timestamp(1136214245, 1),
+        "2006-01-02T15:04:05.000000001Z"
+    );
+    assert_eq!(render_timestamp(951782400, 0), "2000-02-29T00:00:00Z");
+    assert_eq!(render_timestamp(-1, 0), "1969-12-31T23:59:59Z");
+}
+
+#[test]
+fn test_render_duration() {
+    assert_eq!(render_duration(0, 0), "0 seconds");
+    assert_eq!(render_duration(1, 0), "1 second");
+    assert_eq!(render_duration(0, 500_000_000), "0.5 seconds");
+    assert_eq!(
+        render_duration(93784, 500_000_000),
+        "1 day, 2 hours, 3 minutes, 4.5 seconds"
+    );
+    assert_eq!(render_duration(2 * 86400, 0), "2 days");
+    assert_eq!(render_duration(-60, 0), "-1 minute");
+}
+
+#[test]
+fn test_encode_screens() {
+    let screens = [
+        Screen::new("Chain id", "my-chain"),
+        Screen::content("End of Message").with_indent(1).expert(),
+    ];
+    assert_eq!(
+        encode_screens(&screens).unwrap().to_hex(),
+        "a10182a20168436861696e20696402686d792d636861696ea3026e456e64206f66204d657373616765030104f5"
+    );
+}
+
+#[test]
+fn test_render_tx_screens() {
+    let coin = test_coin_context();
+    let mut input = textual_input();
+    input.public_key = PUBLIC_KEY.decode_hex().unwrap().into();
+
+    let unsigned_tx =
+        TxBuilder::<StandardCosmosContext>::unsigned_tx_from_proto(&coin, &input).unwrap();
+    let address =
+        TxBuilder::<StandardCosmosContext>::signer_address_from_proto(&coin, &input).unwrap();
+    assert_eq!(address.to_string(), ADDRESS);
+
+    let registry = TxBuilder::<StandardCosmosContext>::denom_metadata_from_proto(&input);
+    let screens = TextualRenderer::render_tx(&unsigned_tx, ADDRESS, &registry).unwrap();
+
+    // The last screen contains the hash of the raw bytes.
+    let (hash_screen, screens) = screens.split_last().unwrap();
+    assert_eq!(hash_screen.title, "Hash of raw bytes");
+    assert!(hash_screen.expert);
+
+    let expected =
+        vec![
+        Screen::new("Chain id", "cosmoshub-4"),
+        Screen::new("Account number", "1'037"),
+        Screen::new("Sequence", "8"),
+        Screen::new("Address", ADDRESS),
+        Screen::new("Public key", "/cosmos.crypto.secp256k1.PubKey").expert(),
+        Screen::new(
+            "Key",
+            "0257 286E C3F3 7D33 557B BBAA 000B 2774 4AC9 023A A996 7CAE 75A1 81D1 FF91 FA9D C5",
+        )
+        .with_indent(1)
+        .expert(),
+        Screen::content("This transaction has 1 Message"),
+        Screen::new("Message (1/1)", "/cosmos.bank.v1beta1.MsgSend").with_indent(1),
+        Screen::new("From address", ADDRESS).with_indent(2),
+        Screen::new("To address", "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573").with_indent(2),
+        Screen::new("Amount", "1.5 atom").with_indent(2),
+        Screen::content("End of Message"),
+        Screen::new("Memo", "textual"),
+        Screen::new("Fees", "0.002 atom"),
+        Screen::new("Gas limit", "200'000").expert(),
+    ];
+    assert_eq!(screens, expected);
+}
+
+/// The "Basic MsgSend" case of the cosmos-sdk `x/tx/signing/textual` testdata (`tx.json`).
+#[test]
+fn test_render_tx_screens_sdk_testdata() {
+    let coin = test_coin_context();
+
+    let send_msg = Proto::mod_Message::Send {
+        from_address: "cosmos1ulav3hsenupswqfkw2y3sup5kgtqwnvqa8eyhs".into(),
+        to_address: "cosmos1ejrf4cur2wy6kfurg9f2jppp2h3afe5h6pkh5t".into(),
+        amounts: vec![make_amount("uatom", "10000000")],
+        ..Proto::mod_Message::Send::default()
+    };
+    let input = Proto::SigningInput {
+        signing_mode: Proto::SigningMode::Textual,
+        account_number: 1,
+        chain_id: "my-chain".into(),
+        sequence: 2,
+        memo: "GM".into(),
+        fee: Some(make_fee(100000, make_amount("uatom", "2000"))),
+        messages: vec![make_message(MessageEnum::send_coins_message(send_msg))],
+        public_key: "02EBDD7FE4FDEB76DC8A205EF65D790CD30E8A375A5C2528EB3A923AF1FB4D794D"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        denom_metadata: vec![Proto::DenomMetadata {
+            base: "uatom".into(),
+            display: "ATOM".into(),
+            denom_units: vec![
+                Proto::DenomUnit {
+                    denom: "uatom".into(),
+                    exponent: 0,
+                },
+                Proto::DenomUnit {
+                    denom: "ATOM".into(),
+                    exponent: 6,
+                },
+            ],
+        }],
+        ..Proto::SigningInput::default()
+    };
+
+    let unsigned_tx =
+        TxBuilder::<StandardCosmosContext>::unsigned_tx_from_proto(&coin, &input).unwrap();
+    let address =
+        TxBuilder::<StandardCosmosContext>::signer_address_from_proto(&coin, &input).unwrap();
+    let registry = TxBuilder::<StandardCosmosContext>::denom_metadata_from_proto(&input);
+    let screens =
+        TextualRenderer::render_tx(&unsigned_tx, &address.to_string(), &registry).unwrap();
+
+    let expected =
+        vec![
+        Screen::new("Chain id", "my-chain"),
+        Screen::new("Account number", "1"),
+        Screen::new("Sequence", "2"),
+        Screen::new("Address", "cosmos1ulav3hsenupswqfkw2y3sup5kgtqwnvqa8eyhs"),
+        Screen::new("Public key", "/cosmos.crypto.secp256k1.PubKey").expert(),
+        Screen::new(
+            "Key",
+            "02EB DD7F E4FD EB76 DC8A 205E F65D 790C D30E 8A37 5A5C 2528 EB3A 923A F1FB 4D79 4D",
+        )
+        .with_indent(1)
+        .expert(),
+        Screen::content("This transaction has 1 Message"),
+        Screen::new("Message (1/1)", "/cosmos.bank.v1beta1.MsgSend").with_indent(1),
+        Screen::new(
+            "From address",
+            "cosmos1ulav3hsenupswqfkw2y3sup5kgtqwnvqa8eyhs",
+        )
+        .with_indent(2),
+        Screen::new("To address", "cosmos1ejrf4cur2wy6kfurg9f2jppp2h3afe5h6pkh5t").with_indent(2),
+        Screen::new("Amount", "10 ATOM").with_indent(2),
+        Screen::content("End of Message"),
+        Screen::new("Memo", "GM"),
+        Screen::new("Fees", "0.002 ATOM"),
+        Screen::new("Gas limit", "100'000").expert(),
+        Screen::new(
+            "Hash of raw bytes",
+            "9A1D77D352A1927268EEB286CECB697851398ECB7C2485C8BC8BFB7CBA0A552C",
+        )
+        .expert(),
+    ];
+    assert_eq!(screens, expected);
+}
+
+#[test]
+fn test_render_tx_screens_nested_message() {
+    let coin = test_coin_context();
+
+    let transfer_msg = Proto::mod_Message::Transfer {
+        source_port: "transfer".into(),
+        source_channel: "channel-141".into(),
+        token: Some(make_amount("uatom", "1500000")),
+        sender: ADDRESS.into(),
+        receiver: "osmo18s0hdnsllgcclweu9aymw4ngktr2k0rkvn7jmn".into(),
+        timeout_height: Some(Proto::Height {
+            revision_number: 1,
+            revision_height: 8800000,
+        }),
+        ..Proto::mod_Message::Transfer::default()
+    };
+    let mut input = textual_input();
+    input.public_key = PUBLIC_KEY.decode_hex().unwrap().into();
+    input.messages = vec![make_message(MessageEnum::transfer_tokens_message(
+        transfer_msg,
+    ))];
+
+    let unsigned_tx =
+        TxBuilder::<StandardCosmosContext>::unsigned_tx_from_proto(&coin, &input).unwrap();
+    let registry = TxBuilder::<StandardCosmosContext>::denom_metadata_from_proto(&input);
+    let screens = TextualRenderer::render_tx(&unsigned_tx, ADDRESS, &registry).unwrap();
+
+    // Fields are rendered in the order of their field numbers, nested messages are indented.
+    let expected = vec![
+        Screen::new("Message (1/1)", "/ibc.applications.transfer.v1.MsgTransfer").with_indent(1),
+        Screen::new("Source port", "transfer").with_indent(2),
+        Screen::new("Source channel", "channel-141").with_indent(2),
+        Screen::new("Token", "1.5 atom").with_indent(2),
+        Screen::new("Sender", ADDRESS).with_indent(2),
+        Screen::new("Receiver", "osmo18s0hdnsllgcclweu9aymw4ngktr2k0rkvn7jmn").with_indent(2),
+        Screen::new("Timeout height", "Height object").with_indent(2),
+        Screen::new("Revision number", "1").with_indent(3),
+        Screen::new("Revision height", "8'800'000").with_indent(3),
+        Screen::content("End of Message"),
+    ];
+    assert_eq!(screens[7..17], expected);
+}
+
+#[test]
+fn test_sign_textual() {
+    let coin = test_coin_context();
+    let mut input = textual_input();
+    input.private_key = PRIVATE_KEY.decode_hex().unwrap().into();
+
+    let output = TWSigner::<StandardCosmosContext>::sign(&coin, input.clone());
+    assert_eq!(output.error, SigningError::OK);
+
+    let broadcast: serde_json::Value = serde_json::from_str(&output.serialized).unwrap();
+    let tx_bytes = base64::decode(broadcast["tx_bytes"].as_str().unwrap(), false).unwrap();
+    let tx_raw: tx_proto::TxRaw = deserialize(&tx_bytes).unwrap();
+    assert_eq!(tx_raw.signatures, vec![output.signature.to_vec()]);
+
+    // The signer info has the `SIGN_MODE_TEXTUAL` mode info.
+    let auth_info: tx_proto::AuthInfo = deserialize(&tx_raw.auth_info_bytes).unwrap();
+    let mode_info = auth_info.signer_infos[0].mode_info.clone().unwrap();
+    let tx_proto::mod_ModeInfo::OneOfsum::single(single) = mode_info.sum else {
+        panic!("Expected a single mode info");
+    };
+    assert_eq!(single.mode, signing_proto::SignMode::SIGN_MODE_TEXTUAL);
+
+    // The signature is over the CBOR-encoded screens.
+    input.public_key = PUBLIC_KEY.decode_hex().unwrap().into();
+    let preimage = TWTransactionCompiler::<StandardCosmosContext>::preimage_hashes(&coin, input);
+    assert_eq!(preimage.error, SigningError::OK);
+    assert_eq!(preimage.data_hash.to_vec(), sha256(&preimage.data));
+    // `{1: [16 screens]}` - `a1 01 90`.
+    assert_eq!(preimage.data[..3].to_vec().to_hex(), "a10190");
+
+    // The screens contain the hash of the broadcasted raw bytes, prefixed with their lengths.
+    let mut raw_bytes = Vec::new();
+    for bytes in [tx_raw.body_bytes, tx_raw.auth_info_bytes] {
+        raw_bytes.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+        raw_bytes.extend_from_slice(&bytes);
+    }
+    let raw_bytes_hash = sha256(&raw_bytes).to_hex().to_uppercase();
+    let preimage_data = String::from_utf8_lossy(&preimage.data);
+    assert!(preimage_data.contains(&raw_bytes_hash));
+}
+
+#[test]
+fn test_sign_textual_not_supported() {
+    let coin = test_coin_context();
+
+    let mut input = textual_input();
+    input.private_key = PRIVATE_KEY.decode_hex().unwrap().into();
+    input.multisig = Some(Proto::Multisig {
+        threshold: 1,
+        public_keys: vec![PUBLIC_KEY.decode_hex().unwrap().into()],
+        signers: vec![PUBLIC_KEY.decode_hex().unwrap().into()],
+    });
+
+    let output = TWSigner::<StandardCosmosContext>::sign(&coin, input);
+    assert_eq!(output.error, SigningError::Error_not_supported);
+
+    let mut input = textual_input();
+    input.private_key = PRIVATE_KEY.decode_hex().unwrap().into();
+    input.messages = vec![make_message(MessageEnum::sign_direct_message(
+        Proto::mod_Message::SignDirect {
+            body_bytes: Cow::default(),
+            auth_info_bytes: Cow::default(),
+        },
+    ))];
+
+    let output = TWSigner::<StandardCosmosContext>::sign(&coin, input);
+    assert_eq!(output.error, SigningError::Error_not_supported);
+}
+
+#[test]
+fn test_sign_textual_message_not_supported() {
+    let coin = test_coin_context();
+
+    // Messages without a SIGN_MODE_TEXTUAL renderer cannot be signed.
+    let mut input = textual_input();
+    input.private_key = PRIVATE_KEY.decode_hex().unwrap().into();
+    input.messages = vec![make_message(
+        MessageEnum::msg_osmosis_collect_spread_rewards(
+            Proto::mod_Message::MsgOsmosisCollectSpreadRewards {
+                position_ids: vec![1],
+                sender: ADDRESS.into(),
+            },
+        ),
+    )];
+
+    let output = TWSigner::<StandardCosmosContext>::sign(&coin, input);
+    assert_eq!(output.error, SigningError::Error_not_supported);
+}
@@ -5,14 +5,17 @@
 use serde_json::json;
 use std::borrow::Cow;
 use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::address::Address;
 use tw_cosmos_sdk::context::StandardCosmosContext;
 use tw_cosmos_sdk::modules::tx_builder::TxBuilder;
 use tw_cosmos_sdk::test_utils::proto_utils::{make_amount, make_fee, make_message};
 use tw_cosmos_sdk::test_utils::sign_utils::{test_sign_json, test_sign_protobuf, TestInput};
+use tw_cosmos_sdk::transaction::message::wasm_message::instantiate2_address;
 use tw_encoding::base64;
-use tw_encoding::hex::DecodeHex;
+use tw_encoding::hex::{DecodeHex, ToHex};
 use tw_keypair::tw::PublicKeyType;
 use tw_number::U256;
+use tw_proto::Common::Proto::SigningError;
 use tw_proto::Cosmos::Proto;
 use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
 
@@ -249,3 +252,251 @@ fn test_terra_transfer_payload() {
     });
     assert_eq!(actual.value, expected);
 }
+
+const NEUTRON_SENDER: &str = "neutron19h42zjnls2tpmg6yylcg6nr56cjxcx35q6xt57";
+const NEUTRON_CONTRACT: &str = "neutron1465d8udjudl6cd8kgdlh2s37p7q0cf9x7yveumqwqk6ng94qwnmq7n79qn";
+const NEUTRON_PREDICTED_CONTRACT: &str =
+    "neutron18s5zylrg5s94yhjtsdq9gmp3n9ed0qn6zxchsyaldmv73we3gnvq3g8ayc";
+const CODE_CHECKSUM: &str = "13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2a5";
+
+fn neutron_coin() -> TestCoinContext {
+    TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("neutron")
+}
+
+fn make_instantiate() -> Proto::mod_Message::WasmInstantiateContract<'static> {
+    Proto::mod_Message::WasmInstantiateContract {
+        sender_address: NEUTRON_SENDER.into(),
+        admin_address: NEUTRON_SENDER.into(),
+        code_id: 1234,
+        label: "cw20".into(),
+        instantiate_msg: r#"{"name":"Token"}"#.into(),
+        coins: vec![make_amount("untrn", "1000")],
+        ..Proto::mod_Message::WasmInstantiateContract::default()
+    }
+}
+
+#[test]
+fn test_wasm_instantiate_contract() {
+    let coin = neutron_coin();
+
+    let payload = TxBuilder::<StandardCosmosContext>::wasm_instantiate_contract_msg_from_proto(
+        &coin,
+        &make_instantiate(),
+    )
+    .unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(actual.type_url, "/cosmwasm.wasm.v1.MsgInstantiateContract");
+    assert_eq!(actual.value.to_hex(), "0a2e6e657574726f6e31396834327a6a6e6c733274706d673679796c6367366e723536636a7863783335713678743537122e6e657574726f6e31396834327a6a6e6c733274706d673679796c6367366e723536636a786378333571367874353718d2092204637732302a107b226e616d65223a22546f6b656e227d320d0a05756e74726e120431303030");
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "wasm/MsgInstantiateContract");
+    let expected = json!({
+        "admin": NEUTRON_SENDER,
+        "code_id": "1234",
+        "funds": [{"amount": "1000", "denom": "untrn"}],
+        "label": "cw20",
+        "msg": {"name": "Token"},
+        "sender": NEUTRON_SENDER
+    });
+    assert_eq!(actual.value, expected);
+}
+
+#[test]
+fn test_wasm_instantiate_contract2() {
+    let coin = neutron_coin();
+
+    let instantiate = Proto::mod_Message::WasmInstantiateContract {
+        salt: b"salt".to_vec().into(),
+        fix_msg: true,
+        ..make_instantiate()
+    };
+    let payload = TxBuilder::<StandardCosmosContext>::wasm_instantiate_contract_msg_from_proto(
+        &coin,
+        &instantiate,
+    )
+    .unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(actual.type_url, "/cosmwasm.wasm.v1.MsgInstantiateContract2");
+    assert_eq!(actual.value.to_hex(), "0a2e6e657574726f6e31396834327a6a6e6c733274706d673679796c6367366e723536636a7863783335713678743537122e6e657574726f6e31396834327a6a6e6c733274706d673679796c6367366e723536636a786378333571367874353718d2092204637732302a107b226e616d65223a22546f6b656e227d320d0a05756e74726e1204313030303a0473616c744001");
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "wasm/MsgInstantiateContract2");
+    let expected = json!({
+        "admin": NEUTRON_SENDER,
+        "code_id": "1234",
+        "fix_msg": true,
+        "funds": [{"amount": "1000", "denom": "untrn"}],
+        "label": "cw20",
+        "msg": {"name": "Token"},
+        "salt": "c2FsdA==",
+        "sender": NEUTRON_SENDER
+    });
+    assert_eq!(actual.value, expected);
+
+    // The salt must be 1 to 64 bytes long.
+    let instantiate = Proto::mod_Message::WasmInstantiateContract {
+        salt: vec![1; 65].into(),
+        ..make_instantiate()
+    };
+    let err = TxBuilder::<StandardCosmosContext>::wasm_instantiate_contract_msg_from_proto(
+        &coin,
+        &instantiate,
+    )
+    .err()
+    .unwrap();
+    assert_eq!(err.0, SigningError::Error_invalid_params);
+}
+
+#[test]
+fn test_wasm_instantiate2_address() {
+    let checksum = CODE_CHECKSUM.decode_hex().unwrap();
+
+    // Test vector from https://github.com/CosmWasm/wasmd/blob/main/x/wasm/keeper/testdata/build_address_test_vectors.json
+    let creator: Address = "purple1nxvenxve42424242hwamhwamenxvenxvhxf2py"
+        .parse()
+        .unwrap();
+    let address = instantiate2_address(&checksum, &creator, &[0x61], &[]).unwrap();
+    assert_eq!(
+        address.to_string(),
+        "purple1t6r960j945lfv8mhl4mage2rg97w63xeynwrupum2s2l7em4lprs9ce5hk"
+    );
+
+    // The instantiate message is included if `fix_msg` is set.
+    let creator: Address = NEUTRON_SENDER.parse().unwrap();
+    let address =
+        instantiate2_address(&checksum, &creator, b"salt", br#"{"name":"Token"}"#).unwrap();
+    assert_eq!(address.to_string(), NEUTRON_PREDICTED_CONTRACT);
+    let address = instantiate2_address(&checksum, &creator, b"salt", &[]).unwrap();
+    assert_eq!(
+        address.to_string(),
+        "neutron1h2jselaaec2daan2xztcr6n956wk7r4y0jzc90e0zh9dnlrr9n4q8fycn6"
+    );
+
+    // Invalid checksum and salt.
+    instantiate2_address(&checksum[1..], &creator, b"salt", &[]).unwrap_err();
+    instantiate2_address(&checksum, &creator, &[], &[]).unwrap_err();
+}
+
+#[test]
+fn test_wasm_migrate_contract() {
+    let coin = neutron_coin();
+
+    let migrate = Proto::mod_Message::WasmMigrateContract {
+        sender_address: NEUTRON_SENDER.into(),
+        contract_address: NEUTRON_CONTRACT.into(),
+        code_id: 1235,
+        migrate_msg: "{}".into(),
+    };
+    let payload =
+        TxBuilder::<StandardCosmosContext>::wasm_migrate_contract_msg_from_proto(&coin, &migrate)
+            .unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(actual.type_url, "/cosmwasm.wasm.v1.MsgMigrateContract");
+    assert_eq!(actual.value.to_hex(), "0a2e6e657574726f6e31396834327a6a6e6c733274706d673679796c6367366e723536636a786378333571367874353712426e657574726f6e31343635643875646a75646c366364386b67646c6832733337703771306366397837797665756d7177716b366e67393471776e6d71376e3739716e18d30922027b7d");
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "wasm/MsgMigrateContract");
+    let expected = json!({
+        "code_id": "1235",
+        "contract": NEUTRON_CONTRACT,
+        "msg": {},
+        "sender": NEUTRON_SENDER
+    });
+    assert_eq!(actual.value, expected);
+}
+
+#[test]
+fn test_wasm_update_and_clear_admin() {
+    let coin = neutron_coin();
+
+    let update = Proto::mod_Message::WasmUpdateAdmin {
+        sender_address: NEUTRON_SENDER.into(),
+        new_admin_address: NEUTRON_PREDICTED_CONTRACT.into(),
+        contract_address: NEUTRON_CONTRACT.into(),
+    };
+    let payload =
+        TxBuilder::<StandardCosmosContext>::wasm_update_admin_msg_from_proto(&coin, &update)
+            .unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(actual.type_url, "/cosmwasm.wasm.v1.MsgUpdateAdmin");
+    assert_eq!(actual.value.to_hex(), "0a2e6e657574726f6e31396834327a6a6e6c733274706d673679796c6367366e723536636a786378333571367874353712426e657574726f6e313873357a796c72673573393479686a7473647139676d70336e39656430716e367a7863687379616c646d763733776533676e76713367386179631a426e657574726f6e31343635643875646a75646c366364386b67646c6832733337703771306366397837797665756d7177716b366e67393471776e6d71376e3739716e");
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "wasm/MsgUpdateAdmin");
+    let expected = json!({
+        "contract": NEUTRON_CONTRACT,
+        "new_admin": NEUTRON_PREDICTED_CONTRACT,
+        "sender": NEUTRON_SENDER
+    });
+    assert_eq!(actual.value, expected);
+
+    let clear = Proto::mod_Message::WasmClearAdmin {
+        sender_address: NEUTRON_SENDER.into(),
+        contract_address: NEUTRON_CONTRACT.into(),
+    };
+    let payload =
+        TxBuilder::<StandardCosmosContext>::wasm_clear_admin_msg_from_proto(&coin, &clear).unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(actual.type_url, "/cosmwasm.wasm.v1.MsgClearAdmin");
+    assert_eq!(actual.value.to_hex(), "0a2e6e657574726f6e31396834327a6a6e6c733274706d673679796c6367366e723536636a78637833357136787435371a426e657574726f6e31343635643875646a75646c366364386b67646c6832733337703771306366397837797665756d7177716b366e67393471776e6d71376e3739716e");
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "wasm/MsgClearAdmin");
+    let expected = json!({
+        "contract": NEUTRON_CONTRACT,
+        "sender": NEUTRON_SENDER
+    });
+    assert_eq!(actual.value, expected);
+}
+
+#[test]
+fn test_wasm_store_code() {
+    let coin = neutron_coin();
+
+    // Gzip-compressed WASM byte code header.
+    let store = Proto::mod_Message::WasmStoreCode {
+        sender_address: NEUTRON_SENDER.into(),
+        wasm_byte_code: "1f8b0800".decode_hex().unwrap().into(),
+    };
+    let payload =
+        TxBuilder::<StandardCosmosContext>::wasm_store_code_msg_from_proto(&coin, &store).unwrap();
+
+    let actual = payload.to_proto().unwrap();
+    assert_eq!(actual.type_url, "/cosmwasm.wasm.v1.MsgStoreCode");
+    assert_eq!(
+        actual.value.to_hex(),
+        "0a2e6e657574726f6e31396834327a6a6e6c733274706d673679796c6367366e723536636a786378333571367874353712041f8b0800"
+    );
+
+    let actual = payload.to_json().unwrap();
+    assert_eq!(actual.msg_type, "wasm/MsgStoreCode");
+    let expected = json!({
+        "sender": NEUTRON_SENDER,
+        "wasm_byte_code": "H4sIAA=="
+    });
+    assert_eq!(actual.value, expected);
+
+    // Raw WASM byte code is accepted as well.
+    let store = Proto::mod_Message::WasmStoreCode {
+        sender_address: NEUTRON_SENDER.into(),
+        wasm_byte_code: b"\0asm\x01\0\0\0".to_vec().into(),
+    };
+    TxBuilder::<StandardCosmosContext>::wasm_store_code_msg_from_proto(&coin, &store).unwrap();
+
+    // Neither WASM nor gzip.
+    let store = Proto::mod_Message::WasmStoreCode {
+        sender_address: NEUTRON_SENDER.into(),
+        wasm_byte_code: b"code".to_vec().into(),
+    };
+    let err = TxBuilder::<StandardCosmosContext>::wasm_store_code_msg_from_proto(&coin, &store)
+        .err()
+        .unwrap();
+    assert_eq!(err.0, SigningError::Error_invalid_params);
+}
@@ -27,3 +27,15 @@ pub fn decode(s: &str) -> Bech32Result<Decoded> {
     }
     Err(Bech32Error::InvalidChecksum)
 }
+
+/// Decodes a Bech32 string without converting the data part to bytes.
+/// Each byte of `bytes` holds one 5-bit word.
+pub fn decode_words(s: &str) -> Bech32Result<Decoded> {
+    let (hrp, base32_bytes, variant) = bech32::decode(s)?;
+
+    if matches!(variant, Variant::Bech32) {
+        let bytes = base32_bytes.into_iter().map(|word| word.to_u8()).collect();
+        return Ok(Decoded { hrp, bytes });
+    }
+    Err(Bech32Error::InvalidChecksum)
+}
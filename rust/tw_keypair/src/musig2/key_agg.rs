@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::ecdsa::secp256k1::PublicKey;
+use crate::musig2::{
+    has_even_y, point_from_compressed, point_x_bytes, scalar_from_bytes, scalar_from_hash,
+    tagged_hash,
+};
+use crate::{KeyPairError, KeyPairResult};
+use k256::ecdsa::VerifyingKey;
+use k256::{ProjectivePoint, Scalar};
+use tw_hash::{H256, H264};
+
+/// Sorts the public keys lexicographically by their compressed representation
+/// (`KeySort` in BIP-327).
+pub fn sort_public_keys(public_keys: &mut [PublicKey]) {
+    public_keys.sort_by(|a, b| a.compressed().as_slice().cmp(b.compressed().as_slice()));
+}
+
+/// The aggregated public key of all signers, including the tweaks applied to it.
+pub struct KeyAggContext {
+    public_keys: Vec<H264>,
+    keys_hash: H256,
+    second_key: Option<H264>,
+    /// The aggregated (and tweaked) public key.
+    q: ProjectivePoint,
+    /// The accumulated sign of the tweaks.
+    gacc: Scalar,
+    /// The accumulated tweak.
+    tacc: Scalar,
+}
+
+impl KeyAggContext {
+    /// Aggregates the public keys in the given order (`KeyAgg` in BIP-327).
+    /// Use [`sort_public_keys`] to make the result independent of the order.
+    pub fn new(public_keys: &[PublicKey]) -> KeyPairResult<KeyAggContext> {
+        if public_keys.is_empty() {
+            return Err(KeyPairError::InvalidPublicKey);
+        }
+
+        let public_keys: Vec<H264> = public_keys.iter().map(PublicKey::compressed).collect();
+
+        let serialized: Vec<u8> = public_keys
+            .iter()
+            .flat_map(|public| public.take())
+            .collect();
+        let keys_hash = tagged_hash("KeyAgg list", &serialized);
+        let second_key = public_keys
+            .iter()
+            .find(|public| **public != public_keys[0])
+            .copied();
+
+        let mut ctx = KeyAggContext {
+            public_keys,
+            keys_hash,
+            second_key,
+            q: ProjectivePoint::IDENTITY,
+            gacc: Scalar::ONE,
+            tacc: Scalar::ZERO,
+        };
+
+        let mut q = ProjectivePoint::IDENTITY;
+        for public in &ctx.public_keys {
+            q += point_from_compressed(public.as_slice())? * ctx.coefficient(public);
+        }
+
+        if q == ProjectivePoint::IDENTITY {
+            return Err(KeyPairError::InvalidPublicKey);
+        }
+
+        ctx.q = q;
+        Ok(ctx)
+    }
+
+    /// Applies a plain or an X-only tweak to the aggregated public key
+    /// (`ApplyTweak` in BIP-327).
+    pub fn with_tweak(mut self, tweak: &H256, is_xonly: bool) -> KeyPairResult<KeyAggContext> {
+        let g = if is_xonly && !has_even_y(&self.q) {
+            -Scalar::ONE
+        } else {
+            Scalar::ONE
+        };
+
+        let t = scalar_from_bytes(tweak.as_slice()).map_err(|_| KeyPairError::InvalidSecretKey)?;
+
+        let q = self.q * g + ProjectivePoint::GENERATOR * t;
+        if q == ProjectivePoint::IDENTITY {
+            return Err(KeyPairError::InvalidPublicKey);
+        }
+
+        self.q = q;
+        self.gacc = g * self.gacc;
+        self.tacc = t + g * self.tacc;
+        Ok(self)
+    }
+
+    /// Applies the BIP-341 Taproot tweak, so the aggregated public key can be
+    /// used as the output key of a P2TR output. The Merkle root is `None` for
+    /// outputs without a script tree.
+    pub fn with_taproot_tweak(self, merkle_root: Option<&H256>) -> KeyPairResult<KeyAggContext> {
+        let mut data = point_x_bytes(&self.q).into_vec();
+        if let Some(merkle_root) = merkle_root {
+            data.extend_from_slice(merkle_root.as_slice());
+        }
+
+        let tweak = tagged_hash("TapTweak", &data);
+        self.with_tweak(&tweak, true)
+    }
+
+    /// Returns the aggregated public key.
+    pub fn public_key(&self) -> PublicKey {
+        let public = VerifyingKey::from_affine(self.q.to_affine())
+            .expect("Aggregated public key is never the point at infinity");
+        PublicKey::new(public)
+    }
+
+    /// Returns the X-only aggregated public key, such as the output key of a P2TR output.
+    pub fn x_only_public_key(&self) -> H256 {
+        point_x_bytes(&self.q)
+    }
+
+    /// Whether the public key is one of the signers.
+    pub fn contains(&self, public: &H264) -> bool {
+        self.public_keys.contains(public)
+    }
+
+    pub(crate) fn q(&self) -> &ProjectivePoint {
+        &self.q
+    }
+
+    pub(crate) fn gacc(&self) -> &Scalar {
+        &self.gacc
+    }
+
+    pub(crate) fn tacc(&self) -> &Scalar {
+        &self.tacc
+    }
+
+    /// The key aggregation coefficient of the given public key
+    /// (`KeyAggCoeffInternal` in BIP-327).
+    pub(crate) fn coefficient(&self, public: &H264) -> Scalar {
+        if self.second_key.as_ref() == Some(public) {
+            return Scalar::ONE;
+        }
+
+        let mut data = self.keys_hash.into_vec();
+        data.extend_from_slice(public.as_slice());
+        scalar_from_hash(&tagged_hash("KeyAgg coefficient", &data))
+    }
+}
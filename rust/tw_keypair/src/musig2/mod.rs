@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! MuSig2 n-of-n multi-signatures over `secp256k1`, as specified in
+//! [BIP-327](https://github.com/bitcoin/bips/blob/master/bip-0327.mediawiki).
+//! The aggregated signature is an ordinary BIP-340 Schnorr signature.
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use tw_keypair::musig2::{aggregate_nonces, generate_nonce, KeyAggContext, Session};
+//!
+//! // Every signer aggregates the public keys, tweaked for a Taproot key-path spend.
+//! let key_agg = KeyAggContext::new(&public_keys)?.with_taproot_tweak(None)?;
+//!
+//! // Round 1: every signer generates a nonce and shares the public part.
+//! let (secnonce, pubnonce) = generate_nonce(rand, Some(&private), &public, None, None, None)?;
+//! let agg_nonce = aggregate_nonces(&pubnonces)?;
+//!
+//! // Round 2: every signer creates a partial signature.
+//! let session = Session::new(&key_agg, &agg_nonce, sighash.as_slice());
+//! let partial = session.sign(secnonce, &private)?;
+//!
+//! // Anyone can aggregate the partial signatures.
+//! let signature = session.aggregate(&partial_signatures)?;
+//! ```
+
+use crate::{KeyPairError, KeyPairResult};
+use k256::elliptic_curve::bigint::U256;
+use k256::elliptic_curve::ops::Reduce;
+use k256::elliptic_curve::point::AffineCoordinates;
+use k256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use k256::elliptic_curve::PrimeField;
+use k256::{AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar};
+use tw_hash::{H256, H264};
+
+mod key_agg;
+mod nonce;
+mod session;
+
+pub use key_agg::{sort_public_keys, KeyAggContext};
+pub use nonce::{aggregate_nonces, generate_nonce, AggregatedNonce, PublicNonce, SecretNonce};
+pub use session::{PartialSignature, Session};
+
+/// BIP-340 tagged hash: `sha256(sha256(tag) || sha256(tag) || data)`.
+pub(crate) fn tagged_hash(tag: &str, data: &[u8]) -> H256 {
+    let tag_hash = tw_hash::sha2::sha256(tag.as_bytes());
+
+    let mut preimage = Vec::with_capacity(tag_hash.len() * 2 + data.len());
+    preimage.extend_from_slice(&tag_hash);
+    preimage.extend_from_slice(&tag_hash);
+    preimage.extend_from_slice(data);
+
+    H256::try_from(tw_hash::sha2::sha256(&preimage).as_slice())
+        .expect("Expected 32 byte array sha256 hash")
+}
+
+/// Interprets the hash as an integer modulo the curve order.
+pub(crate) fn scalar_from_hash(hash: &H256) -> Scalar {
+    <Scalar as Reduce<U256>>::reduce_bytes(&FieldBytes::from(hash.take()))
+}
+
+/// Interprets the bytes as a scalar, fails if the value exceeds the curve order.
+pub(crate) fn scalar_from_bytes(bytes: &[u8]) -> KeyPairResult<Scalar> {
+    let bytes = H256::try_from(bytes).map_err(|_| KeyPairError::InvalidSignature)?;
+
+    Option::from(Scalar::from_repr(FieldBytes::from(bytes.take())))
+        .ok_or(KeyPairError::InvalidSignature)
+}
+
+/// Parses a compressed point (`cpoint` in BIP-327).
+pub(crate) fn point_from_compressed(bytes: &[u8]) -> KeyPairResult<ProjectivePoint> {
+    if bytes.len() != H264::len() {
+        return Err(KeyPairError::InvalidPublicKey);
+    }
+
+    let encoded = EncodedPoint::from_bytes(bytes).map_err(|_| KeyPairError::InvalidPublicKey)?;
+    Option::<AffinePoint>::from(AffinePoint::from_encoded_point(&encoded))
+        .map(ProjectivePoint::from)
+        .ok_or(KeyPairError::InvalidPublicKey)
+}
+
+/// Serializes a point in compressed form (`cbytes` in BIP-327). Must not be
+/// the point at infinity.
+pub(crate) fn point_to_compressed(point: &ProjectivePoint) -> H264 {
+    let compressed = true;
+    H264::try_from(point.to_affine().to_encoded_point(compressed).as_bytes())
+        .expect("Expected 33 byte array of a non-infinity point")
+}
+
+/// The X coordinate of the point (`xbytes` in BIP-327).
+pub(crate) fn point_x_bytes(point: &ProjectivePoint) -> H256 {
+    H256::try_from(&point.to_affine().x()[..]).expect("Expected 32 byte X coordinate")
+}
+
+pub(crate) fn has_even_y(point: &ProjectivePoint) -> bool {
+    !bool::from(point.to_affine().y_is_odd())
+}
@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::ecdsa::secp256k1::{PrivateKey, PublicKey};
+use crate::musig2::{point_from_compressed, point_to_compressed, scalar_from_hash, tagged_hash};
+use crate::{KeyPairError, KeyPairResult};
+use k256::{ProjectivePoint, Scalar};
+use tw_hash::{Hash, H256, H264};
+use tw_misc::traits::ToBytesZeroizing;
+use zeroize::{Zeroize, Zeroizing};
+
+/// The length of a serialized public or aggregated nonce.
+const NONCE_LEN: usize = 66;
+/// The length of a serialized secret nonce.
+#[cfg(feature = "test-utils")]
+const SECRET_NONCE_LEN: usize = 97;
+
+/// The secret part of a signer's nonce. Must be used exactly once, hence
+/// [`crate::musig2::Session::sign`] consumes it.
+pub struct SecretNonce {
+    k1: Scalar,
+    k2: Scalar,
+    public: H264,
+}
+
+impl SecretNonce {
+    pub(crate) fn k1(&self) -> &Scalar {
+        &self.k1
+    }
+
+    pub(crate) fn k2(&self) -> &Scalar {
+        &self.k2
+    }
+
+    /// The compressed public key of the signer the nonce was generated for.
+    pub(crate) fn public(&self) -> &H264 {
+        &self.public
+    }
+}
+
+impl Drop for SecretNonce {
+    fn drop(&mut self) {
+        self.k1 = Scalar::ZERO;
+        self.k2 = Scalar::ZERO;
+    }
+}
+
+/// Restores a secret nonce from `k1 || k2 || public` (97 bytes), as in the BIP-327 test vectors.
+/// Available in tests only, as restoring a nonce enables signing twice with it.
+#[cfg(feature = "test-utils")]
+impl<'a> TryFrom<&'a [u8]> for SecretNonce {
+    type Error = KeyPairError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != SECRET_NONCE_LEN {
+            return Err(KeyPairError::InvalidSecretKey);
+        }
+
+        let k1 = crate::musig2::scalar_from_bytes(&bytes[0..32])
+            .map_err(|_| KeyPairError::InvalidSecretKey)?;
+        let k2 = crate::musig2::scalar_from_bytes(&bytes[32..64])
+            .map_err(|_| KeyPairError::InvalidSecretKey)?;
+        let public = H264::try_from(&bytes[64..]).map_err(|_| KeyPairError::InvalidPublicKey)?;
+
+        Ok(SecretNonce { k1, k2, public })
+    }
+}
+
+/// The public part of a signer's nonce, shared with all other signers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PublicNonce {
+    pub(crate) r1: ProjectivePoint,
+    pub(crate) r2: ProjectivePoint,
+}
+
+impl PublicNonce {
+    /// Serializes the nonce as two compressed points (66 bytes).
+    pub fn to_bytes(&self) -> Hash<NONCE_LEN> {
+        tw_hash::concat(point_to_compressed(&self.r1), point_to_compressed(&self.r2))
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for PublicNonce {
+    type Error = KeyPairError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != NONCE_LEN {
+            return Err(KeyPairError::InvalidSignature);
+        }
+
+        let r1 =
+            point_from_compressed(&bytes[0..33]).map_err(|_| KeyPairError::InvalidSignature)?;
+        let r2 = point_from_compressed(&bytes[33..]).map_err(|_| KeyPairError::InvalidSignature)?;
+        Ok(PublicNonce { r1, r2 })
+    }
+}
+
+/// The sum of the public nonces of all signers (`NonceAgg` in BIP-327).
+/// Unlike public nonces, the points may be at infinity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AggregatedNonce {
+    pub(crate) r1: ProjectivePoint,
+    pub(crate) r2: ProjectivePoint,
+}
+
+impl AggregatedNonce {
+    /// Serializes the nonce as two compressed points (66 bytes), the point
+    /// at infinity is encoded as 33 zero bytes.
+    pub fn to_bytes(&self) -> Hash<NONCE_LEN> {
+        tw_hash::concat(point_to_bytes_ext(&self.r1), point_to_bytes_ext(&self.r2))
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for AggregatedNonce {
+    type Error = KeyPairError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != NONCE_LEN {
+            return Err(KeyPairError::InvalidSignature);
+        }
+
+        let r1 = point_from_bytes_ext(&bytes[0..33])?;
+        let r2 = point_from_bytes_ext(&bytes[33..])?;
+        Ok(AggregatedNonce { r1, r2 })
+    }
+}
+
+/// Generates a nonce for a signing session (`NonceGen` in BIP-327).
+///
+/// * `rand` - 32 bytes of fresh randomness, must never be reused.
+/// * `private` - the private key of the signer, optional but recommended.
+/// * `public` - the public key of the signer.
+/// * `agg_public` - the X-only aggregated (and tweaked) public key, if known.
+/// * `message` - the message to be signed, if known.
+/// * `extra_in` - any additional input, such as a session identifier.
+pub fn generate_nonce(
+    rand: &H256,
+    private: Option<&PrivateKey>,
+    public: &PublicKey,
+    agg_public: Option<&H256>,
+    message: Option<&[u8]>,
+    extra_in: Option<&[u8]>,
+) -> KeyPairResult<(SecretNonce, PublicNonce)> {
+    let mut rand = match private {
+        Some(private) => {
+            let aux = tagged_hash("MuSig/aux", rand.as_slice());
+            let mut secret = private.to_zeroizing_vec();
+            secret
+                .iter_mut()
+                .zip(aux.as_slice())
+                .for_each(|(byte, aux)| *byte ^= aux);
+            H256::try_from(secret.as_slice()).map_err(|_| KeyPairError::InvalidSecretKey)?
+        },
+        None => *rand,
+    };
+
+    let public = public.compressed();
+    let agg_public = agg_public.map(H256::as_slice).unwrap_or_default();
+    let extra_in = extra_in.unwrap_or_default();
+
+    let mut data = Zeroizing::new(Vec::new());
+    data.extend_from_slice(rand.as_slice());
+    data.push(public.as_slice().len() as u8);
+    data.extend_from_slice(public.as_slice());
+    data.push(agg_public.len() as u8);
+    data.extend_from_slice(agg_public);
+    match message {
+        Some(message) => {
+            data.push(1);
+            data.extend_from_slice(&(message.len() as u64).to_be_bytes());
+            data.extend_from_slice(message);
+        },
+        None => data.push(0),
+    }
+    data.extend_from_slice(&(extra_in.len() as u32).to_be_bytes());
+    data.extend_from_slice(extra_in);
+    rand.zeroize();
+
+    let derive_k = |index: u8| {
+        let mut preimage = data.clone();
+        preimage.push(index);
+        scalar_from_hash(&tagged_hash("MuSig/nonce", &preimage))
+    };
+
+    let k1 = derive_k(0);
+    let k2 = derive_k(1);
+    if bool::from(k1.is_zero()) || bool::from(k2.is_zero()) {
+        return Err(KeyPairError::SigningError);
+    }
+
+    let pubnonce = PublicNonce {
+        r1: ProjectivePoint::GENERATOR * k1,
+        r2: ProjectivePoint::GENERATOR * k2,
+    };
+    let secnonce = SecretNonce { k1, k2, public };
+
+    Ok((secnonce, pubnonce))
+}
+
+/// Aggregates the public nonces of all signers (`NonceAgg` in BIP-327).
+pub fn aggregate_nonces(pubnonces: &[PublicNonce]) -> KeyPairResult<AggregatedNonce> {
+    if pubnonces.is_empty() {
+        return Err(KeyPairError::InvalidSignature);
+    }
+
+    let (r1, r2) = pubnonces.iter().fold(
+        (ProjectivePoint::IDENTITY, ProjectivePoint::IDENTITY),
+        |(r1, r2), nonce| (r1 + nonce.r1, r2 + nonce.r2),
+    );
+
+    Ok(AggregatedNonce { r1, r2 })
+}
+
+/// `cbytes_ext` in BIP-327.
+fn point_to_bytes_ext(point: &ProjectivePoint) -> H264 {
+    if *point == ProjectivePoint::IDENTITY {
+        return H264::default();
+    }
+
+    point_to_compressed(point)
+}
+
+/// `cpoint_ext` in BIP-327.
+fn point_from_bytes_ext(bytes: &[u8]) -> KeyPairResult<ProjectivePoint> {
+    if bytes.iter().all(|byte| *byte == 0) {
+        return Ok(ProjectivePoint::IDENTITY);
+    }
+
+    point_from_compressed(bytes).map_err(|_| KeyPairError::InvalidSignature)
+}
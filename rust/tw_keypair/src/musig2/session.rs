@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::ecdsa::secp256k1::{PrivateKey, PublicKey};
+use crate::musig2::{
+    has_even_y, point_x_bytes, scalar_from_bytes, scalar_from_hash, tagged_hash, AggregatedNonce,
+    KeyAggContext, PublicNonce, SecretNonce,
+};
+use crate::{KeyPairError, KeyPairResult};
+use k256::{ProjectivePoint, Scalar};
+use tw_hash::{H256, H512};
+
+/// A partial signature of a single signer.
+pub type PartialSignature = H256;
+
+/// The values shared by all signers of a message (`GetSessionValues` in BIP-327).
+pub struct Session<'a> {
+    key_agg: &'a KeyAggContext,
+    /// The nonce coefficient.
+    b: Scalar,
+    /// The final nonce.
+    r: ProjectivePoint,
+    /// The BIP-340 challenge.
+    e: Scalar,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(key_agg: &'a KeyAggContext, agg_nonce: &AggregatedNonce, message: &[u8]) -> Self {
+        let q_x = point_x_bytes(key_agg.q());
+
+        let mut data = agg_nonce.to_bytes().into_vec();
+        data.extend_from_slice(q_x.as_slice());
+        data.extend_from_slice(message);
+        let b = scalar_from_hash(&tagged_hash("MuSig/noncecoef", &data));
+
+        let r = agg_nonce.r1 + agg_nonce.r2 * b;
+        let r = if r == ProjectivePoint::IDENTITY {
+            ProjectivePoint::GENERATOR
+        } else {
+            r
+        };
+
+        let mut data = point_x_bytes(&r).into_vec();
+        data.extend_from_slice(q_x.as_slice());
+        data.extend_from_slice(message);
+        let e = scalar_from_hash(&tagged_hash("BIP0340/challenge", &data));
+
+        Session { key_agg, b, r, e }
+    }
+
+    /// Creates a partial signature (`Sign` in BIP-327). The secret nonce is
+    /// consumed, since reusing it leaks the private key.
+    pub fn sign(
+        &self,
+        secnonce: SecretNonce,
+        private: &PrivateKey,
+    ) -> KeyPairResult<PartialSignature> {
+        let (k1, k2) = if has_even_y(&self.r) {
+            (*secnonce.k1(), *secnonce.k2())
+        } else {
+            (-*secnonce.k1(), -*secnonce.k2())
+        };
+
+        if bool::from(k1.is_zero()) || bool::from(k2.is_zero()) {
+            return Err(KeyPairError::SigningError);
+        }
+
+        let public = private.public();
+        let public_bytes = public.compressed();
+        if public_bytes != *secnonce.public() || !self.key_agg.contains(&public_bytes) {
+            return Err(KeyPairError::InvalidSecretKey);
+        }
+
+        let a = self.key_agg.coefficient(&public_bytes);
+        let g = self.g();
+        let d = g * self.key_agg.gacc() * private.secret.as_nonzero_scalar().as_ref();
+
+        let s = k1 + self.b * k2 + self.e * a * d;
+        let psig = PartialSignature::try_from(&s.to_bytes()[..]).expect("Expected 32 byte scalar");
+
+        // Verify the partial signature to protect against faults.
+        let pubnonce = PublicNonce {
+            r1: ProjectivePoint::GENERATOR * secnonce.k1(),
+            r2: ProjectivePoint::GENERATOR * secnonce.k2(),
+        };
+        if !self.verify_partial(&psig, &pubnonce, &public) {
+            return Err(KeyPairError::SigningError);
+        }
+
+        Ok(psig)
+    }
+
+    /// Verifies the partial signature of a signer (`PartialSigVerify` in BIP-327).
+    pub fn verify_partial(
+        &self,
+        psig: &PartialSignature,
+        pubnonce: &PublicNonce,
+        public: &PublicKey,
+    ) -> bool {
+        let Ok(s) = scalar_from_bytes(psig.as_slice()) else {
+            return false;
+        };
+
+        let public_bytes = public.compressed();
+        if !self.key_agg.contains(&public_bytes) {
+            return false;
+        }
+
+        let re = pubnonce.r1 + pubnonce.r2 * self.b;
+        let re = if has_even_y(&self.r) { re } else { -re };
+
+        let a = self.key_agg.coefficient(&public_bytes);
+        let g = self.g() * self.key_agg.gacc();
+        let p = ProjectivePoint::from(*public.public.as_affine());
+
+        ProjectivePoint::GENERATOR * s == re + p * (self.e * a * g)
+    }
+
+    /// Aggregates the partial signatures of all signers into a BIP-340
+    /// Schnorr signature (`PartialSigAgg` in BIP-327).
+    pub fn aggregate(&self, psigs: &[PartialSignature]) -> KeyPairResult<H512> {
+        let mut s = Scalar::ZERO;
+        for psig in psigs {
+            s += scalar_from_bytes(psig.as_slice())?;
+        }
+        s += self.e * self.g() * self.key_agg.tacc();
+
+        let mut signature = point_x_bytes(&self.r).into_vec();
+        signature.extend_from_slice(&s.to_bytes());
+        H512::try_from(signature.as_slice()).map_err(|_| KeyPairError::InvalidSignature)
+    }
+
+    fn g(&self) -> Scalar {
+        if has_even_y(self.key_agg.q()) {
+            Scalar::ONE
+        } else {
+            -Scalar::ONE
+        }
+    }
+}
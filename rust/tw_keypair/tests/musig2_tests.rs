@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use k256::schnorr::signature::hazmat::PrehashVerifier;
+use tw_encoding::hex;
+use tw_hash::H256;
+use tw_keypair::ecdsa::secp256k1::{PrivateKey, PublicKey};
+use tw_keypair::musig2::{
+    aggregate_nonces, generate_nonce, sort_public_keys, AggregatedNonce, KeyAggContext,
+    SecretNonce, Session,
+};
+use tw_keypair::KeyPairError;
+
+fn public(hex: &str) -> PublicKey {
+    PublicKey::try_from(hex).unwrap()
+}
+
+/// Test vectors from BIP-327 `key_agg_vectors.json`.
+#[test]
+fn test_musig2_key_agg() {
+    let x1 = public("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9");
+    let x2 = public("03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659");
+    let x3 = public("023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66");
+
+    let tests = [
+        (
+            vec![x1.clone(), x2.clone(), x3.clone()],
+            "90539EEDE565F5D054F32CC0C220126889ED1E5D193BAF15AEF344FE59D4610C",
+        ),
+        (
+            vec![x3.clone(), x2.clone(), x1.clone()],
+            "6204DE8B083426DC6EAF9502D27024D53FC826BF7D2012148A0575435DF54B2B",
+        ),
+        (
+            vec![x1.clone(), x1.clone(), x1.clone()],
+            "B436E3BAD62B8CD409969A224731C193D051162D8C5AE8B109306127DA3AA935",
+        ),
+        (
+            vec![x1.clone(), x1, x2.clone(), x2],
+            "69BC22BFA5D106306E48A20679DE1D7389386124D07571D0D872686028C26A3E",
+        ),
+    ];
+
+    for (public_keys, expected) in tests {
+        let key_agg = KeyAggContext::new(&public_keys).unwrap();
+        assert_eq!(key_agg.x_only_public_key(), H256::from(expected));
+    }
+
+    assert_eq!(
+        KeyAggContext::new(&[]).err(),
+        Some(KeyPairError::InvalidPublicKey)
+    );
+}
+
+/// Test vectors from BIP-327 `sign_verify_vectors.json`.
+#[test]
+fn test_musig2_sign() {
+    let private =
+        PrivateKey::try_from("7FB9E0E687ADA1EEBF7ECFE2F21E73EBDB51A7D450948DFE8D76D7F2D1007671")
+            .unwrap();
+    let public_keys = [
+        public("03935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9"),
+        public("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"),
+        public("02DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA661"),
+    ];
+    let secnonce = "508B81A611F100A6B2B6B29656590898AF488BCF2E1F55CF22E5CFB84421FE61FA27FD49B1D50085B481285E1CA205D55C82CC1B31FF5CD54A489829355901F703935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9";
+    let agg_nonce = "028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61037496A3CC86926D452CAFCFD55D25972CA1675D549310DE296BFF42F72EEEA8C9";
+    let message =
+        hex::decode("F95466D086770E689964664219266FE5ED215C92AE20BAB5C9D79ADDDDF3C0CF").unwrap();
+
+    let tests = [
+        (
+            [0, 1, 2],
+            "012ABBCB52B3016AC03AD82395A1A415C48B93DEF78718E62A7A90052FE224FB",
+        ),
+        (
+            [1, 0, 2],
+            "9FF2F7AAA856150CC8819254218D3ADEEB0535269051897724F9DB3789513A52",
+        ),
+        (
+            [1, 2, 0],
+            "FA23C359F6FAC4E7796BB93BC9F0532A95468C539BA20FF86D7C76ED92227900",
+        ),
+    ];
+
+    let agg_nonce = AggregatedNonce::try_from(hex::decode(agg_nonce).unwrap().as_slice()).unwrap();
+
+    for (key_indices, expected) in tests {
+        let public_keys: Vec<_> = key_indices
+            .iter()
+            .map(|index| public_keys[*index].clone())
+            .collect();
+        let key_agg = KeyAggContext::new(&public_keys).unwrap();
+        let secnonce = SecretNonce::try_from(hex::decode(secnonce).unwrap().as_slice()).unwrap();
+
+        let session = Session::new(&key_agg, &agg_nonce, &message);
+        let partial = session.sign(secnonce, &private).unwrap();
+        assert_eq!(partial, H256::from(expected));
+    }
+}
+
+#[test]
+fn test_musig2_taproot_sign_aggregate() {
+    let privates: Vec<_> = [
+        "afeefca74d9a325cf1d6b6911d61a65c32afa8e02bd5e78e2e4ac2910bab45f5",
+        "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a",
+        "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3",
+    ]
+    .into_iter()
+    .map(|secret| PrivateKey::try_from(secret).unwrap())
+    .collect();
+
+    let mut public_keys: Vec<_> = privates.iter().map(PrivateKey::public).collect();
+    sort_public_keys(&mut public_keys);
+
+    let key_agg = KeyAggContext::new(&public_keys)
+        .unwrap()
+        .with_taproot_tweak(None)
+        .unwrap();
+    let agg_public = key_agg.x_only_public_key();
+
+    let message = H256::from("7d6f8ec4f3ff5b4b8fc2b6e2d38b7a5ad8b0f4e6f8a3cc5c9d1d4e8a1b2c3d4e");
+
+    // Round 1.
+    let (secnonces, pubnonces): (Vec<_>, Vec<_>) = privates
+        .iter()
+        .enumerate()
+        .map(|(i, private)| {
+            let rand = H256::from([i as u8 + 1; 32]);
+            generate_nonce(
+                &rand,
+                Some(private),
+                &private.public(),
+                Some(&agg_public),
+                Some(message.as_slice()),
+                None,
+            )
+            .unwrap()
+        })
+        .unzip();
+    let agg_nonce = aggregate_nonces(&pubnonces).unwrap();
+
+    // Round 2.
+    let session = Session::new(&key_agg, &agg_nonce, message.as_slice());
+    let partials: Vec<_> = privates
+        .iter()
+        .zip(secnonces)
+        .zip(pubnonces.iter())
+        .map(|((private, secnonce), pubnonce)| {
+            let partial = session.sign(secnonce, private).unwrap();
+            assert!(session.verify_partial(&partial, pubnonce, &private.public()));
+            partial
+        })
+        .collect();
+
+    // A partial signature does not verify for a different signer.
+    assert!(!session.verify_partial(&partials[0], &pubnonces[1], &privates[1].public()));
+
+    let signature = session.aggregate(&partials).unwrap();
+
+    // The aggregated signature is an ordinary BIP-340 signature.
+    let verifying_key = k256::schnorr::VerifyingKey::from_bytes(agg_public.as_slice()).unwrap();
+    let signature = k256::schnorr::Signature::try_from(signature.as_slice()).unwrap();
+    verifying_key
+        .verify_prehash(message.as_slice(), &signature)
+        .unwrap();
+}
@@ -3,7 +3,9 @@
 // Copyright © 2017 Trust Wallet.
 
 mod any;
+mod duration;
 mod timestamp;
 
 pub use any::*;
+pub use duration::*;
 pub use timestamp::*;
@@ -1,3 +1,4 @@
+use crate::forks::{self, sighash_algorithm};
 use crate::{Error, Result};
 use bitcoin::blockdata::locktime::absolute::{Height, LockTime, Time};
 use bitcoin::consensus::Encodable;
@@ -10,26 +11,73 @@ use tw_proto::Utxo::Proto::{self, SighashType};
 
 type ProtoLockTimeVariant = Proto::mod_LockTime::OneOfvariant;
 type ProtoSigningMethod = Proto::SigningMethod;
+type ProtoSighashAlgorithm = Proto::mod_Chain::SighashAlgorithm;
 
+/// The chain-specific parts of the compiler, i.e. the signature hash algorithm
+/// and the transaction format. The default methods follow Bitcoin.
 pub trait UtxoContext {
     type SigningInput<'a>;
     type SigningOutput;
     type PreSigningOutput;
+
+    /// The network parameters of the chain, such as the Zcash consensus branch id.
+    type Params;
+
+    /// Reads the network parameters. Fails if the chain is not handled by the context.
+    fn params(chain: &Option<Proto::Chain>) -> Result<Self::Params>;
+
+    /// Computes the signature hash of the input at `index`, or returns `None`
+    /// if the Bitcoin algorithms apply (legacy, BIP-143 and BIP-341).
+    fn signature_hash(
+        _params: &Self::Params,
+        _tx: &Transaction,
+        _index: usize,
+        _inputs: &[Proto::TxIn<'_>],
+    ) -> Result<Option<[u8; 32]>> {
+        Ok(None)
+    }
+
+    /// Encodes the transaction in the format of the chain.
+    fn encode(_params: &Self::Params, tx: &Transaction) -> Result<Vec<u8>> {
+        let mut buffer = vec![];
+        tx.consensus_encode(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Returns the transaction identifier in the internal byte order.
+    fn txid(_params: &Self::Params, tx: &Transaction) -> Result<[u8; 32]> {
+        Ok(tx.txid().to_byte_array())
+    }
+
+    /// Returns the weight of the transaction.
+    fn weight(_params: &Self::Params, tx: &Transaction) -> Result<u64> {
+        Ok(tx.weight().to_wu())
+    }
 }
 
+/// Bitcoin and the forks that share its transaction format, such as Litecoin
+/// and Dogecoin.
 pub struct StandardBitcoinContext;
 
 impl UtxoContext for StandardBitcoinContext {
     type SigningInput<'a> = Proto::SigningInput<'a>;
     type SigningOutput = Proto::SigningInput<'static>;
     type PreSigningOutput = Proto::SigningInput<'static>;
+    type Params = ();
+
+    fn params(chain: &Option<Proto::Chain>) -> Result<Self::Params> {
+        match sighash_algorithm(chain) {
+            ProtoSighashAlgorithm::Bitcoin => Ok(()),
+            _ => Err(Error::from(Proto::Error::Error_unsupported_chain)),
+        }
+    }
 }
 
 pub struct Compiler<Context: UtxoContext> {
     _phantom: PhantomData<Context>,
 }
 
-impl Compiler<StandardBitcoinContext> {
+impl<Context: UtxoContext> Compiler<Context> {
     #[inline]
     pub fn preimage_hashes(proto: Proto::SigningInput<'_>) -> Proto::PreSigningOutput<'static> {
         Self::preimage_hashes_impl(proto)
@@ -80,13 +128,31 @@ impl Compiler<StandardBitcoinContext> {
             ));
         }
 
+        // Inputs whose inscriptions are transferred deliberately are spent
+        // first. Other protected inputs are never spent.
+        let (transfers, available): (Vec<_>, Vec<_>) = std::mem::take(&mut proto.inputs)
+            .into_iter()
+            .partition(|input| protection_of(input) == Protection::Transfer);
+        let (protected, mut available): (Vec<_>, Vec<_>) = available
+            .into_iter()
+            .partition(|input| protection_of(input) == Protection::Protected);
+
+        // Spending all inputs would spend the protected ones.
+        if proto.input_selector == Proto::InputSelector::UseAll && !protected.is_empty() {
+            return Err(Error::from(Proto::Error::Error_protected_input));
+        }
+
         // If enabled, sort the order of the UTXOs.
         if let Proto::InputSelector::SelectAscending = proto.input_selector {
-            proto.inputs.sort_by(|a, b| a.value.cmp(&b.value));
+            available.sort_by(|a, b| a.value.cmp(&b.value));
         } else if let Proto::InputSelector::SelectDescending = proto.input_selector {
-            proto.inputs.sort_by(|a, b| b.value.cmp(&a.value));
+            available.sort_by(|a, b| b.value.cmp(&a.value));
         }
 
+        let transfer_count = transfers.len();
+        proto.inputs = transfers;
+        proto.inputs.extend(available);
+
         // Add change output generation is enabled, push it to the proto structure.
         if !proto.disable_change_output {
             proto.outputs.push(Proto::TxOut {
@@ -96,6 +162,8 @@ impl Compiler<StandardBitcoinContext> {
             });
         }
 
+        let params = Context::params(&proto.chain)?;
+
         // Prepare the `bitcoin` crate native transaction structure, used for fee calculation.
         let mut tx = Transaction {
             version: proto.version,
@@ -136,8 +204,9 @@ impl Compiler<StandardBitcoinContext> {
                 let mut total_input_weight = 0;
 
                 // For each iteration, we calculate the full fee estimate and
-                // exit when the total amount + fees have been covered.
-                for txin in available {
+                // exit when the total amount + fees have been covered. The
+                // transferred inputs are always selected.
+                for (index, txin) in available.into_iter().enumerate() {
                     let n_txin = convert_proto_to_txin(&txin)?;
                     tx.input.push(n_txin);
 
@@ -161,7 +230,9 @@ impl Compiler<StandardBitcoinContext> {
                     let weight_estimate = tx.weight().to_wu() + total_input_weight;
                     let fee_estimate = (weight_estimate + 3) / 4 * proto.weight_base;
 
-                    if total_input_amount >= total_output_amount + fee_estimate {
+                    if index + 1 >= transfer_count
+                        && total_input_amount >= total_output_amount + fee_estimate
+                    {
                         // Enough inputs to cover the output and fee estimate.
                         break;
                     }
@@ -169,6 +240,10 @@ impl Compiler<StandardBitcoinContext> {
             },
         };
 
+        // The inscribed satoshis of the transferred inputs must be sent to the
+        // first output, instead of being spent as fees or change.
+        check_transfers_preserved(&proto.inputs[..transfer_count], &proto.outputs)?;
+
         // Update the `total input amount based on the selected inputs.
         let total_input_amount: u64 = proto.inputs.iter().map(|input| input.value).sum();
 
@@ -209,6 +284,16 @@ impl Compiler<StandardBitcoinContext> {
         let mut sighashes: Vec<(Vec<u8>, ProtoSigningMethod, Proto::SighashType)> = vec![];
 
         for (index, input) in proto.inputs.iter().enumerate() {
+            // Bitcoin forks use their own signature hash algorithm.
+            if let Some(sighash) = Context::signature_hash(&params, &tx, index, &proto.inputs)? {
+                sighashes.push((
+                    sighash.to_vec(),
+                    ProtoSigningMethod::Legacy,
+                    input.sighash_type,
+                ));
+                continue;
+            }
+
             match input.signing_method {
                 // Use the legacy hashing mechanism (e.g. P2SH, P2PK, P2PKH).
                 ProtoSigningMethod::Legacy => {
@@ -266,14 +351,7 @@ impl Compiler<StandardBitcoinContext> {
                     let sighash_type = TapSighashType::from_consensus_u8(input.sighash_type as u8)
                         .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash_type))?;
 
-                    let prevouts = proto
-                        .inputs
-                        .iter()
-                        .map(|i| TxOut {
-                            value: i.value,
-                            script_pubkey: ScriptBuf::from_bytes(i.script_pubkey.to_vec()),
-                        })
-                        .collect::<Vec<TxOut>>();
+                    let prevouts = forks::prevouts(&proto.inputs);
 
                     let sighash = cache.taproot_signature_hash(
                         index,
@@ -305,13 +383,8 @@ impl Compiler<StandardBitcoinContext> {
                     let sighash_type = TapSighashType::from_consensus_u8(input.sighash_type as u8)
                         .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash_type))?;
 
-                    let prevouts = Prevouts::One(
-                        index,
-                        TxOut {
-                            value: input.value,
-                            script_pubkey: ScriptBuf::from_bytes(input.script_pubkey.to_vec()),
-                        },
-                    );
+                    let prevout = forks::prevouts(std::slice::from_ref(input)).remove(0);
+                    let prevouts = Prevouts::One(index, prevout);
 
                     let sighash = cache.taproot_signature_hash(
                         index,
@@ -333,7 +406,7 @@ impl Compiler<StandardBitcoinContext> {
         let tx = cache.into_transaction();
         // The transaction identifier, which we represent in
         // non-reversed/non-network order.
-        let txid: Vec<u8> = tx.txid().as_byte_array().iter().copied().rev().collect();
+        let txid: Vec<u8> = Context::txid(&params, tx)?.iter().copied().rev().collect();
 
         Ok(Proto::PreSigningOutput {
             error: Proto::Error::OK,
@@ -359,6 +432,8 @@ impl Compiler<StandardBitcoinContext> {
                     signing_method: input.signing_method,
                     sighash_type: input.sighash_type,
                     leaf_hash: input.leaf_hash.to_vec().into(),
+                    protection: input.protection,
+                    prevout_script_pubkey: input.prevout_script_pubkey.to_vec().into(),
                 })
                 .collect(),
             outputs: proto
@@ -392,6 +467,8 @@ impl Compiler<StandardBitcoinContext> {
             return Err(Error::from(Proto::Error::Error_no_outputs_specified));
         }
 
+        let params = Context::params(&proto.chain)?;
+
         let mut tx = Transaction {
             version: proto.version,
             lock_time: lock_time_from_proto(&proto.lock_time)?,
@@ -436,14 +513,12 @@ impl Compiler<StandardBitcoinContext> {
         debug_assert_eq!(tx.output.len(), proto.outputs.len());
 
         // Encode the transaction.
-        let mut buffer = vec![];
-        tx.consensus_encode(&mut buffer)
-            .map_err(|_| Error::from(Proto::Error::Error_failed_encoding))?;
+        let buffer = Context::encode(&params, &tx)?;
 
         // The transaction identifier, which we represent in
         // non-reversed/non-network order.
-        let txid: Vec<u8> = tx.txid().as_byte_array().iter().copied().rev().collect();
-        let weight = tx.weight().to_wu();
+        let txid: Vec<u8> = Context::txid(&params, &tx)?.iter().copied().rev().collect();
+        let weight = Context::weight(&params, &tx)?;
 
         // Calculate the effective fee.
         let total_output_amount = tx.output.iter().map(|out| out.value).sum::<u64>();
@@ -478,6 +553,59 @@ fn convert_proto_to_txin<'a>(proto: &'a Proto::TxIn<'a>) -> Result<TxIn> {
     })
 }
 
+// How the satoshis of an input are protected from being spent as fees or change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protection {
+    None,
+    Protected,
+    Transfer,
+}
+
+fn protection_of(input: &Proto::TxIn<'_>) -> Protection {
+    let Some(protection) = &input.protection else {
+        return Protection::None;
+    };
+
+    if protection.locked {
+        Protection::Protected
+    } else if protection.transfer {
+        Protection::Transfer
+    } else if !protection.inscription_offsets.is_empty() {
+        Protection::Protected
+    } else {
+        Protection::None
+    }
+}
+
+// Checks whether the inscribed satoshis of the transferred inputs, which are
+// spent first, keep their offset within the first output.
+fn check_transfers_preserved(
+    transfers: &[Proto::TxIn<'_>],
+    outputs: &[Proto::TxOut<'_>],
+) -> Result<()> {
+    let first_output_value = outputs.first().map_or(0, |output| output.value);
+
+    let mut input_offset = 0;
+    for input in transfers {
+        let offsets = input
+            .protection
+            .as_ref()
+            .map(|protection| protection.inscription_offsets.as_slice())
+            .unwrap_or_default();
+
+        let preserved = offsets
+            .iter()
+            .all(|offset| *offset < input.value && input_offset + offset < first_output_value);
+        if !preserved {
+            return Err(Error::from(Proto::Error::Error_inscription_not_preserved));
+        }
+
+        input_offset += input.value;
+    }
+
+    Ok(())
+}
+
 // Convenience function to retreive the lock time. If none is provided, the
 // default lock time is used (immediately spendable).
 fn lock_time_from_proto(proto: &Option<Proto::LockTime>) -> Result<LockTime> {
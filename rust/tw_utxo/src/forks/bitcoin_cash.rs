@@ -0,0 +1,121 @@
+use super::{legacy_sighash_type, sighash_algorithm, split_anyone_can_pay};
+use crate::compiler::UtxoContext;
+use crate::{Error, Result};
+use bitcoin::consensus::Encodable;
+use bitcoin::hashes::{sha256d, Hash, HashEngine};
+use bitcoin::sighash::EcdsaSighashType;
+use bitcoin::{Script, Transaction};
+use tw_proto::Utxo::Proto;
+
+type ProtoSighashAlgorithm = Proto::mod_Chain::SighashAlgorithm;
+
+/// The flag that is added to the sighash type of every signature (replay
+/// protection).
+pub const SIGHASH_FORKID: u32 = 0x40;
+
+/// Bitcoin Cash and the chains that share its replay protected signature hashes.
+pub struct BitcoinCashContext;
+
+impl UtxoContext for BitcoinCashContext {
+    type SigningInput<'a> = Proto::SigningInput<'a>;
+    type SigningOutput = Proto::SigningInput<'static>;
+    type PreSigningOutput = Proto::SigningInput<'static>;
+    /// The fork id.
+    type Params = u32;
+
+    fn params(chain: &Option<Proto::Chain>) -> Result<Self::Params> {
+        match (sighash_algorithm(chain), chain) {
+            (ProtoSighashAlgorithm::ForkId, Some(chain)) => Ok(chain.fork_id),
+            _ => Err(Error::from(Proto::Error::Error_unsupported_chain)),
+        }
+    }
+
+    fn signature_hash(
+        fork_id: &Self::Params,
+        tx: &Transaction,
+        index: usize,
+        inputs: &[Proto::TxIn<'_>],
+    ) -> Result<Option<[u8; 32]>> {
+        let input = &inputs[index];
+        let sighash_type = legacy_sighash_type(input)?;
+        let script_code = Script::from_bytes(input.script_pubkey.as_ref());
+
+        signature_hash(tx, index, script_code, input.value, sighash_type, *fork_id).map(Some)
+    }
+
+    /// Bitcoin Cash has no Segwit, a byte simply counts as four weight units.
+    fn weight(fork_id: &Self::Params, tx: &Transaction) -> Result<u64> {
+        Ok(Self::encode(fork_id, tx)?.len() as u64 * 4)
+    }
+}
+
+/// Computes the signature hash of a legacy input based on BIP-143, with the
+/// `SIGHASH_FORKID` flag and the fork id added to the sighash type.
+///
+/// See <https://github.com/bitcoincashorg/bitcoincash.org/blob/master/spec/replay-protected-sighash.md>
+pub fn signature_hash(
+    tx: &Transaction,
+    index: usize,
+    script_code: &Script,
+    value: u64,
+    sighash_type: EcdsaSighashType,
+    fork_id: u32,
+) -> Result<[u8; 32]> {
+    let (base_type, anyone_can_pay) = split_anyone_can_pay(sighash_type);
+    let single = base_type == EcdsaSighashType::Single;
+    let none = base_type == EcdsaSighashType::None;
+
+    let zero = sha256d::Hash::all_zeros();
+
+    let hash_prevouts = if anyone_can_pay {
+        zero
+    } else {
+        let mut engine = sha256d::Hash::engine();
+        for txin in &tx.input {
+            txin.previous_output.consensus_encode(&mut engine)?;
+        }
+        sha256d::Hash::from_engine(engine)
+    };
+
+    let hash_sequence = if anyone_can_pay || single || none {
+        zero
+    } else {
+        let mut engine = sha256d::Hash::engine();
+        for txin in &tx.input {
+            txin.sequence.consensus_encode(&mut engine)?;
+        }
+        sha256d::Hash::from_engine(engine)
+    };
+
+    let hash_outputs = if !single && !none {
+        let mut engine = sha256d::Hash::engine();
+        for txout in &tx.output {
+            txout.consensus_encode(&mut engine)?;
+        }
+        sha256d::Hash::from_engine(engine)
+    } else if single && index < tx.output.len() {
+        let mut engine = sha256d::Hash::engine();
+        tx.output[index].consensus_encode(&mut engine)?;
+        sha256d::Hash::from_engine(engine)
+    } else {
+        zero
+    };
+
+    let txin = &tx.input[index];
+
+    let mut engine = sha256d::Hash::engine();
+    tx.version.consensus_encode(&mut engine)?;
+    engine.input(hash_prevouts.as_byte_array());
+    engine.input(hash_sequence.as_byte_array());
+    txin.previous_output.consensus_encode(&mut engine)?;
+    script_code.consensus_encode(&mut engine)?;
+    value.consensus_encode(&mut engine)?;
+    txin.sequence.consensus_encode(&mut engine)?;
+    engine.input(hash_outputs.as_byte_array());
+    tx.lock_time.consensus_encode(&mut engine)?;
+
+    let sighash_type = sighash_type.to_u32() | SIGHASH_FORKID | (fork_id << 8);
+    sighash_type.consensus_encode(&mut engine)?;
+
+    Ok(sha256d::Hash::from_engine(engine).to_byte_array())
+}
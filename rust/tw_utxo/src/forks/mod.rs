@@ -0,0 +1,68 @@
+//! Transaction formats and signature hash algorithms of Bitcoin forks.
+//!
+//! Litecoin and Dogecoin share the Bitcoin format and are handled by
+//! [`StandardBitcoinContext`](crate::compiler::StandardBitcoinContext), while
+//! Bitcoin Cash ([`bitcoin_cash::BitcoinCashContext`]) and Zcash
+//! ([`zcash::ZcashContext`], transparent transactions only) commit to
+//! different data in their signature hashes.
+
+use crate::{Error, Result};
+use bitcoin::sighash::EcdsaSighashType;
+use bitcoin::{ScriptBuf, TxOut};
+use tw_proto::Utxo::Proto::{self, SighashType};
+
+pub mod bitcoin_cash;
+pub mod zcash;
+
+/// Returns the signature hash algorithm of the chain, Bitcoin by default.
+pub fn sighash_algorithm(chain: &Option<Proto::Chain>) -> Proto::mod_Chain::SighashAlgorithm {
+    chain
+        .as_ref()
+        .map(|chain| chain.sighash_algorithm)
+        .unwrap_or_default()
+}
+
+/// Returns the sighash type of a legacy input. Bitcoin forks only support
+/// legacy inputs (e.g. P2PKH, P2SH).
+pub(crate) fn legacy_sighash_type(input: &Proto::TxIn<'_>) -> Result<EcdsaSighashType> {
+    if input.signing_method != Proto::SigningMethod::Legacy {
+        return Err(Error::from(Proto::Error::Error_invalid_signing_method));
+    }
+
+    if let SighashType::UseDefault = input.sighash_type {
+        return Ok(EcdsaSighashType::All);
+    }
+
+    EcdsaSighashType::from_standard(input.sighash_type as u32)
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash_type))
+}
+
+/// Returns the outputs spent by the inputs. The scriptPubkey of an input
+/// falls back to its script code if the spent output's one is not specified.
+pub(crate) fn prevouts(inputs: &[Proto::TxIn<'_>]) -> Vec<TxOut> {
+    inputs
+        .iter()
+        .map(|input| {
+            let script_pubkey = if input.prevout_script_pubkey.is_empty() {
+                input.script_pubkey.as_ref()
+            } else {
+                input.prevout_script_pubkey.as_ref()
+            };
+
+            TxOut {
+                value: input.value,
+                script_pubkey: ScriptBuf::from_bytes(script_pubkey.to_vec()),
+            }
+        })
+        .collect()
+}
+
+/// Splits the `SIGHASH_ANYONECANPAY` flag off the sighash type.
+pub(crate) fn split_anyone_can_pay(sighash_type: EcdsaSighashType) -> (EcdsaSighashType, bool) {
+    match sighash_type {
+        EcdsaSighashType::AllPlusAnyoneCanPay => (EcdsaSighashType::All, true),
+        EcdsaSighashType::NonePlusAnyoneCanPay => (EcdsaSighashType::None, true),
+        EcdsaSighashType::SinglePlusAnyoneCanPay => (EcdsaSighashType::Single, true),
+        other => (other, false),
+    }
+}
@@ -0,0 +1,436 @@
+use super::{legacy_sighash_type, prevouts, sighash_algorithm, split_anyone_can_pay};
+use crate::compiler::UtxoContext;
+use crate::{Error, Result};
+use bitcoin::consensus::Encodable;
+use bitcoin::hashes::{sha256d, Hash};
+use bitcoin::sighash::EcdsaSighashType;
+use bitcoin::{Script, Transaction, TxOut};
+use tw_hash::blake2::blake2_b_personal;
+use tw_proto::Utxo::Proto;
+
+type ProtoSighashAlgorithm = Proto::mod_Chain::SighashAlgorithm;
+
+/// The overwintered flag of the transaction header.
+const OVERWINTERED_FLAG: u32 = 1 << 31;
+
+pub const SAPLING_VERSION_GROUP_ID: u32 = 0x892F2085;
+pub const NU5_VERSION_GROUP_ID: u32 = 0x26A7270A;
+
+pub const SAPLING_BRANCH_ID: u32 = 0x76B809BB;
+pub const BLOSSOM_BRANCH_ID: u32 = 0x2BB40E60;
+pub const HEARTWOOD_BRANCH_ID: u32 = 0xF5B9230B;
+pub const CANOPY_BRANCH_ID: u32 = 0xE9FF75A6;
+pub const NU5_BRANCH_ID: u32 = 0xC2D6D0B4;
+pub const NU6_BRANCH_ID: u32 = 0xC8E71055;
+
+const HASH_LEN: usize = 32;
+
+/// The transaction format of a transparent Zcash transaction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ZcashVersion {
+    /// v4 transactions, signed according to ZIP-243.
+    Sapling,
+    /// v5 transactions, signed according to ZIP-244.
+    Nu5,
+}
+
+/// The parameters of a transparent Zcash transaction. Note that the version of
+/// the Bitcoin transaction is ignored, the header is derived from `version`.
+#[derive(Clone, Copy, Debug)]
+pub struct ZcashParams {
+    pub version: ZcashVersion,
+    pub branch_id: u32,
+    pub expiry_height: u32,
+}
+
+impl ZcashParams {
+    pub fn new(version: ZcashVersion, branch_id: u32, expiry_height: u32) -> Result<Self> {
+        if branch_id == 0 {
+            return Err(Error::from(Proto::Error::Error_missing_branch_id));
+        }
+
+        Ok(ZcashParams {
+            version,
+            branch_id,
+            expiry_height,
+        })
+    }
+
+    fn header(&self) -> u32 {
+        match self.version {
+            ZcashVersion::Sapling => 4 | OVERWINTERED_FLAG,
+            ZcashVersion::Nu5 => 5 | OVERWINTERED_FLAG,
+        }
+    }
+
+    fn version_group_id(&self) -> u32 {
+        match self.version {
+            ZcashVersion::Sapling => SAPLING_VERSION_GROUP_ID,
+            ZcashVersion::Nu5 => NU5_VERSION_GROUP_ID,
+        }
+    }
+
+    /// Returns the BLAKE2b personalization of the final hash, which commits to
+    /// the consensus branch id.
+    fn branch_personalization(&self, prefix: &[u8; 12]) -> [u8; 16] {
+        let mut personal = [0; 16];
+        personal[..12].copy_from_slice(prefix);
+        personal[12..].copy_from_slice(&self.branch_id.to_le_bytes());
+        personal
+    }
+}
+
+/// Zcash transparent transactions, either Sapling (v4) or NU5 (v5).
+pub struct ZcashContext;
+
+impl UtxoContext for ZcashContext {
+    type SigningInput<'a> = Proto::SigningInput<'a>;
+    type SigningOutput = Proto::SigningInput<'static>;
+    type PreSigningOutput = Proto::SigningInput<'static>;
+    type Params = ZcashParams;
+
+    fn params(chain: &Option<Proto::Chain>) -> Result<Self::Params> {
+        let version = match sighash_algorithm(chain) {
+            ProtoSighashAlgorithm::ZcashSapling => ZcashVersion::Sapling,
+            ProtoSighashAlgorithm::ZcashNu5 => ZcashVersion::Nu5,
+            _ => return Err(Error::from(Proto::Error::Error_unsupported_chain)),
+        };
+
+        // The algorithm is only set with the chain.
+        let chain = chain
+            .as_ref()
+            .ok_or_else(|| Error::from(Proto::Error::Error_unsupported_chain))?;
+        ZcashParams::new(version, chain.branch_id, chain.expiry_height)
+    }
+
+    fn signature_hash(
+        params: &Self::Params,
+        tx: &Transaction,
+        index: usize,
+        inputs: &[Proto::TxIn<'_>],
+    ) -> Result<Option<[u8; 32]>> {
+        let input = &inputs[index];
+        let sighash_type = legacy_sighash_type(input)?;
+        let script_code = Script::from_bytes(input.script_pubkey.as_ref());
+
+        signature_hash(
+            tx,
+            params,
+            index,
+            script_code,
+            &prevouts(inputs),
+            sighash_type,
+        )
+        .map(Some)
+    }
+
+    fn encode(params: &Self::Params, tx: &Transaction) -> Result<Vec<u8>> {
+        encode(tx, params)
+    }
+
+    fn txid(params: &Self::Params, tx: &Transaction) -> Result<[u8; 32]> {
+        txid(tx, params)
+    }
+
+    /// Transparent transactions have no Segwit, a byte simply counts as four
+    /// weight units.
+    fn weight(params: &Self::Params, tx: &Transaction) -> Result<u64> {
+        Ok(encode(tx, params)?.len() as u64 * 4)
+    }
+}
+
+/// Encodes the transparent transaction.
+pub fn encode(tx: &Transaction, params: &ZcashParams) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+
+    params.header().consensus_encode(&mut buffer)?;
+    params.version_group_id().consensus_encode(&mut buffer)?;
+
+    match params.version {
+        ZcashVersion::Sapling => {
+            tx.input.consensus_encode(&mut buffer)?;
+            tx.output.consensus_encode(&mut buffer)?;
+            tx.lock_time.consensus_encode(&mut buffer)?;
+            params.expiry_height.consensus_encode(&mut buffer)?;
+            // valueBalance
+            0_u64.consensus_encode(&mut buffer)?;
+            // vShieldedSpend, vShieldedOutput, vJoinSplit
+            buffer.extend_from_slice(&[0, 0, 0]);
+        },
+        ZcashVersion::Nu5 => {
+            params.branch_id.consensus_encode(&mut buffer)?;
+            tx.lock_time.consensus_encode(&mut buffer)?;
+            params.expiry_height.consensus_encode(&mut buffer)?;
+            tx.input.consensus_encode(&mut buffer)?;
+            tx.output.consensus_encode(&mut buffer)?;
+            // nSpendsSapling, nOutputsSapling, nActionsOrchard
+            buffer.extend_from_slice(&[0, 0, 0]);
+        },
+    }
+
+    Ok(buffer)
+}
+
+/// Returns the transaction id in the internal byte order.
+pub fn txid(tx: &Transaction, params: &ZcashParams) -> Result<[u8; 32]> {
+    match params.version {
+        ZcashVersion::Sapling => {
+            let encoded = encode(tx, params)?;
+            Ok(sha256d::Hash::hash(&encoded).to_byte_array())
+        },
+        ZcashVersion::Nu5 => {
+            let digests = TxIdDigests::new(tx, params)?;
+
+            let mut data = vec![];
+            data.extend_from_slice(&digests.header);
+            data.extend_from_slice(&digests.transparent()?);
+            data.extend_from_slice(&blake2b(b"ZTxIdSaplingHash", &[])?);
+            data.extend_from_slice(&blake2b(b"ZTxIdOrchardHash", &[])?);
+
+            let personal = params.branch_personalization(b"ZcashTxHash_");
+            to_array(blake2b(&personal, &data)?)
+        },
+    }
+}
+
+/// Computes the signature hash of a transparent input. The previous outputs
+/// of all inputs are required by ZIP-244.
+pub fn signature_hash(
+    tx: &Transaction,
+    params: &ZcashParams,
+    index: usize,
+    script_code: &Script,
+    prevouts: &[TxOut],
+    sighash_type: EcdsaSighashType,
+) -> Result<[u8; 32]> {
+    if prevouts.len() != tx.input.len() || index >= tx.input.len() {
+        return Err(Error::from(Proto::Error::Error_sighash_failed));
+    }
+
+    match params.version {
+        ZcashVersion::Sapling => {
+            let value = prevouts[index].value;
+            sapling_signature_hash(tx, params, index, script_code, value, sighash_type)
+        },
+        ZcashVersion::Nu5 => nu5_signature_hash(tx, params, index, prevouts, sighash_type),
+    }
+}
+
+/// See <https://zips.z.cash/zip-0243>
+fn sapling_signature_hash(
+    tx: &Transaction,
+    params: &ZcashParams,
+    index: usize,
+    script_code: &Script,
+    value: u64,
+    sighash_type: EcdsaSighashType,
+) -> Result<[u8; 32]> {
+    let (base_type, anyone_can_pay) = split_anyone_can_pay(sighash_type);
+    let single = base_type == EcdsaSighashType::Single;
+    let none = base_type == EcdsaSighashType::None;
+
+    let zero = vec![0; HASH_LEN];
+
+    let hash_prevouts = if anyone_can_pay {
+        zero.clone()
+    } else {
+        blake2b(b"ZcashPrevoutHash", &encode_prevouts(tx)?)?
+    };
+
+    let hash_sequence = if anyone_can_pay || single || none {
+        zero.clone()
+    } else {
+        blake2b(b"ZcashSequencHash", &encode_sequences(tx)?)?
+    };
+
+    let hash_outputs = if !single && !none {
+        blake2b(b"ZcashOutputsHash", &encode_outputs(&tx.output)?)?
+    } else if single && index < tx.output.len() {
+        blake2b(
+            b"ZcashOutputsHash",
+            &encode_outputs(&tx.output[index..=index])?,
+        )?
+    } else {
+        zero.clone()
+    };
+
+    let txin = &tx.input[index];
+
+    let mut preimage = vec![];
+    params.header().consensus_encode(&mut preimage)?;
+    params.version_group_id().consensus_encode(&mut preimage)?;
+    preimage.extend_from_slice(&hash_prevouts);
+    preimage.extend_from_slice(&hash_sequence);
+    preimage.extend_from_slice(&hash_outputs);
+    // hashJoinSplits, hashShieldedSpends, hashShieldedOutputs
+    preimage.extend_from_slice(&zero);
+    preimage.extend_from_slice(&zero);
+    preimage.extend_from_slice(&zero);
+    tx.lock_time.consensus_encode(&mut preimage)?;
+    params.expiry_height.consensus_encode(&mut preimage)?;
+    // valueBalance
+    0_u64.consensus_encode(&mut preimage)?;
+    sighash_type.to_u32().consensus_encode(&mut preimage)?;
+    txin.previous_output.consensus_encode(&mut preimage)?;
+    script_code.consensus_encode(&mut preimage)?;
+    value.consensus_encode(&mut preimage)?;
+    txin.sequence.consensus_encode(&mut preimage)?;
+
+    let personal = params.branch_personalization(b"ZcashSigHash");
+    to_array(blake2b(&personal, &preimage)?)
+}
+
+/// See <https://zips.z.cash/zip-0244>
+fn nu5_signature_hash(
+    tx: &Transaction,
+    params: &ZcashParams,
+    index: usize,
+    prevouts: &[TxOut],
+    sighash_type: EcdsaSighashType,
+) -> Result<[u8; 32]> {
+    let (base_type, anyone_can_pay) = split_anyone_can_pay(sighash_type);
+    let single = base_type == EcdsaSighashType::Single;
+    let none = base_type == EcdsaSighashType::None;
+
+    let digests = TxIdDigests::new(tx, params)?;
+
+    let (prevouts_digest, amounts_digest, scripts_digest, sequence_digest) = if anyone_can_pay {
+        (
+            blake2b(b"ZTxIdPrevoutHash", &[])?,
+            blake2b(b"ZTxTrAmountsHash", &[])?,
+            blake2b(b"ZTxTrScriptsHash", &[])?,
+            blake2b(b"ZTxIdSequencHash", &[])?,
+        )
+    } else {
+        let mut amounts = vec![];
+        let mut scripts = vec![];
+        for prevout in prevouts {
+            prevout.value.consensus_encode(&mut amounts)?;
+            prevout.script_pubkey.consensus_encode(&mut scripts)?;
+        }
+
+        (
+            digests.prevouts.clone(),
+            blake2b(b"ZTxTrAmountsHash", &amounts)?,
+            blake2b(b"ZTxTrScriptsHash", &scripts)?,
+            digests.sequence.clone(),
+        )
+    };
+
+    let outputs_digest = if single {
+        if index < tx.output.len() {
+            blake2b(
+                b"ZTxIdOutputsHash",
+                &encode_outputs(&tx.output[index..=index])?,
+            )?
+        } else {
+            blake2b(b"ZTxIdOutputsHash", &[])?
+        }
+    } else if none {
+        blake2b(b"ZTxIdOutputsHash", &[])?
+    } else {
+        digests.outputs.clone()
+    };
+
+    let txin = &tx.input[index];
+    let prevout = &prevouts[index];
+
+    let mut txin_data = vec![];
+    txin.previous_output.consensus_encode(&mut txin_data)?;
+    prevout.value.consensus_encode(&mut txin_data)?;
+    prevout.script_pubkey.consensus_encode(&mut txin_data)?;
+    txin.sequence.consensus_encode(&mut txin_data)?;
+    let txin_digest = blake2b(b"Zcash___TxInHash", &txin_data)?;
+
+    let mut transparent = vec![sighash_type.to_u32() as u8];
+    transparent.extend_from_slice(&prevouts_digest);
+    transparent.extend_from_slice(&amounts_digest);
+    transparent.extend_from_slice(&scripts_digest);
+    transparent.extend_from_slice(&sequence_digest);
+    transparent.extend_from_slice(&outputs_digest);
+    transparent.extend_from_slice(&txin_digest);
+
+    let mut data = vec![];
+    data.extend_from_slice(&digests.header);
+    data.extend_from_slice(&blake2b(b"ZTxIdTranspaHash", &transparent)?);
+    data.extend_from_slice(&blake2b(b"ZTxIdSaplingHash", &[])?);
+    data.extend_from_slice(&blake2b(b"ZTxIdOrchardHash", &[])?);
+
+    let personal = params.branch_personalization(b"ZcashTxHash_");
+    to_array(blake2b(&personal, &data)?)
+}
+
+/// The ZIP-244 digests that are shared by the transaction id and the
+/// signature hashes.
+struct TxIdDigests {
+    header: Vec<u8>,
+    prevouts: Vec<u8>,
+    sequence: Vec<u8>,
+    outputs: Vec<u8>,
+    is_empty: bool,
+}
+
+impl TxIdDigests {
+    fn new(tx: &Transaction, params: &ZcashParams) -> Result<Self> {
+        let mut header = vec![];
+        params.header().consensus_encode(&mut header)?;
+        params.version_group_id().consensus_encode(&mut header)?;
+        params.branch_id.consensus_encode(&mut header)?;
+        tx.lock_time.consensus_encode(&mut header)?;
+        params.expiry_height.consensus_encode(&mut header)?;
+
+        Ok(TxIdDigests {
+            header: blake2b(b"ZTxIdHeadersHash", &header)?,
+            prevouts: blake2b(b"ZTxIdPrevoutHash", &encode_prevouts(tx)?)?,
+            sequence: blake2b(b"ZTxIdSequencHash", &encode_sequences(tx)?)?,
+            outputs: blake2b(b"ZTxIdOutputsHash", &encode_outputs(&tx.output)?)?,
+            is_empty: tx.input.is_empty() && tx.output.is_empty(),
+        })
+    }
+
+    fn transparent(&self) -> Result<Vec<u8>> {
+        if self.is_empty {
+            return blake2b(b"ZTxIdTranspaHash", &[]);
+        }
+
+        let mut data = vec![];
+        data.extend_from_slice(&self.prevouts);
+        data.extend_from_slice(&self.sequence);
+        data.extend_from_slice(&self.outputs);
+        blake2b(b"ZTxIdTranspaHash", &data)
+    }
+}
+
+fn encode_prevouts(tx: &Transaction) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+    for txin in &tx.input {
+        txin.previous_output.consensus_encode(&mut buffer)?;
+    }
+    Ok(buffer)
+}
+
+fn encode_sequences(tx: &Transaction) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+    for txin in &tx.input {
+        txin.sequence.consensus_encode(&mut buffer)?;
+    }
+    Ok(buffer)
+}
+
+fn encode_outputs(outputs: &[TxOut]) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+    for txout in outputs {
+        txout.consensus_encode(&mut buffer)?;
+    }
+    Ok(buffer)
+}
+
+fn blake2b(personal: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    blake2_b_personal(data, HASH_LEN, personal)
+        .map_err(|_| Error::from(Proto::Error::Error_sighash_failed))
+}
+
+fn to_array(hash: Vec<u8>) -> Result<[u8; 32]> {
+    hash.try_into()
+        .map_err(|_| Error::from(Proto::Error::Error_sighash_failed))
+}
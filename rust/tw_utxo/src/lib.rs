@@ -1,6 +1,7 @@
 use tw_proto::Utxo::Proto;
 
 pub mod compiler;
+pub mod forks;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -19,6 +20,12 @@ impl From<bitcoin::sighash::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(_value: std::io::Error) -> Self {
+        Error(Proto::Error::Error_failed_encoding)
+    }
+}
+
 impl From<Error> for Proto::Error {
     fn from(value: Error) -> Self {
         value.0
@@ -54,6 +54,7 @@ fn input_selector_all() {
         change_script_pubkey: Default::default(),
         // DISABLE change output.
         disable_change_output: true,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -83,6 +84,7 @@ fn input_selector_all() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -146,6 +148,7 @@ fn input_selector_all_insufficient_inputs() {
         change_script_pubkey: Default::default(),
         // DISABLE change output.
         disable_change_output: true,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -169,6 +172,7 @@ fn input_selector_all_insufficient_inputs() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -222,6 +226,7 @@ fn input_selector_one_input_required() {
         change_script_pubkey: Default::default(),
         // DISABLE change output.
         disable_change_output: true,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -248,6 +253,7 @@ fn input_selector_one_input_required() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -316,6 +322,7 @@ fn input_selector_two_inputs_required() {
         change_script_pubkey: Default::default(),
         // DISABLE change output.
         disable_change_output: true,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -344,6 +351,7 @@ fn input_selector_two_inputs_required() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -399,6 +407,7 @@ fn input_selector_one_input_cannot_cover_fees() {
         change_script_pubkey: Default::default(),
         // DISABLE change output.
         disable_change_output: true,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -423,6 +432,7 @@ fn input_selector_one_input_cannot_cover_fees() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -466,6 +476,7 @@ fn input_selector_exact_balance_no_change() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -516,6 +527,7 @@ fn input_selector_empty_script_bufs() {
         change_script_pubkey: Default::default(),
         // ENABLE change output.
         disable_change_output: false,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -573,6 +585,7 @@ fn input_selector_select_ascending() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -587,3 +600,153 @@ fn input_selector_select_ascending() {
     assert_eq!(output.outputs.len(), 2);
     assert_eq!(output.outputs[0], out1);
 }
+
+#[test]
+fn input_selector_skips_protected_inputs() {
+    let txid = txid_rev("1e1cdc48aa990d7e154a161d5b5f1cad737742e97d2712ab188027bb42e6e47b");
+
+    // Inscribed input.
+    let tx1 = Proto::TxIn {
+        txid: txid.as_slice().into(),
+        value: 10_000,
+        sequence: u32::MAX,
+        protection: Some(Proto::InputProtection {
+            inscription_offsets: vec![0],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    // Locked input.
+    let tx2 = Proto::TxIn {
+        txid: txid.as_slice().into(),
+        value: 8_000,
+        sequence: u32::MAX,
+        protection: Some(Proto::InputProtection {
+            locked: true,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let tx3 = Proto::TxIn {
+        txid: txid.as_slice().into(),
+        value: 4_000,
+        sequence: u32::MAX,
+        ..Default::default()
+    };
+
+    let out1 = Proto::TxOut {
+        value: 3_000,
+        script_pubkey: Default::default(),
+    };
+
+    let change_script = change_output();
+    let signing = Proto::SigningInput {
+        version: 2,
+        lock_time: Default::default(),
+        inputs: vec![tx1.clone(), tx2.clone(), tx3.clone()],
+        outputs: vec![out1.clone()],
+        input_selector: Proto::InputSelector::SelectDescending,
+        weight_base: WEIGHT_BASE,
+        change_script_pubkey: change_script.as_bytes().into(),
+        disable_change_output: false,
+        chain: None,
+    };
+
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing.clone());
+    assert_eq!(output.error, Proto::Error::OK);
+
+    // Only the unprotected input is selected.
+    assert_eq!(output.inputs.len(), 1);
+    assert_eq!(output.inputs[0], tx3);
+
+    // The protected inputs are not sufficient.
+    let signing_insufficient = Proto::SigningInput {
+        outputs: vec![Proto::TxOut {
+            value: 5_000,
+            script_pubkey: Default::default(),
+        }],
+        ..signing.clone()
+    };
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing_insufficient);
+    assert_eq!(output.error, Proto::Error::Error_insufficient_inputs);
+
+    // Protected inputs must not be spent explicitly.
+    let signing_all = Proto::SigningInput {
+        input_selector: Proto::InputSelector::UseAll,
+        ..signing
+    };
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing_all);
+    assert_eq!(output.error, Proto::Error::Error_protected_input);
+}
+
+#[test]
+fn input_selector_transfer_inscription() {
+    let txid = txid_rev("1e1cdc48aa990d7e154a161d5b5f1cad737742e97d2712ab188027bb42e6e47b");
+
+    let tx1 = Proto::TxIn {
+        txid: txid.as_slice().into(),
+        value: 5_000,
+        sequence: u32::MAX,
+        ..Default::default()
+    };
+
+    // The inscribed input, which is transferred.
+    let tx2 = Proto::TxIn {
+        txid: txid.as_slice().into(),
+        value: 546,
+        sequence: u32::MAX,
+        protection: Some(Proto::InputProtection {
+            inscription_offsets: vec![100],
+            transfer: true,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let tx3 = Proto::TxIn {
+        txid: txid.as_slice().into(),
+        value: 4_000,
+        sequence: u32::MAX,
+        ..Default::default()
+    };
+
+    let out1 = Proto::TxOut {
+        value: 546,
+        script_pubkey: Default::default(),
+    };
+
+    let change_script = change_output();
+    let signing = Proto::SigningInput {
+        version: 2,
+        lock_time: Default::default(),
+        inputs: vec![tx1.clone(), tx2.clone(), tx3.clone()],
+        outputs: vec![out1.clone()],
+        input_selector: Proto::InputSelector::SelectAscending,
+        weight_base: WEIGHT_BASE,
+        change_script_pubkey: change_script.as_bytes().into(),
+        disable_change_output: false,
+        chain: None,
+    };
+
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing.clone());
+    assert_eq!(output.error, Proto::Error::OK);
+
+    // The inscribed input is spent first, the fee is covered by the smallest
+    // remaining input.
+    assert_eq!(output.inputs.len(), 2);
+    assert_eq!(output.inputs[0], tx2);
+    assert_eq!(output.inputs[1], tx3);
+
+    // The inscribed satoshi would be sent to the change output.
+    let signing_too_small = Proto::SigningInput {
+        outputs: vec![Proto::TxOut {
+            value: 100,
+            script_pubkey: Default::default(),
+        }],
+        ..signing
+    };
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing_too_small);
+    assert_eq!(output.error, Proto::Error::Error_inscription_not_preserved);
+}
@@ -33,6 +33,8 @@ fn sighash_input_p2pkh_output_p2tr_key_spend() {
             signing_method: Proto::SigningMethod::Legacy,
             weight_estimate: 1,
             leaf_hash: Default::default(),
+            protection: None,
+            prevout_script_pubkey: Default::default(),
         }],
         outputs: vec![Proto::TxOut {
             value: 50 * 100_000_000 - 1_000_000,
@@ -42,6 +44,7 @@ fn sighash_input_p2pkh_output_p2tr_key_spend() {
         weight_base: 1,
         change_script_pubkey: Default::default(),
         disable_change_output: true,
+        chain: None,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -0,0 +1,324 @@
+mod common;
+use common::{pubkey_hash_from_hex, txid_rev};
+
+use bitcoin::absolute::LockTime;
+use bitcoin::hashes::Hash;
+use bitcoin::sighash::EcdsaSighashType;
+use bitcoin::{OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+use tw_encoding::hex;
+use tw_proto::Utxo::Proto;
+use tw_utxo::compiler::{Compiler, StandardBitcoinContext};
+use tw_utxo::forks::zcash::{self, ZcashContext, ZcashParams, ZcashVersion};
+
+// Test vector 3 of https://zips.z.cash/zip-0243
+fn zip243_transaction() -> Transaction {
+    let txid =
+        hex::decode("a8c685478265f4c14dada651969c45a65e1aeb8cd6791f2f5bb6a1d9952104d9").unwrap();
+    let script_sig = hex::decode("483045022100a61e5d557568c2ddc1d9b03a7173c6ce7c996c4daecab007ac8f34bee01e6b9702204d38fdc0bcf2728a69fde78462a10fb45a9baa27873e6a5fc45fb5c76764202a01210365ffea3efa3908918a8b8627724af852fc9b86d7375b103ab0543cf418bcaa7f").unwrap();
+
+    Transaction {
+        // Ignored, the header is derived from the Zcash version.
+        version: 4,
+        lock_time: LockTime::from_consensus(0x0004b029),
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: Txid::from_slice(&txid).unwrap(),
+                vout: 1,
+            },
+            script_sig: ScriptBuf::from_bytes(script_sig),
+            sequence: Sequence(0xfffffffe),
+            witness: Witness::new(),
+        }],
+        output: vec![
+            TxOut {
+                value: 0x02625a00,
+                script_pubkey: ScriptBuf::new_p2pkh(&pubkey_hash_from_hex(
+                    "8132712c3ff19f3a151234616777420a6d7ef226",
+                )),
+            },
+            TxOut {
+                value: 0x0098958b,
+                script_pubkey: ScriptBuf::new_p2pkh(&pubkey_hash_from_hex(
+                    "5453e4698f02a38abdaa521cd1ff2dee6fac1871",
+                )),
+            },
+        ],
+    }
+}
+
+#[test]
+fn zcash_sapling_encode_and_sighash() {
+    let tx = zip243_transaction();
+    let params =
+        ZcashParams::new(ZcashVersion::Sapling, zcash::SAPLING_BRANCH_ID, 0x0004b048).unwrap();
+
+    let encoded = zcash::encode(&tx, &params).unwrap();
+    assert_eq!(
+        hex::encode(encoded, false),
+        "0400008085202f8901a8c685478265f4c14dada651969c45a65e1aeb8cd6791f2f5bb6a1d9952104d9010000006b483045022100a61e5d557568c2ddc1d9b03a7173c6ce7c996c4daecab007ac8f34bee01e6b9702204d38fdc0bcf2728a69fde78462a10fb45a9baa27873e6a5fc45fb5c76764202a01210365ffea3efa3908918a8b8627724af852fc9b86d7375b103ab0543cf418bcaa7ffeffffff02005a6202000000001976a9148132712c3ff19f3a151234616777420a6d7ef22688ac8b959800000000001976a9145453e4698f02a38abdaa521cd1ff2dee6fac187188ac29b0040048b004000000000000000000000000"
+    );
+
+    let script_code = ScriptBuf::new_p2pkh(&pubkey_hash_from_hex(
+        "507173527b4c3318a2aecd793bf1cfed705950cf",
+    ));
+    let prevouts = vec![TxOut {
+        value: 0x02faf080,
+        script_pubkey: script_code.clone(),
+    }];
+
+    let sighash = zcash::signature_hash(
+        &tx,
+        &params,
+        0,
+        &script_code,
+        &prevouts,
+        EcdsaSighashType::All,
+    )
+    .unwrap();
+    assert_eq!(
+        hex::encode(sighash, false),
+        "f3148f80dfab5e573d5edfe7a850f5fd39234f80b5429d3a57edcc11e34c585b"
+    );
+}
+
+#[test]
+fn zcash_nu5_encode() {
+    let tx = zip243_transaction();
+    let params = ZcashParams::new(ZcashVersion::Nu5, zcash::NU5_BRANCH_ID, 0).unwrap();
+
+    let encoded = zcash::encode(&tx, &params).unwrap();
+    let encoded = hex::encode(encoded, false);
+
+    // header, versionGroupId, consensusBranchId, lockTime, expiryHeight
+    assert!(encoded.starts_with("050000800a27a726b4d0d6c229b0040000000000"));
+    // No Sapling spends, Sapling outputs or Orchard actions.
+    assert!(encoded.ends_with("88ac000000"));
+
+    // The signature hash commits to the consensus branch id.
+    let script_code = tx.output[0].script_pubkey.clone();
+    let prevouts = vec![TxOut {
+        value: 0x02faf080,
+        script_pubkey: script_code.clone(),
+    }];
+    let nu5 = zcash::signature_hash(
+        &tx,
+        &params,
+        0,
+        &script_code,
+        &prevouts,
+        EcdsaSighashType::All,
+    );
+
+    let params = ZcashParams::new(ZcashVersion::Nu5, zcash::NU6_BRANCH_ID, 0).unwrap();
+    let nu6 = zcash::signature_hash(
+        &tx,
+        &params,
+        0,
+        &script_code,
+        &prevouts,
+        EcdsaSighashType::All,
+    );
+    assert_ne!(nu5.unwrap(), nu6.unwrap());
+}
+
+#[test]
+fn zcash_missing_branch_id() {
+    let err = ZcashParams::new(ZcashVersion::Sapling, 0, 0).unwrap_err();
+    assert_eq!(
+        Proto::Error::from(err),
+        Proto::Error::Error_missing_branch_id
+    );
+}
+
+#[test]
+fn sighash_zcash_sapling_p2pkh() {
+    let input_script_pubkey = ScriptBuf::new_p2pkh(&pubkey_hash_from_hex(
+        "f84c7f4dd3c3dc311676444fdead6e6d290d50e3",
+    ));
+    let output_script_pubkey = ScriptBuf::new_p2pkh(&pubkey_hash_from_hex(
+        "49964a736f3713d64283fd0018626ba50091c7e9",
+    ));
+
+    let txid = txid_rev("3a19dd44032dfed61bfca5ba5751aab8a107b30609cbd5d70dc5ef09885b6853");
+
+    let signing = Proto::SigningInput {
+        version: 4,
+        lock_time: Default::default(),
+        inputs: vec![Proto::TxIn {
+            txid: txid.into(),
+            vout: 0,
+            value: 494_000,
+            sequence: u32::MAX,
+            script_pubkey: input_script_pubkey.as_bytes().into(),
+            sighash_type: Proto::SighashType::All,
+            signing_method: Proto::SigningMethod::Legacy,
+            weight_estimate: 1,
+            leaf_hash: Default::default(),
+            protection: None,
+            prevout_script_pubkey: Default::default(),
+        }],
+        outputs: vec![Proto::TxOut {
+            value: 488_000,
+            script_pubkey: output_script_pubkey.as_bytes().into(),
+        }],
+        input_selector: Proto::InputSelector::UseAll,
+        weight_base: 1,
+        change_script_pubkey: Default::default(),
+        disable_change_output: true,
+        chain: Some(Proto::Chain {
+            sighash_algorithm: Proto::mod_Chain::SighashAlgorithm::ZcashSapling,
+            branch_id: zcash::SAPLING_BRANCH_ID,
+            ..Default::default()
+        }),
+    };
+
+    let output = Compiler::<ZcashContext>::preimage_hashes(signing);
+    assert_eq!(output.error, Proto::Error::OK);
+
+    let hashes = output.sighashes;
+    assert_eq!(hashes.len(), 1);
+    assert_eq!(
+        hex::encode(hashes[0].sighash.as_ref(), false),
+        "ca9e8bc923c8e71440284859f91259afc001d229aa9969e486e69da186e5268a"
+    );
+}
+
+#[test]
+fn sighash_zcash_segwit_not_supported() {
+    let signing = Proto::SigningInput {
+        inputs: vec![Proto::TxIn {
+            txid: vec![1; 32].into(),
+            value: 494_000,
+            script_pubkey: vec![0; 22].into(),
+            signing_method: Proto::SigningMethod::Segwit,
+            ..Default::default()
+        }],
+        outputs: vec![Proto::TxOut {
+            value: 488_000,
+            script_pubkey: vec![0; 22].into(),
+        }],
+        disable_change_output: true,
+        chain: Some(Proto::Chain {
+            sighash_algorithm: Proto::mod_Chain::SighashAlgorithm::ZcashSapling,
+            branch_id: zcash::SAPLING_BRANCH_ID,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let output = Compiler::<ZcashContext>::preimage_hashes(signing);
+    assert_eq!(output.error, Proto::Error::Error_invalid_signing_method);
+}
+
+#[test]
+fn sighash_zcash_nu5_p2sh_commits_to_prevout_script_pubkey() {
+    let redeem_script = ScriptBuf::new_p2pkh(&pubkey_hash_from_hex(
+        "f84c7f4dd3c3dc311676444fdead6e6d290d50e3",
+    ));
+    let prevout_script_pubkey = ScriptBuf::new_p2sh(&redeem_script.script_hash());
+
+    let signing = |prevout_script_pubkey: &ScriptBuf| Proto::SigningInput {
+        version: 5,
+        inputs: vec![Proto::TxIn {
+            txid: vec![1; 32].into(),
+            value: 494_000,
+            sequence: u32::MAX,
+            script_pubkey: redeem_script.as_bytes().to_vec().into(),
+            signing_method: Proto::SigningMethod::Legacy,
+            prevout_script_pubkey: prevout_script_pubkey.as_bytes().to_vec().into(),
+            ..Default::default()
+        }],
+        outputs: vec![Proto::TxOut {
+            value: 488_000,
+            script_pubkey: redeem_script.as_bytes().to_vec().into(),
+        }],
+        disable_change_output: true,
+        chain: Some(Proto::Chain {
+            sighash_algorithm: Proto::mod_Chain::SighashAlgorithm::ZcashNu5,
+            branch_id: zcash::NU5_BRANCH_ID,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let output = Compiler::<ZcashContext>::preimage_hashes(signing(&prevout_script_pubkey));
+    assert_eq!(output.error, Proto::Error::OK);
+    // The spent scriptPubkey is preserved for `compile`.
+    assert_eq!(
+        output.inputs[0].prevout_script_pubkey.as_ref(),
+        prevout_script_pubkey.as_bytes()
+    );
+
+    // ZIP-244 commits to the P2SH scriptPubkey, not to the redeem script.
+    let tx = Transaction {
+        version: 5,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: Txid::from_slice(&[1; 32]).unwrap(),
+                vout: 0,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: 488_000,
+            script_pubkey: redeem_script.clone(),
+        }],
+    };
+    let params = ZcashParams::new(ZcashVersion::Nu5, zcash::NU5_BRANCH_ID, 0).unwrap();
+    let prevouts = vec![TxOut {
+        value: 494_000,
+        script_pubkey: prevout_script_pubkey,
+    }];
+    let expected = zcash::signature_hash(
+        &tx,
+        &params,
+        0,
+        &redeem_script,
+        &prevouts,
+        EcdsaSighashType::All,
+    )
+    .unwrap();
+    assert_eq!(output.sighashes[0].sighash.as_ref(), expected);
+
+    // Without the spent scriptPubkey, the redeem script is committed to instead.
+    let output = Compiler::<ZcashContext>::preimage_hashes(signing(&ScriptBuf::new()));
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_ne!(output.sighashes[0].sighash.as_ref(), expected);
+}
+
+#[test]
+fn sighash_zcash_unsupported_chain() {
+    let signing = Proto::SigningInput {
+        inputs: vec![Proto::TxIn {
+            txid: vec![1; 32].into(),
+            value: 494_000,
+            script_pubkey: vec![0; 25].into(),
+            signing_method: Proto::SigningMethod::Legacy,
+            ..Default::default()
+        }],
+        outputs: vec![Proto::TxOut {
+            value: 488_000,
+            script_pubkey: vec![0; 25].into(),
+        }],
+        disable_change_output: true,
+        chain: Some(Proto::Chain {
+            sighash_algorithm: Proto::mod_Chain::SighashAlgorithm::ZcashSapling,
+            branch_id: zcash::SAPLING_BRANCH_ID,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing.clone());
+    assert_eq!(output.error, Proto::Error::Error_unsupported_chain);
+
+    let signing = Proto::SigningInput {
+        chain: None,
+        ..signing
+    };
+    let output = Compiler::<ZcashContext>::preimage_hashes(signing);
+    assert_eq!(output.error, Proto::Error::Error_unsupported_chain);
+}